@@ -464,6 +464,18 @@ impl sc_cli::CliConfiguration for NormalizedRunCmd {
 		Ok(self.base.rpc_params.rpc_rate_limit)
 	}
 
+	fn rpc_rate_limit_per_ip(&self) -> sc_cli::Result<Option<std::num::NonZeroU32>> {
+		Ok(self.base.rpc_params.rpc_rate_limit_per_ip)
+	}
+
+	fn rpc_method_costs(&self) -> sc_cli::Result<sc_service::config::RpcMethodCosts> {
+		Ok(self.base.rpc_params.rpc_method_costs())
+	}
+
+	fn rpc_max_subscriptions_per_ip(&self) -> sc_cli::Result<Option<u32>> {
+		Ok(self.base.rpc_params.rpc_max_subscriptions_per_ip)
+	}
+
 	fn rpc_rate_limit_whitelisted_ips(&self) -> sc_cli::Result<Vec<sc_service::config::IpNetwork>> {
 		Ok(self.base.rpc_params.rpc_rate_limit_whitelisted_ips.clone())
 	}
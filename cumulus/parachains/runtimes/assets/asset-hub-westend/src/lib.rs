@@ -1093,6 +1093,9 @@ impl pallet_revive::Config for Runtime {
 	type NativeToEthRatio = ConstU32<1_000_000>; // 10^(18 - 12) Eth is 10^18, Native is 10^12.
 	type EthGasEncoder = ();
 	type FindAuthor = <Runtime as pallet_authorship::Config>::FindAuthor;
+	type Xcm = PolkadotXcm;
+	type NominationPools = ();
+	type ConvictionVoting = ();
 }
 
 parameter_types! {
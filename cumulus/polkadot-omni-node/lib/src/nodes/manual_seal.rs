@@ -171,6 +171,7 @@ impl<NodeSpec: NodeSpecT> ManualSealNode<NodeSpec> {
 		let client_for_cidp = client.clone();
 		let params = sc_consensus_manual_seal::ManualSealParams {
 			block_import: client.clone(),
+			backend: Some(backend.clone()),
 			env: proposer,
 			client: client.clone(),
 			pool: transaction_pool.clone(),
@@ -242,6 +243,8 @@ impl<NodeSpec: NodeSpecT> ManualSealNode<NodeSpec> {
 					))
 				}
 			},
+			time_travel: None,
+			impersonation_provider: None,
 		};
 		let authorship_future = sc_consensus_manual_seal::run_manual_seal(params);
 		task_manager.spawn_essential_handle().spawn_blocking(
@@ -900,8 +900,11 @@ pub fn node_config(
 			message_buffer_capacity: Default::default(),
 			batch_config: RpcBatchRequestConfig::Unlimited,
 			rate_limit: None,
+			rate_limit_per_ip: None,
+			rate_limit_method_costs: Default::default(),
 			rate_limit_whitelisted_ips: Default::default(),
 			rate_limit_trust_proxy_headers: Default::default(),
+			max_subs_per_ip: None,
 		},
 		prometheus_config: None,
 		telemetry_endpoints: None,
@@ -1038,8 +1041,11 @@ pub fn run_relay_chain_validator_node(
 			max_buffer_capacity_per_connection: config.rpc.message_buffer_capacity,
 			rpc_methods: config.rpc.methods,
 			rate_limit: config.rpc.rate_limit,
+			rate_limit_per_ip: config.rpc.rate_limit_per_ip,
+			rate_limit_method_costs: config.rpc.rate_limit_method_costs.clone(),
 			rate_limit_trust_proxy_headers: config.rpc.rate_limit_trust_proxy_headers,
 			rate_limit_whitelisted_ips: config.rpc.rate_limit_whitelisted_ips.clone(),
+			max_subscriptions_per_ip: config.rpc.max_subs_per_ip,
 			retry_random_port: true,
 			is_optional: false,
 		}]);
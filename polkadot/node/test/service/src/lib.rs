@@ -229,8 +229,11 @@ pub fn node_config(
 			message_buffer_capacity: Default::default(),
 			batch_config: RpcBatchRequestConfig::Unlimited,
 			rate_limit: None,
+			rate_limit_per_ip: None,
+			rate_limit_method_costs: Default::default(),
 			rate_limit_whitelisted_ips: Default::default(),
 			rate_limit_trust_proxy_headers: Default::default(),
+			max_subs_per_ip: None,
 		},
 		prometheus_config: None,
 		telemetry_endpoints: None,
@@ -81,8 +81,11 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 			message_buffer_capacity: Default::default(),
 			batch_config: RpcBatchRequestConfig::Unlimited,
 			rate_limit: None,
+			rate_limit_per_ip: None,
+			rate_limit_method_costs: Default::default(),
 			rate_limit_whitelisted_ips: Default::default(),
 			rate_limit_trust_proxy_headers: Default::default(),
+			max_subs_per_ip: None,
 		},
 		prometheus_config: None,
 		telemetry_endpoints: None,
@@ -103,6 +106,7 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 			config,
 			None,
 			false,
+			false,
 			|_, _| (),
 		)
 		.expect("Creates node")
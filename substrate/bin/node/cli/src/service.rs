@@ -174,6 +174,7 @@ pub fn create_extrinsic(
 pub fn new_partial(
 	config: &Configuration,
 	mixnet_config: Option<&sc_mixnet::Config>,
+	enable_event_index: bool,
 ) -> Result<
 	sc_service::PartialComponents<
 		FullClient,
@@ -350,11 +351,17 @@ pub fn new_partial(
 						beefy_best_block_stream: beefy_rpc_links
 							.from_voter_best_beefy_stream
 							.clone(),
+						subscription_executor: subscription_executor.clone(),
+					},
+					statement: node_rpc::StatementDeps {
+						statement_store: rpc_statement_store.clone(),
+						statement_notifications: rpc_statement_store
+							.statement_notification_stream(),
 						subscription_executor,
 					},
-					statement_store: rpc_statement_store.clone(),
 					backend: rpc_backend.clone(),
 					mixnet_api: mixnet_api.as_ref().cloned(),
+					event_index: enable_event_index,
 				};
 
 				node_rpc::create_full(deps).map_err(Into::into)
@@ -403,6 +410,7 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 	config: Configuration,
 	mixnet_config: Option<sc_mixnet::Config>,
 	disable_hardware_benchmarks: bool,
+	enable_event_index: bool,
 	with_startup_data: impl FnOnce(
 		&sc_consensus_babe::BabeBlockImport<
 			Block,
@@ -422,6 +430,10 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 	let prometheus_registry = config.prometheus_registry().cloned();
 	let enable_offchain_worker = config.offchain_worker.enabled;
 
+	if enable_event_index && !config.state_pruning.as_ref().is_some_and(|p| p.is_archive()) {
+		return Err(ServiceError::Other("The event index requires an archive node".into()))
+	}
+
 	let hwbench = (!disable_hardware_benchmarks)
 		.then(|| {
 			config.database.path().map(|database_path| {
@@ -441,7 +453,7 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 		transaction_pool,
 		other:
 			(rpc_builder, import_setup, rpc_setup, mut telemetry, statement_store, mixnet_api_backend),
-	} = new_partial(&config, mixnet_config.as_ref())?;
+	} = new_partial(&config, mixnet_config.as_ref(), enable_event_index)?;
 
 	let metrics = N::register_notification_metrics(
 		config.prometheus_config.as_ref().map(|cfg| &cfg.registry),
@@ -762,6 +774,14 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 		);
 	}
 
+	if enable_event_index {
+		task_manager.spawn_handle().spawn_blocking(
+			"event-indexer",
+			None,
+			sc_event_index::run_event_indexer::<_, FullBackend, _>(client.clone()),
+		);
+	}
+
 	// Spawn statement protocol worker
 	let statement_protocol_executor = {
 		let spawn_handle = task_manager.spawn_handle();
@@ -826,6 +846,7 @@ pub fn new_full(config: Configuration, cli: Cli) -> Result<TaskManager, ServiceE
 				config,
 				mixnet_config,
 				cli.no_hardware_benchmarks,
+				cli.event_index,
 				|_, _| (),
 			)
 			.map(|NewFullBase { task_manager, .. }| task_manager)?;
@@ -836,6 +857,7 @@ pub fn new_full(config: Configuration, cli: Cli) -> Result<TaskManager, ServiceE
 				config,
 				mixnet_config,
 				cli.no_hardware_benchmarks,
+				cli.event_index,
 				|_, _| (),
 			)
 			.map(|NewFullBase { task_manager, .. }| task_manager)?;
@@ -923,6 +945,7 @@ mod tests {
 						config,
 						None,
 						false,
+						false,
 						|block_import: &sc_consensus_babe::BabeBlockImport<Block, _, _>,
 						 babe_link: &sc_consensus_babe::BabeLink<Block>| {
 							setup_handles = Some((block_import.clone(), babe_link.clone()));
@@ -1127,6 +1150,7 @@ mod tests {
 						config,
 						None,
 						false,
+						false,
 						|_, _| (),
 					)?;
 				Ok(sc_service_test::TestNetComponents::new(
@@ -44,6 +44,7 @@ use sc_consensus_grandpa::{
 	FinalityProofProvider, GrandpaJustificationStream, SharedAuthoritySet, SharedVoterState,
 };
 pub use sc_rpc::SubscriptionTaskExecutor;
+use sc_statement_store::StatementNotificationStream;
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
 use sp_application_crypto::RuntimeAppPublic;
@@ -86,6 +87,16 @@ pub struct BeefyDeps<AuthorityId: AuthorityIdBound> {
 	pub subscription_executor: SubscriptionTaskExecutor,
 }
 
+/// Dependencies for the statement store.
+pub struct StatementDeps {
+	/// Shared statement store reference.
+	pub statement_store: Arc<dyn sp_statement_store::StatementStore>,
+	/// Receives notifications about the statements newly accepted by the store.
+	pub statement_notifications: StatementNotificationStream,
+	/// Executor to drive the statement subscriptions.
+	pub subscription_executor: SubscriptionTaskExecutor,
+}
+
 /// Full client dependencies.
 pub struct FullDeps<C, P, SC, B, AuthorityId: AuthorityIdBound> {
 	/// The client instance to use.
@@ -102,12 +113,14 @@ pub struct FullDeps<C, P, SC, B, AuthorityId: AuthorityIdBound> {
 	pub grandpa: GrandpaDeps<B>,
 	/// BEEFY specific dependencies.
 	pub beefy: BeefyDeps<AuthorityId>,
-	/// Shared statement store reference.
-	pub statement_store: Arc<dyn sp_statement_store::StatementStore>,
+	/// Statement store specific dependencies.
+	pub statement: StatementDeps,
 	/// The backend used by the node.
 	pub backend: Arc<B>,
 	/// Mixnet API.
 	pub mixnet_api: Option<sc_mixnet::Api>,
+	/// Whether the events are indexed, to serve `archive_v1_events`.
+	pub event_index: bool,
 }
 
 /// Instantiate all Full RPC extensions.
@@ -120,9 +133,10 @@ pub fn create_full<C, P, SC, B, AuthorityId>(
 		babe,
 		grandpa,
 		beefy,
-		statement_store,
+		statement,
 		backend,
 		mixnet_api,
+		event_index,
 	}: FullDeps<C, P, SC, B, AuthorityId>,
 ) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
 where
@@ -131,6 +145,8 @@ where
 		+ HeaderBackend<Block>
 		+ AuxStore
 		+ HeaderMetadata<Block, Error = BlockChainError>
+		+ sc_client_api::BlockchainEvents<Block>
+		+ sc_client_api::StorageProvider<Block, B>
 		+ Sync
 		+ Send
 		+ 'static,
@@ -139,6 +155,7 @@ where
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BabeApi<Block>,
 	C::Api: BlockBuilder<Block>,
+	C::Api: sp_api::Core<Block> + sp_api::Metadata<Block>,
 	P: TransactionPool + 'static,
 	SC: SelectChain<Block> + 'static,
 	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
@@ -148,14 +165,17 @@ where
 {
 	use mmr_rpc::{Mmr, MmrApiServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
-	use sc_consensus_babe_rpc::{Babe, BabeApiServer};
+	use sc_consensus_babe_rpc::{Babe, BabeApiServer, BabeForkChoice};
 	use sc_consensus_beefy_rpc::{Beefy, BeefyApiServer};
 	use sc_consensus_grandpa_rpc::{Grandpa, GrandpaApiServer};
 	use sc_rpc::{
 		dev::{Dev, DevApiServer},
+		forks::{Forks, ForksApiServer},
 		mixnet::MixnetApiServer,
 		statement::StatementApiServer,
 	};
+	use sc_rpc_graphql::{GraphQl, GraphQlApiServer};
+	use sc_rpc_spec_v2::archive::{ArchiveEvents, ArchiveEventsApiServer};
 	use sc_sync_state_rpc::{SyncState, SyncStateApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 	use substrate_state_trie_migration_rpc::{StateMigration, StateMigrationApiServer};
@@ -185,6 +205,15 @@ where
 		.into_rpc(),
 	)?;
 	io.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+	io.merge(
+		Forks::new(
+			client.clone(),
+			select_chain.clone(),
+			Some(Box::new(BabeForkChoice::new(client.clone()))),
+			subscription_executor.clone(),
+		)
+		.into_rpc(),
+	)?;
 	io.merge(
 		Babe::new(client.clone(), babe_worker_handle.clone(), keystore, select_chain).into_rpc(),
 	)?;
@@ -205,8 +234,16 @@ where
 	)?;
 
 	io.merge(StateMigration::new(client.clone(), backend).into_rpc())?;
-	io.merge(Dev::new(client).into_rpc())?;
-	let statement_store = sc_rpc::statement::StatementStore::new(statement_store).into_rpc();
+	io.merge(Dev::new(client.clone()).into_rpc())?;
+	io.merge(GraphQl::new::<Block, B, C>(client.clone()).into_rpc())?;
+	let StatementDeps { statement_store, statement_notifications, subscription_executor } =
+		statement;
+	let statement_store = sc_rpc::statement::StatementStore::new(
+		statement_store,
+		move || statement_notifications.subscribe(100_000),
+		subscription_executor,
+	)
+	.into_rpc();
 	io.merge(statement_store)?;
 
 	if let Some(mixnet_api) = mixnet_api {
@@ -214,6 +251,10 @@ where
 		io.merge(mixnet)?;
 	}
 
+	if event_index {
+		io.merge(ArchiveEvents::<Block, _>::new(client.clone()).into_rpc())?;
+	}
+
 	io.merge(
 		Beefy::<Block, AuthorityId>::new(
 			beefy.beefy_finality_proof_stream,
@@ -1487,6 +1487,9 @@ impl pallet_revive::Config for Runtime {
 	type NativeToEthRatio = ConstU32<1_000_000>; // 10^(18 - 12) Eth is 10^18, Native is 10^12.
 	type EthGasEncoder = ();
 	type FindAuthor = <Runtime as pallet_authorship::Config>::FindAuthor;
+	type Xcm = ();
+	type NominationPools = NominationPools;
+	type ConvictionVoting = ConvictionVoting;
 }
 
 impl pallet_sudo::Config for Runtime {
@@ -40,6 +40,7 @@ use sp_runtime::{
 	Digest, ExtrinsicInclusionMode, Percent, SaturatedConversion,
 };
 use std::{marker::PhantomData, pin::Pin, sync::Arc, time};
+use tracing::Instrument;
 
 use prometheus_endpoint::Registry as PrometheusRegistry;
 use sc_proposer_metrics::{EndProposingReason, MetricsLink as PrometheusMetrics};
@@ -285,6 +286,11 @@ where
 	) -> Self::Proposal {
 		let (tx, rx) = oneshot::channel();
 		let spawn_handle = self.spawn_handle.clone();
+		let span = tracing::info_span!(
+			"propose",
+			parent_number = %self.parent_number,
+			parent_hash = ?self.parent_hash,
+		);
 
 		spawn_handle.spawn_blocking(
 			"basic-authorship-proposer",
@@ -294,6 +300,7 @@ where
 				let deadline = (self.now)() + max_duration - max_duration / 10;
 				let res = self
 					.propose_with(inherent_data, inherent_digests, deadline, block_size_limit)
+					.instrument(span)
 					.await;
 				if tx.send(res).is_err() {
 					trace!(
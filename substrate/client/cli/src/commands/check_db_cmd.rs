@@ -0,0 +1,123 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::{
+	error,
+	params::{DatabaseParams, PruningParams, SharedParams},
+	CliConfiguration,
+};
+use clap::Parser;
+use sc_client_api::{Backend, UsageProvider};
+use sc_service::chain_ops::{check_db, revert_chain_to};
+use sp_runtime::traits::{Block as BlockT, Saturating};
+use std::sync::Arc;
+
+use super::revert_cmd::AuxRevertHandler;
+
+/// The `check-db` command used to check the integrity of the database.
+///
+/// The canonical chain is walked from the best block down to genesis, checking the headers,
+/// bodies and justifications of the blocks and that the states which should be kept are
+/// complete. Leaves that don't lead to the canonical chain are reported as orphans.
+#[derive(Debug, Parser)]
+pub struct CheckDbCmd {
+	/// Number of best blocks whose whole state is read, on top of the last finalized block.
+	#[arg(long, value_name = "COUNT", default_value_t = 1)]
+	pub state_depth: u64,
+
+	/// Revert the chain to the last block such that neither it nor its ancestors have issues.
+	///
+	/// Finalized blocks are reverted if needed.
+	#[arg(long)]
+	pub repair: bool,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl CheckDbCmd {
+	/// Run the check-db command
+	pub async fn run<B, BA, C>(
+		&self,
+		client: Arc<C>,
+		backend: Arc<BA>,
+		aux_revert: Option<AuxRevertHandler<C, BA, B>>,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		BA: Backend<B>,
+		C: UsageProvider<B>,
+	{
+		let report = check_db(&*backend, self.state_depth)?;
+		for issue in &report.issues {
+			eprintln!("{issue}");
+		}
+		println!(
+			"Checked {} blocks and {} states, found {} issues.",
+			report.checked_blocks,
+			report.walked_states,
+			report.issues.len(),
+		);
+		if report.issues.is_empty() {
+			return Ok(())
+		}
+
+		let Some((number, hash)) = report.last_valid else {
+			return Err("The genesis block is invalid, the chain must be purged".into())
+		};
+		if !self.repair {
+			return Err(format!(
+				"The database has issues, run with `--repair` to revert to block #{number} ({hash})"
+			)
+			.into())
+		}
+
+		let blocks = client.usage_info().chain.best_number.saturating_sub(number);
+		if let Some(aux_revert) = aux_revert {
+			aux_revert(client.clone(), backend.clone(), blocks)?;
+		}
+		revert_chain_to(client, backend, number)?;
+		if report.issues.iter().any(|issue| issue.number().is_none()) {
+			return Err("Orphan leaves are not removed by `--repair`".into())
+		}
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for CheckDbCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -28,7 +28,8 @@ use clap::Parser;
 use regex::Regex;
 use sc_service::{
 	config::{
-		BasePath, IpNetwork, PrometheusConfig, RpcBatchRequestConfig, TransactionPoolOptions,
+		BasePath, IpNetwork, PrometheusConfig, RpcBatchRequestConfig, RpcMethodCosts,
+		TransactionPoolOptions,
 	},
 	ChainSpec, Role,
 };
@@ -322,6 +323,18 @@ impl CliConfiguration for RunCmd {
 		Ok(self.rpc_params.rpc_rate_limit)
 	}
 
+	fn rpc_rate_limit_per_ip(&self) -> Result<Option<NonZeroU32>> {
+		Ok(self.rpc_params.rpc_rate_limit_per_ip)
+	}
+
+	fn rpc_method_costs(&self) -> Result<RpcMethodCosts> {
+		Ok(self.rpc_params.rpc_method_costs())
+	}
+
+	fn rpc_max_subscriptions_per_ip(&self) -> Result<Option<u32>> {
+		Ok(self.rpc_params.rpc_max_subscriptions_per_ip)
+	}
+
 	fn rpc_rate_limit_whitelisted_ips(&self) -> Result<Vec<IpNetwork>> {
 		Ok(self.rpc_params.rpc_rate_limit_whitelisted_ips.clone())
 	}
@@ -29,12 +29,12 @@ use sc_service::{
 	config::{
 		BasePath, Configuration, DatabaseSource, ExecutorConfiguration, IpNetwork, KeystoreConfig,
 		NetworkConfiguration, NodeKeyConfig, OffchainWorkerConfig, PrometheusConfig, PruningMode,
-		Role, RpcBatchRequestConfig, RpcConfiguration, RpcMethods, TelemetryEndpoints,
-		TransactionPoolOptions, WasmExecutionMethod,
+		Role, RpcBatchRequestConfig, RpcConfiguration, RpcMethodCosts, RpcMethods,
+		TelemetryEndpoints, TransactionPoolOptions, WasmExecutionMethod,
 	},
 	BlocksPruning, ChainSpec, TracingReceiver,
 };
-use sc_tracing::logging::LoggerBuilder;
+use sc_tracing::{logging::LoggerBuilder, otlp::OtlpConfig};
 use std::{num::NonZeroU32, path::PathBuf};
 
 /// The maximum number of characters for a node name.
@@ -366,6 +366,21 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(None)
 	}
 
+	/// RPC rate limit shared by all the connections of an ip address.
+	fn rpc_rate_limit_per_ip(&self) -> Result<Option<NonZeroU32>> {
+		Ok(None)
+	}
+
+	/// Cost of the RPC methods counted against the rate limits.
+	fn rpc_method_costs(&self) -> Result<RpcMethodCosts> {
+		Ok(Default::default())
+	}
+
+	/// Maximum number of RPC subscriptions shared by all the connections of an ip address.
+	fn rpc_max_subscriptions_per_ip(&self) -> Result<Option<u32>> {
+		Ok(None)
+	}
+
 	/// RPC rate limit whitelisted ip addresses.
 	fn rpc_rate_limit_whitelisted_ips(&self) -> Result<Vec<IpNetwork>> {
 		Ok(vec![])
@@ -450,6 +465,14 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(self.shared_params().tracing_receiver())
 	}
 
+	/// Get the configuration of the OpenTelemetry span export from the current object (if any)
+	///
+	/// By default this is retrieved from [`SharedParams`] if it is available. Otherwise its
+	/// `None`.
+	fn otlp_config(&self) -> Result<Option<OtlpConfig>> {
+		Ok(self.shared_params().otlp_config())
+	}
+
 	/// Get the node key from the current object
 	///
 	/// By default this is retrieved from `NodeKeyParams` if it is available. Otherwise its
@@ -523,7 +546,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			impl_name: C::impl_name(),
 			impl_version: C::impl_version(),
 			tokio_handle,
-			transaction_pool: self.transaction_pool(is_dev)?,
+			transaction_pool: self.transaction_pool(is_dev)?.resolve_journal_path(&config_dir),
 			network: self.network_config(
 				&chain_spec,
 				is_dev,
@@ -561,8 +584,11 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 				message_buffer_capacity: self.rpc_buffer_capacity_per_connection()?,
 				batch_config: self.rpc_batch_config()?,
 				rate_limit: self.rpc_rate_limit()?,
+				rate_limit_per_ip: self.rpc_rate_limit_per_ip()?,
+				rate_limit_method_costs: self.rpc_method_costs()?,
 				rate_limit_whitelisted_ips: self.rpc_rate_limit_whitelisted_ips()?,
 				rate_limit_trust_proxy_headers: self.rpc_rate_limit_trust_proxy_headers()?,
+				max_subs_per_ip: self.rpc_max_subscriptions_per_ip()?,
 			},
 			prometheus_config: self
 				.prometheus_config(DCV::prometheus_listen_port(), &chain_spec)?,
@@ -649,6 +675,10 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			logger.with_profiling(tracing_receiver, tracing_targets);
 		}
 
+		if let Some(otlp_config) = self.otlp_config()? {
+			logger.with_otlp(otlp_config);
+		}
+
 		if self.disable_log_color()? {
 			logger.with_colors(false);
 		}
@@ -33,7 +33,7 @@ mod transaction_pool_params;
 
 use crate::arg_enums::{CryptoScheme, OutputType};
 use clap::Args;
-use sc_service::config::{IpNetwork, RpcBatchRequestConfig};
+use sc_service::config::{IpNetwork, RpcBatchRequestConfig, RpcMethodCosts};
 use sp_core::crypto::{Ss58AddressFormat, Ss58AddressFormatRegistry};
 use sp_runtime::{
 	generic::BlockId,
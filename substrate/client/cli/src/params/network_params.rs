@@ -24,6 +24,7 @@ use clap::Args;
 use sc_network::{
 	config::{
 		NetworkConfiguration, NodeKeyConfig, NonReservedPeerMode, SetConfig, TransportConfig,
+		WarpSyncCheckpointConfig,
 	},
 	multiaddr::Protocol,
 };
@@ -33,6 +34,10 @@ use sc_service::{
 };
 use std::{borrow::Cow, num::NonZeroUsize, path::PathBuf};
 
+fn parse_hash(s: &str) -> Result<Vec<u8>, String> {
+	array_bytes::hex2bytes(s).map_err(|err| format!("Invalid hash: {err:?}"))
+}
+
 /// Parameters used to create the network configuration.
 #[derive(Debug, Clone, Args)]
 pub struct NetworkParams {
@@ -170,6 +175,25 @@ pub struct NetworkParams {
 	#[arg(long, value_name = "COUNT", default_value_t = 64)]
 	pub max_blocks_per_request: u32,
 
+	/// Start warp sync from the trusted checkpoint stored in the given file.
+	///
+	/// Warp proofs are only downloaded for the authority set changes after the checkpoint.
+	/// Requires `--warp-sync-checkpoint-hash`.
+	#[arg(long, value_name = "PATH", requires = "warp_sync_checkpoint_hash")]
+	pub warp_sync_checkpoint: Option<PathBuf>,
+
+	/// Hash of the warp sync checkpoint file the checkpoint is verified against.
+	///
+	/// The hash is computed over the whole SCALE encoded checkpoint with the hashing of the chain
+	/// headers, so that it commits to the header as well as to the authority set.
+	#[arg(
+		long,
+		value_name = "HASH",
+		value_parser = parse_hash,
+		requires = "warp_sync_checkpoint"
+	)]
+	pub warp_sync_checkpoint_hash: Option<Vec<u8>>,
+
 	/// Network backend used for P2P networking.
 	///
 	/// litep2p network backend is considered experimental and isn't as stable as the libp2p
@@ -245,6 +269,12 @@ impl NetworkParams {
 				is_dev || matches!(chain_type, ChainType::Local | ChainType::Development),
 		};
 
+		let warp_sync_checkpoint = self
+			.warp_sync_checkpoint
+			.clone()
+			.zip(self.warp_sync_checkpoint_hash.clone())
+			.map(|(path, trusted_hash)| WarpSyncCheckpointConfig { path, trusted_hash });
+
 		NetworkConfiguration {
 			boot_nodes,
 			net_config_path,
@@ -271,6 +301,7 @@ impl NetworkParams {
 			max_parallel_downloads: self.max_parallel_downloads,
 			max_blocks_per_request: self.max_blocks_per_request,
 			min_peers_to_start_warp_sync: None,
+			warp_sync_checkpoint,
 			enable_dht_random_walk: !self.reserved_only,
 			allow_non_globals_in_dht,
 			kademlia_disjoint_query_paths: self.kademlia_disjoint_query_paths,
@@ -41,6 +41,10 @@ pub struct PruningParams {
 	///
 	/// - NUMBER: Keep the data of the last NUMBER of finalized blocks.
 	///
+	/// - sparse:NUMBER:INTERVAL: Keep the data of the last NUMBER of finalized blocks and of every
+	///   finalized block whose number is a multiple of INTERVAL. Requires ParityDb, as it relies
+	///   on the reference counting of the database.
+	///
 	/// [default: 256]
 	#[arg(alias = "pruning", long, value_name = "PRUNING_MODE")]
 	pub state_pruning: Option<DatabasePruningMode>,
@@ -74,6 +78,11 @@ impl PruningParams {
 
 	/// Get the block pruning value from the parameters
 	pub fn blocks_pruning(&self) -> error::Result<BlocksPruning> {
+		if let DatabasePruningMode::Sparse { .. } = self.blocks_pruning {
+			return Err(error::Error::Input(
+				"Sparse pruning is only supported by `--state-pruning`".into(),
+			))
+		}
 		Ok(self.blocks_pruning.into())
 	}
 }
@@ -91,6 +100,14 @@ pub enum DatabasePruningMode {
 	ArchiveCanonical,
 	/// Keep the data of the last number of finalized blocks.
 	Custom(u32),
+	/// Keep the data of the last number of finalized blocks and of every finalized block whose
+	/// number is a multiple of the checkpoint interval.
+	Sparse {
+		/// Number of the last finalized blocks to keep.
+		window: u32,
+		/// Number of blocks between two kept checkpoints.
+		checkpoint_interval: u32,
+	},
 }
 
 impl std::str::FromStr for DatabasePruningMode {
@@ -100,6 +117,17 @@ impl std::str::FromStr for DatabasePruningMode {
 		match input {
 			"archive" => Ok(Self::Archive),
 			"archive-canonical" => Ok(Self::ArchiveCanonical),
+			sparse if sparse.starts_with("sparse:") => {
+				let invalid = || "Invalid sparse pruning mode specified".to_string();
+				let (window, checkpoint_interval) =
+					sparse["sparse:".len()..].split_once(':').ok_or_else(invalid)?;
+				let window = window.parse().map_err(|_| invalid())?;
+				let checkpoint_interval = match checkpoint_interval.parse() {
+					Ok(0) | Err(_) => return Err(invalid()),
+					Ok(checkpoint_interval) => checkpoint_interval,
+				};
+				Ok(Self::Sparse { window, checkpoint_interval })
+			},
 			bc => bc
 				.parse()
 				.map_err(|_| "Invalid pruning mode specified".to_string())
@@ -114,6 +142,8 @@ impl Into<PruningMode> for DatabasePruningMode {
 			DatabasePruningMode::Archive => PruningMode::ArchiveAll,
 			DatabasePruningMode::ArchiveCanonical => PruningMode::ArchiveCanonical,
 			DatabasePruningMode::Custom(n) => PruningMode::blocks_pruning(n),
+			DatabasePruningMode::Sparse { window, checkpoint_interval } =>
+				PruningMode::sparse_pruning(window, checkpoint_interval),
 		}
 	}
 }
@@ -124,6 +154,8 @@ impl Into<BlocksPruning> for DatabasePruningMode {
 			DatabasePruningMode::Archive => BlocksPruning::KeepAll,
 			DatabasePruningMode::ArchiveCanonical => BlocksPruning::KeepFinalized,
 			DatabasePruningMode::Custom(n) => BlocksPruning::Some(n),
+			// Rejected by `PruningParams::blocks_pruning`, block bodies have no checkpoints.
+			DatabasePruningMode::Sparse { window, .. } => BlocksPruning::Some(window),
 		}
 	}
 }
@@ -161,5 +193,18 @@ mod tests {
 
 		assert!(matches!(dbg!(pruning.state_pruning), Some(DatabasePruningMode::ArchiveCanonical)));
 		assert!(matches!(pruning.blocks_pruning, DatabasePruningMode::ArchiveCanonical));
+
+		let Cli { pruning } = Cli::parse_from(["", "--state-pruning=sparse:256:14400"]);
+
+		assert!(matches!(
+			pruning.state_pruning,
+			Some(DatabasePruningMode::Sparse { window: 256, checkpoint_interval: 14400 })
+		));
+		assert!(Cli::try_parse_from(["", "--state-pruning=sparse:256:0"]).is_err());
+		assert!(Cli::try_parse_from(["", "--blocks-pruning=sparse:256:14400"])
+			.unwrap()
+			.pruning
+			.blocks_pruning()
+			.is_err());
 	}
 }
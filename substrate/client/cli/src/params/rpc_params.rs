@@ -18,7 +18,7 @@
 
 use crate::{
 	arg_enums::{Cors, RpcMethods},
-	params::{IpNetwork, RpcBatchRequestConfig},
+	params::{IpNetwork, RpcBatchRequestConfig, RpcMethodCosts},
 	RPC_DEFAULT_MAX_CONNECTIONS, RPC_DEFAULT_MAX_REQUEST_SIZE_MB, RPC_DEFAULT_MAX_RESPONSE_SIZE_MB,
 	RPC_DEFAULT_MAX_SUBS_PER_CONN, RPC_DEFAULT_MESSAGE_CAPACITY_PER_CONN,
 };
@@ -38,6 +38,9 @@ const RPC_MAX_BUF_CAP_PER_CONN: &str = "max-buffer-capacity-per-connection";
 const RPC_RATE_LIMIT: &str = "rate-limit";
 const RPC_RATE_LIMIT_TRUST_PROXY_HEADERS: &str = "rate-limit-trust-proxy-headers";
 const RPC_RATE_LIMIT_WHITELISTED_IPS: &str = "rate-limit-whitelisted-ips";
+const RPC_RATE_LIMIT_PER_IP: &str = "rate-limit-per-ip";
+const RPC_METHOD_COST: &str = "method-cost";
+const RPC_MAX_SUBS_PER_IP: &str = "max-subscriptions-per-ip";
 const RPC_RETRY_RANDOM_PORT: &str = "retry-random-port";
 const RPC_METHODS: &str = "methods";
 const RPC_OPTIONAL: &str = "optional";
@@ -81,9 +84,33 @@ pub struct RpcParams {
 	///
 	/// For example `--rpc-rate-limit 10` will maximum allow
 	/// 10 calls per minute per connection.
+	///
+	/// Calls to methods configured with `--rpc-method-cost` count as several calls.
 	#[arg(long)]
 	pub rpc_rate_limit: Option<NonZeroU32>,
 
+	/// RPC rate limiting (calls/minute) shared by all connections of an IP address.
+	///
+	/// This is disabled by default. Unlike `--rpc-rate-limit`, the limit isn't reset when
+	/// reconnecting.
+	///
+	/// Calls to methods configured with `--rpc-method-cost` count as several calls.
+	#[arg(long)]
+	pub rpc_rate_limit_per_ip: Option<NonZeroU32>,
+
+	/// Cost of an RPC method counted against the rate limits, `1` by default.
+	///
+	/// For example `--rpc-method-cost state_getKeysPaged:10` makes a call to
+	/// `state_getKeysPaged` count as 10 calls.
+	#[arg(long, value_name = "METHOD:COST", num_args = 1.., value_parser = parse_method_cost)]
+	pub rpc_method_cost: Vec<(String, NonZeroU32)>,
+
+	/// Set the maximum concurrent subscriptions shared by all connections of an IP address.
+	///
+	/// This is disabled by default.
+	#[arg(long)]
+	pub rpc_max_subscriptions_per_ip: Option<u32>,
+
 	/// Disable RPC rate limiting for certain ip addresses.
 	///
 	/// Each IP address must be in CIDR notation such as `1.2.3.4/24`.
@@ -143,6 +170,12 @@ pub struct RpcParams {
 	///    available For example this may be useful if some platforms doesn't support ipv6
 	///    (optional)
 	///  • rate-limit: The rate limit in calls per minute for each connection (optional)
+	///  • rate-limit-per-ip: The rate limit in calls per minute shared by all connections of an
+	///    IP address (optional)
+	///  • method-cost: The cost of a method counted against the rate limits as
+	///    `<method>:<cost>`, this can be enabled more than once (optional)
+	///  • max-subscriptions-per-ip: The maximum number of subscriptions shared by all
+	///    connections of an IP address (optional)
 	///  • rate-limit-trust-proxy-headers: Trust proxy headers for disable rate limiting (optional)
 	///  • rate-limit-whitelisted-ips: Disable rate limiting for certain ip addresses, this can be
 	/// enabled more than once (optional)  • retry-random-port: If the port is already in use,
@@ -153,7 +186,7 @@ pub struct RpcParams {
 		long,
 		num_args = 1..,
 		verbatim_doc_comment,
-		conflicts_with_all = &["rpc_external", "unsafe_rpc_external", "rpc_port", "rpc_cors", "rpc_rate_limit_trust_proxy_headers", "rpc_rate_limit", "rpc_rate_limit_whitelisted_ips", "rpc_message_buffer_capacity_per_connection", "rpc_disable_batch_requests", "rpc_max_subscriptions_per_connection", "rpc_max_request_size", "rpc_max_response_size"]
+		conflicts_with_all = &["rpc_external", "unsafe_rpc_external", "rpc_port", "rpc_cors", "rpc_rate_limit_trust_proxy_headers", "rpc_rate_limit", "rpc_rate_limit_whitelisted_ips", "rpc_rate_limit_per_ip", "rpc_method_cost", "rpc_max_subscriptions_per_ip", "rpc_message_buffer_capacity_per_connection", "rpc_disable_batch_requests", "rpc_max_subscriptions_per_connection", "rpc_max_request_size", "rpc_max_response_size"]
 	)]
 	pub experimental_rpc_endpoint: Vec<RpcEndpoint>,
 
@@ -213,6 +246,11 @@ impl RpcParams {
 			.into())
 	}
 
+	/// Returns the cost of the RPC methods.
+	pub fn rpc_method_costs(&self) -> RpcMethodCosts {
+		self.rpc_method_cost.iter().cloned().collect()
+	}
+
 	/// Returns the RPC endpoints.
 	pub fn rpc_addr(
 		&self,
@@ -254,8 +292,11 @@ impl RpcParams {
 				listen_addr: SocketAddr::new(std::net::IpAddr::V4(ipv4), port),
 				rpc_methods: self.rpc_methods,
 				rate_limit: self.rpc_rate_limit,
+				rate_limit_per_ip: self.rpc_rate_limit_per_ip,
+				rate_limit_method_costs: self.rpc_method_cost.clone(),
 				rate_limit_trust_proxy_headers: self.rpc_rate_limit_trust_proxy_headers,
 				rate_limit_whitelisted_ips: self.rpc_rate_limit_whitelisted_ips.clone(),
+				max_subscriptions_per_ip: self.rpc_max_subscriptions_per_ip,
 				max_payload_in_mb: self.rpc_max_request_size,
 				max_payload_out_mb: self.rpc_max_response_size,
 				max_subscriptions_per_connection: self.rpc_max_subscriptions_per_connection,
@@ -270,8 +311,11 @@ impl RpcParams {
 				listen_addr: SocketAddr::new(std::net::IpAddr::V6(ipv6), port),
 				rpc_methods: self.rpc_methods,
 				rate_limit: self.rpc_rate_limit,
+				rate_limit_per_ip: self.rpc_rate_limit_per_ip,
+				rate_limit_method_costs: self.rpc_method_cost.clone(),
 				rate_limit_trust_proxy_headers: self.rpc_rate_limit_trust_proxy_headers,
 				rate_limit_whitelisted_ips: self.rpc_rate_limit_whitelisted_ips.clone(),
+				max_subscriptions_per_ip: self.rpc_max_subscriptions_per_ip,
 				max_payload_in_mb: self.rpc_max_request_size,
 				max_payload_out_mb: self.rpc_max_response_size,
 				max_subscriptions_per_connection: self.rpc_max_subscriptions_per_connection,
@@ -345,10 +389,16 @@ pub struct RpcEndpoint {
 	pub max_buffer_capacity_per_connection: u32,
 	/// Rate limit per minute.
 	pub rate_limit: Option<NonZeroU32>,
+	/// Rate limit per minute shared by all the connections of an IP address.
+	pub rate_limit_per_ip: Option<NonZeroU32>,
+	/// Cost of the methods counted against the rate limits.
+	pub rate_limit_method_costs: Vec<(String, NonZeroU32)>,
 	/// Whether to trust proxy headers for rate limiting.
 	pub rate_limit_trust_proxy_headers: bool,
 	/// Whitelisted IPs for rate limiting.
 	pub rate_limit_whitelisted_ips: Vec<IpNetwork>,
+	/// Maximum number of subscriptions shared by all the connections of an IP address.
+	pub max_subscriptions_per_ip: Option<u32>,
 	/// CORS.
 	pub cors: Option<Vec<String>>,
 	/// RPC methods to expose.
@@ -379,6 +429,9 @@ impl std::str::FromStr for RpcEndpoint {
 		let mut rate_limit = None;
 		let mut rate_limit_trust_proxy_headers = None;
 		let mut rate_limit_whitelisted_ips = Vec::new();
+		let mut rate_limit_per_ip = None;
+		let mut rate_limit_method_costs = Vec::new();
+		let mut max_subscriptions_per_ip = None;
 		let mut retry_random_port = None;
 
 		for input in s.split(',') {
@@ -474,6 +527,28 @@ impl std::str::FromStr for RpcEndpoint {
 						.map_err(|_| invalid_value(RPC_RATE_LIMIT_WHITELISTED_IPS, &val))?;
 					rate_limit_whitelisted_ips.push(ip);
 				},
+				RPC_RATE_LIMIT_PER_IP => {
+					if rate_limit_per_ip.is_some() {
+						return Err(only_once_err(RPC_RATE_LIMIT_PER_IP));
+					}
+
+					let val =
+						val.parse().map_err(|_| invalid_value(RPC_RATE_LIMIT_PER_IP, &val))?;
+					rate_limit_per_ip = Some(val);
+				},
+				RPC_METHOD_COST => {
+					let cost =
+						parse_method_cost(val).map_err(|_| invalid_value(RPC_METHOD_COST, &val))?;
+					rate_limit_method_costs.push(cost);
+				},
+				RPC_MAX_SUBS_PER_IP => {
+					if max_subscriptions_per_ip.is_some() {
+						return Err(only_once_err(RPC_MAX_SUBS_PER_IP));
+					}
+
+					let val = val.parse().map_err(|_| invalid_value(RPC_MAX_SUBS_PER_IP, &val))?;
+					max_subscriptions_per_ip = Some(val);
+				},
 				RPC_RETRY_RANDOM_PORT => {
 					if retry_random_port.is_some() {
 						return Err(only_once_err(RPC_RETRY_RANDOM_PORT));
@@ -541,8 +616,11 @@ impl std::str::FromStr for RpcEndpoint {
 				.unwrap_or(RPC_DEFAULT_MAX_SUBS_PER_CONN),
 			rpc_methods: rpc_methods.unwrap_or(RpcMethods::Auto),
 			rate_limit,
+			rate_limit_per_ip,
+			rate_limit_method_costs,
 			rate_limit_trust_proxy_headers: rate_limit_trust_proxy_headers.unwrap_or(false),
 			rate_limit_whitelisted_ips,
+			max_subscriptions_per_ip,
 			is_optional: is_optional.unwrap_or(false),
 			retry_random_port: retry_random_port.unwrap_or(false),
 		})
@@ -561,8 +639,11 @@ impl Into<sc_service::config::RpcEndpoint> for RpcEndpoint {
 			max_subscriptions_per_connection: self.max_subscriptions_per_connection,
 			rpc_methods: self.rpc_methods.into(),
 			rate_limit: self.rate_limit,
+			rate_limit_per_ip: self.rate_limit_per_ip,
+			rate_limit_method_costs: self.rate_limit_method_costs.into_iter().collect(),
 			rate_limit_trust_proxy_headers: self.rate_limit_trust_proxy_headers,
 			rate_limit_whitelisted_ips: self.rate_limit_whitelisted_ips,
+			max_subscriptions_per_ip: self.max_subscriptions_per_ip,
 			cors: self.cors,
 			retry_random_port: self.retry_random_port,
 			is_optional: self.is_optional,
@@ -578,6 +659,13 @@ impl RpcEndpoint {
 	}
 }
 
+fn parse_method_cost(s: &str) -> Result<(String, NonZeroU32), String> {
+	let (method, cost) =
+		s.split_once(':').ok_or_else(|| format!("`{s}`, expects: `method:cost`"))?;
+	let cost = cost.parse().map_err(|_| format!("Illegal `--rpc-method-cost` value: {s}"))?;
+	Ok((method.to_string(), cost))
+}
+
 fn only_once_err(reason: &str) -> String {
 	format!("`{reason}` is only allowed be specified once")
 }
@@ -620,7 +708,8 @@ mod tests {
 			"listen-addr=127.0.0.1:9944,methods=unsafe,cors=*,optional=true,retry-random-port=true,rate-limit=99,\
 			max-batch-request-len=100,rate-limit-trust-proxy-headers=true,max-connections=33,max-request-size=4,\
 			max-response-size=3,max-subscriptions-per-connection=7,max-buffer-capacity-per-connection=8,\
-			rate-limit-whitelisted-ips=192.168.1.0/24,rate-limit-whitelisted-ips=ff01::0/32"
+			rate-limit-whitelisted-ips=192.168.1.0/24,rate-limit-whitelisted-ips=ff01::0/32,\
+			rate-limit-per-ip=1000,method-cost=state_getKeysPaged:10,max-subscriptions-per-ip=64"
 		).unwrap();
 		assert_eq!(endpoint.listen_addr, ([127, 0, 0, 1], 9944).into());
 		assert_eq!(endpoint.rpc_methods, RpcMethods::Unsafe);
@@ -642,6 +731,12 @@ mod tests {
 		assert_eq!(endpoint.max_payload_out_mb, 3);
 		assert_eq!(endpoint.max_subscriptions_per_connection, 7);
 		assert_eq!(endpoint.max_buffer_capacity_per_connection, 8);
+		assert_eq!(endpoint.rate_limit_per_ip, Some(NonZeroU32::new(1000).unwrap()));
+		assert_eq!(
+			endpoint.rate_limit_method_costs,
+			vec![("state_getKeysPaged".to_string(), NonZeroU32::new(10).unwrap())]
+		);
+		assert_eq!(endpoint.max_subscriptions_per_ip, Some(64));
 	}
 
 	#[test]
@@ -671,6 +766,17 @@ mod tests {
 		assert_eq!(addr.is_optional, true);
 	}
 
+	#[test]
+	fn parse_method_cost_works() {
+		assert_eq!(
+			parse_method_cost("state_getKeysPaged:10"),
+			Ok(("state_getKeysPaged".to_string(), NonZeroU32::new(10).unwrap()))
+		);
+		assert!(parse_method_cost("state_getKeysPaged=10").is_err());
+		assert!(parse_method_cost("state_getKeysPaged:0").is_err());
+		assert!(RpcEndpoint::from_str("listen-addr=127.0.0.1:9944,method-cost=foo:0").is_err());
+	}
+
 	#[test]
 	fn parse_rpc_endpoint_batch_options_mutually_exclusive() {
 		assert!(RpcEndpoint::from_str(
@@ -19,6 +19,7 @@
 use crate::arg_enums::TracingReceiver;
 use clap::Args;
 use sc_service::config::BasePath;
+use sc_tracing::otlp::{self, OtlpConfig};
 use std::path::PathBuf;
 
 /// Shared parameters used by all `CoreParams`.
@@ -89,6 +90,44 @@ pub struct SharedParams {
 	/// Receiver to process tracing messages.
 	#[arg(long, value_name = "RECEIVER", value_enum, ignore_case = true, default_value_t = TracingReceiver::Log)]
 	pub tracing_receiver: TracingReceiver,
+
+	/// Export spans to an OpenTelemetry collector.
+	///
+	/// The collector must accept OTLP over HTTP with JSON encoding, e.g.
+	/// `http://localhost:4318/v1/traces`. TLS isn't supported, `https` endpoints are rejected.
+	#[arg(long, value_name = "URL", value_parser = parse_otlp_endpoint)]
+	pub otlp_endpoint: Option<String>,
+
+	/// Targets of the spans exported to the OpenTelemetry collector.
+	///
+	/// Syntax is the same as for `--tracing-targets`. By default, the spans of block import, block
+	/// authorship and sync request handling are exported.
+	#[arg(long, value_name = "TARGETS", requires = "otlp_endpoint")]
+	pub otlp_targets: Option<String>,
+
+	/// Ratio of the traces exported to the OpenTelemetry collector [default: 1.0].
+	#[arg(
+		long,
+		value_name = "RATIO",
+		requires = "otlp_endpoint",
+		value_parser = parse_sampling_ratio
+	)]
+	pub otlp_sampling_ratio: Option<f64>,
+
+	/// Service name reported to the OpenTelemetry collector [default: substrate].
+	#[arg(long, value_name = "NAME", requires = "otlp_endpoint")]
+	pub otlp_service_name: Option<String>,
+}
+
+fn parse_otlp_endpoint(s: &str) -> Result<String, String> {
+	otlp::check_endpoint(s).map(|()| s.into()).map_err(|e| e.to_string())
+}
+
+fn parse_sampling_ratio(s: &str) -> Result<f64, String> {
+	match s.parse::<f64>() {
+		Ok(ratio) if (0.0..=1.0).contains(&ratio) => Ok(ratio),
+		_ => Err(format!("Illegal `--otlp-sampling-ratio` value: {s}, expected 0.0 to 1.0")),
+	}
 }
 
 impl SharedParams {
@@ -145,4 +184,14 @@ impl SharedParams {
 	pub fn tracing_targets(&self) -> Option<String> {
 		self.tracing_targets.clone()
 	}
+
+	/// Configuration of the OpenTelemetry span export, if enabled.
+	pub fn otlp_config(&self) -> Option<OtlpConfig> {
+		self.otlp_endpoint.clone().map(|endpoint| OtlpConfig {
+			endpoint,
+			targets: self.otlp_targets.clone().unwrap_or_else(|| otlp::DEFAULT_TARGETS.into()),
+			sampling_ratio: self.otlp_sampling_ratio.unwrap_or(1.0),
+			service_name: self.otlp_service_name.clone().unwrap_or_else(|| "substrate".into()),
+		})
+	}
 }
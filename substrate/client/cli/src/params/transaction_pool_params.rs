@@ -17,7 +17,8 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use clap::{Args, ValueEnum};
-use sc_transaction_pool::TransactionPoolOptions;
+use sc_transaction_pool::{MempoolJournalOptions, PoolLimit, SenderLimit, TransactionPoolOptions};
+use std::{path::PathBuf, time::Duration};
 
 /// Type of transaction pool to be used
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -56,20 +57,98 @@ pub struct TransactionPoolParams {
 	#[arg(long, value_name = "SECONDS")]
 	pub tx_ban_seconds: Option<u64>,
 
+	/// Maximum number of transactions of a single sender in the transaction pool.
+	///
+	/// Only applies to transactions received from the network or over RPC.
+	#[arg(long, value_name = "COUNT")]
+	pub pool_sender_limit: Option<usize>,
+
+	/// Maximum number of kilobytes of the transactions of a single sender in the pool.
+	///
+	/// Only applies to transactions received from the network or over RPC.
+	#[arg(long, value_name = "KB")]
+	pub pool_sender_kbytes: Option<usize>,
+
+	/// Length in bytes of the encoded nonce at the end of the `provides` tag of a transaction.
+	///
+	/// The rest of the tag identifies the sender for the per-sender limits. Defaults to the
+	/// `u32` nonce used by most FRAME runtimes.
+	#[arg(long, value_name = "BYTES", default_value_t = 4)]
+	pub pool_sender_nonce_bytes: usize,
+
+	/// Minimal priority bump in percent for a transaction to replace a pending transaction with
+	/// the same nonce.
+	///
+	/// By default any transaction with a higher priority replaces the pending one.
+	#[arg(long, value_name = "PERCENT", default_value_t = 0)]
+	pub pool_replacement_bump: u64,
+
 	/// The type of transaction pool to be instantiated.
 	#[arg(long, value_enum, default_value_t = TransactionPoolType::SingleState)]
 	pub pool_type: TransactionPoolType,
+
+	/// Persist the pending transactions of the fork-aware transaction pool across restarts.
+	///
+	/// Transactions are periodically written to a journal file and are revalidated when the node
+	/// starts.
+	#[arg(long)]
+	pub pool_journal: bool,
+
+	/// Path of the transaction pool journal file.
+	///
+	/// A relative path is resolved against the chain's data directory. Defaults to
+	/// `txpool/mempool.journal`.
+	#[arg(long, value_name = "PATH", requires = "pool_journal")]
+	pub pool_journal_path: Option<PathBuf>,
+
+	/// Maximum number of transactions kept in the transaction pool journal.
+	#[arg(long, value_name = "COUNT", default_value_t = 8192, requires = "pool_journal")]
+	pub pool_journal_limit: usize,
+
+	/// Maximum number of kilobytes of the transactions kept in the transaction pool journal.
+	#[arg(long, value_name = "COUNT", default_value_t = 20480, requires = "pool_journal")]
+	pub pool_journal_kbytes: usize,
+
+	/// Maximum age of the journaled transactions restored at startup.
+	#[arg(long, value_name = "SECONDS", default_value_t = 3600, requires = "pool_journal")]
+	pub pool_journal_max_age: u64,
 }
 
 impl TransactionPoolParams {
 	/// Fill the given `PoolConfiguration` by looking at the cli parameters.
 	pub fn transaction_pool(&self, is_dev: bool) -> TransactionPoolOptions {
-		TransactionPoolOptions::new_with_params(
+		let mut options = TransactionPoolOptions::new_with_params(
 			self.pool_limit,
 			self.pool_kbytes * 1024,
 			self.tx_ban_seconds,
 			self.pool_type.into(),
 			is_dev,
 		)
+		.with_replacement_bump(self.pool_replacement_bump);
+
+		if self.pool_sender_limit.is_some() || self.pool_sender_kbytes.is_some() {
+			options = options.with_sender_limit(SenderLimit {
+				limit: PoolLimit {
+					count: self.pool_sender_limit.unwrap_or(usize::MAX),
+					total_bytes: self.pool_sender_kbytes.map_or(usize::MAX, |kb| kb * 1024),
+				},
+				nonce_len: self.pool_sender_nonce_bytes,
+			});
+		}
+
+		if !self.pool_journal {
+			return options;
+		}
+
+		let path = self
+			.pool_journal_path
+			.clone()
+			.unwrap_or_else(|| PathBuf::from("txpool").join("mempool.journal"));
+		options.with_mempool_journal(MempoolJournalOptions {
+			max_count: self.pool_journal_limit,
+			max_bytes: self.pool_journal_kbytes * 1024,
+			max_age: Duration::from_secs(self.pool_journal_max_age),
+			..MempoolJournalOptions::new(path)
+		})
 	}
 }
@@ -281,8 +281,11 @@ mod tests {
 					port: 9944,
 					batch_config: sc_service::config::RpcBatchRequestConfig::Unlimited,
 					rate_limit: None,
+					rate_limit_per_ip: None,
+					rate_limit_method_costs: Default::default(),
 					rate_limit_whitelisted_ips: Default::default(),
 					rate_limit_trust_proxy_headers: Default::default(),
+					max_subs_per_ip: None,
 				},
 				prometheus_config: None,
 				telemetry_endpoints: None,
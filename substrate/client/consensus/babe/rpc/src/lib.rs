@@ -18,7 +18,7 @@
 
 //! RPC api for babe.
 
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, marker::PhantomData, sync::Arc};
 
 use futures::TryFutureExt;
 use jsonrpsee::{
@@ -29,9 +29,14 @@ use jsonrpsee::{
 };
 use serde::{Deserialize, Serialize};
 
-use sc_consensus_babe::{authorship, BabeWorkerHandle};
+use sc_client_api::AuxStore;
+use sc_consensus_babe::{authorship, aux_schema, find_pre_digest, BabeWorkerHandle};
 use sc_consensus_epochs::Epoch as EpochT;
-use sc_rpc_api::{check_if_safe, UnsafeRpcError};
+use sc_rpc_api::{
+	check_if_safe,
+	forks::{ForkChoice, ForkChoiceProvider},
+	UnsafeRpcError,
+};
 use sp_api::ProvideRuntimeApi;
 use sp_application_crypto::AppCrypto;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
@@ -147,6 +152,43 @@ where
 	}
 }
 
+/// Provides the BABE fork-choice data of the blocks to the forks RPC.
+///
+/// The weight of a block is the number of blocks of its chain authored in a primary slot.
+pub struct BabeForkChoice<B, C> {
+	client: Arc<C>,
+	_phantom: PhantomData<B>,
+}
+
+impl<B, C> BabeForkChoice<B, C> {
+	/// Creates a new BABE fork-choice provider.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _phantom: PhantomData }
+	}
+}
+
+impl<B, C> ForkChoiceProvider<B::Hash> for BabeForkChoice<B, C>
+where
+	B: BlockT,
+	C: HeaderBackend<B> + AuxStore + Send + Sync,
+{
+	fn fork_choice(&self, hash: B::Hash) -> Option<ForkChoice> {
+		let weight = aux_schema::load_block_weight(&*self.client, hash).ok().flatten()?;
+		let pre_digest = self
+			.client
+			.header(hash)
+			.ok()
+			.flatten()
+			.and_then(|header| find_pre_digest::<B>(&header).ok());
+
+		Some(ForkChoice {
+			weight: weight.into(),
+			slot: pre_digest.as_ref().map(|pre_digest| *pre_digest.slot()),
+			author: pre_digest.as_ref().map(|pre_digest| pre_digest.authority_index()),
+		})
+	}
+}
+
 /// Holds information about the `slot`'s that can be claimed by a given key.
 #[derive(Clone, Default, Debug, Deserialize, Serialize)]
 pub struct EpochAuthorship {
@@ -21,6 +21,7 @@ use std::{collections::BTreeSet, sync::Arc, time::Duration};
 use sc_network::{NetworkPeers, ReputationChange};
 use sc_network_gossip::{MessageIntent, ValidationResult, Validator, ValidatorContext};
 use sc_network_types::PeerId;
+use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
 use sp_runtime::traits::{Block, Hash, Header, NumberFor};
 
 use codec::{Decode, DecodeAll, Encode};
@@ -45,6 +46,10 @@ const REBROADCAST_AFTER: Duration = Duration::from_secs(60);
 #[cfg(test)]
 const REBROADCAST_AFTER: Duration = Duration::from_secs(5);
 
+/// The maximum number of votes for future rounds handed to the fisherman per gossip filter, and
+/// waiting in the channel to it. Votes past the limit are dropped.
+const MAX_FUTURE_VOTES: usize = 1024;
+
 #[derive(Debug, PartialEq)]
 pub(super) enum Action<H> {
 	// repropagate under given topic, to the given peers, applying cost/benefit to originator.
@@ -133,11 +138,17 @@ struct Filter<B: Block, AuthorityId: AuthorityIdBound> {
 	inner: Option<FilterInner<B, AuthorityId>>,
 	// cache of seen valid justifications in active rounds
 	rounds_with_valid_proofs: BTreeSet<NumberFor<B>>,
+	// round and voter of the votes for future rounds handed to the fisherman
+	future_votes: BTreeSet<(NumberFor<B>, AuthorityId)>,
 }
 
 impl<B: Block, AuthorityId: AuthorityIdBound> Filter<B, AuthorityId> {
 	pub fn new() -> Self {
-		Self { inner: None, rounds_with_valid_proofs: BTreeSet::new() }
+		Self {
+			inner: None,
+			rounds_with_valid_proofs: BTreeSet::new(),
+			future_votes: BTreeSet::new(),
+		}
 	}
 
 	/// Update filter to new `start` and `set_id`.
@@ -149,13 +160,16 @@ impl<B: Block, AuthorityId: AuthorityIdBound> Filter<B, AuthorityId> {
 			Some(f) if f.validator_set.id() == cfg.validator_set.id() => {
 				f.start = cfg.start;
 				f.end = cfg.end;
+				self.future_votes.retain(|(round, _)| *round > cfg.end);
 			},
-			_ =>
+			_ => {
+				self.future_votes.clear();
 				self.inner = Some(FilterInner {
 					start: cfg.start,
 					end: cfg.end,
 					validator_set: cfg.validator_set.clone(),
-				}),
+				});
+			},
 		}
 	}
 
@@ -211,6 +225,18 @@ impl<B: Block, AuthorityId: AuthorityIdBound> Filter<B, AuthorityId> {
 	fn validator_set(&self) -> Option<&ValidatorSet<AuthorityId>> {
 		self.inner.as_ref().map(|f| &f.validator_set)
 	}
+
+	/// Check if the vote of `id` for the future `round` was already handed to the fisherman.
+	fn is_future_vote_known(&self, round: NumberFor<B>, id: &AuthorityId) -> bool {
+		self.future_votes.contains(&(round, id.clone()))
+	}
+
+	/// Note the vote of `id` for the future `round` as handed to the fisherman.
+	///
+	/// Returns `false` if the vote is already known or if too many votes are known already.
+	fn note_future_vote(&mut self, round: NumberFor<B>, id: AuthorityId) -> bool {
+		self.future_votes.len() < MAX_FUTURE_VOTES && self.future_votes.insert((round, id))
+	}
 }
 
 /// BEEFY gossip validator
@@ -231,6 +257,9 @@ where
 	next_rebroadcast: Mutex<Instant>,
 	known_peers: Arc<Mutex<KnownPeers<B>>>,
 	network: Arc<N>,
+	future_votes: TracingUnboundedSender<
+		VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>,
+	>,
 }
 
 impl<B, N, AuthorityId> GossipValidator<B, N, AuthorityId>
@@ -238,15 +267,29 @@ where
 	B: Block,
 	AuthorityId: AuthorityIdBound,
 {
-	pub(crate) fn new(known_peers: Arc<Mutex<KnownPeers<B>>>, network: Arc<N>) -> Self {
-		Self {
+	/// Create a new gossip validator, along with the stream of votes of the current set for
+	/// rounds beyond the gossip filter, which may be future block voting equivocations.
+	pub(crate) fn new(
+		known_peers: Arc<Mutex<KnownPeers<B>>>,
+		network: Arc<N>,
+	) -> (
+		Self,
+		TracingUnboundedReceiver<
+			VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>,
+		>,
+	) {
+		let (future_votes, future_votes_rx) =
+			tracing_unbounded("mpsc_beefy_future_votes", MAX_FUTURE_VOTES);
+		let validator = Self {
 			votes_topic: votes_topic::<B>(),
 			justifs_topic: proofs_topic::<B>(),
 			gossip_filter: RwLock::new(Filter::new()),
 			next_rebroadcast: Mutex::new(Instant::now() + REBROADCAST_AFTER),
 			known_peers,
 			network,
-		}
+			future_votes,
+		};
+		(validator, future_votes_rx)
 	}
 
 	/// Update gossip validator filter.
@@ -288,7 +331,19 @@ where
 
 			match filter.consider_vote(round, set_id) {
 				Consider::RejectPast => return Action::Discard(cost::OUTDATED_MESSAGE),
-				Consider::RejectFuture => return Action::Discard(cost::FUTURE_MESSAGE),
+				Consider::RejectFuture => {
+					// Let the fisherman check the votes of the current set for future blocks.
+					let is_current_voter = |set: &ValidatorSet<AuthorityId>| {
+						set.id() == set_id && set.validators().contains(&vote.id)
+					};
+					if filter.validator_set().is_some_and(is_current_voter) &&
+						!filter.is_future_vote_known(round, &vote.id)
+					{
+						drop(filter);
+						return self.validate_future_vote(vote, sender)
+					}
+					return Action::Discard(cost::FUTURE_MESSAGE)
+				},
 				// When we can't evaluate, it's our fault (e.g. filter not initialized yet), we
 				// discard the vote without punishing or rewarding the sending peer.
 				Consider::CannotEvaluate => return Action::DiscardNoReport,
@@ -317,6 +372,33 @@ where
 		}
 	}
 
+	/// Verify a vote of the current set for a future round and hand it to the fisherman, once per
+	/// voter and round.
+	fn validate_future_vote(
+		&self,
+		vote: VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>,
+		sender: &PeerId,
+	) -> Action<B::Hash> {
+		if !BeefyKeystore::verify(&vote.id, &vote.signature, &vote.commitment.encode()) {
+			debug!(
+				target: LOG_TARGET,
+				"🥩 Bad signature on message: {:?}, from: {:?}", vote, sender
+			);
+			return Action::Discard(cost::BAD_SIGNATURE)
+		}
+
+		let round = vote.commitment.block_number;
+		if !self.gossip_filter.write().note_future_vote(round, vote.id.clone()) {
+			return Action::Discard(cost::FUTURE_MESSAGE)
+		}
+		if self.future_votes.len() >= MAX_FUTURE_VOTES {
+			debug!(target: LOG_TARGET, "🥩 Too many pending future votes, dropping: {:?}", vote);
+		} else {
+			let _ = self.future_votes.unbounded_send(vote);
+		}
+		Action::Discard(cost::FUTURE_MESSAGE)
+	}
+
 	fn validate_finality_proof(
 		&self,
 		proof: BeefyVersionedFinalityProof<B, AuthorityId>,
@@ -669,7 +751,7 @@ pub(crate) mod tests {
 
 		let (network, mut report_stream) = TestNetwork::new();
 
-		let gv = GossipValidator::<Block, _, ecdsa_crypto::AuthorityId>::new(
+		let (gv, mut future_votes) = GossipValidator::<Block, _, ecdsa_crypto::AuthorityId>::new(
 			Arc::new(Mutex::new(KnownPeers::new())),
 			Arc::new(network),
 		);
@@ -724,6 +806,23 @@ pub(crate) mod tests {
 		assert!(matches!(res, ValidationResult::Discard));
 		expected_report.cost_benefit = cost::FUTURE_MESSAGE;
 		assert_eq!(report_stream.try_next().unwrap().unwrap(), expected_report);
+		// the vote is handed to the fisherman
+		assert_eq!(future_votes.try_recv().unwrap(), vote);
+		// but only once
+		let res = gv.validate(&mut context, &sender, &encoded);
+		assert!(matches!(res, ValidationResult::Discard));
+		assert_eq!(report_stream.try_next().unwrap().unwrap(), expected_report);
+		assert!(future_votes.try_recv().is_err());
+		// and only if correctly signed
+		let mut forged_vote = dummy_vote(4);
+		forged_vote.signature = vote.signature.clone();
+		let forged_vote =
+			GossipMessage::<Block, ecdsa_crypto::AuthorityId>::Vote(forged_vote).encode();
+		let res = gv.validate(&mut context, &sender, &forged_vote);
+		assert!(matches!(res, ValidationResult::Discard));
+		expected_report.cost_benefit = cost::BAD_SIGNATURE;
+		assert_eq!(report_stream.try_next().unwrap().unwrap(), expected_report);
+		assert!(future_votes.try_recv().is_err());
 
 		// reject if the round is not live anymore
 		gv.update_filter(GossipFilterCfg { start: 7, end: 10, validator_set: &validator_set });
@@ -734,6 +833,7 @@ pub(crate) mod tests {
 		assert!(matches!(res, ValidationResult::Discard));
 		expected_report.cost_benefit = cost::OUTDATED_MESSAGE;
 		assert_eq!(report_stream.try_next().unwrap().unwrap(), expected_report);
+		assert!(future_votes.try_recv().is_err());
 
 		// now verify proofs validation
 
@@ -792,7 +892,7 @@ pub(crate) mod tests {
 		let keys = vec![Keyring::Alice.public()];
 		let validator_set =
 			ValidatorSet::<ecdsa_crypto::AuthorityId>::new(keys.clone(), 0).unwrap();
-		let gv = GossipValidator::<Block, _, ecdsa_crypto::AuthorityId>::new(
+		let (gv, _) = GossipValidator::<Block, _, ecdsa_crypto::AuthorityId>::new(
 			Arc::new(Mutex::new(KnownPeers::new())),
 			Arc::new(TestNetwork::new().0),
 		);
@@ -885,7 +985,7 @@ pub(crate) mod tests {
 		let keys = vec![Keyring::Alice.public()];
 		let validator_set =
 			ValidatorSet::<ecdsa_crypto::AuthorityId>::new(keys.clone(), 0).unwrap();
-		let gv = GossipValidator::<Block, _, ecdsa_crypto::AuthorityId>::new(
+		let (gv, _) = GossipValidator::<Block, _, ecdsa_crypto::AuthorityId>::new(
 			Arc::new(Mutex::new(KnownPeers::new())),
 			Arc::new(TestNetwork::new().0),
 		);
@@ -45,6 +45,8 @@ pub enum Error {
 	FinalityProofGossipStreamTerminated,
 	#[error("Finality stream terminated")]
 	FinalityStreamTerminated,
+	#[error("Future votes stream terminated")]
+	FutureVotesStreamTerminated,
 	#[error("Votes gossiping stream terminated")]
 	VotesGossipStreamTerminated,
 }
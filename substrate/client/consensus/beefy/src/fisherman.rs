@@ -16,32 +16,54 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{error::Error, keystore::BeefyKeystore, round::Rounds, LOG_TARGET};
-use log::{debug, error, warn};
+use crate::{
+	error::Error, justification::BeefyVersionedFinalityProof, keystore::BeefyKeystore,
+	round::Rounds, LOG_TARGET,
+};
+use log::{debug, error, trace, warn};
+use parking_lot::Mutex;
 use sc_client_api::Backend;
 use sp_api::ProvideRuntimeApi;
 use sp_application_crypto::RuntimeAppPublic;
 use sp_blockchain::HeaderBackend;
 use sp_consensus_beefy::{
-	check_double_voting_proof, AuthorityIdBound, BeefyApi, BeefySignatureHasher, DoubleVotingProof,
-	OpaqueKeyOwnershipProof, ValidatorSetId,
+	check_commitment_signature, check_double_voting_proof, known_payloads,
+	mmr::find_mmr_root_digest, AuthorityIdBound, BeefyApi, BeefySignatureHasher, Commitment,
+	DoubleVotingProof, ForkVotingProof, FutureBlockVotingProof, MmrRootHash,
+	OpaqueKeyOwnershipProof, ValidatorSetId, VersionedFinalityProof, VoteMessage,
 };
 use sp_runtime::{
 	generic::BlockId,
 	traits::{Block, NumberFor},
 };
-use std::{marker::PhantomData, sync::Arc};
+use std::{collections::BTreeSet, iter, marker::PhantomData, sync::Arc};
+
+/// The maximum number of fork and future block votes remembered as reported.
+const MAX_REPORTED_VOTES: usize = 1024;
 
 /// Helper struct containing the key ownership proof for a validator.
 pub struct ProvedValidator {
 	pub key_owner_proof: OpaqueKeyOwnershipProof,
 }
 
+/// An equivocation a commitment may be part of.
+#[derive(Clone, Copy)]
+enum Equivocation<Hash> {
+	/// The commitment is for a block not part of the canonical chain. Contains the hash of the
+	/// canonical block at the same height.
+	Fork(Hash),
+	/// The commitment is for a block beyond the best one.
+	FutureBlock,
+}
+
 /// Helper used to check and report equivocations.
 pub struct Fisherman<B, BE, RuntimeApi, AuthorityId: AuthorityIdBound> {
 	backend: Arc<BE>,
 	runtime: Arc<RuntimeApi>,
 	key_store: Arc<BeefyKeystore<AuthorityId>>,
+	/// The set id, round and voter of the fork and future block votes reported so far, so that
+	/// rebroadcast votes aren't reported again.
+	reported_votes: Mutex<BTreeSet<(ValidatorSetId, NumberFor<B>, AuthorityId)>>,
 
 	_phantom: PhantomData<B>,
 }
@@ -57,7 +79,13 @@ where
 		runtime: Arc<RuntimeApi>,
 		keystore: Arc<BeefyKeystore<AuthorityId>>,
 	) -> Self {
-		Self { backend, runtime, key_store: keystore, _phantom: Default::default() }
+		Self {
+			backend,
+			runtime,
+			key_store: keystore,
+			reported_votes: Default::default(),
+			_phantom: Default::default(),
+		}
 	}
 
 	fn prove_offenders<'a>(
@@ -164,4 +192,239 @@ where
 
 		Ok(())
 	}
+
+	/// Find out whether the `commitment` is for a block not part of the canonical chain or for a
+	/// block beyond the best one.
+	///
+	/// Commitments for blocks that aren't finalized yet by GRANDPA can't be checked against the
+	/// canonical chain, nor can the ones for blocks without an MMR root digest.
+	fn check_commitment(
+		&self,
+		commitment: &Commitment<NumberFor<B>>,
+	) -> Result<Option<Equivocation<B::Hash>>, Error> {
+		let number = commitment.block_number;
+		let blockchain = self.backend.blockchain();
+		let info = blockchain.info();
+		if number > info.best_number {
+			return Ok(Some(Equivocation::FutureBlock));
+		}
+		if number > info.finalized_number {
+			return Ok(None);
+		}
+
+		let canonical_hash = blockchain.expect_block_hash_from_id(&BlockId::Number(number))?;
+		let canonical_header = blockchain.expect_header(canonical_hash)?;
+		let Some(canonical_root) = find_mmr_root_digest::<B>(&canonical_header) else {
+			return Ok(None);
+		};
+		// Same as the runtime: a commitment without MMR root or with a different one is not
+		// canonical.
+		let roots: Vec<_> = commitment
+			.payload
+			.get_all_decoded::<MmrRootHash>(&known_payloads::MMR_ROOT_ID)
+			.collect();
+		if roots.is_empty() || roots.iter().any(|root| *root != Some(canonical_root)) {
+			return Ok(Some(Equivocation::Fork(canonical_hash)));
+		}
+
+		Ok(None)
+	}
+
+	/// Check the `vote` against the canonical chain and report it if it's for a block not part of
+	/// the canonical chain or for a block beyond the best one.
+	pub fn check_vote(
+		&self,
+		vote: VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>,
+	) -> Result<(), Error> {
+		match self.check_commitment(&vote.commitment)? {
+			Some(Equivocation::Fork(canonical_hash)) =>
+				self.report_fork_voting(vote, canonical_hash),
+			Some(Equivocation::FutureBlock) => self.report_future_block_voting(vote),
+			None => Ok(()),
+		}
+	}
+
+	/// Check the finality `proof` against the canonical chain and report each of its votes if
+	/// it's for a block not part of the canonical chain or for a block beyond the best one.
+	///
+	/// The signatures are matched with the validator set active at the proven block, or at the
+	/// best block if the proven block is beyond it.
+	pub fn check_proof(
+		&self,
+		proof: BeefyVersionedFinalityProof<B, AuthorityId>,
+	) -> Result<(), Error> {
+		let VersionedFinalityProof::V1(signed_commitment) = proof;
+		let Some(equivocation) = self.check_commitment(&signed_commitment.commitment)? else {
+			return Ok(());
+		};
+
+		let at = match equivocation {
+			Equivocation::Fork(canonical_hash) => canonical_hash,
+			Equivocation::FutureBlock => self.backend.blockchain().info().best_hash,
+		};
+		let validator_set = match self.runtime.runtime_api().validator_set(at) {
+			Ok(Some(validator_set)) => validator_set,
+			Ok(None) => return Ok(()),
+			Err(e) => return Err(Error::RuntimeApi(e)),
+		};
+		let commitment = signed_commitment.commitment;
+		if validator_set.id() != commitment.validator_set_id {
+			debug!(
+				target: LOG_TARGET,
+				"🥩 Skipping report for proof of #{:?} from unknown set {}",
+				commitment.block_number,
+				commitment.validator_set_id
+			);
+			return Ok(());
+		}
+
+		let signatures = validator_set.validators().iter().zip(signed_commitment.signatures);
+		for (id, signature) in signatures {
+			let Some(signature) = signature else { continue };
+			let vote = VoteMessage { commitment: commitment.clone(), id: id.clone(), signature };
+			match equivocation {
+				Equivocation::Fork(canonical_hash) =>
+					self.report_fork_voting(vote, canonical_hash)?,
+				Equivocation::FutureBlock => self.report_future_block_voting(vote)?,
+			}
+		}
+
+		Ok(())
+	}
+
+	fn vote_key(
+		vote: &VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>,
+	) -> (ValidatorSetId, NumberFor<B>, AuthorityId) {
+		(vote.commitment.validator_set_id, vote.commitment.block_number, vote.id.clone())
+	}
+
+	/// Remember the `vote` as reported, forgetting the oldest reported votes past
+	/// [`MAX_REPORTED_VOTES`].
+	fn note_reported(
+		&self,
+		vote: &VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>,
+	) {
+		let mut reported_votes = self.reported_votes.lock();
+		reported_votes.insert(Self::vote_key(vote));
+		while reported_votes.len() > MAX_REPORTED_VOTES {
+			reported_votes.pop_first();
+		}
+	}
+
+	/// Whether the `vote` should be reported: it's not reported yet, correctly signed and not our
+	/// own.
+	fn should_report(
+		&self,
+		vote: &VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>,
+	) -> bool {
+		if self.reported_votes.lock().contains(&Self::vote_key(vote)) {
+			trace!(target: LOG_TARGET, "🥩 Skipping report for already reported vote {:?}", vote);
+			return false;
+		}
+
+		if !check_commitment_signature::<_, _, BeefySignatureHasher>(
+			&vote.commitment,
+			&vote.id,
+			&vote.signature,
+		) {
+			debug!(target: LOG_TARGET, "🥩 Skipping report for badly signed vote {:?}", vote);
+			return false;
+		}
+
+		if self.key_store.authority_id(std::slice::from_ref(&vote.id)).is_some() {
+			warn!(target: LOG_TARGET, "🥩 Skipping report for own equivocation");
+			return false;
+		}
+
+		true
+	}
+
+	/// Report the `vote` for a block not part of the canonical chain to the BEEFY runtime module.
+	/// The ancestry proof of the canonical block at the same height is generated at the best
+	/// block, which is also where the report is submitted.
+	fn report_fork_voting(
+		&self,
+		vote: VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>,
+		canonical_hash: B::Hash,
+	) -> Result<(), Error> {
+		if !self.should_report(&vote) {
+			return Ok(());
+		}
+
+		let best_block_hash = self.backend.blockchain().info().best_hash;
+		let runtime_api = self.runtime.runtime_api();
+		let ancestry_proof = match runtime_api
+			.generate_ancestry_proof(best_block_hash, vote.commitment.block_number, None)
+			.map_err(Error::RuntimeApi)?
+		{
+			Some(ancestry_proof) => ancestry_proof,
+			None => {
+				debug!(
+					target: LOG_TARGET,
+					"🥩 No ancestry proof for #{:?}. Skipping report for fork voting",
+					vote.commitment.block_number
+				);
+				return Ok(());
+			},
+		};
+		let header = self.backend.blockchain().expect_header(best_block_hash)?;
+
+		let validator_set_id = vote.commitment.validator_set_id;
+		let proof = ForkVotingProof { vote, ancestry_proof, header };
+		// the key ownership proof is generated at the block the offender voted on.
+		let key_owner_proofs = self.prove_offenders(
+			BlockId::Hash(canonical_hash),
+			iter::once(&proof.vote.id),
+			validator_set_id,
+		)?;
+
+		for ProvedValidator { key_owner_proof, .. } in key_owner_proofs {
+			runtime_api
+				.submit_report_fork_voting_unsigned_extrinsic(
+					best_block_hash,
+					proof.clone(),
+					key_owner_proof,
+				)
+				.map_err(Error::RuntimeApi)?;
+		}
+
+		self.note_reported(&proof.vote);
+
+		Ok(())
+	}
+
+	/// Report the `vote` for a block beyond the best one to the BEEFY runtime module.
+	fn report_future_block_voting(
+		&self,
+		vote: VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>,
+	) -> Result<(), Error> {
+		if !self.should_report(&vote) {
+			return Ok(());
+		}
+
+		// the voted block doesn't exist, the offender must be part of the current set.
+		let best_block_hash = self.backend.blockchain().info().best_hash;
+		let validator_set_id = vote.commitment.validator_set_id;
+		let proof = FutureBlockVotingProof { vote };
+		let key_owner_proofs = self.prove_offenders(
+			BlockId::Hash(best_block_hash),
+			iter::once(&proof.vote.id),
+			validator_set_id,
+		)?;
+
+		for ProvedValidator { key_owner_proof, .. } in key_owner_proofs {
+			self.runtime
+				.runtime_api()
+				.submit_report_future_block_voting_unsigned_extrinsic(
+					best_block_hash,
+					proof.clone(),
+					key_owner_proof,
+				)
+				.map_err(Error::RuntimeApi)?;
+		}
+
+		self.note_reported(&proof.vote);
+
+		Ok(())
+	}
 }
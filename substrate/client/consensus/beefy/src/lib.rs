@@ -41,10 +41,12 @@ use sc_network::{NetworkRequest, NotificationService, ProtocolName};
 use sc_network_gossip::{GossipEngine, Network as GossipNetwork, Syncing as GossipSyncing};
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver};
 use sp_api::ProvideRuntimeApi;
+use sp_application_crypto::RuntimeAppPublic;
 use sp_blockchain::{Backend as BlockchainBackend, HeaderBackend};
 use sp_consensus::{Error as ConsensusError, SyncOracle};
 use sp_consensus_beefy::{
-	AuthorityIdBound, BeefyApi, ConsensusLog, PayloadProvider, ValidatorSet, BEEFY_ENGINE_ID,
+	AuthorityIdBound, BeefyApi, ConsensusLog, PayloadProvider, ValidatorSet, VoteMessage,
+	BEEFY_ENGINE_ID,
 };
 use sp_keystore::KeystorePtr;
 use sp_runtime::traits::{Block, Header as HeaderT, NumberFor, Zero};
@@ -241,6 +243,10 @@ pub(crate) struct BeefyComms<B: Block, N, AuthorityId: AuthorityIdBound> {
 	pub gossip_engine: GossipEngine<B>,
 	pub gossip_validator: Arc<GossipValidator<B, N, AuthorityId>>,
 	pub on_demand_justifications: OnDemandJustificationsEngine<B, AuthorityId>,
+	/// Votes of the current set for rounds beyond the gossip filter, checked by the fisherman.
+	pub future_votes: TracingUnboundedReceiver<
+		VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>,
+	>,
 }
 
 /// Helper builder object for building [worker::BeefyWorker].
@@ -566,7 +572,7 @@ pub async fn start_beefy_gadget<B, BE, C, N, P, R, S, AuthorityId>(
 	let known_peers = Arc::new(Mutex::new(KnownPeers::new()));
 	// Default votes filter is to discard everything.
 	// Validator is updated later with correct starting round and set id.
-	let gossip_validator =
+	let (gossip_validator, future_votes) =
 		communication::gossip::GossipValidator::new(known_peers.clone(), network.clone());
 	let gossip_validator = Arc::new(gossip_validator);
 	let gossip_engine = GossipEngine::new(
@@ -586,7 +592,8 @@ pub async fn start_beefy_gadget<B, BE, C, N, P, R, S, AuthorityId>(
 		known_peers,
 		prometheus_registry.clone(),
 	);
-	let mut beefy_comms = BeefyComms { gossip_engine, gossip_validator, on_demand_justifications };
+	let mut beefy_comms =
+		BeefyComms { gossip_engine, gossip_validator, on_demand_justifications, future_votes };
 
 	// We re-create and re-run the worker in this loop in order to quickly reinit and resume after
 	// select recoverable errors.
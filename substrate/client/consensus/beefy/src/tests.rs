@@ -64,9 +64,9 @@ use sp_consensus_beefy::{
 	known_payloads,
 	mmr::{find_mmr_root_digest, MmrRootProvider},
 	test_utils::Keyring as BeefyKeyring,
-	BeefyApi, Commitment, ConsensusLog, DoubleVotingProof, MmrRootHash, OpaqueKeyOwnershipProof,
-	Payload, SignedCommitment, ValidatorSet, ValidatorSetId, VersionedFinalityProof, VoteMessage,
-	BEEFY_ENGINE_ID,
+	BeefyApi, Commitment, ConsensusLog, DoubleVotingProof, ForkVotingProof, FutureBlockVotingProof,
+	MmrRootHash, OpaqueKeyOwnershipProof, Payload, SignedCommitment, ValidatorSet, ValidatorSetId,
+	VersionedFinalityProof, VoteMessage, BEEFY_ENGINE_ID,
 };
 use sp_core::H256;
 use sp_keystore::{testing::MemoryKeystore, Keystore, KeystorePtr};
@@ -74,10 +74,10 @@ use sp_mmr_primitives::{Error as MmrError, MmrApi};
 use sp_runtime::{
 	codec::{Decode, Encode},
 	traits::{Header as HeaderT, NumberFor},
-	BuildStorage, DigestItem, EncodedJustification, Justifications, Storage,
+	BuildStorage, DigestItem, EncodedJustification, Justifications, OpaqueValue, Storage,
 };
 use std::{marker::PhantomData, sync::Arc, task::Poll};
-use substrate_test_runtime_client::{BlockBuilderExt, ClientExt};
+use substrate_test_runtime_client::{runtime::Header, BlockBuilderExt, ClientExt};
 use tokio::time::Duration;
 
 const GENESIS_HASH: H256 = H256::zero();
@@ -85,8 +85,8 @@ pub(crate) fn beefy_gossip_proto_name() -> ProtocolName {
 	gossip_protocol_name(GENESIS_HASH, None)
 }
 
-const GOOD_MMR_ROOT: MmrRootHash = MmrRootHash::repeat_byte(0xbf);
-const BAD_MMR_ROOT: MmrRootHash = MmrRootHash::repeat_byte(0x42);
+pub(crate) const GOOD_MMR_ROOT: MmrRootHash = MmrRootHash::repeat_byte(0xbf);
+pub(crate) const BAD_MMR_ROOT: MmrRootHash = MmrRootHash::repeat_byte(0x42);
 const ALTERNATE_BAD_MMR_ROOT: MmrRootHash = MmrRootHash::repeat_byte(0x13);
 
 type BeefyBlockImport = crate::BeefyBlockImport<
@@ -266,6 +266,10 @@ pub(crate) struct TestApi {
 	pub mmr_root_hash: MmrRootHash,
 	pub reported_equivocations:
 		Option<Arc<Mutex<Vec<DoubleVotingProof<NumberFor<Block>, AuthorityId, Signature>>>>>,
+	pub reported_fork_votings:
+		Option<Arc<Mutex<Vec<ForkVotingProof<Header, AuthorityId, OpaqueValue>>>>>,
+	pub reported_future_block_votings:
+		Option<Arc<Mutex<Vec<FutureBlockVotingProof<NumberFor<Block>, AuthorityId>>>>>,
 }
 
 impl TestApi {
@@ -279,6 +283,8 @@ impl TestApi {
 			validator_set: Some(validator_set.clone()),
 			mmr_root_hash,
 			reported_equivocations: None,
+			reported_fork_votings: None,
+			reported_future_block_votings: None,
 		}
 	}
 
@@ -288,11 +294,15 @@ impl TestApi {
 			validator_set: Some(validator_set.clone()),
 			mmr_root_hash: GOOD_MMR_ROOT,
 			reported_equivocations: None,
+			reported_fork_votings: None,
+			reported_future_block_votings: None,
 		}
 	}
 
 	pub fn allow_equivocations(&mut self) {
 		self.reported_equivocations = Some(Arc::new(Mutex::new(vec![])));
+		self.reported_fork_votings = Some(Arc::new(Mutex::new(vec![])));
+		self.reported_future_block_votings = Some(Arc::new(Mutex::new(vec![])));
 	}
 }
 
@@ -330,10 +340,42 @@ sp_api::mock_impl_runtime_apis! {
 			}
 		}
 
+		fn submit_report_fork_voting_unsigned_extrinsic(
+			proof: ForkVotingProof<Header, AuthorityId, OpaqueValue>,
+			_dummy: OpaqueKeyOwnershipProof,
+		) -> Option<()> {
+			if let Some(votings_buf) = self.inner.reported_fork_votings.as_ref() {
+				votings_buf.lock().push(proof);
+				None
+			} else {
+				panic!("Fork votings not expected, but following proof was reported: {:?}", proof);
+			}
+		}
+
+		fn submit_report_future_block_voting_unsigned_extrinsic(
+			proof: FutureBlockVotingProof<NumberFor<Block>, AuthorityId>,
+			_dummy: OpaqueKeyOwnershipProof,
+		) -> Option<()> {
+			if let Some(votings_buf) = self.inner.reported_future_block_votings.as_ref() {
+				votings_buf.lock().push(proof);
+				None
+			} else {
+				panic!(
+					"Future block votings not expected, but following proof was reported: {:?}",
+					proof
+				);
+			}
+		}
+
 		fn generate_key_ownership_proof(
 			_dummy1: ValidatorSetId,
 			_dummy2: AuthorityId,
 		) -> Option<OpaqueKeyOwnershipProof> { Some(OpaqueKeyOwnershipProof::new(vec![])) }
+
+		fn generate_ancestry_proof(
+			_dummy1: NumberFor<Block>,
+			_dummy2: Option<NumberFor<Block>>,
+		) -> Option<OpaqueValue> { Some(OpaqueValue::new(vec![])) }
 	}
 
 	impl MmrApi<Block, MmrRootHash, NumberFor<Block>> for RuntimeApi {
@@ -343,7 +385,7 @@ sp_api::mock_impl_runtime_apis! {
 	}
 }
 
-fn add_mmr_digest(builder: &mut impl BlockBuilderExt, mmr_hash: MmrRootHash) {
+pub(crate) fn add_mmr_digest(builder: &mut impl BlockBuilderExt, mmr_hash: MmrRootHash) {
 	builder
 		.push_deposit_log_digest_item(DigestItem::Consensus(
 			BEEFY_ENGINE_ID,
@@ -1495,7 +1537,7 @@ async fn gossipped_finality_proofs() {
 	let charlie = &mut net.peers[2];
 	let known_peers = Arc::new(Mutex::new(KnownPeers::<Block>::new()));
 	// Charlie will run just the gossip engine and not the full voter.
-	let gossip_validator = GossipValidator::new(known_peers, Arc::new(TestNetwork::new().0));
+	let (gossip_validator, _) = GossipValidator::new(known_peers, Arc::new(TestNetwork::new().0));
 	let charlie_gossip_validator = Arc::new(gossip_validator);
 	charlie_gossip_validator.update_filter(GossipFilterCfg::<Block, ecdsa_crypto::AuthorityId> {
 		start: 1,
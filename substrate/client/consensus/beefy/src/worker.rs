@@ -522,6 +522,7 @@ where
 	where
 		<AuthorityId as RuntimeAppPublic>::Signature: Encode + Decode,
 	{
+		self.check_vote(vote.clone());
 		let block_num = vote.commitment.block_number;
 		match self.voting_oracle().triage_round(block_num)? {
 			RoundAction::Process =>
@@ -548,6 +549,7 @@ where
 		&mut self,
 		justification: BeefyVersionedFinalityProof<B, AuthorityId>,
 	) -> Result<(), Error> {
+		self.check_proof(justification.clone());
 		let signed_commitment = match justification {
 			VersionedFinalityProof::V1(ref sc) => sc,
 		};
@@ -958,6 +960,14 @@ where
 						break Error::VotesGossipStreamTerminated;
 					}
 				},
+				// Votes for future blocks are only checked for equivocations.
+				vote = self.comms.future_votes.next() => {
+					if let Some(vote) = vote {
+						self.check_vote(vote);
+					} else {
+						break Error::FutureVotesStreamTerminated;
+					}
+				},
 			}
 
 			// Act on changed 'state'.
@@ -980,6 +990,35 @@ where
 		let rounds = self.persisted_state.voting_oracle.active_rounds()?;
 		self.fisherman.report_double_voting(proof, rounds)
 	}
+
+	/// Check the given vote for fork or future block voting and report it to the BEEFY runtime
+	/// module.
+	///
+	/// Skipped during major sync, when the local chain can't be trusted to be the canonical one.
+	fn check_vote(
+		&self,
+		vote: VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>,
+	) {
+		if self.sync.is_major_syncing() {
+			return;
+		}
+		if let Err(err) = self.fisherman.check_vote(vote) {
+			debug!(target: LOG_TARGET, "🥩 Failed to check vote: {}", err);
+		}
+	}
+
+	/// Check the given finality proof for fork or future block voting and report its signers to
+	/// the BEEFY runtime module.
+	///
+	/// Skipped during major sync, when the local chain can't be trusted to be the canonical one.
+	fn check_proof(&self, proof: BeefyVersionedFinalityProof<B, AuthorityId>) {
+		if self.sync.is_major_syncing() {
+			return;
+		}
+		if let Err(err) = self.fisherman.check_proof(proof) {
+			debug!(target: LOG_TARGET, "🥩 Failed to check finality proof: {}", err);
+		}
+	}
 }
 
 /// Calculate next block number to vote on.
@@ -1028,8 +1067,8 @@ pub(crate) mod tests {
 			request_response::outgoing_requests_engine::OnDemandJustificationsEngine,
 		},
 		tests::{
-			create_beefy_keystore, get_beefy_streams, make_beefy_ids, BeefyPeer, BeefyTestNet,
-			TestApi,
+			add_mmr_digest, create_beefy_keystore, get_beefy_streams, make_beefy_ids, BeefyPeer,
+			BeefyTestNet, TestApi, BAD_MMR_ROOT, GOOD_MMR_ROOT,
 		},
 		BeefyRPCLinks, KnownPeers,
 	};
@@ -1040,11 +1079,12 @@ pub(crate) mod tests {
 	use sc_network_sync::SyncingService;
 	use sc_network_test::TestNetFactory;
 	use sp_blockchain::Backend as BlockchainBackendT;
+	use sp_consensus::BlockOrigin;
 	use sp_consensus_beefy::{
 		ecdsa_crypto, known_payloads,
 		known_payloads::MMR_ROOT_ID,
 		mmr::MmrRootProvider,
-		test_utils::{generate_double_voting_proof, Keyring},
+		test_utils::{generate_double_voting_proof, signed_vote, Keyring},
 		ConsensusLog, Payload, SignedCommitment,
 	};
 	use sp_runtime::traits::{Header as HeaderT, One};
@@ -1111,7 +1151,7 @@ pub(crate) mod tests {
 			.take_notification_service(&crate::tests::beefy_gossip_proto_name())
 			.unwrap();
 		let known_peers = Arc::new(Mutex::new(KnownPeers::new()));
-		let gossip_validator =
+		let (gossip_validator, future_votes) =
 			GossipValidator::new(known_peers.clone(), Arc::new(TestNetwork::new().0));
 		let gossip_validator = Arc::new(gossip_validator);
 		let gossip_engine = GossipEngine::new(
@@ -1145,7 +1185,8 @@ pub(crate) mod tests {
 		)
 		.unwrap();
 		let payload_provider = MmrRootProvider::new(api.clone());
-		let comms = BeefyComms { gossip_engine, gossip_validator, on_demand_justifications };
+		let comms =
+			BeefyComms { gossip_engine, gossip_validator, on_demand_justifications, future_votes };
 		let key_store: Arc<BeefyKeystore<ecdsa_crypto::AuthorityId>> =
 			Arc::new(Some(keystore).into());
 		BeefyWorker {
@@ -1632,4 +1673,104 @@ pub(crate) mod tests {
 		// verify nothing reported to runtime
 		assert!(api_alice.reported_equivocations.as_ref().unwrap().lock().is_empty());
 	}
+
+	#[tokio::test]
+	async fn should_report_fork_and_future_block_voting() {
+		let set_id = 1;
+		let keys = [Keyring::Alice, Keyring::Bob];
+		let validator_set = ValidatorSet::new(make_beefy_ids(&keys), set_id).unwrap();
+		// Alice is the fisherman, equivocations are allowed/expected
+		let mut api_alice = TestApi::with_validator_set(&validator_set);
+		api_alice.allow_equivocations();
+		let api_alice = Arc::new(api_alice);
+
+		let mut net = BeefyTestNet::new(1);
+		let mut worker = create_beefy_worker(net.peer(0), &keys[0], 1, validator_set.clone());
+		worker.runtime = api_alice.clone();
+		worker.fisherman = Arc::new(Fisherman::new(
+			worker.backend.clone(),
+			worker.runtime.clone(),
+			worker.key_store.clone(),
+		));
+
+		// let there be a finalized block with num = 2 and a good MMR root digest:
+		let hashes = net.peer(0).generate_blocks(1, BlockOrigin::File, |mut builder| {
+			add_mmr_digest(&mut builder, GOOD_MMR_ROOT);
+			builder.build().unwrap().block
+		});
+		worker.backend.finalize_block(hashes[0], None).unwrap();
+		let best_header = worker.backend.blockchain().expect_header(hashes[0]).unwrap();
+
+		let good_payload = Payload::from_single_entry(MMR_ROOT_ID, GOOD_MMR_ROOT.encode());
+		let bad_payload = Payload::from_single_entry(MMR_ROOT_ID, BAD_MMR_ROOT.encode());
+		let nothing_reported = || {
+			api_alice.reported_fork_votings.as_ref().unwrap().lock().is_empty() &&
+				api_alice.reported_future_block_votings.as_ref().unwrap().lock().is_empty()
+		};
+
+		// votes on the canonical chain are not reported
+		let vote = signed_vote(2, good_payload.clone(), set_id, &Keyring::Bob);
+		assert_eq!(worker.fisherman.check_vote(vote), Ok(()));
+		assert!(nothing_reported());
+
+		// neither are votes for blocks without MMR root digest
+		let vote = signed_vote(1, bad_payload.clone(), set_id, &Keyring::Bob);
+		assert_eq!(worker.fisherman.check_vote(vote), Ok(()));
+		assert!(nothing_reported());
+
+		// Bob votes on a fork, expect Alice to report it
+		let fork_vote = signed_vote(2, bad_payload.clone(), set_id, &Keyring::Bob);
+		assert_eq!(worker.fisherman.check_vote(fork_vote.clone()), Ok(()));
+		{
+			let reported = api_alice.reported_fork_votings.as_ref().unwrap().lock();
+			assert_eq!(reported.len(), 1);
+			assert_eq!(reported[0].vote, fork_vote);
+			assert_eq!(reported[0].header, best_header);
+		}
+		api_alice.reported_fork_votings.as_ref().unwrap().lock().clear();
+
+		// Bob votes on a future block, expect Alice to report it
+		let future_vote = signed_vote(3, good_payload.clone(), set_id, &Keyring::Bob);
+		assert_eq!(worker.fisherman.check_vote(future_vote.clone()), Ok(()));
+		{
+			let reported = api_alice.reported_future_block_votings.as_ref().unwrap().lock();
+			assert_eq!(reported.len(), 1);
+			assert_eq!(reported[0].vote, future_vote);
+		}
+		api_alice.reported_future_block_votings.as_ref().unwrap().lock().clear();
+
+		// rebroadcast votes are not reported again, nor are the ones of a finality proof
+		assert_eq!(worker.fisherman.check_vote(fork_vote.clone()), Ok(()));
+		assert_eq!(worker.fisherman.check_vote(future_vote.clone()), Ok(()));
+		let proof = VersionedFinalityProof::V1(SignedCommitment {
+			commitment: fork_vote.commitment.clone(),
+			signatures: vec![None, Some(fork_vote.signature.clone())],
+		});
+		assert_eq!(worker.fisherman.check_proof(proof), Ok(()));
+		assert!(nothing_reported());
+
+		// badly signed votes are simply ignored
+		let mut bad_vote = fork_vote.clone();
+		bad_vote.id = Keyring::Charlie.public();
+		assert_eq!(worker.fisherman.check_vote(bad_vote), Ok(()));
+		assert!(nothing_reported());
+
+		// votes done by 'self' are simply ignored (not reported)
+		let self_vote = signed_vote(2, bad_payload.clone(), set_id, &Keyring::Alice);
+		assert_eq!(worker.fisherman.check_vote(self_vote), Ok(()));
+		assert!(nothing_reported());
+
+		// a finality proof for a future block gets Bob reported
+		let future_vote = signed_vote(4, good_payload, set_id, &Keyring::Bob);
+		let proof = VersionedFinalityProof::V1(SignedCommitment {
+			commitment: future_vote.commitment.clone(),
+			signatures: vec![None, Some(future_vote.signature.clone())],
+		});
+		assert_eq!(worker.fisherman.check_proof(proof), Ok(()));
+		{
+			let reported = api_alice.reported_future_block_votings.as_ref().unwrap().lock();
+			assert_eq!(reported.len(), 1);
+			assert_eq!(reported[0].vote, future_vote);
+		}
+	}
 }
@@ -32,6 +32,7 @@ use std::{
 	fmt,
 	time::{Duration, Instant},
 };
+use tracing::Instrument;
 
 use sp_consensus::{error::Error as ConsensusError, BlockOrigin};
 use sp_runtime::{
@@ -357,7 +358,8 @@ pub(crate) async fn verify_single_block_metered<B: BlockT, V: Verifier<B>>(
 		import_block.state_action = StateAction::ExecuteIfPossible;
 	}
 
-	let import_block = verifier.verify(import_block).await.map_err(|msg| {
+	let verify_span = tracing::info_span!("verify_block", %number, ?hash);
+	let import_block = verifier.verify(import_block).instrument(verify_span).await.map_err(|msg| {
 		if let Some(ref peer) = peer {
 			trace!(
 				target: LOG_TARGET,
@@ -402,7 +404,8 @@ pub(crate) async fn import_single_block_metered<Block: BlockT>(
 	let number = *import_block.header.number();
 	let parent_hash = *import_block.header.parent_hash();
 
-	let imported = import_handle.import_block(import_block).await;
+	let import_span = tracing::info_span!("import_block", %number, ?hash);
+	let imported = import_handle.import_block(import_block).instrument(import_span).await;
 	if let Some(metrics) = metrics {
 		metrics.report_verification_and_import(started.elapsed() + verification_time);
 	}
@@ -20,6 +20,7 @@
 //! that expect this inherent.
 
 use crate::Error;
+use parking_lot::Mutex;
 use sc_client_api::{AuxStore, UsageProvider};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
@@ -31,7 +32,7 @@ use sp_consensus_babe::BabeApi;
 use sp_consensus_slots::{Slot, SlotDuration};
 use sp_inherents::{InherentData, InherentDataProvider, InherentIdentifier};
 use sp_runtime::traits::{Block as BlockT, Zero};
-use sp_timestamp::{InherentType, INHERENT_IDENTIFIER};
+use sp_timestamp::{InherentType, Timestamp, INHERENT_IDENTIFIER};
 use std::{
 	sync::{atomic, Arc},
 	time::SystemTime,
@@ -159,3 +160,74 @@ impl InherentDataProvider for SlotTimestampProvider {
 		None
 	}
 }
+
+/// Timestamp source for runtimes without slot based consensus, which can be moved forward or set
+/// for the next block using the `engine_increaseTime` and `engine_setNextBlockTimestamp` RPCs.
+///
+/// The same instance should be passed to [`ManualSealParams`](crate::ManualSealParams) and used
+/// by `create_inherent_data_providers` through [`TimeTravel::inherent_data_provider`].
+#[derive(Clone, Default)]
+pub struct TimeTravel {
+	state: Arc<Mutex<TimeTravelState>>,
+}
+
+/// State of a [`TimeTravel`], restored when reverting to a snapshot.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct TimeTravelState {
+	// milliseconds added to the system time
+	offset: i64,
+	// exact timestamp of the next block, if set
+	next: Option<u64>,
+}
+
+impl TimeTravel {
+	/// Create a new time travel timestamp source, following the system time.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Set the timestamp of the next block. The blocks after it are timestamped relative to it.
+	///
+	/// Fails if `timestamp` doesn't fit in an `i64`.
+	pub fn set_next_block_timestamp(&self, timestamp: u64) -> Result<(), Error> {
+		let millis = i64::try_from(timestamp)
+			.map_err(|_| Error::StringError(format!("Timestamp {timestamp} is too large")))?;
+		let mut state = self.state.lock();
+		state.offset = millis - Timestamp::current().as_millis() as i64;
+		state.next = Some(timestamp);
+		Ok(())
+	}
+
+	/// Move the timestamp of the next blocks forward by `duration` milliseconds.
+	///
+	/// Returns the total offset from the system time, in milliseconds.
+	pub fn increase_time(&self, duration: u64) -> i64 {
+		let mut state = self.state.lock();
+		state.offset = state.offset.saturating_add(i64::try_from(duration).unwrap_or(i64::MAX));
+		state.next = state.next.map(|next| next.saturating_add(duration));
+		state.offset
+	}
+
+	/// Get the timestamp of the next block, consuming the one set by
+	/// [`TimeTravel::set_next_block_timestamp`].
+	pub fn next_timestamp(&self) -> Timestamp {
+		let mut state = self.state.lock();
+		let timestamp = state.next.take().unwrap_or_else(|| {
+			(Timestamp::current().as_millis() as i64).saturating_add(state.offset).max(0) as u64
+		});
+		Timestamp::new(timestamp)
+	}
+
+	/// Create the timestamp inherent data provider for the next block.
+	pub fn inherent_data_provider(&self) -> sp_timestamp::InherentDataProvider {
+		sp_timestamp::InherentDataProvider::new(self.next_timestamp())
+	}
+
+	pub(crate) fn state(&self) -> TimeTravelState {
+		*self.state.lock()
+	}
+
+	pub(crate) fn restore(&self, state: TimeTravelState) {
+		*self.state.lock() = state;
+	}
+}
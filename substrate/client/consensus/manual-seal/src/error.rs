@@ -35,6 +35,8 @@ mod codes {
 	pub const CONSENSUS_ERROR: i32 = 14_000;
 	pub const INHERENTS_ERROR: i32 = 15_000;
 	pub const BLOCKCHAIN_ERROR: i32 = 16_000;
+	pub const DISABLED: i32 = 17_000;
+	pub const NOT_IMPERSONATED: i32 = 18_000;
 	pub const UNKNOWN_ERROR: i32 = 20_000;
 }
 
@@ -61,6 +63,12 @@ pub enum Error {
 	/// Supplied parent_hash doesn't exist in chain
 	#[error("Supplied parent_hash: {0} doesn't exist in chain")]
 	BlockNotFound(String),
+	/// The node doesn't provide what the command needs
+	#[error("{0} is not enabled on this node")]
+	Disabled(&'static str),
+	/// Supplied account isn't impersonated
+	#[error("Account {0} is not impersonated")]
+	NotImpersonated(String),
 	/// Some string error
 	#[error("{0}")]
 	StringError(String),
@@ -97,6 +105,8 @@ impl Error {
 			ConsensusError(_) => codes::CONSENSUS_ERROR,
 			InherentError(_) => codes::INHERENTS_ERROR,
 			BlockchainError(_) => codes::BLOCKCHAIN_ERROR,
+			Disabled(_) => codes::DISABLED,
+			NotImpersonated(_) => codes::NOT_IMPERSONATED,
 			SendError(_) | Canceled(_) => codes::SERVER_SHUTTING_DOWN,
 			_ => codes::UNKNOWN_ERROR,
 		}
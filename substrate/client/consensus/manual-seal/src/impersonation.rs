@@ -0,0 +1,34 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Extrinsics on behalf of impersonated accounts.
+
+use crate::Error;
+use sp_runtime::traits::Block as BlockT;
+
+/// Something that can create extrinsics dispatching a call on behalf of an account, without
+/// having its key. Manual seal uses this trait object for the impersonation RPCs.
+///
+/// This is runtime specific, e.g. a runtime with `pallet-sudo` can wrap the call into a
+/// `Sudo::sudo_as` extrinsic signed with the development sudo key.
+pub trait ImpersonationProvider<B: BlockT>: Send + Sync {
+	/// Create an extrinsic dispatching the SCALE-encoded `call` with the signed origin of `who`,
+	/// a SCALE-encoded account id. The extrinsic is submitted at the block `at`.
+	fn create_extrinsic(&self, at: B::Hash, who: &[u8], call: &[u8])
+		-> Result<B::Extrinsic, Error>;
+}
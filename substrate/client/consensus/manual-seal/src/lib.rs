@@ -34,25 +34,33 @@ use sp_blockchain::HeaderBackend;
 use sp_consensus::{Environment, Proposer, SelectChain};
 use sp_core::traits::SpawnNamed;
 use sp_inherents::CreateInherentDataProviders;
-use sp_runtime::{traits::Block as BlockT, ConsensusEngineId};
-use std::{marker::PhantomData, sync::Arc, time::Duration};
+use sp_runtime::{
+	traits::{Block as BlockT, Hash as HashT, HashingFor},
+	ConsensusEngineId,
+};
+use std::{collections::HashSet, marker::PhantomData, sync::Arc, time::Duration};
 
 mod error;
 mod finalize_block;
+mod impersonation;
 mod seal_block;
+mod snapshot;
 
 pub mod consensus;
 pub mod rpc;
 
 pub use self::{
-	consensus::ConsensusDataProvider,
+	consensus::{timestamp::TimeTravel, ConsensusDataProvider},
 	error::Error,
 	finalize_block::{finalize_block, FinalizeBlockParams},
+	impersonation::ImpersonationProvider,
 	rpc::{CreatedBlock, EngineCommand},
 	seal_block::{seal_block, SealBlockParams, MAX_PROPOSAL_DURATION},
 };
-use sc_transaction_pool_api::TransactionPool;
+use sc_transaction_pool_api::{MaintainedTransactionPool, TransactionPool, TransactionSource};
+use snapshot::Snapshots;
 use sp_api::ProvideRuntimeApi;
+use sp_core::hexdisplay::HexDisplay;
 
 const LOG_TARGET: &str = "manual-seal";
 
@@ -87,10 +95,13 @@ where
 }
 
 /// Params required to start the manual sealing authorship task.
-pub struct ManualSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, TP, SC, CS, CIDP, P> {
+pub struct ManualSealParams<B: BlockT, BI, CB, E, C: ProvideRuntimeApi<B>, TP, SC, CS, CIDP, P> {
 	/// Block import instance.
 	pub block_import: BI,
 
+	/// Backend instance, enables reverting to snapshots if set.
+	pub backend: Option<Arc<CB>>,
+
 	/// The environment we are producing blocks for.
 	pub env: E,
 
@@ -112,6 +123,12 @@ pub struct ManualSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, TP, SC, C
 
 	/// Something that can create the inherent data providers.
 	pub create_inherent_data_providers: CIDP,
+
+	/// Timestamp source used by `create_inherent_data_providers`, enables time travel if set.
+	pub time_travel: Option<TimeTravel>,
+
+	/// Something that can create extrinsics on behalf of accounts, enables impersonation if set.
+	pub impersonation_provider: Option<Box<dyn ImpersonationProvider<B>>>,
 }
 
 /// Params required to start the instant sealing authorship task.
@@ -154,6 +171,7 @@ pub struct DelayedFinalizeParams<C, S> {
 pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 	ManualSealParams {
 		mut block_import,
+		backend,
 		mut env,
 		client,
 		pool,
@@ -161,7 +179,9 @@ pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 		select_chain,
 		consensus_data_provider,
 		create_inherent_data_providers,
-	}: ManualSealParams<B, BI, E, C, TP, SC, CS, CIDP, P>,
+		time_travel,
+		impersonation_provider,
+	}: ManualSealParams<B, BI, CB, E, C, TP, SC, CS, CIDP, P>,
 ) where
 	B: BlockT + 'static,
 	BI: BlockImport<B, Error = sp_consensus::Error> + Send + Sync + 'static,
@@ -171,10 +191,13 @@ pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 	E::Proposer: Proposer<B, Proof = P>,
 	CS: Stream<Item = EngineCommand<<B as BlockT>::Hash>> + Unpin + 'static,
 	SC: SelectChain<B> + 'static,
-	TP: TransactionPool<Block = B>,
+	TP: MaintainedTransactionPool<Block = B>,
 	CIDP: CreateInherentDataProviders<B, ()>,
 	P: codec::Encode + Send + Sync + 'static,
 {
+	let mut snapshots = Snapshots::<B>::new();
+	let mut impersonated = HashSet::new();
+
 	while let Some(command) = commands_stream.next().await {
 		match command {
 			EngineCommand::SealNewBlock { create_empty, finalize, parent_hash, sender } => {
@@ -204,10 +227,95 @@ pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 				})
 				.await
 			},
+			EngineCommand::SetNextBlockTimestamp { timestamp, mut sender } => {
+				let result = time_travel
+					.as_ref()
+					.ok_or(Error::Disabled("Time travel"))
+					.and_then(|time_travel| time_travel.set_next_block_timestamp(timestamp));
+				rpc::send_result(&mut sender, result)
+			},
+			EngineCommand::IncreaseTime { duration, mut sender } => {
+				let result = time_travel
+					.as_ref()
+					.map(|time_travel| time_travel.increase_time(duration))
+					.ok_or(Error::Disabled("Time travel"));
+				rpc::send_result(&mut sender, result)
+			},
+			EngineCommand::Snapshot { mut sender } => {
+				let result = backend
+					.as_ref()
+					.map(|_| snapshots.take(&*client, time_travel.as_ref()))
+					.ok_or(Error::Disabled("Snapshots"));
+				rpc::send_result(&mut sender, result)
+			},
+			EngineCommand::Revert { id, mut sender } => {
+				let result = match backend.as_ref() {
+					Some(backend) =>
+						snapshots
+							.revert(id, &*client, &**backend, &*pool, time_travel.as_ref())
+							.await,
+					None => Err(Error::Disabled("Snapshots")),
+				};
+				rpc::send_result(&mut sender, result)
+			},
+			EngineCommand::Impersonate { who, enable, mut sender } => {
+				let result = match impersonation_provider {
+					Some(_) if enable => {
+						impersonated.insert(who);
+						Ok(())
+					},
+					Some(_) => {
+						impersonated.remove(&who);
+						Ok(())
+					},
+					None => Err(Error::Disabled("Impersonation")),
+				};
+				rpc::send_result(&mut sender, result)
+			},
+			EngineCommand::SubmitImpersonated { who, call, mut sender } => {
+				let result = submit_impersonated(
+					impersonation_provider.as_deref(),
+					&impersonated,
+					&*client,
+					&*pool,
+					who,
+					call,
+				)
+				.await;
+				rpc::send_result(&mut sender, result)
+			},
 		}
 	}
 }
 
+/// Submit `call` on behalf of the impersonated account `who` to the transaction pool.
+async fn submit_impersonated<B, C, TP>(
+	impersonation_provider: Option<&dyn ImpersonationProvider<B>>,
+	impersonated: &HashSet<Vec<u8>>,
+	client: &C,
+	pool: &TP,
+	who: Vec<u8>,
+	call: Vec<u8>,
+) -> Result<<B as BlockT>::Hash, Error>
+where
+	B: BlockT,
+	C: HeaderBackend<B>,
+	TP: TransactionPool<Block = B>,
+{
+	let impersonation_provider = impersonation_provider.ok_or(Error::Disabled("Impersonation"))?;
+	if !impersonated.contains(&who) {
+		return Err(Error::NotImpersonated(format!("0x{}", HexDisplay::from(&who))))
+	}
+
+	let at = client.info().best_hash;
+	let extrinsic = impersonation_provider.create_extrinsic(at, &who, &call)?;
+	let hash = HashingFor::<B>::hash_of(&extrinsic);
+	pool.submit_one(at, TransactionSource::Local, extrinsic)
+		.await
+		.map_err(|err| Error::StringError(err.to_string()))?;
+	Ok(hash)
+}
+
 /// runs the background authorship task for the instant seal engine.
 /// instant-seal creates a new block for every transaction imported into
 /// the transaction pool.
@@ -229,7 +337,7 @@ pub async fn run_instant_seal<B, BI, CB, E, C, TP, SC, CIDP, P>(
 	E: Environment<B> + 'static,
 	E::Proposer: Proposer<B, Proof = P>,
 	SC: SelectChain<B> + 'static,
-	TP: TransactionPool<Block = B>,
+	TP: MaintainedTransactionPool<Block = B>,
 	CIDP: CreateInherentDataProviders<B, ()>,
 	P: codec::Encode + Send + Sync + 'static,
 {
@@ -244,6 +352,7 @@ pub async fn run_instant_seal<B, BI, CB, E, C, TP, SC, CIDP, P>(
 
 	run_manual_seal(ManualSealParams {
 		block_import,
+		backend: None,
 		env,
 		client,
 		pool,
@@ -251,6 +360,8 @@ pub async fn run_instant_seal<B, BI, CB, E, C, TP, SC, CIDP, P>(
 		select_chain,
 		consensus_data_provider,
 		create_inherent_data_providers,
+		time_travel: None,
+		impersonation_provider: None,
 	})
 	.await
 }
@@ -279,7 +390,7 @@ pub async fn run_instant_seal_and_finalize<B, BI, CB, E, C, TP, SC, CIDP, P>(
 	E: Environment<B> + 'static,
 	E::Proposer: Proposer<B, Proof = P>,
 	SC: SelectChain<B> + 'static,
-	TP: TransactionPool<Block = B>,
+	TP: MaintainedTransactionPool<Block = B>,
 	CIDP: CreateInherentDataProviders<B, ()>,
 	P: codec::Encode + Send + Sync + 'static,
 {
@@ -294,6 +405,7 @@ pub async fn run_instant_seal_and_finalize<B, BI, CB, E, C, TP, SC, CIDP, P>(
 
 	run_manual_seal(ManualSealParams {
 		block_import,
+		backend: None,
 		env,
 		client,
 		pool,
@@ -301,6 +413,8 @@ pub async fn run_instant_seal_and_finalize<B, BI, CB, E, C, TP, SC, CIDP, P>(
 		select_chain,
 		consensus_data_provider,
 		create_inherent_data_providers,
+		time_travel: None,
+		impersonation_provider: None,
 	})
 	.await
 }
@@ -346,14 +460,20 @@ pub async fn run_delayed_finalize<B, CB, C, S>(
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::rpc::ManualSealApiServer;
+	use codec::Encode;
 	use sc_basic_authorship::ProposerFactory;
 	use sc_consensus::ImportedAux;
 	use sc_transaction_pool::{BasicPool, FullChainApi, Options, RevalidationType};
 	use sc_transaction_pool_api::{MaintainedTransactionPool, TransactionPool, TransactionSource};
+	use sp_core::H256;
 	use sp_inherents::InherentData;
 	use sp_runtime::generic::{Digest, DigestItem};
 	use substrate_test_runtime_client::{
-		DefaultTestClientBuilderExt, Sr25519Keyring::*, TestClientBuilder, TestClientBuilderExt,
+		runtime::{Block, Extrinsic},
+		DefaultTestClientBuilderExt,
+		Sr25519Keyring::*,
+		TestClientBuilder, TestClientBuilderExt,
 	};
 	use substrate_test_runtime_transaction_pool::{uxt, TestApi};
 
@@ -393,6 +513,19 @@ mod tests {
 		}
 	}
 
+	struct TestImpersonationProvider;
+	impl ImpersonationProvider<Block> for TestImpersonationProvider {
+		fn create_extrinsic(
+			&self,
+			_at: H256,
+			_who: &[u8],
+			_call: &[u8],
+		) -> Result<Extrinsic, Error> {
+			// the test runtime can't dispatch calls on behalf of accounts, use a transfer instead.
+			Ok(uxt(Alice, 0))
+		}
+	}
+
 	#[tokio::test]
 	async fn instant_seal() {
 		let builder = TestClientBuilder::new();
@@ -433,6 +566,7 @@ mod tests {
 		// spawn the background authorship task
 		tokio::spawn(run_manual_seal(ManualSealParams {
 			block_import: client.clone(),
+			backend: None,
 			env,
 			client: client.clone(),
 			pool: pool.clone(),
@@ -440,6 +574,8 @@ mod tests {
 			select_chain,
 			create_inherent_data_providers: |_, _| async { Ok(()) },
 			consensus_data_provider: None,
+			time_travel: None,
+			impersonation_provider: None,
 		}));
 
 		// submit a transaction to pool.
@@ -511,6 +647,7 @@ mod tests {
 		// spawn the background authorship task
 		tokio::spawn(run_manual_seal(ManualSealParams {
 			block_import: client.clone(),
+			backend: None,
 			commands_stream,
 			env,
 			client: client.clone(),
@@ -518,6 +655,8 @@ mod tests {
 			select_chain,
 			create_inherent_data_providers: |_, _| async { Ok(()) },
 			consensus_data_provider: None,
+			time_travel: None,
+			impersonation_provider: None,
 		}));
 
 		let delay_sec = 5;
@@ -587,6 +726,7 @@ mod tests {
 		// spawn the background authorship task
 		tokio::spawn(run_manual_seal(ManualSealParams {
 			block_import: client.clone(),
+			backend: None,
 			env,
 			client: client.clone(),
 			pool: pool.clone(),
@@ -594,6 +734,8 @@ mod tests {
 			select_chain,
 			consensus_data_provider: None,
 			create_inherent_data_providers: |_, _| async { Ok(()) },
+			time_travel: None,
+			impersonation_provider: None,
 		}));
 
 		// submit a transaction to pool.
@@ -671,6 +813,7 @@ mod tests {
 		// spawn the background authorship task
 		tokio::spawn(run_manual_seal(ManualSealParams {
 			block_import: client.clone(),
+			backend: None,
 			env,
 			client: client.clone(),
 			pool: pool.clone(),
@@ -678,6 +821,8 @@ mod tests {
 			select_chain,
 			consensus_data_provider: None,
 			create_inherent_data_providers: |_, _| async { Ok(()) },
+			time_travel: None,
+			impersonation_provider: None,
 		}));
 
 		// submit a transaction to pool.
@@ -775,6 +920,7 @@ mod tests {
 		// spawn the background authorship task
 		tokio::spawn(run_manual_seal(ManualSealParams {
 			block_import: client.clone(),
+			backend: None,
 			env,
 			client: client.clone(),
 			pool: pool.clone(),
@@ -783,6 +929,8 @@ mod tests {
 			// use a provider that pushes some post digest data
 			consensus_data_provider: Some(Box::new(TestDigestProvider { _client: client.clone() })),
 			create_inherent_data_providers: |_, _| async { Ok(()) },
+			time_travel: None,
+			impersonation_provider: None,
 		}));
 
 		let (tx, rx) = futures::channel::oneshot::channel();
@@ -800,4 +948,224 @@ mod tests {
 		let header = client.header(created_block.hash).unwrap().unwrap();
 		assert_eq!(header.number, 1);
 	}
+
+	#[tokio::test]
+	async fn manual_seal_snapshot_and_revert() {
+		let builder = TestClientBuilder::new();
+		let backend = builder.backend();
+		let (client, select_chain) = builder.build_with_longest_chain();
+		let client = Arc::new(client);
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let genesis_hash = client.info().genesis_hash;
+		let pool_api = Arc::new(FullChainApi::new(client.clone(), None, &spawner.clone()));
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(),
+			true.into(),
+			pool_api,
+			None,
+			RevalidationType::Full,
+			spawner.clone(),
+			0,
+			genesis_hash,
+			genesis_hash,
+		));
+		let env = ProposerFactory::new(spawner.clone(), client.clone(), pool.clone(), None, None);
+		let time_travel = TimeTravel::new();
+
+		let (sink, commands_stream) = futures::channel::mpsc::channel(1024);
+
+		// spawn the background authorship task
+		tokio::spawn(run_manual_seal(ManualSealParams {
+			block_import: client.clone(),
+			backend: Some(backend),
+			env,
+			client: client.clone(),
+			pool: pool.clone(),
+			commands_stream,
+			select_chain,
+			consensus_data_provider: None,
+			create_inherent_data_providers: |_, _| async { Ok(()) },
+			time_travel: Some(time_travel.clone()),
+			impersonation_provider: None,
+		}));
+		let rpc = rpc::ManualSeal::new(sink);
+
+		let created_blocks = rpc.create_blocks(1, false).await.unwrap();
+		assert_eq!(created_blocks.len(), 1);
+		time_travel.increase_time(1_000);
+		let id = rpc.snapshot().await.unwrap();
+
+		// move the chain and the time forward, including a transaction
+		let maintain = |hash| {
+			pool.maintain(sc_transaction_pool_api::ChainEvent::NewBestBlock {
+				hash,
+				tree_route: None,
+			})
+		};
+		maintain(created_blocks[0].hash).await;
+		pool.submit_one(created_blocks[0].hash, SOURCE, uxt(Alice, 0)).await.unwrap();
+		let hash = rpc.create_blocks(1, false).await.unwrap()[0].hash;
+		maintain(hash).await;
+		assert_eq!(pool.status().ready, 0);
+		assert_eq!(rpc.create_blocks(2, false).await.unwrap().len(), 2);
+		assert_eq!(client.info().best_number, 4);
+		time_travel.increase_time(2_000);
+
+		// revert to the snapshot, the transaction is back in the pool
+		assert!(rpc.revert(id).await.unwrap());
+		assert_eq!(client.info().best_number, 1);
+		assert_eq!(client.info().best_hash, created_blocks[0].hash);
+		assert_eq!(rpc.increase_time(0).await.unwrap(), 1_000);
+		assert_eq!(pool.status().ready, 1);
+
+		// the snapshot can only be reverted to once
+		assert!(!rpc.revert(id).await.unwrap());
+
+		// blocks are built on top of the snapshot block
+		let created_blocks = rpc.create_blocks(1, false).await.unwrap();
+		let header = client.header(created_blocks[0].hash).unwrap().unwrap();
+		assert_eq!(header.number, 2);
+
+		// snapshots of blocks that left the best chain can't be reverted to, but are kept
+		let id = rpc.snapshot().await.unwrap();
+		let genesis_hash = client.info().genesis_hash;
+		let mut parent_hash = Some(genesis_hash);
+		for _ in 0..3 {
+			parent_hash = Some(rpc.create_block(true, false, parent_hash).await.unwrap().hash);
+		}
+		assert_eq!(client.info().best_number, 3);
+		assert_matches::assert_matches!(rpc.revert(id).await, Err(Error::StringError(_)));
+		assert_matches::assert_matches!(rpc.revert(id).await, Err(Error::StringError(_)));
+
+		// and too many blocks can't be created at once
+		assert_matches::assert_matches!(
+			rpc.create_blocks(rpc::MAX_BLOCKS_PER_CALL + 1, false).await,
+			Err(Error::StringError(_))
+		);
+	}
+
+	#[test]
+	fn manual_seal_time_travel() {
+		let time_travel = TimeTravel::new();
+
+		let now = sp_timestamp::Timestamp::current();
+		assert!(time_travel.next_timestamp() >= now);
+
+		// the next block gets the exact timestamp, the ones after it are relative to it.
+		time_travel.set_next_block_timestamp(42).unwrap();
+		assert_eq!(time_travel.next_timestamp(), 42u64);
+		assert!(time_travel.next_timestamp() < 60_000u64);
+
+		let offset = time_travel.increase_time(60_000);
+		let now = sp_timestamp::Timestamp::current();
+		assert!(offset < 0);
+		assert!(time_travel.next_timestamp() >= 60_000u64);
+		assert!(time_travel.next_timestamp() < now);
+	}
+
+	/// The timestamp put in the inherent data of the next block.
+	async fn produced_timestamp(time_travel: &TimeTravel) -> u64 {
+		use sp_inherents::InherentDataProvider;
+		let data = time_travel.inherent_data_provider().create_inherent_data().await.unwrap();
+		let timestamp: sp_timestamp::Timestamp =
+			data.get_data(&sp_timestamp::INHERENT_IDENTIFIER).unwrap().unwrap();
+		timestamp.as_millis()
+	}
+
+	#[tokio::test]
+	async fn manual_seal_time_travel_timestamps() {
+		const MINUTE: u64 = 60_000;
+		let time_travel = TimeTravel::new();
+
+		time_travel.set_next_block_timestamp(1_000_000).unwrap();
+		assert_eq!(produced_timestamp(&time_travel).await, 1_000_000);
+
+		// increasing the time also moves the timestamp set for the next block
+		time_travel.set_next_block_timestamp(2_000_000).unwrap();
+		time_travel.increase_time(500);
+		assert_eq!(produced_timestamp(&time_travel).await, 2_000_500);
+		let next = produced_timestamp(&time_travel).await;
+		assert!((2_000_500..2_000_500 + MINUTE).contains(&next));
+
+		// the following blocks are moved forward as well
+		time_travel.increase_time(60 * MINUTE);
+		let after = produced_timestamp(&time_travel).await;
+		assert!((next + 60 * MINUTE..next + 61 * MINUTE).contains(&after));
+
+		// timestamps that don't fit in an `i64` are rejected and don't change the state
+		let state = time_travel.state();
+		assert_matches::assert_matches!(
+			time_travel.set_next_block_timestamp(u64::MAX),
+			Err(Error::StringError(_))
+		);
+		assert_eq!(time_travel.state(), state);
+
+		// huge increases saturate instead of wrapping around
+		assert!(time_travel.increase_time(u64::MAX) > 0);
+		assert_eq!(time_travel.increase_time(u64::MAX), i64::MAX);
+		assert_eq!(produced_timestamp(&time_travel).await, i64::MAX as u64);
+	}
+
+	#[tokio::test]
+	async fn manual_seal_impersonation() {
+		let builder = TestClientBuilder::new();
+		let (client, select_chain) = builder.build_with_longest_chain();
+		let client = Arc::new(client);
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let genesis_hash = client.info().genesis_hash;
+		let pool_api = Arc::new(FullChainApi::new(client.clone(), None, &spawner.clone()));
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(),
+			true.into(),
+			pool_api,
+			None,
+			RevalidationType::Full,
+			spawner.clone(),
+			0,
+			genesis_hash,
+			genesis_hash,
+		));
+		let env = ProposerFactory::new(spawner.clone(), client.clone(), pool.clone(), None, None);
+
+		let (sink, commands_stream) = futures::channel::mpsc::channel(1024);
+
+		// spawn the background authorship task
+		tokio::spawn(run_manual_seal(ManualSealParams {
+			block_import: client.clone(),
+			backend: None,
+			env,
+			client: client.clone(),
+			pool: pool.clone(),
+			commands_stream,
+			select_chain,
+			consensus_data_provider: None,
+			create_inherent_data_providers: |_, _| async { Ok(()) },
+			time_travel: None,
+			impersonation_provider: Some(Box::new(TestImpersonationProvider)),
+		}));
+		let rpc = rpc::ManualSeal::new(sink);
+		let who = sp_core::Bytes(Alice.to_account_id().encode());
+		let call = sp_core::Bytes(vec![]);
+
+		// only impersonated accounts are accepted
+		assert_matches::assert_matches!(
+			rpc.submit_impersonated_extrinsic(who.clone(), call.clone()).await,
+			Err(Error::NotImpersonated(_))
+		);
+
+		rpc.impersonate_account(who.clone()).await.unwrap();
+		let hash = rpc.submit_impersonated_extrinsic(who.clone(), call.clone()).await.unwrap();
+		assert_eq!(hash, pool.hash_of(&uxt(Alice, 0)));
+		assert_eq!(pool.status().ready, 1);
+
+		rpc.stop_impersonating_account(who.clone()).await.unwrap();
+		assert_matches::assert_matches!(
+			rpc.submit_impersonated_extrinsic(who, call).await,
+			Err(Error::NotImpersonated(_))
+		);
+
+		// time travel and snapshots are not enabled
+		assert_matches::assert_matches!(rpc.increase_time(1).await, Err(Error::Disabled(_)));
+		assert_matches::assert_matches!(rpc.snapshot().await, Err(Error::Disabled(_)));
+	}
 }
@@ -26,8 +26,12 @@ use futures::{
 use jsonrpsee::{core::async_trait, proc_macros::rpc};
 use sc_consensus::ImportedAux;
 use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
 use sp_runtime::EncodedJustification;
 
+/// The maximum number of blocks created by a single `engine_createBlocks` call.
+pub const MAX_BLOCKS_PER_CALL: u32 = 1000;
+
 /// Sender passed to the authorship task to report errors or successes.
 pub type Sender<T> = Option<oneshot::Sender<std::result::Result<T, Error>>>;
 
@@ -59,6 +63,52 @@ pub enum EngineCommand<Hash> {
 		/// finalization justification
 		justification: Option<EncodedJustification>,
 	},
+	/// Tells the engine to set the timestamp of the next block
+	SetNextBlockTimestamp {
+		/// unix timestamp in milliseconds
+		timestamp: u64,
+		/// sender to report errors/success to the rpc.
+		sender: Sender<()>,
+	},
+	/// Tells the engine to move the timestamp of the next blocks forward
+	IncreaseTime {
+		/// duration in milliseconds
+		duration: u64,
+		/// sender to report the total offset from the system time, in milliseconds, to the rpc.
+		sender: Sender<i64>,
+	},
+	/// Tells the engine to take a snapshot of the best block, to revert to it later on
+	Snapshot {
+		/// sender to report the id of the snapshot to the rpc.
+		sender: Sender<u64>,
+	},
+	/// Tells the engine to revert the chain to the snapshot with the supplied id
+	///
+	/// The snapshot and the ones taken after it can't be reverted to anymore.
+	Revert {
+		/// id of the snapshot
+		id: u64,
+		/// sender to report whether the snapshot was found to the rpc.
+		sender: Sender<bool>,
+	},
+	/// Tells the engine to start or stop impersonating an account
+	Impersonate {
+		/// SCALE-encoded account id
+		who: Vec<u8>,
+		/// start impersonating if true, stop otherwise.
+		enable: bool,
+		/// sender to report errors/success to the rpc.
+		sender: Sender<()>,
+	},
+	/// Tells the engine to submit a call on behalf of an impersonated account
+	SubmitImpersonated {
+		/// SCALE-encoded account id
+		who: Vec<u8>,
+		/// SCALE-encoded call
+		call: Vec<u8>,
+		/// sender to report the hash of the submitted extrinsic to the rpc.
+		sender: Sender<Hash>,
+	},
 }
 
 /// RPC trait that provides methods for interacting with the manual-seal authorship task over rpc.
@@ -80,6 +130,45 @@ pub trait ManualSealApi<Hash> {
 		hash: Hash,
 		justification: Option<EncodedJustification>,
 	) -> Result<bool, Error>;
+
+	/// Instructs the manual-seal authorship task to create `count` blocks, empty if there are no
+	/// transactions in the pool. At most [`MAX_BLOCKS_PER_CALL`] blocks are created per call.
+	#[method(name = "engine_createBlocks")]
+	async fn create_blocks(
+		&self,
+		count: u32,
+		finalize: bool,
+	) -> Result<Vec<CreatedBlock<Hash>>, Error>;
+
+	/// Sets the timestamp of the next block, in milliseconds since the unix epoch
+	#[method(name = "engine_setNextBlockTimestamp")]
+	async fn set_next_block_timestamp(&self, timestamp: u64) -> Result<(), Error>;
+
+	/// Moves the timestamp of the next blocks forward by `duration` milliseconds, returns the
+	/// total offset from the system time
+	#[method(name = "engine_increaseTime")]
+	async fn increase_time(&self, duration: u64) -> Result<i64, Error>;
+
+	/// Takes a snapshot of the best block, returns its id
+	#[method(name = "engine_snapshot")]
+	async fn snapshot(&self) -> Result<u64, Error>;
+
+	/// Reverts the chain to the snapshot with the supplied id, returns false if there is none
+	#[method(name = "engine_revert")]
+	async fn revert(&self, id: u64) -> Result<bool, Error>;
+
+	/// Allows submitting calls on behalf of the SCALE-encoded account id `who`
+	#[method(name = "engine_impersonateAccount")]
+	async fn impersonate_account(&self, who: Bytes) -> Result<(), Error>;
+
+	/// Stops allowing submitting calls on behalf of the SCALE-encoded account id `who`
+	#[method(name = "engine_stopImpersonatingAccount")]
+	async fn stop_impersonating_account(&self, who: Bytes) -> Result<(), Error>;
+
+	/// Submits the SCALE-encoded `call` on behalf of the impersonated account `who`, returns the
+	/// hash of the extrinsic
+	#[method(name = "engine_submitImpersonatedExtrinsic")]
+	async fn submit_impersonated_extrinsic(&self, who: Bytes, call: Bytes) -> Result<Hash, Error>;
 }
 
 /// A struct that implements the [`ManualSealApiServer`].
@@ -103,6 +192,17 @@ impl<Hash> ManualSeal<Hash> {
 	pub fn new(import_block_channel: mpsc::Sender<EngineCommand<Hash>>) -> Self {
 		Self { import_block_channel }
 	}
+
+	/// Send the command built by `command` to the authorship task and wait for its result.
+	async fn request<T>(
+		&self,
+		command: impl FnOnce(Sender<T>) -> EngineCommand<Hash>,
+	) -> Result<T, Error> {
+		let mut sink = self.import_block_channel.clone();
+		let (sender, receiver) = oneshot::channel();
+		sink.send(command(Some(sender))).await?;
+		receiver.await?
+	}
 }
 
 #[async_trait]
@@ -143,6 +243,67 @@ impl<Hash: Send + 'static> ManualSealApiServer<Hash> for ManualSeal<Hash> {
 		sink.send(command).await?;
 		receiver.await.map(|_| true).map_err(Into::into)
 	}
+
+	async fn create_blocks(
+		&self,
+		count: u32,
+		finalize: bool,
+	) -> Result<Vec<CreatedBlock<Hash>>, Error> {
+		if count > MAX_BLOCKS_PER_CALL {
+			return Err(Error::StringError(format!(
+				"At most {MAX_BLOCKS_PER_CALL} blocks can be created per call"
+			)))
+		}
+		let mut created_blocks = Vec::new();
+		for _ in 0..count {
+			let created_block = self
+				.request(|sender| EngineCommand::SealNewBlock {
+					create_empty: true,
+					finalize,
+					parent_hash: None,
+					sender,
+				})
+				.await?;
+			created_blocks.push(created_block);
+		}
+		Ok(created_blocks)
+	}
+
+	async fn set_next_block_timestamp(&self, timestamp: u64) -> Result<(), Error> {
+		self.request(|sender| EngineCommand::SetNextBlockTimestamp { timestamp, sender })
+			.await
+	}
+
+	async fn increase_time(&self, duration: u64) -> Result<i64, Error> {
+		self.request(|sender| EngineCommand::IncreaseTime { duration, sender }).await
+	}
+
+	async fn snapshot(&self) -> Result<u64, Error> {
+		self.request(|sender| EngineCommand::Snapshot { sender }).await
+	}
+
+	async fn revert(&self, id: u64) -> Result<bool, Error> {
+		self.request(|sender| EngineCommand::Revert { id, sender }).await
+	}
+
+	async fn impersonate_account(&self, who: Bytes) -> Result<(), Error> {
+		self.request(|sender| EngineCommand::Impersonate { who: who.0, enable: true, sender })
+			.await
+	}
+
+	async fn stop_impersonating_account(&self, who: Bytes) -> Result<(), Error> {
+		self.request(|sender| EngineCommand::Impersonate { who: who.0, enable: false, sender })
+			.await
+	}
+
+	async fn submit_impersonated_extrinsic(&self, who: Bytes, call: Bytes) -> Result<Hash, Error> {
+		self.request(|sender| EngineCommand::SubmitImpersonated {
+			who: who.0,
+			call: call.0,
+			sender,
+		})
+		.await
+	}
 }
 
 /// report any errors or successes encountered by the authorship task back
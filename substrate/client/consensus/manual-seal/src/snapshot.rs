@@ -0,0 +1,135 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Chain snapshots, to revert the chain to later on.
+
+use crate::{
+	consensus::timestamp::{TimeTravel, TimeTravelState},
+	Error,
+};
+use sc_client_api::backend::Backend as ClientBackend;
+use sc_transaction_pool_api::{ChainEvent, MaintainedTransactionPool};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, NumberFor, Saturating, Zero};
+
+/// The best block and time travel state at the time a snapshot was taken.
+struct Snapshot<B: BlockT> {
+	id: u64,
+	number: NumberFor<B>,
+	hash: B::Hash,
+	time: Option<TimeTravelState>,
+}
+
+/// Snapshots taken by the authorship task, in the order they were taken.
+pub(crate) struct Snapshots<B: BlockT> {
+	next_id: u64,
+	snapshots: Vec<Snapshot<B>>,
+}
+
+impl<B: BlockT> Snapshots<B> {
+	pub(crate) fn new() -> Self {
+		Self { next_id: 0, snapshots: Vec::new() }
+	}
+
+	/// Record the best block and the time travel state, returns the id of the snapshot.
+	pub(crate) fn take<C: HeaderBackend<B>>(
+		&mut self,
+		client: &C,
+		time_travel: Option<&TimeTravel>,
+	) -> u64 {
+		let info = client.info();
+		let id = self.next_id;
+		self.next_id += 1;
+		self.snapshots.push(Snapshot {
+			id,
+			number: info.best_number,
+			hash: info.best_hash,
+			time: time_travel.map(TimeTravel::state),
+		});
+		id
+	}
+
+	/// Revert the chain to the snapshot `id`, reverting finalized blocks if needed. The snapshot
+	/// and the ones taken after it are discarded once the chain is reverted.
+	///
+	/// Finalized blocks can only be reverted if their state wasn't pruned, e.g. on archive nodes.
+	/// The transaction `pool` is moved to the snapshot block first, while the reverted blocks can
+	/// still be read, so it resubmits the transactions of the reverted blocks that weren't
+	/// finalized.
+	///
+	/// Returns `false` if there is no snapshot `id`.
+	pub(crate) async fn revert<C, CB, TP>(
+		&mut self,
+		id: u64,
+		client: &C,
+		backend: &CB,
+		pool: &TP,
+		time_travel: Option<&TimeTravel>,
+	) -> Result<bool, Error>
+	where
+		C: HeaderBackend<B>,
+		CB: ClientBackend<B>,
+		TP: MaintainedTransactionPool<Block = B>,
+	{
+		let Some(index) = self.snapshots.iter().position(|snapshot| snapshot.id == id) else {
+			return Ok(false)
+		};
+		let (number, hash) = (self.snapshots[index].number, self.snapshots[index].hash);
+
+		// only the best chain can be reverted.
+		if client.hash(number)? != Some(hash) {
+			return Err(Error::StringError(format!(
+				"Snapshot block #{number} ({hash}) is not part of the best chain"
+			)))
+		}
+
+		let info = client.info();
+		let blocks = info.best_number.saturating_sub(number);
+		let reverted = if blocks.is_zero() {
+			Ok(())
+		} else {
+			pool.maintain(ChainEvent::NewBestBlock { hash, tree_route: None }).await;
+			let revert_finalized = number < info.finalized_number;
+			backend.revert(blocks, revert_finalized).map(|(reverted, _)| {
+				log::info!(
+					target: crate::LOG_TARGET,
+					"⏪ Reverted {reverted} blocks to snapshot #{number} ({hash})",
+				)
+			})
+		};
+		// blocks without state, e.g. pruned finalized ones, can't be reverted.
+		let best_hash = client.info().best_hash;
+		if best_hash != hash {
+			// the transaction pool follows the best block again.
+			pool.maintain(ChainEvent::NewBestBlock { hash: best_hash, tree_route: None })
+				.await;
+			reverted?;
+			return Err(Error::StringError(format!(
+				"Failed to revert to snapshot block #{number} ({hash})"
+			)))
+		}
+		reverted?;
+
+		let snapshot = self.snapshots.drain(index..).next().expect("`index` is in bounds; qed");
+		if let (Some(time_travel), Some(time)) = (time_travel, snapshot.time) {
+			time_travel.restore(time);
+		}
+
+		Ok(true)
+	}
+}
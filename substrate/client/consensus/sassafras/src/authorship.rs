@@ -0,0 +1,625 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Sassafras tickets generation, slot claiming and block authoring.
+
+use crate::{
+	aux_schema, find_next_epoch_digest, find_slot, ticket_claim_message, vrf, AuthorityId,
+	AuthorityIndex, AuthoritySignature, Epoch, SassafrasApi, SassafrasConfiguration,
+	SassafrasIntermediate, SassafrasLink, SlotClaim, TicketBody, TicketClaim, TicketEnvelope,
+	TicketId, TicketSecret, INTERMEDIATE_KEY, KEY_TYPE, LOG_TARGET,
+};
+use codec::Encode;
+use futures::prelude::*;
+use log::{debug, info, warn};
+use sc_client_api::{backend::AuxStore, BlockchainEvents};
+use sc_consensus::block_import::{BlockImport, BlockImportParams, StateAction};
+use sc_consensus_epochs::{
+	descendent_query, Epoch as EpochT, EpochIdentifier, EpochIdentifierPosition,
+	SharedEpochChanges, ViableEpochDescriptor,
+};
+use sc_consensus_slots::{
+	BackoffAuthoringBlocksStrategy, InherentDataProviderExt, SlotInfo, SlotProportion,
+	StorageChanges,
+};
+use sc_telemetry::TelemetryHandle;
+use sc_transaction_pool_api::OffchainTransactionPoolFactory;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::{Error as ClientError, HeaderBackend, HeaderMetadata};
+use sp_consensus::{
+	BlockOrigin, Environment, Error as ConsensusError, Proposer, SelectChain, SyncOracle,
+};
+use sp_consensus_sassafras::ticket_id_threshold;
+use sp_consensus_slots::Slot;
+use sp_core::{ed25519, Pair, U256};
+use sp_inherents::CreateInherentDataProviders;
+use sp_keystore::KeystorePtr;
+use sp_runtime::{
+	traits::{Block as BlockT, Header, NumberFor, One},
+	DigestItem,
+};
+use std::{
+	pin::Pin,
+	sync::Arc,
+	task::{Context, Poll},
+	time::Duration,
+};
+
+/// Get the index of the fallback author of the given slot, used when no ticket is assigned to
+/// it.
+///
+/// This always assigns the slot to some authority unless the authorities list is empty.
+pub(crate) fn secondary_authority_index(slot: Slot, epoch: &Epoch) -> Option<AuthorityIndex> {
+	if epoch.authorities.is_empty() {
+		return None
+	}
+
+	let rand = U256::from_big_endian(
+		&(epoch.randomness, slot).using_encoded(sp_crypto_hashing::blake2_256),
+	);
+
+	let authorities_len = U256::from(epoch.authorities.len());
+	Some((rand % authorities_len).as_u32())
+}
+
+/// Try to claim the given slot and return the claim together with the key of the author.
+///
+/// If `maybe_ticket` is given, the slot is assigned to the ticket and can only be claimed by its
+/// owner. Otherwise, the slot can only be claimed by its fallback author.
+pub(crate) fn claim_slot(
+	slot: Slot,
+	epoch: &Epoch,
+	maybe_ticket: Option<(TicketId, TicketBody)>,
+	keystore: &KeystorePtr,
+) -> Option<(SlotClaim, AuthorityId)> {
+	let mut epoch_index = epoch.index;
+	if epoch.end_slot() <= slot {
+		// Slot doesn't strictly belong to the epoch, create a clone with fixed values.
+		epoch_index = epoch.clone_for_slot(slot).index;
+	}
+
+	let (authority_idx, ticket_secret) = match &maybe_ticket {
+		Some((ticket_id, _)) => {
+			let (authority_idx, secret) = epoch.tickets_aux.get(ticket_id)?;
+			(*authority_idx, Some(secret))
+		},
+		None => (secondary_authority_index(slot, epoch)?, None),
+	};
+	let authority_id = epoch.authorities.get(authority_idx as usize)?;
+
+	let data = vrf::slot_claim_sign_data(&epoch.randomness, slot, epoch_index);
+	let vrf_signature = keystore
+		.bandersnatch_vrf_sign(KEY_TYPE, authority_id.as_ref(), &data)
+		.ok()
+		.flatten()?;
+
+	let ticket_claim = match (maybe_ticket, ticket_secret) {
+		(Some((ticket_id, body)), Some(secret)) => {
+			let erased_pair = ed25519::Pair::from_seed(&secret.0);
+			if erased_pair.public() != body.erased_public {
+				warn!(target: LOG_TARGET, "Secret of ticket {:032x} doesn't match", ticket_id);
+				return None
+			}
+			let erased_signature = erased_pair.sign(&ticket_claim_message(&vrf_signature));
+			Some(TicketClaim { erased_signature })
+		},
+		_ => None,
+	};
+
+	let claim = SlotClaim { authority_idx, slot, vrf_signature, ticket_claim };
+	Some((claim, authority_id.clone()))
+}
+
+/// Generate the tickets of the local authorities for the given epoch.
+///
+/// Only the tickets whose identifier is below the threshold of the epoch are returned, together
+/// with a ring-VRF signature built from the epoch authorities. The secrets needed to claim the
+/// slots the tickets get assigned to are stored in [`Epoch::tickets_aux`].
+pub(crate) fn generate_epoch_tickets(
+	epoch: &mut Epoch,
+	ring_context: &vrf::RingContext,
+	keystore: &KeystorePtr,
+) -> Vec<TicketEnvelope> {
+	let mut tickets = Vec::new();
+
+	let threshold = ticket_id_threshold(
+		epoch.config.redundancy_factor,
+		epoch.length,
+		epoch.config.attempts_number,
+		epoch.authorities.len() as u32,
+	);
+	debug!(
+		target: LOG_TARGET,
+		"Generating tickets for epoch {} @ threshold {:032x}",
+		epoch.index,
+		threshold,
+	);
+
+	let authorities: Vec<_> =
+		epoch.authorities.iter().map(|authority| authority.clone().into_inner()).collect();
+	let local_keys = keystore.bandersnatch_public_keys(KEY_TYPE);
+
+	for (authority_idx, public) in authorities.iter().enumerate() {
+		if !local_keys.contains(public) {
+			continue
+		}
+		debug!(target: LOG_TARGET, ">>> Generating tickets for authority {}", authority_idx);
+		let prover = ring_context.prover(&authorities, authority_idx);
+
+		for attempt_idx in 0..epoch.config.attempts_number {
+			let vrf_input = vrf::ticket_id_input(&epoch.randomness, attempt_idx, epoch.index);
+			let Some(pre_output) = keystore
+				.bandersnatch_vrf_pre_output(KEY_TYPE, public, &vrf_input)
+				.ok()
+				.flatten()
+			else {
+				break
+			};
+
+			let ticket_id = vrf::make_ticket_id(&pre_output);
+			if ticket_id >= threshold {
+				continue
+			}
+
+			let (erased_pair, erased_seed) = ed25519::Pair::generate();
+			let revealed_seed = sp_crypto_hashing::blake2_256(
+				&[b"sassafras-revealed".as_slice(), &pre_output.make_bytes()].concat(),
+			);
+			let revealed_pair = ed25519::Pair::from_seed(&revealed_seed);
+			let body = TicketBody {
+				attempt_idx,
+				erased_public: erased_pair.public(),
+				revealed_public: revealed_pair.public(),
+			};
+
+			let sign_data = vrf::ticket_body_sign_data(&body, vrf_input);
+			let Some(signature) = keystore
+				.bandersnatch_ring_vrf_sign(KEY_TYPE, public, &sign_data, &prover)
+				.ok()
+				.flatten()
+			else {
+				break
+			};
+			debug!(target: LOG_TARGET, "    → {:032x}", ticket_id);
+
+			epoch
+				.tickets_aux
+				.insert(ticket_id, (authority_idx as AuthorityIndex, TicketSecret(erased_seed)));
+			tickets.push(TicketEnvelope { body, signature });
+		}
+	}
+
+	tickets
+}
+
+/// Parameters for Sassafras.
+pub struct SassafrasParams<B: BlockT, C, SC, E, I, SO, L, CIDP, BS> {
+	/// The keystore that manages the keys of the node.
+	pub keystore: KeystorePtr,
+	/// The client to use
+	pub client: Arc<C>,
+	/// The SelectChain Strategy
+	pub select_chain: SC,
+	/// The environment we are producing blocks for.
+	pub env: E,
+	/// The underlying block-import object to supply our produced blocks to.
+	/// This must be a `SassafrasBlockImport` or a wrapper of it, otherwise
+	/// critical consensus logic will be omitted.
+	pub block_import: I,
+	/// A sync oracle
+	pub sync_oracle: SO,
+	/// Hook into the sync module to control the justification sync process.
+	pub justification_sync_link: L,
+	/// Something that can create the inherent data providers.
+	pub create_inherent_data_providers: CIDP,
+	/// Force authoring of blocks even if we are offline
+	pub force_authoring: bool,
+	/// Strategy and parameters for backing off block production.
+	pub backoff_authoring_blocks: Option<BS>,
+	/// The link created by [`crate::block_import`].
+	pub sassafras_link: SassafrasLink<B>,
+	/// The proportion of the slot dedicated to proposing.
+	pub block_proposal_slot_portion: SlotProportion,
+	/// The maximum proportion of the slot dedicated to proposing with any lenience factor applied
+	/// due to no blocks being produced.
+	pub max_block_proposal_slot_portion: Option<SlotProportion>,
+	/// Handle use to report telemetries.
+	pub telemetry: Option<TelemetryHandle>,
+	/// The offchain transaction pool factory.
+	///
+	/// Used to submit the generated tickets.
+	pub offchain_tx_pool_factory: OffchainTransactionPoolFactory<B>,
+}
+
+/// Start the Sassafras worker.
+///
+/// The worker authors blocks in the slots claimed by the local authorities, and generates and
+/// submits their tickets for the next epoch whenever a new epoch is announced.
+pub fn start_sassafras<B, C, SC, E, I, SO, CIDP, BS, L, Error>(
+	SassafrasParams {
+		keystore,
+		client,
+		select_chain,
+		env,
+		block_import,
+		sync_oracle,
+		justification_sync_link,
+		create_inherent_data_providers,
+		force_authoring,
+		backoff_authoring_blocks,
+		sassafras_link,
+		block_proposal_slot_portion,
+		max_block_proposal_slot_portion,
+		telemetry,
+		offchain_tx_pool_factory,
+	}: SassafrasParams<B, C, SC, E, I, SO, L, CIDP, BS>,
+) -> Result<SassafrasWorker, ConsensusError>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>
+		+ HeaderBackend<B>
+		+ HeaderMetadata<B, Error = ClientError>
+		+ BlockchainEvents<B>
+		+ AuxStore
+		+ Send
+		+ Sync
+		+ 'static,
+	C::Api: SassafrasApi<B>,
+	SC: SelectChain<B> + 'static,
+	E: Environment<B, Error = Error> + Send + Sync + 'static,
+	E::Proposer: Proposer<B, Error = Error>,
+	I: BlockImport<B, Error = ConsensusError> + Send + Sync + 'static,
+	SO: SyncOracle + Send + Sync + Clone + 'static,
+	L: sc_consensus::JustificationSyncLink<B> + 'static,
+	CIDP: CreateInherentDataProviders<B, ()> + Send + Sync + 'static,
+	CIDP::InherentDataProviders: InherentDataProviderExt + Send,
+	BS: BackoffAuthoringBlocksStrategy<NumberFor<B>> + Send + Sync + 'static,
+	Error: std::error::Error + Send + From<ConsensusError> + From<I::Error> + 'static,
+{
+	let worker = SassafrasSlotWorker {
+		client: client.clone(),
+		block_import,
+		env,
+		sync_oracle: sync_oracle.clone(),
+		justification_sync_link,
+		force_authoring,
+		backoff_authoring_blocks,
+		keystore: keystore.clone(),
+		epoch_changes: sassafras_link.epoch_changes.clone(),
+		config: sassafras_link.config.clone(),
+		block_proposal_slot_portion,
+		max_block_proposal_slot_portion,
+		telemetry,
+	};
+
+	info!(target: LOG_TARGET, "👶 Starting Sassafras Authorship worker");
+
+	let slot_worker = sc_consensus_slots::start_slot_worker(
+		sassafras_link.config.slot_duration(),
+		select_chain,
+		sc_consensus_slots::SimpleSlotWorkerToSlotWorker(worker),
+		sync_oracle.clone(),
+		create_inherent_data_providers,
+	);
+
+	let tickets_worker = tickets_worker(
+		client,
+		keystore,
+		sassafras_link.epoch_changes,
+		sync_oracle,
+		offchain_tx_pool_factory,
+	);
+
+	let inner = future::select(Box::pin(slot_worker), Box::pin(tickets_worker)).map(|_| ());
+	Ok(SassafrasWorker { inner: Box::pin(inner) })
+}
+
+/// Generate and submit the tickets of the local authorities for each announced epoch.
+///
+/// Tickets are generated once the block announcing the epoch becomes the best block, and
+/// submitted through the offchain transaction pool.
+async fn tickets_worker<B, C, SO>(
+	client: Arc<C>,
+	keystore: KeystorePtr,
+	epoch_changes: SharedEpochChanges<B, Epoch>,
+	sync_oracle: SO,
+	offchain_tx_pool_factory: OffchainTransactionPoolFactory<B>,
+) where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + BlockchainEvents<B> + AuxStore,
+	C::Api: SassafrasApi<B>,
+	SO: SyncOracle,
+{
+	let mut notifications = client.import_notification_stream();
+
+	while let Some(notification) = notifications.next().await {
+		if !notification.is_new_best || sync_oracle.is_major_syncing() {
+			continue
+		}
+		match find_next_epoch_digest::<B>(&notification.header) {
+			Ok(Some(_)) => {},
+			Ok(None) => continue,
+			Err(e) => {
+				warn!(target: LOG_TARGET, "Malformed epoch change digest: {}", e);
+				continue
+			},
+		}
+
+		let hash = notification.hash;
+		let number = *notification.header.number();
+		// The epoch announced by the first block is stored alongside the genesis epoch.
+		let position = if number == One::one() {
+			EpochIdentifierPosition::Genesis1
+		} else {
+			EpochIdentifierPosition::Regular
+		};
+		let epoch_id = EpochIdentifier { position, hash, number };
+
+		let Some(mut epoch) = epoch_changes.shared_data().epoch(&epoch_id).cloned() else {
+			warn!(target: LOG_TARGET, "Unexpected missing epoch data for {:?}", epoch_id);
+			continue
+		};
+
+		let ring_context = match client.runtime_api().ring_context(hash) {
+			Ok(Some(ring_context)) => ring_context,
+			Ok(None) => {
+				debug!(target: LOG_TARGET, "Ring context not initialized yet");
+				continue
+			},
+			Err(e) => {
+				warn!(target: LOG_TARGET, "Unable to read the ring context: {}", e);
+				continue
+			},
+		};
+
+		// Generating the ring proofs is expensive, so it is done without holding the lock.
+		let tickets = generate_epoch_tickets(&mut epoch, &ring_context, &keystore);
+		if tickets.is_empty() {
+			continue
+		}
+		let (epoch_index, tickets_count) = (epoch.index, tickets.len());
+
+		{
+			let mut epoch_changes = epoch_changes.shared_data();
+			let Some(target_epoch) = epoch_changes.epoch_mut(&epoch_id) else {
+				warn!(target: LOG_TARGET, "Epoch {:?} was pruned meanwhile", epoch_id);
+				continue
+			};
+			target_epoch.tickets_aux = epoch.tickets_aux;
+			if let Err(e) = aux_schema::write_epoch_changes::<B, _, _>(&epoch_changes, |insert| {
+				client.insert_aux(insert, [])
+			}) {
+				warn!(target: LOG_TARGET, "Unable to persist the tickets: {}", e);
+			}
+		}
+
+		let mut runtime_api = client.runtime_api();
+
+		// Register the offchain tx pool to be able to use it from the runtime.
+		runtime_api
+			.register_extension(offchain_tx_pool_factory.offchain_transaction_pool(hash));
+
+		match runtime_api.submit_tickets_unsigned_extrinsic(hash, tickets) {
+			Ok(true) => info!(
+				target: LOG_TARGET,
+				"🎫 Submitted {} tickets for epoch {}", tickets_count, epoch_index,
+			),
+			Ok(false) => warn!(target: LOG_TARGET, "Tickets for epoch {} rejected", epoch_index),
+			Err(e) => warn!(target: LOG_TARGET, "Unable to submit the tickets: {}", e),
+		}
+	}
+}
+
+/// Worker for Sassafras which implements `Future<Output=()>`. This must be polled.
+#[must_use]
+pub struct SassafrasWorker {
+	inner: Pin<Box<dyn Future<Output = ()> + Send + 'static>>,
+}
+
+impl Future for SassafrasWorker {
+	type Output = ();
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+		self.inner.as_mut().poll(cx)
+	}
+}
+
+struct SassafrasSlotWorker<B: BlockT, C, E, I, SO, L, BS> {
+	client: Arc<C>,
+	block_import: I,
+	env: E,
+	sync_oracle: SO,
+	justification_sync_link: L,
+	force_authoring: bool,
+	backoff_authoring_blocks: Option<BS>,
+	keystore: KeystorePtr,
+	epoch_changes: SharedEpochChanges<B, Epoch>,
+	config: SassafrasConfiguration,
+	block_proposal_slot_portion: SlotProportion,
+	max_block_proposal_slot_portion: Option<SlotProportion>,
+	telemetry: Option<TelemetryHandle>,
+}
+
+#[async_trait::async_trait]
+impl<B, C, E, I, Error, SO, L, BS> sc_consensus_slots::SimpleSlotWorker<B>
+	for SassafrasSlotWorker<B, C, E, I, SO, L, BS>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + HeaderMetadata<B, Error = ClientError>,
+	C::Api: SassafrasApi<B>,
+	E: Environment<B, Error = Error> + Send + Sync,
+	E::Proposer: Proposer<B, Error = Error>,
+	I: BlockImport<B> + Send + Sync + 'static,
+	SO: SyncOracle + Send + Clone + Sync,
+	L: sc_consensus::JustificationSyncLink<B>,
+	BS: BackoffAuthoringBlocksStrategy<NumberFor<B>> + Send + Sync,
+	Error: std::error::Error + Send + From<ConsensusError> + From<I::Error> + 'static,
+{
+	type Claim = (SlotClaim, AuthorityId);
+	type SyncOracle = SO;
+	type JustificationSyncLink = L;
+	type CreateProposer =
+		Pin<Box<dyn Future<Output = Result<E::Proposer, ConsensusError>> + Send + 'static>>;
+	type Proposer = E::Proposer;
+	type BlockImport = I;
+	type AuxData = ViableEpochDescriptor<B::Hash, NumberFor<B>, Epoch>;
+
+	fn logging_target(&self) -> &'static str {
+		LOG_TARGET
+	}
+
+	fn block_import(&mut self) -> &mut Self::BlockImport {
+		&mut self.block_import
+	}
+
+	fn aux_data(&self, parent: &B::Header, slot: Slot) -> Result<Self::AuxData, ConsensusError> {
+		self.epoch_changes
+			.shared_data()
+			.epoch_descriptor_for_child_of(
+				descendent_query(&*self.client),
+				&parent.hash(),
+				*parent.number(),
+				slot,
+			)
+			.map_err(|e| ConsensusError::ChainLookup(e.to_string()))?
+			.ok_or(ConsensusError::InvalidAuthoritiesSet)
+	}
+
+	fn authorities_len(&self, epoch_descriptor: &Self::AuxData) -> Option<usize> {
+		self.epoch_changes
+			.shared_data()
+			.viable_epoch(epoch_descriptor, |slot| Epoch::genesis(&self.config, slot))
+			.map(|epoch| epoch.as_ref().authorities.len())
+	}
+
+	async fn claim_slot(
+		&mut self,
+		parent_header: &B::Header,
+		slot: Slot,
+		epoch_descriptor: &ViableEpochDescriptor<B::Hash, NumberFor<B>, Epoch>,
+	) -> Option<Self::Claim> {
+		debug!(target: LOG_TARGET, "Attempting to claim slot {}", slot);
+
+		let maybe_ticket = self
+			.client
+			.runtime_api()
+			.slot_ticket(parent_header.hash(), slot)
+			.map_err(|e| warn!(target: LOG_TARGET, "Unable to read the slot ticket: {}", e))
+			.ok()?;
+
+		let s = claim_slot(
+			slot,
+			self.epoch_changes
+				.shared_data()
+				.viable_epoch(epoch_descriptor, |slot| Epoch::genesis(&self.config, slot))?
+				.as_ref(),
+			maybe_ticket,
+			&self.keystore,
+		);
+
+		if s.is_some() {
+			debug!(target: LOG_TARGET, "Claimed slot {}", slot);
+		}
+
+		s
+	}
+
+	fn pre_digest_data(&self, _slot: Slot, claim: &Self::Claim) -> Vec<DigestItem> {
+		vec![DigestItem::from(&claim.0)]
+	}
+
+	async fn block_import_params(
+		&self,
+		header: B::Header,
+		header_hash: &B::Hash,
+		body: Vec<B::Extrinsic>,
+		storage_changes: StorageChanges<B>,
+		(_, public): Self::Claim,
+		epoch_descriptor: Self::AuxData,
+	) -> Result<BlockImportParams<B>, ConsensusError> {
+		let signature: AuthoritySignature = self
+			.keystore
+			.bandersnatch_sign(KEY_TYPE, public.as_ref(), header_hash.as_ref())
+			.map_err(|e| ConsensusError::CannotSign(format!("{}. Key: {:?}", e, public)))?
+			.ok_or_else(|| {
+				ConsensusError::CannotSign(format!(
+					"Could not find key in keystore. Key: {:?}",
+					public
+				))
+			})?
+			.into();
+
+		let mut import_block = BlockImportParams::new(BlockOrigin::Own, header);
+		import_block.post_digests.push(DigestItem::from(&signature));
+		import_block.body = Some(body);
+		import_block.state_action =
+			StateAction::ApplyChanges(sc_consensus::StorageChanges::Changes(storage_changes));
+		import_block
+			.insert_intermediate(INTERMEDIATE_KEY, SassafrasIntermediate::<B> { epoch_descriptor });
+
+		Ok(import_block)
+	}
+
+	fn force_authoring(&self) -> bool {
+		self.force_authoring
+	}
+
+	fn should_backoff(&self, slot: Slot, chain_head: &B::Header) -> bool {
+		if let Some(ref strategy) = self.backoff_authoring_blocks {
+			if let Ok(chain_head_slot) = find_slot::<B>(chain_head) {
+				return strategy.should_backoff(
+					*chain_head.number(),
+					chain_head_slot,
+					self.client.info().finalized_number,
+					slot,
+					self.logging_target(),
+				)
+			}
+		}
+		false
+	}
+
+	fn sync_oracle(&mut self) -> &mut Self::SyncOracle {
+		&mut self.sync_oracle
+	}
+
+	fn justification_sync_link(&mut self) -> &mut Self::JustificationSyncLink {
+		&mut self.justification_sync_link
+	}
+
+	fn proposer(&mut self, block: &B::Header) -> Self::CreateProposer {
+		Box::pin(self.env.init(block).map_err(|e| ConsensusError::ClientImport(e.to_string())))
+	}
+
+	fn telemetry(&self) -> Option<TelemetryHandle> {
+		self.telemetry.clone()
+	}
+
+	fn proposing_remaining_duration(&self, slot_info: &SlotInfo<B>) -> Duration {
+		let parent_slot = find_slot::<B>(&slot_info.chain_head).ok();
+
+		sc_consensus_slots::proposing_remaining_duration(
+			parent_slot,
+			slot_info,
+			&self.block_proposal_slot_portion,
+			self.max_block_proposal_slot_portion.as_ref(),
+			sc_consensus_slots::SlotLenienceType::Exponential,
+			self.logging_target(),
+		)
+	}
+}
@@ -0,0 +1,365 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Sassafras client tests.
+
+use super::*;
+use crate::{
+	authorship::{claim_slot, generate_epoch_tickets, secondary_authority_index},
+	verification::{check_header, VerificationParams},
+};
+use sc_block_builder::BlockBuilderBuilder;
+use sc_consensus::block_import::BlockImportParams;
+use sc_consensus_slots::CheckedHeader;
+use sp_consensus::BlockOrigin;
+use sp_core::crypto::VrfSecret;
+use sp_keyring::BandersnatchKeyring as Keyring;
+use sp_keystore::{testing::MemoryKeystore, Keystore, KeystorePtr};
+use sp_runtime::{Digest, DigestItem};
+use substrate_test_runtime_client::{
+	runtime::{Block as TestBlock, Header as TestHeader, TEST_RUNTIME_SASSAFRAS_EPOCH_KEY},
+	Backend as TestBackend, BlockBuilderExt, DefaultTestClientBuilderExt, TestClientBuilder,
+	TestClientBuilderExt,
+};
+
+type TestClient = substrate_test_runtime_client::TestClient;
+type TestBlockImport = SassafrasBlockImport<TestBlock, TestClient, Arc<TestClient>>;
+type Hash = <TestBlock as BlockT>::Hash;
+
+const EPOCH_LENGTH: u32 = 6;
+
+fn create_keystore(authority: Keyring) -> KeystorePtr {
+	let keystore = MemoryKeystore::new();
+	keystore
+		.bandersnatch_generate_new(KEY_TYPE, Some(&authority.to_seed()))
+		.expect("Creates authority key");
+	keystore.into()
+}
+
+fn test_epoch(authorities: &[Keyring]) -> Epoch {
+	sp_consensus_sassafras::Epoch {
+		index: 1,
+		start: 6.into(),
+		length: EPOCH_LENGTH,
+		randomness: [3; 32],
+		authorities: authorities.iter().map(|a| a.public().into()).collect(),
+		config: EpochConfiguration { redundancy_factor: 1, attempts_number: 2 },
+	}
+	.into()
+}
+
+fn sealed_header(claim: &SlotClaim, signer: Keyring) -> TestHeader {
+	let mut header = TestHeader::new(
+		1,
+		Default::default(),
+		Default::default(),
+		Default::default(),
+		Digest { logs: vec![DigestItem::from(claim)] },
+	);
+	let signature: AuthoritySignature = signer.sign(header.hash().as_ref()).into();
+	header.digest_mut().push(DigestItem::from(&signature));
+	header
+}
+
+fn check(
+	header: TestHeader,
+	claim: &SlotClaim,
+	epoch: &Epoch,
+	maybe_ticket: Option<(TicketId, TicketBody)>,
+) -> Result<(), Error<TestBlock>> {
+	let params = VerificationParams { header, claim, slot_now: claim.slot, epoch, maybe_ticket };
+	match check_header::<TestBlock>(params)? {
+		CheckedHeader::Checked(..) => Ok(()),
+		CheckedHeader::Deferred(..) => panic!("Slot is not in the future"),
+	}
+}
+
+#[test]
+fn claims_fallback_slots() {
+	let authorities = [Keyring::Alice, Keyring::Bob, Keyring::Charlie];
+	let epoch = test_epoch(&authorities);
+	let keystore = create_keystore(Keyring::Alice);
+
+	let mut claimed = 0;
+	for slot in 6..36 {
+		let slot = Slot::from(slot);
+		let expected = secondary_authority_index(slot, &epoch).unwrap();
+		match claim_slot(slot, &epoch, None, &keystore) {
+			Some((claim, author)) => {
+				assert_eq!(expected, 0);
+				assert_eq!(claim.authority_idx, 0);
+				assert_eq!(author, Keyring::Alice.public().into());
+				assert!(claim.ticket_claim.is_none());
+				check(sealed_header(&claim, Keyring::Alice), &claim, &epoch, None).unwrap();
+				claimed += 1;
+			},
+			None => assert_ne!(expected, 0),
+		}
+	}
+	assert!(claimed > 0);
+}
+
+#[test]
+fn generates_and_claims_tickets() {
+	sp_tracing::try_init_simple();
+
+	let authorities = [Keyring::Alice, Keyring::Bob, Keyring::Charlie];
+	let mut epoch = test_epoch(&authorities);
+	let keystore = create_keystore(Keyring::Bob);
+	let ring_context = vrf::RingContext::new_testing();
+
+	let tickets = generate_epoch_tickets(&mut epoch, &ring_context, &keystore);
+	assert_eq!(tickets.len(), epoch.config.attempts_number as usize);
+	assert_eq!(epoch.tickets_aux.len(), tickets.len());
+
+	let public_keys: Vec<_> = authorities.iter().map(|a| a.public()).collect();
+	let verifier = ring_context.verifier(&public_keys);
+	for ticket in &tickets {
+		let input = vrf::ticket_id_input(&epoch.randomness, ticket.body.attempt_idx, epoch.index);
+		let sign_data = vrf::ticket_body_sign_data(&ticket.body, input);
+		assert!(ticket.signature.ring_vrf_verify(&sign_data, &verifier));
+
+		let ticket_id = vrf::make_ticket_id(&ticket.signature.pre_output);
+		assert_eq!(epoch.tickets_aux.get(&ticket_id).map(|(idx, _)| *idx), Some(1));
+	}
+
+	// Claim a slot with a ticket.
+	let slot = Slot::from(7);
+	let ticket_id = vrf::make_ticket_id(&tickets[0].signature.pre_output);
+	let ticket = Some((ticket_id, tickets[0].body.clone()));
+	let (claim, author) = claim_slot(slot, &epoch, ticket.clone(), &keystore).unwrap();
+	assert_eq!(author, Keyring::Bob.public().into());
+	assert!(claim.ticket_claim.is_some());
+	check(sealed_header(&claim, Keyring::Bob), &claim, &epoch, ticket.clone()).unwrap();
+
+	// The claim must match the ticket assigned to the slot.
+	assert!(matches!(
+		check(sealed_header(&claim, Keyring::Bob), &claim, &epoch, None),
+		Err(Error::UnexpectedTicketClaim(_)),
+	));
+	let other_ticket = Some((ticket_id, tickets[1].body.clone()));
+	assert!(matches!(
+		check(sealed_header(&claim, Keyring::Bob), &claim, &epoch, other_ticket),
+		Err(Error::TicketVerificationFailed(_)),
+	));
+
+	// Tickets owned by other authorities can't be claimed.
+	assert!(claim_slot(slot, &epoch, ticket.clone(), &create_keystore(Keyring::Alice)).is_none());
+
+	// A slot assigned to a ticket can't be claimed without it.
+	let fallback_idx = secondary_authority_index(slot, &epoch).unwrap();
+	let fallback_keystore = create_keystore(authorities[fallback_idx as usize]);
+	let (claim, _) = claim_slot(slot, &epoch, None, &fallback_keystore).unwrap();
+	let header = sealed_header(&claim, authorities[fallback_idx as usize]);
+	assert!(matches!(
+		check(header, &claim, &epoch, ticket),
+		Err(Error::MissingTicketClaim(_))
+	));
+}
+
+#[test]
+fn rejects_bad_seal_and_wrong_author() {
+	let authorities = [Keyring::Alice, Keyring::Bob, Keyring::Charlie];
+	let epoch = test_epoch(&authorities);
+
+	let slot = Slot::from(8);
+	let expected_idx = secondary_authority_index(slot, &epoch).unwrap() as usize;
+	let keystore = create_keystore(authorities[expected_idx]);
+	let (claim, _) = claim_slot(slot, &epoch, None, &keystore).unwrap();
+
+	// Sealed by another authority.
+	let other = authorities[(expected_idx + 1) % authorities.len()];
+	assert!(matches!(
+		check(sealed_header(&claim, other), &claim, &epoch, None),
+		Err(Error::BadSignature(_))
+	));
+
+	// Claimed by an authority which isn't the fallback author of the slot.
+	let data = vrf::slot_claim_sign_data(&epoch.randomness, slot, epoch.index);
+	let other_idx = (expected_idx + 1) % authorities.len();
+	let claim = SlotClaim {
+		authority_idx: other_idx as AuthorityIndex,
+		slot,
+		vrf_signature: other.pair().vrf_sign(&data),
+		ticket_claim: None,
+	};
+	assert!(matches!(
+		check(sealed_header(&claim, other), &claim, &epoch, None),
+		Err(Error::InvalidAuthor(expected, got))
+			if expected as usize == expected_idx && got as usize == other_idx
+	));
+
+	// Claimed with a VRF signature for another slot.
+	let (mut claim, _) = claim_slot(slot, &epoch, None, &keystore).unwrap();
+	claim.slot = slot + 3 * EPOCH_LENGTH as u64;
+	assert!(matches!(
+		check(sealed_header(&claim, authorities[expected_idx]), &claim, &epoch, None),
+		Err(Error::VrfVerificationFailed)
+	));
+}
+
+fn test_client() -> (Arc<TestClient>, Arc<TestBackend>, TestBlockImport, SassafrasLink<TestBlock>) {
+	let genesis_epoch = sp_consensus_sassafras::Epoch {
+		index: 0,
+		start: 0.into(),
+		length: EPOCH_LENGTH,
+		randomness: [0; 32],
+		authorities: vec![Keyring::Alice.public().into()],
+		config: EpochConfiguration { redundancy_factor: 1, attempts_number: 2 },
+	};
+	let (client, backend) = TestClientBuilder::new()
+		.add_extra_storage(TEST_RUNTIME_SASSAFRAS_EPOCH_KEY.to_vec(), genesis_epoch.encode())
+		.build_with_backend();
+	let client = Arc::new(client);
+	let config = configuration(&*client, SlotDuration::from_millis(6000)).unwrap();
+	assert_eq!(config.epoch, genesis_epoch);
+
+	let (block_import, link) = block_import(config, client.clone(), client.clone()).unwrap();
+	(client, backend, block_import, link)
+}
+
+/// Build and import a block authored by Alice on top of `parent_hash` at the given `slot`.
+async fn import_block(
+	client: &TestClient,
+	block_import: &TestBlockImport,
+	link: &SassafrasLink<TestBlock>,
+	parent_hash: Hash,
+	slot: u64,
+	announce_epoch: bool,
+) -> Result<Hash, ConsensusError> {
+	let slot = Slot::from(slot);
+	let parent_number = client.header(parent_hash).unwrap().unwrap().number;
+
+	let epoch_descriptor = link
+		.epoch_changes
+		.shared_data()
+		.epoch_descriptor_for_child_of(
+			descendent_query(client),
+			&parent_hash,
+			parent_number,
+			slot,
+		)
+		.unwrap()
+		.unwrap();
+	let epoch = link
+		.epoch_changes
+		.shared_data()
+		.viable_epoch(&epoch_descriptor, |slot| Epoch::genesis(&link.config, slot))
+		.unwrap()
+		.into_cloned_inner();
+	let (claim, _) = claim_slot(slot, &epoch, None, &create_keystore(Keyring::Alice)).unwrap();
+
+	let mut builder = BlockBuilderBuilder::new(client)
+		.on_parent_block(parent_hash)
+		.with_parent_block_number(parent_number)
+		.with_inherent_digests(Digest { logs: vec![DigestItem::from(&claim)] })
+		.build()
+		.unwrap();
+	if announce_epoch {
+		let descriptor = NextEpochDescriptor {
+			randomness: [parent_number as u8; 32],
+			authorities: epoch.authorities.clone(),
+			config: None,
+		};
+		builder
+			.push_deposit_log_digest_item(DigestItem::Consensus(
+				SASSAFRAS_ENGINE_ID,
+				ConsensusLog::NextEpochData(descriptor).encode(),
+			))
+			.unwrap();
+	}
+	let block = builder.build().unwrap().block;
+	let hash = block.header.hash();
+
+	let (header, body) = block.deconstruct();
+	let mut params = BlockImportParams::new(BlockOrigin::Own, header);
+	params.body = Some(body);
+	params.insert_intermediate(
+		INTERMEDIATE_KEY,
+		SassafrasIntermediate::<TestBlock> { epoch_descriptor },
+	);
+	block_import.import_block(params).await?;
+	Ok(hash)
+}
+
+#[tokio::test]
+async fn importing_blocks_tracks_epoch_changes() {
+	let (client, backend, block_import, link) = test_client();
+	let genesis_hash = client.info().genesis_hash;
+
+	// The first block starts the genesis epoch and announces the next one.
+	assert!(matches!(
+		import_block(&client, &block_import, &link, genesis_hash, 1, false).await,
+		Err(ConsensusError::ClientImport(_))
+	));
+	let mut parent_hash = import_block(&client, &block_import, &link, genesis_hash, 1, true)
+		.await
+		.unwrap();
+	for slot in 2..7 {
+		parent_hash = import_block(&client, &block_import, &link, parent_hash, slot, false)
+			.await
+			.unwrap();
+	}
+
+	// The first block of epoch #1 must announce epoch #2.
+	assert!(matches!(
+		import_block(&client, &block_import, &link, parent_hash, 8, false).await,
+		Err(ConsensusError::ClientImport(_))
+	));
+	assert!(matches!(
+		import_block(&client, &block_import, &link, parent_hash, 6, false).await,
+		Err(ConsensusError::ClientImport(_))
+	));
+	let epoch_one_hash = import_block(&client, &block_import, &link, parent_hash, 8, true)
+		.await
+		.unwrap();
+	assert_eq!(client.info().best_hash, epoch_one_hash);
+	assert_eq!(block_weight(&*client, epoch_one_hash).unwrap(), Some(0));
+
+	let epoch_changes = link.epoch_changes.shared_data();
+	let epoch_at = |parent_hash, parent_number, slot: u64| {
+		let descriptor = epoch_changes
+			.epoch_descriptor_for_child_of(
+				descendent_query(&*client),
+				&parent_hash,
+				parent_number,
+				slot.into(),
+			)
+			.unwrap()
+			.unwrap();
+		epoch_changes
+			.viable_epoch(&descriptor, |slot| Epoch::genesis(&link.config, slot))
+			.unwrap()
+			.into_cloned_inner()
+	};
+
+	let epoch = epoch_at(parent_hash, 6, 6);
+	assert_eq!((epoch.index, *epoch.start), (0, 1));
+	let epoch = epoch_at(epoch_one_hash, 7, 9);
+	assert_eq!((epoch.index, *epoch.start, epoch.randomness), (1, 7, [0; 32]));
+	let epoch = epoch_at(epoch_one_hash, 7, 13);
+	assert_eq!((epoch.index, *epoch.start, epoch.randomness), (2, 13, [6; 32]));
+	drop(epoch_changes);
+
+	// Reverting drops the epoch changes announced by the reverted blocks.
+	revert(client.clone(), backend, 1).unwrap();
+	let epoch_changes = aux_schema::load_epoch_changes::<TestBlock, _>(&*client).unwrap();
+	let tree_len = epoch_changes.shared_data().tree().iter().count();
+	assert_eq!(tree_len, 1);
+	assert_eq!(block_weight(&*client, epoch_one_hash).unwrap(), None);
+}
@@ -2588,7 +2588,7 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 	fn requires_full_sync(&self) -> bool {
 		matches!(
 			self.storage.state_db.pruning_mode(),
-			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical
+			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical | PruningMode::Sparse { .. }
 		)
 	}
 
@@ -3755,6 +3755,42 @@ pub(crate) mod tests {
 		}
 	}
 
+	#[test]
+	fn sparse_pruning_requires_reference_counting_database() {
+		let settings = |source| DatabaseSettings {
+			trie_cache_maximum_size: None,
+			state_pruning: Some(PruningMode::sparse_pruning(2, 4)),
+			blocks_pruning: BlocksPruning::KeepFinalized,
+			source,
+			metrics_registry: None,
+		};
+		let is_unsupported = |result: Result<Backend<Block>, _>| {
+			matches!(
+				result,
+				Err(sp_blockchain::Error::StateDatabase(message))
+					if message.contains("native reference counting")
+			)
+		};
+
+		// Databases without native reference counting, the state db counts the references.
+		let db = sp_database::as_database(kvdb_memorydb::create(crate::utils::NUM_COLUMNS));
+		let source = DatabaseSource::Custom { db, require_create_flag: true };
+		assert!(is_unsupported(Backend::<Block>::new(settings(source), 0)));
+
+		#[cfg(feature = "rocksdb")]
+		{
+			let dir = tempfile::TempDir::new().unwrap();
+			let source = DatabaseSource::RocksDb { path: dir.path().to_owned(), cache_size: 16 };
+			assert!(is_unsupported(Backend::<Block>::new(settings(source), 0)));
+		}
+
+		// ParityDb counts the references natively.
+		let dir = tempfile::TempDir::new().unwrap();
+		let source = DatabaseSource::ParityDb { path: dir.path().to_owned() };
+		let backend = Backend::<Block>::new(settings(source), 0).unwrap();
+		assert_eq!(backend.storage.state_db.pruning_mode(), PruningMode::sparse_pruning(2, 4));
+	}
+
 	#[test]
 	fn prune_blocks_on_finalize() {
 		let pruning_modes =
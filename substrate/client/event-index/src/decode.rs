@@ -0,0 +1,358 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Decoding of `System::Events` using the type registry of the runtime metadata.
+
+use crate::Error;
+use codec::{Compact, Decode};
+use frame_metadata::{v14::StorageEntryType, RuntimeMetadata, RuntimeMetadataPrefixed};
+use scale_info::{
+	form::PortableForm, Field, Path, PortableRegistry, TypeDef, TypeDefPrimitive, Variant,
+};
+
+/// An event decoded from `System::Events`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DecodedEvent {
+	/// Name of the pallet that emitted the event.
+	pub pallet: String,
+	/// Name of the event variant.
+	pub variant: String,
+	/// SCALE encoded topics of the event.
+	pub topics: Vec<Vec<u8>>,
+	/// SCALE encoded accounts found in the fields of the event, without duplicates.
+	pub accounts: Vec<Vec<u8>>,
+}
+
+/// Decodes the events of a runtime.
+pub(crate) struct EventDecoder {
+	registry: PortableRegistry,
+	/// Type id of the `EventRecord` stored in `System::Events`.
+	record_ty: u32,
+}
+
+impl EventDecoder {
+	/// Create a decoder from the SCALE encoded runtime metadata.
+	pub fn from_metadata(metadata: &[u8]) -> Result<Self, Error> {
+		let metadata = RuntimeMetadataPrefixed::decode(&mut &metadata[..])?;
+		let (registry, pallets) = match metadata.1 {
+			RuntimeMetadata::V14(metadata) => (
+				metadata.types,
+				metadata.pallets.into_iter().map(|p| p.storage).collect::<Vec<_>>(),
+			),
+			RuntimeMetadata::V15(metadata) => (
+				metadata.types,
+				metadata.pallets.into_iter().map(|p| p.storage).collect::<Vec<_>>(),
+			),
+			_ => return Err(Error::Metadata("Unsupported metadata version".into())),
+		};
+
+		let events_ty = pallets
+			.into_iter()
+			.flatten()
+			.filter(|storage| storage.prefix == "System")
+			.flat_map(|storage| storage.entries)
+			.find(|entry| entry.name == "Events")
+			.and_then(|entry| match entry.ty {
+				StorageEntryType::Plain(ty) => Some(ty.id),
+				_ => None,
+			})
+			.ok_or_else(|| Error::Metadata("`System::Events` not found".into()))?;
+
+		Self::new(registry, events_ty)
+	}
+
+	/// Create a decoder for the `Vec<EventRecord>` type `events_ty` of the `registry`.
+	pub fn new(registry: PortableRegistry, events_ty: u32) -> Result<Self, Error> {
+		let record_ty = match registry.resolve(events_ty).map(|ty| &ty.type_def) {
+			Some(TypeDef::Sequence(sequence)) => sequence.type_param.id,
+			_ => return Err(Error::Metadata("`System::Events` is not a sequence".into())),
+		};
+		Ok(Self { registry, record_ty })
+	}
+
+	/// Decode the SCALE encoded `System::Events`.
+	pub fn decode(&self, mut input: &[u8]) -> Result<Vec<DecodedEvent>, Error> {
+		let input = &mut input;
+		let len = Compact::<u32>::decode(input)?.0;
+		let fields = match self.registry.resolve(self.record_ty).map(|ty| &ty.type_def) {
+			Some(TypeDef::Composite(composite)) => &composite.fields,
+			_ => return Err(Error::Metadata("`EventRecord` is not a composite".into())),
+		};
+
+		let mut events = Vec::with_capacity(len as usize);
+		for _ in 0..len {
+			let mut event = None;
+			let mut topics = Vec::new();
+			for field in fields {
+				match field.name.as_deref() {
+					Some("event") => event = Some(self.decode_event(field.ty.id, input)?),
+					Some("topics") => topics = self.decode_topics(field.ty.id, input)?,
+					_ => self.skip(field.ty.id, input, &mut Vec::new())?,
+				}
+			}
+			let (pallet, variant, accounts) =
+				event.ok_or_else(|| Error::Metadata("`EventRecord` has no event".into()))?;
+			events.push(DecodedEvent { pallet, variant, topics, accounts });
+		}
+		Ok(events)
+	}
+
+	/// Decode the names of the pallet and the variant of an outer event, and the accounts found
+	/// in its fields.
+	fn decode_event(
+		&self,
+		ty: u32,
+		input: &mut &[u8],
+	) -> Result<(String, String, Vec<Vec<u8>>), Error> {
+		let pallet = self.read_variant(ty, input)?;
+		let [field] = &pallet.fields[..] else {
+			return Err(Error::Metadata(format!("Event of `{}` is not a newtype", pallet.name)))
+		};
+		let variant = self.read_variant(field.ty.id, input)?;
+		let mut accounts = Vec::new();
+		self.skip_fields(&variant.fields, input, &mut accounts)?;
+		Ok((pallet.name.clone(), variant.name.clone(), accounts))
+	}
+
+	/// Decode the topics of an event, keeping each topic in its encoded form.
+	fn decode_topics(&self, ty: u32, input: &mut &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+		let topic_ty = match self.registry.resolve(ty).map(|ty| &ty.type_def) {
+			Some(TypeDef::Sequence(sequence)) => sequence.type_param.id,
+			_ => return Err(Error::Metadata("Event topics are not a sequence".into())),
+		};
+		let len = Compact::<u32>::decode(input)?.0;
+		(0..len)
+			.map(|_| {
+				let start = *input;
+				self.skip(topic_ty, input, &mut Vec::new())?;
+				Ok(start[..start.len() - input.len()].to_vec())
+			})
+			.collect()
+	}
+
+	/// Read the index of a variant of the enum `ty`.
+	fn read_variant(&self, ty: u32, input: &mut &[u8]) -> Result<&Variant<PortableForm>, Error> {
+		let Some(TypeDef::Variant(def)) = self.registry.resolve(ty).map(|ty| &ty.type_def) else {
+			return Err(Error::Metadata(format!("Type {ty} is not an enum")))
+		};
+		let index = u8::decode(input)?;
+		def.variants
+			.iter()
+			.find(|variant| variant.index == index)
+			.ok_or_else(|| Error::Metadata(format!("Unknown variant {index} of type {ty}")))
+	}
+
+	fn skip_fields(
+		&self,
+		fields: &[Field<PortableForm>],
+		input: &mut &[u8],
+		accounts: &mut Vec<Vec<u8>>,
+	) -> Result<(), Error> {
+		fields.iter().try_for_each(|field| self.skip(field.ty.id, input, accounts))
+	}
+
+	/// Skip a value of type `ty`, collecting the accounts it contains into `accounts`.
+	fn skip(&self, ty: u32, input: &mut &[u8], accounts: &mut Vec<Vec<u8>>) -> Result<(), Error> {
+		let ty_info =
+			self.registry.resolve(ty).ok_or_else(|| Error::Metadata(format!("Unknown type {ty}")))?;
+		if is_account(&ty_info.path) {
+			let start = *input;
+			self.skip_def(ty, &ty_info.type_def, input, &mut Vec::new())?;
+			let account = start[..start.len() - input.len()].to_vec();
+			if !accounts.contains(&account) {
+				accounts.push(account);
+			}
+			return Ok(())
+		}
+		self.skip_def(ty, &ty_info.type_def, input, accounts)
+	}
+
+	fn skip_def(
+		&self,
+		ty: u32,
+		def: &TypeDef<PortableForm>,
+		input: &mut &[u8],
+		accounts: &mut Vec<Vec<u8>>,
+	) -> Result<(), Error> {
+		match def {
+			TypeDef::Composite(composite) => self.skip_fields(&composite.fields, input, accounts),
+			TypeDef::Variant(_) => {
+				let variant = self.read_variant(ty, input)?;
+				self.skip_fields(&variant.fields, input, accounts)
+			},
+			TypeDef::Sequence(sequence) => {
+				let len = Compact::<u32>::decode(input)?.0;
+				self.skip_many(sequence.type_param.id, len, input, accounts)
+			},
+			TypeDef::Array(array) =>
+				self.skip_many(array.type_param.id, array.len, input, accounts),
+			TypeDef::Tuple(tuple) =>
+				tuple.fields.iter().try_for_each(|field| self.skip(field.id, input, accounts)),
+			TypeDef::Primitive(primitive) => {
+				let len = match primitive {
+					TypeDefPrimitive::Str => Compact::<u32>::decode(input)?.0 as usize,
+					primitive => primitive_size(primitive),
+				};
+				advance(input, len)
+			},
+			TypeDef::Compact(_) => skip_compact(input),
+			TypeDef::BitSequence(bits) => {
+				let store_bits = match self.registry.resolve(bits.bit_store_type.id) {
+					Some(scale_info::Type { type_def: TypeDef::Primitive(primitive), .. })
+						if primitive_size(primitive) > 0 =>
+						primitive_size(primitive) * 8,
+					_ => return Err(Error::Metadata("Unsupported bit store type".into())),
+				};
+				let len = Compact::<u32>::decode(input)?.0 as usize;
+				advance(input, len.div_ceil(store_bits) * store_bits / 8)
+			},
+		}
+	}
+
+	fn skip_many(
+		&self,
+		ty: u32,
+		len: u32,
+		input: &mut &[u8],
+		accounts: &mut Vec<Vec<u8>>,
+	) -> Result<(), Error> {
+		if let Some(TypeDef::Primitive(TypeDefPrimitive::U8)) =
+			self.registry.resolve(ty).map(|ty| &ty.type_def)
+		{
+			return advance(input, len as usize)
+		}
+		(0..len).try_for_each(|_| self.skip(ty, input, accounts))
+	}
+}
+
+/// Whether the type with the given path is an account identifier.
+fn is_account(path: &Path<PortableForm>) -> bool {
+	path.segments.last().is_some_and(|name| name == "AccountId32" || name == "AccountId20")
+}
+
+/// Size of a fixed width primitive, `0` for strings.
+fn primitive_size(primitive: &TypeDefPrimitive) -> usize {
+	match primitive {
+		TypeDefPrimitive::Bool | TypeDefPrimitive::U8 | TypeDefPrimitive::I8 => 1,
+		TypeDefPrimitive::U16 | TypeDefPrimitive::I16 => 2,
+		TypeDefPrimitive::Char | TypeDefPrimitive::U32 | TypeDefPrimitive::I32 => 4,
+		TypeDefPrimitive::U64 | TypeDefPrimitive::I64 => 8,
+		TypeDefPrimitive::U128 | TypeDefPrimitive::I128 => 16,
+		TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => 32,
+		TypeDefPrimitive::Str => 0,
+	}
+}
+
+/// Skip a compact encoded integer.
+fn skip_compact(input: &mut &[u8]) -> Result<(), Error> {
+	let first = *input.first().ok_or_else(|| codec::Error::from("Not enough data"))?;
+	let len = match first & 0b11 {
+		0b00 => 1,
+		0b01 => 2,
+		0b10 => 4,
+		_ => (first >> 2) as usize + 5,
+	};
+	advance(input, len)
+}
+
+fn advance(input: &mut &[u8], len: usize) -> Result<(), Error> {
+	if input.len() < len {
+		return Err(codec::Error::from("Not enough data").into())
+	}
+	*input = &input[len..];
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use scale_info::{meta_type, Registry, TypeInfo};
+	use sp_core::H256;
+
+	#[derive(Encode, TypeInfo)]
+	enum Phase {
+		ApplyExtrinsic(u32),
+	}
+
+	#[derive(Encode, TypeInfo)]
+	struct AccountId32([u8; 32]);
+
+	#[derive(Encode, TypeInfo)]
+	enum BalancesEvent {
+		#[codec(index = 2)]
+		Transfer {
+			from: AccountId32,
+			to: AccountId32,
+			#[codec(compact)]
+			amount: u128,
+			memo: String,
+		},
+	}
+
+	#[derive(Encode, TypeInfo)]
+	enum RuntimeEvent {
+		#[codec(index = 5)]
+		Balances(BalancesEvent),
+	}
+
+	#[derive(Encode, TypeInfo)]
+	struct EventRecord {
+		phase: Phase,
+		event: RuntimeEvent,
+		topics: Vec<H256>,
+	}
+
+	#[test]
+	fn events_are_decoded_using_the_registry() {
+		let mut registry = Registry::new();
+		let events_ty = registry.register_type(&meta_type::<Vec<EventRecord>>()).id;
+		let decoder = EventDecoder::new(registry.into(), events_ty).unwrap();
+
+		let transfer = |to, amount, topics| EventRecord {
+			phase: Phase::ApplyExtrinsic(1),
+			event: RuntimeEvent::Balances(BalancesEvent::Transfer {
+				from: AccountId32([1; 32]),
+				to: AccountId32(to),
+				amount,
+				memo: "memo".into(),
+			}),
+			topics,
+		};
+		let events = vec![
+			transfer([2; 32], u128::MAX, vec![H256::repeat_byte(1)]),
+			transfer([1; 32], 1, Vec::new()),
+		];
+
+		let expected = |topics: Vec<Vec<u8>>, accounts: Vec<Vec<u8>>| DecodedEvent {
+			pallet: "Balances".into(),
+			variant: "Transfer".into(),
+			topics,
+			accounts,
+		};
+		assert_eq!(
+			decoder.decode(&events.encode()).unwrap(),
+			vec![
+				expected(vec![vec![1; 32]], vec![vec![1; 32], vec![2; 32]]),
+				// The same account is only reported once.
+				expected(Vec::new(), vec![vec![1; 32]]),
+			],
+		);
+		assert!(decoder.decode(&events.encode()[..40]).is_err());
+	}
+}
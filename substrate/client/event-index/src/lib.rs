@@ -0,0 +1,571 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Index of the runtime events emitted by finalized blocks.
+//!
+//! [`run_event_indexer`] decodes `System::Events` of every finalized block using the metadata of
+//! the runtime and records in the auxiliary storage of the client which block emitted which
+//! events, by pallet, by pallet and event variant, by event topic and by the accounts found in
+//! the fields of the events. [`EventIndex::query`] looks up the events matching an
+//! [`EventFilter`] in a range of blocks.
+//!
+//! Only finalized blocks are indexed, so the index never has to deal with forks. Entries are
+//! grouped in buckets of [`BUCKET_SIZE`] blocks to bound the size of the values rewritten when
+//! a block is indexed and the number of lookups needed to query a range of blocks.
+//!
+//! Blocks whose events can't be read are recorded as pending and retried each time a block is
+//! finalized, up to [`MAX_PENDING_BLOCKS`] of them.
+
+#![warn(missing_docs)]
+
+mod decode;
+
+use codec::{Decode, Encode};
+use decode::{DecodedEvent, EventDecoder};
+use futures::StreamExt;
+use log::{debug, warn};
+use sc_client_api::{
+	backend::Backend, AuxStore, BlockchainEvents, HeaderBackend, StorageKey, StorageProvider,
+};
+use sp_api::{Core, Metadata, ProvideRuntimeApi};
+use sp_core::hashing::twox_128;
+use sp_runtime::{traits::Block as BlockT, SaturatedConversion};
+use std::{collections::BTreeMap, marker::PhantomData, sync::Arc};
+
+/// Number of blocks whose events share a database entry.
+pub const BUCKET_SIZE: u64 = 64;
+
+/// Maximum number of buckets looked up by a single query.
+///
+/// Queries covering more buckets return a cursor to continue from.
+pub const MAX_BUCKETS_PER_QUERY: u64 = 1024;
+
+/// Maximum number of blocks whose events couldn't be read that are kept for a retry.
+///
+/// The indexer stops making progress once this many blocks are pending.
+pub const MAX_PENDING_BLOCKS: usize = 1024;
+
+/// Key under which the number of the last indexed block is stored.
+const LAST_INDEXED_KEY: &[u8] = b"event_index_last_indexed";
+
+/// Key under which the numbers of the blocks to retry are stored.
+const PENDING_KEY: &[u8] = b"event_index_pending";
+
+/// Prefix of the keys indexing events by pallet and variant.
+const EVENT_PREFIX: &[u8] = b"event_index_event";
+
+/// Prefix of the keys indexing events by topic.
+const TOPIC_PREFIX: &[u8] = b"event_index_topic";
+
+/// Prefix of the keys indexing events by account.
+const ACCOUNT_PREFIX: &[u8] = b"event_index_account";
+
+const LOG_TARGET: &str = "event-index";
+
+/// Event index errors.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// Client error.
+	#[error(transparent)]
+	Client(#[from] sp_blockchain::Error),
+	/// Runtime API error.
+	#[error(transparent)]
+	RuntimeApi(#[from] sp_api::ApiError),
+	/// Decoding error.
+	#[error(transparent)]
+	Codec(#[from] codec::Error),
+	/// The runtime metadata can't be used to decode events.
+	#[error("Invalid runtime metadata: {0}")]
+	Metadata(String),
+}
+
+/// Events an [`EventQuery`] is looking for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventFilter {
+	/// Events emitted by a pallet.
+	Pallet {
+		/// Name of the pallet in the runtime.
+		pallet: String,
+		/// Name of the event variant, any event of the pallet if `None`.
+		variant: Option<String>,
+	},
+	/// Events with the given SCALE encoded topic.
+	Topic(Vec<u8>),
+	/// Events with the given SCALE encoded account in their fields.
+	Account(Vec<u8>),
+}
+
+/// Position of an event in the chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
+pub struct EventCursor {
+	/// Number of the block that emitted the event.
+	pub block_number: u64,
+	/// Index of the event in `System::Events` of the block.
+	pub event_index: u32,
+}
+
+/// An event found in the index.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct IndexedEvent<Hash> {
+	/// Number of the block that emitted the event.
+	pub block_number: u64,
+	/// Hash of the block that emitted the event.
+	pub block_hash: Hash,
+	/// Index of the event in `System::Events` of the block.
+	pub event_index: u32,
+}
+
+impl<Hash> IndexedEvent<Hash> {
+	/// Position of the event in the chain.
+	pub fn cursor(&self) -> EventCursor {
+		EventCursor { block_number: self.block_number, event_index: self.event_index }
+	}
+}
+
+/// A query of the events emitted by a range of blocks.
+#[derive(Debug, Clone)]
+pub struct EventQuery {
+	/// The events to look for.
+	pub filter: EventFilter,
+	/// First block of the range.
+	pub from: u64,
+	/// Last block of the range, inclusive.
+	pub to: u64,
+	/// Position to continue a previous query from, inclusive.
+	pub start: Option<EventCursor>,
+	/// Maximum number of events to return.
+	pub limit: usize,
+}
+
+/// A page of the events matching an [`EventQuery`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventPage<Hash> {
+	/// Matching events ordered by their position in the chain.
+	pub events: Vec<IndexedEvent<Hash>>,
+	/// Position to continue the query from if there may be more matching events.
+	pub next: Option<EventCursor>,
+	/// Number of the last indexed block, events of later blocks are not returned.
+	pub last_indexed: Option<u64>,
+	/// Blocks of the range whose events couldn't be read yet, their events are not returned.
+	pub pending: Vec<u64>,
+}
+
+/// Index of the events emitted by finalized blocks.
+pub struct EventIndex<Block, Client> {
+	client: Arc<Client>,
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block, Client> EventIndex<Block, Client>
+where
+	Block: BlockT,
+	Client: AuxStore,
+{
+	/// Create a new instance reading the index from the auxiliary storage of `client`.
+	pub fn new(client: Arc<Client>) -> Self {
+		Self { client, _phantom: PhantomData }
+	}
+
+	/// Number of the last indexed block.
+	pub fn last_indexed(&self) -> Result<Option<u64>, Error> {
+		self.read(LAST_INDEXED_KEY)
+	}
+
+	/// Numbers of the blocks whose events couldn't be read yet.
+	pub fn pending(&self) -> Result<Vec<u64>, Error> {
+		Ok(self.read(PENDING_KEY)?.unwrap_or_default())
+	}
+
+	/// Look up the events matching `query`.
+	pub fn query(&self, query: &EventQuery) -> Result<EventPage<Block::Hash>, Error> {
+		let start = query
+			.start
+			.unwrap_or(EventCursor { block_number: query.from, event_index: 0 })
+			.max(EventCursor { block_number: query.from, event_index: 0 });
+		let last_indexed = self.last_indexed()?;
+		let to = query.to.min(last_indexed.unwrap_or_default());
+		let pending = self
+			.pending()?
+			.into_iter()
+			.filter(|number| (query.from..=to).contains(number))
+			.collect::<Vec<_>>();
+
+		let mut events = Vec::new();
+		let mut bucket = start.block_number / BUCKET_SIZE;
+		let last_bucket = to / BUCKET_SIZE;
+		let mut scanned = 0;
+		while start.block_number <= to && bucket <= last_bucket {
+			if scanned == MAX_BUCKETS_PER_QUERY {
+				let next = EventCursor { block_number: bucket * BUCKET_SIZE, event_index: 0 };
+				return Ok(EventPage { events, next: Some(next), last_indexed, pending })
+			}
+
+			let entries: Vec<IndexedEvent<Block::Hash>> =
+				self.read(&filter_key(&query.filter, bucket))?.unwrap_or_default();
+			for entry in entries {
+				if entry.cursor() < start || entry.block_number > to {
+					continue
+				}
+				if events.len() == query.limit {
+					let next = Some(entry.cursor());
+					return Ok(EventPage { events, next, last_indexed, pending })
+				}
+				events.push(entry);
+			}
+
+			bucket += 1;
+			scanned += 1;
+		}
+
+		Ok(EventPage { events, next: None, last_indexed, pending })
+	}
+
+	/// Record the `events` emitted by the block `number`.
+	///
+	/// The block may be older than the last indexed block if it was pending.
+	fn index_block(
+		&self,
+		number: u64,
+		hash: Block::Hash,
+		events: &[DecodedEvent],
+	) -> Result<(), Error> {
+		let bucket = number / BUCKET_SIZE;
+		let mut entries = BTreeMap::<_, Vec<_>>::new();
+		for (event_index, event) in events.iter().enumerate() {
+			let entry = IndexedEvent {
+				block_number: number,
+				block_hash: hash,
+				event_index: event_index as u32,
+			};
+			let pallet = |variant| EventFilter::Pallet { pallet: event.pallet.clone(), variant };
+			let filters = [pallet(None), pallet(Some(event.variant.clone()))]
+				.into_iter()
+				.chain(event.topics.iter().cloned().map(EventFilter::Topic))
+				.chain(event.accounts.iter().cloned().map(EventFilter::Account));
+			for filter in filters {
+				entries.entry(filter_key(&filter, bucket)).or_default().push(entry.clone());
+			}
+		}
+
+		let mut values = Vec::with_capacity(entries.len());
+		for (key, new_entries) in entries {
+			let mut bucket_entries: Vec<IndexedEvent<Block::Hash>> =
+				self.read(&key)?.unwrap_or_default();
+			// Makes indexing the same block again a no-op.
+			bucket_entries.retain(|entry| entry.block_number != number);
+			bucket_entries.extend(new_entries);
+			bucket_entries.sort_by_key(IndexedEvent::cursor);
+			values.push((key, bucket_entries.encode()));
+		}
+
+		let mut pending = self.pending()?;
+		pending.retain(|pending| *pending != number);
+		self.commit(values, number, pending)
+	}
+
+	/// Skip the block `number` whose events couldn't be read, to retry it later.
+	fn defer_block(&self, number: u64) -> Result<(), Error> {
+		let mut pending = self.pending()?;
+		if !pending.contains(&number) {
+			pending.push(number);
+		}
+		self.commit(Vec::new(), number, pending)
+	}
+
+	/// Write the index `values` and the `pending` blocks, and mark the block `number` as indexed.
+	fn commit(
+		&self,
+		values: Vec<(Vec<u8>, Vec<u8>)>,
+		number: u64,
+		pending: Vec<u64>,
+	) -> Result<(), Error> {
+		let last_indexed = self.last_indexed()?.map_or(number, |last| last.max(number)).encode();
+		let pending = pending.encode();
+		let insert = values
+			.iter()
+			.map(|(key, value)| (&key[..], &value[..]))
+			.chain([(LAST_INDEXED_KEY, &last_indexed[..]), (PENDING_KEY, &pending[..])])
+			.collect::<Vec<_>>();
+		self.client.insert_aux(&insert, &[])?;
+		Ok(())
+	}
+
+	fn read<T: Decode>(&self, key: &[u8]) -> Result<Option<T>, Error> {
+		self.client
+			.get_aux(key)?
+			.map(|value| T::decode(&mut &value[..]))
+			.transpose()
+			.map_err(Into::into)
+	}
+}
+
+/// Database key of the entries matching `filter` in `bucket`.
+fn filter_key(filter: &EventFilter, bucket: u64) -> Vec<u8> {
+	match filter {
+		EventFilter::Pallet { pallet, variant } => (EVENT_PREFIX, pallet, variant, bucket).encode(),
+		EventFilter::Topic(topic) => (TOPIC_PREFIX, topic, bucket).encode(),
+		EventFilter::Account(account) => (ACCOUNT_PREFIX, account, bucket).encode(),
+	}
+}
+
+/// Decodes the events of finalized blocks and adds them to the index.
+struct Indexer<Block, Client> {
+	client: Arc<Client>,
+	index: EventIndex<Block, Client>,
+	/// Event decoder of the last seen runtime, by spec version.
+	decoder: Option<(u32, EventDecoder)>,
+}
+
+impl<Block, Client> Indexer<Block, Client>
+where
+	Block: BlockT,
+	Client: HeaderBackend<Block> + ProvideRuntimeApi<Block> + AuxStore,
+	Client::Api: Core<Block> + Metadata<Block>,
+{
+	/// Index all finalized blocks that were not indexed yet.
+	fn catch_up<BE>(&mut self) -> Result<(), Error>
+	where
+		BE: Backend<Block>,
+		Client: StorageProvider<Block, BE>,
+	{
+		for number in self.index.pending()? {
+			let hash = self.finalized_hash(number)?;
+			match self.block_events(hash) {
+				Ok(events) => self.index.index_block(number, hash, &events)?,
+				Err(e) => debug!(
+					target: LOG_TARGET,
+					"Failed again to read events of block #{number} ({hash}): {e}"
+				),
+			}
+		}
+
+		let finalized = self.client.info().finalized_number.saturated_into::<u64>();
+		let mut number = self.index.last_indexed()?.map_or(1, |last| last + 1);
+		while number <= finalized {
+			let hash = self.finalized_hash(number)?;
+			match self.block_events(hash) {
+				Ok(events) => self.index.index_block(number, hash, &events)?,
+				// Blocks whose events can't be read are retried later rather than stalling the
+				// index, unless too many of them are pending already.
+				Err(e) if self.index.pending()?.len() < MAX_PENDING_BLOCKS => {
+					warn!(
+						target: LOG_TARGET,
+						"Failed to read events of block #{number} ({hash}), retrying later: {e}"
+					);
+					self.index.defer_block(number)?;
+				},
+				Err(e) => return Err(e),
+			}
+			number += 1;
+		}
+		Ok(())
+	}
+
+	/// Hash of the finalized block `number`.
+	fn finalized_hash(&self, number: u64) -> Result<Block::Hash, Error> {
+		let hash = self.client.hash(number.saturated_into())?.ok_or_else(|| {
+			sp_blockchain::Error::UnknownBlock(format!("Finalized block #{number}"))
+		})?;
+		Ok(hash)
+	}
+
+	/// Decode the events emitted by the block `hash`.
+	fn block_events<BE>(&mut self, hash: Block::Hash) -> Result<Vec<DecodedEvent>, Error>
+	where
+		BE: Backend<Block>,
+		Client: StorageProvider<Block, BE>,
+	{
+		let key = StorageKey([twox_128(b"System"), twox_128(b"Events")].concat());
+		let Some(events) = self.client.storage(hash, &key)? else { return Ok(Vec::new()) };
+
+		let api = self.client.runtime_api();
+		let spec_version = api.version(hash)?.spec_version;
+		if self.decoder.as_ref().map_or(true, |(version, _)| *version != spec_version) {
+			debug!(target: LOG_TARGET, "Loading event metadata of spec version {spec_version}");
+			let metadata = match api.metadata_at_version(hash, 15) {
+				Ok(Some(metadata)) => metadata,
+				_ => api.metadata(hash)?,
+			};
+			self.decoder = Some((spec_version, EventDecoder::from_metadata(&metadata)?));
+		}
+		let (_, decoder) = self.decoder.as_ref().expect("Decoder was set above; qed");
+		decoder.decode(&events.0)
+	}
+}
+
+/// Index the events of finalized blocks as they get finalized.
+///
+/// Starts with the blocks finalized while the indexer wasn't running. Requires the state of all
+/// finalized blocks, so it should only be used with archive nodes.
+///
+/// Indexing reads the database and calls the runtime synchronously, possibly for a long time
+/// while catching up, so the returned future must be spawned as a blocking task.
+pub async fn run_event_indexer<Block, BE, Client>(client: Arc<Client>)
+where
+	Block: BlockT,
+	BE: Backend<Block>,
+	Client: BlockchainEvents<Block>
+		+ HeaderBackend<Block>
+		+ StorageProvider<Block, BE>
+		+ ProvideRuntimeApi<Block>
+		+ AuxStore
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: Core<Block> + Metadata<Block>,
+{
+	let mut finality_notifications = client.finality_notification_stream();
+	let mut indexer = Indexer { index: EventIndex::new(client.clone()), client, decoder: None };
+
+	loop {
+		if let Err(e) = indexer.catch_up::<BE>() {
+			warn!(target: LOG_TARGET, "Failed to index events: {e}");
+		}
+		if finality_notifications.next().await.is_none() {
+			return
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::H256;
+	use substrate_test_runtime_client::runtime::Block;
+
+	fn event(pallet: &str, variant: &str, topics: Vec<Vec<u8>>) -> DecodedEvent {
+		DecodedEvent {
+			pallet: pallet.into(),
+			variant: variant.into(),
+			topics,
+			accounts: Vec::new(),
+		}
+	}
+
+	fn query(filter: EventFilter, from: u64, to: u64, limit: usize) -> EventQuery {
+		EventQuery { filter, from, to, start: None, limit }
+	}
+
+	fn balances(variant: Option<&str>) -> EventFilter {
+		EventFilter::Pallet { pallet: "Balances".into(), variant: variant.map(Into::into) }
+	}
+
+	#[test]
+	fn events_are_found_by_pallet_variant_topic_and_account() {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let index = EventIndex::<Block, _>::new(client);
+		assert_eq!(index.last_indexed().unwrap(), None);
+
+		let topic = vec![7; 32];
+		for number in 1..=200u64 {
+			let mut events = vec![event("System", "ExtrinsicSuccess", Vec::new())];
+			if number % 10 == 0 {
+				let mut transfer = event("Balances", "Transfer", vec![topic.clone()]);
+				transfer.accounts = vec![vec![1; 32], vec![(number % 20) as u8; 32]];
+				events.push(transfer);
+			}
+			if number % 50 == 0 {
+				events.push(event("Balances", "Deposit", Vec::new()));
+			}
+			index.index_block(number, H256::repeat_byte(number as u8), &events).unwrap();
+		}
+		assert_eq!(index.last_indexed().unwrap(), Some(200));
+
+		let page = index.query(&query(balances(Some("Transfer")), 1, 1000, 100)).unwrap();
+		assert_eq!(page.events.len(), 20);
+		assert_eq!(page.next, None);
+		assert_eq!(page.last_indexed, Some(200));
+		assert_eq!(
+			page.events[0],
+			IndexedEvent { block_number: 10, block_hash: H256::repeat_byte(10), event_index: 1 },
+		);
+
+		let page = index.query(&query(balances(None), 45, 100, 100)).unwrap();
+		let positions =
+			page.events.iter().map(|e| (e.block_number, e.event_index)).collect::<Vec<_>>();
+		assert_eq!(
+			positions,
+			vec![(50, 1), (50, 2), (60, 1), (70, 1), (80, 1), (90, 1), (100, 1), (100, 2)],
+		);
+
+		let page = index.query(&query(EventFilter::Topic(topic), 1, 200, 100)).unwrap();
+		assert_eq!(page.events.len(), 20);
+
+		let page = index.query(&query(EventFilter::Account(vec![1; 32]), 1, 200, 100)).unwrap();
+		assert_eq!(page.events.len(), 20);
+		let page = index.query(&query(EventFilter::Account(vec![10; 32]), 1, 200, 100)).unwrap();
+		let blocks = page.events.iter().map(|e| e.block_number).collect::<Vec<_>>();
+		assert_eq!(blocks, (0..10).map(|n| n * 20 + 10).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn pending_blocks_are_indexed_later() {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let index = EventIndex::<Block, _>::new(client);
+		let transfer = || vec![event("Balances", "Transfer", Vec::new())];
+
+		index.index_block(1, H256::repeat_byte(1), &transfer()).unwrap();
+		index.defer_block(2).unwrap();
+		index.index_block(3, H256::repeat_byte(3), &transfer()).unwrap();
+		assert_eq!(index.last_indexed().unwrap(), Some(3));
+		assert_eq!(index.pending().unwrap(), vec![2]);
+
+		let page = index.query(&query(balances(Some("Transfer")), 1, 3, 100)).unwrap();
+		assert_eq!(page.events.iter().map(|e| e.block_number).collect::<Vec<_>>(), vec![1, 3]);
+		assert_eq!(page.pending, vec![2]);
+
+		// Indexing the pending block keeps the events ordered and the last indexed block.
+		index.index_block(2, H256::repeat_byte(2), &transfer()).unwrap();
+		assert_eq!(index.last_indexed().unwrap(), Some(3));
+		assert_eq!(index.pending().unwrap(), Vec::<u64>::new());
+
+		let page = index.query(&query(balances(Some("Transfer")), 1, 3, 100)).unwrap();
+		assert_eq!(page.events.iter().map(|e| e.block_number).collect::<Vec<_>>(), vec![1, 2, 3]);
+		assert!(page.pending.is_empty());
+	}
+
+	#[test]
+	fn queries_are_paginated() {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let index = EventIndex::<Block, _>::new(client);
+		for number in 1..=100u64 {
+			let events = vec![event("Balances", "Transfer", Vec::new()); 2];
+			index.index_block(number, H256::repeat_byte(number as u8), &events).unwrap();
+		}
+		// Indexing a block again doesn't duplicate its events.
+		let events = vec![event("Balances", "Transfer", Vec::new()); 2];
+		index.index_block(100, H256::repeat_byte(100), &events).unwrap();
+
+		let mut query = query(balances(Some("Transfer")), 1, 100, 15);
+		let mut found = Vec::new();
+		loop {
+			let page = index.query(&query).unwrap();
+			assert!(page.events.len() <= 15);
+			found.extend(page.events);
+			match page.next {
+				Some(next) => query.start = Some(next),
+				None => break,
+			}
+		}
+
+		let expected =
+			(1..=100u64).flat_map(|number| [(number, 0), (number, 1)]).collect::<Vec<_>>();
+		let found = found.iter().map(|e| (e.block_number, e.event_index)).collect::<Vec<_>>();
+		assert_eq!(found, expected);
+	}
+}
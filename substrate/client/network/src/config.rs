@@ -584,6 +584,15 @@ impl NotificationConfig for NonDefaultSetConfig {
 	}
 }
 
+/// Location of a trusted warp sync checkpoint.
+#[derive(Clone, Debug)]
+pub struct WarpSyncCheckpointConfig {
+	/// Path of the file holding the SCALE encoded checkpoint.
+	pub path: PathBuf,
+	/// Hash the encoded checkpoint must have, see `WarpSyncCheckpoint::hash` of `sc-network-sync`.
+	pub trusted_hash: Vec<u8>,
+}
+
 /// Network service configuration.
 #[derive(Clone, Debug)]
 pub struct NetworkConfiguration {
@@ -629,6 +638,9 @@ pub struct NetworkConfiguration {
 	/// Number of peers that need to be connected before warp sync is started.
 	pub min_peers_to_start_warp_sync: Option<usize>,
 
+	/// Trusted checkpoint to start warp sync from instead of the genesis authority set.
+	pub warp_sync_checkpoint: Option<WarpSyncCheckpointConfig>,
+
 	/// Initial syncing mode.
 	pub sync_mode: SyncMode,
 
@@ -680,6 +692,7 @@ impl NetworkConfiguration {
 			max_parallel_downloads: 5,
 			max_blocks_per_request: 64,
 			min_peers_to_start_warp_sync: None,
+			warp_sync_checkpoint: None,
 			sync_mode: SyncMode::Full,
 			enable_dht_random_walk: true,
 			allow_non_globals_in_dht: false,
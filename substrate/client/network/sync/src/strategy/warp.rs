@@ -30,7 +30,7 @@ use crate::{
 	types::{BadPeer, SyncState, SyncStatus},
 	LOG_TARGET,
 };
-use codec::{Decode, Encode};
+use codec::{Decode, DecodeAll, Encode};
 use futures::{channel::oneshot, FutureExt};
 use log::{debug, error, trace, warn};
 use sc_network::{IfDisconnected, ProtocolName};
@@ -40,7 +40,7 @@ use sc_network_common::sync::message::{
 use sc_network_types::PeerId;
 use sp_blockchain::HeaderBackend;
 use sp_runtime::{
-	traits::{Block as BlockT, Header, NumberFor, Zero},
+	traits::{Block as BlockT, Hash as HashT, Header, NumberFor, Zero},
 	Justifications, SaturatedConversion,
 };
 use std::{any::Any, collections::HashMap, fmt, sync::Arc};
@@ -151,10 +151,57 @@ pub struct WarpSyncProgress<Block: BlockT> {
 	pub total_bytes: u64,
 }
 
+/// A trusted starting point for warp sync.
+///
+/// Warp proofs are only requested for the authority set changes after the checkpoint, the
+/// range up to the checkpoint is covered by trusting it instead. The checkpoint is trusted by its
+/// [hash](WarpSyncCheckpoint::hash), which commits to the header as well as to the authority set.
+#[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+pub struct WarpSyncCheckpoint<Block: BlockT> {
+	/// Header of a finalized block.
+	pub header: Block::Header,
+	/// Id of the authority set that finalizes the blocks following the checkpoint.
+	pub set_id: SetId,
+	/// Authorities of the set `set_id`.
+	pub authorities: AuthorityList,
+}
+
+impl<Block: BlockT> WarpSyncCheckpoint<Block> {
+	/// Hash of the SCALE encoded checkpoint, hashed with the hashing of the block header.
+	pub fn hash(&self) -> Block::Hash {
+		<<Block::Header as Header>::Hashing as HashT>::hash_of(self)
+	}
+
+	/// Decode a SCALE encoded checkpoint and ensure the encoding hashes to `trusted_hash`.
+	///
+	/// See [`WarpSyncCheckpoint::hash`].
+	pub fn decode_and_verify(encoded: &[u8], trusted_hash: &[u8]) -> Result<Self, String> {
+		let hash = <<Block::Header as Header>::Hashing as HashT>::hash(encoded);
+		if hash.as_ref() != trusted_hash {
+			return Err(format!(
+				"Warp sync checkpoint hash {hash:?} does not match the trusted hash 0x{}",
+				array_bytes::bytes2hex("", trusted_hash),
+			))
+		}
+		Self::decode_all(&mut &encoded[..])
+			.map_err(|e| format!("Failed to decode warp sync checkpoint: {e}"))
+	}
+}
+
 /// Warp sync configuration as accepted by [`WarpSync`].
 pub enum WarpSyncConfig<Block: BlockT> {
 	/// Standard warp sync for the chain.
 	WithProvider(Arc<dyn WarpSyncProvider<Block>>),
+	/// Warp sync starting from a trusted checkpoint instead of the genesis authority set.
+	///
+	/// It is expected that the checkpoint was verified against a trusted hash, see
+	/// [`WarpSyncCheckpoint::decode_and_verify`].
+	WithCheckpoint {
+		/// Provider used to verify the warp proofs following the checkpoint.
+		provider: Arc<dyn WarpSyncProvider<Block>>,
+		/// The checkpoint to start from.
+		checkpoint: WarpSyncCheckpoint<Block>,
+	},
 	/// Skip downloading proofs and use provided header of the state that should be downloaded.
 	///
 	/// It is expected that the header provider ensures that the header is trusted.
@@ -164,7 +211,10 @@ pub enum WarpSyncConfig<Block: BlockT> {
 /// Warp sync phase used by warp sync state machine.
 enum Phase<B: BlockT> {
 	/// Waiting for enough peers to connect.
-	WaitingForPeers { warp_sync_provider: Arc<dyn WarpSyncProvider<B>> },
+	WaitingForPeers {
+		warp_sync_provider: Arc<dyn WarpSyncProvider<B>>,
+		checkpoint: Option<WarpSyncCheckpoint<B>>,
+	},
 	/// Downloading warp proofs.
 	WarpProof {
 		set_id: SetId,
@@ -259,7 +309,18 @@ where
 
 		let phase = match warp_sync_config {
 			WarpSyncConfig::WithProvider(warp_sync_provider) =>
-				Phase::WaitingForPeers { warp_sync_provider },
+				Phase::WaitingForPeers { warp_sync_provider, checkpoint: None },
+			WarpSyncConfig::WithCheckpoint { provider, checkpoint } => {
+				debug!(
+					target: LOG_TARGET,
+					"Starting warp sync from checkpoint #{} ({}), set_id={}.",
+					checkpoint.header.number(),
+					checkpoint.header.hash(),
+					checkpoint.set_id,
+				);
+				let checkpoint = Some(checkpoint);
+				Phase::WaitingForPeers { warp_sync_provider: provider, checkpoint }
+			},
 			WarpSyncConfig::WithTarget(target_header) => Phase::TargetBlock(target_header),
 		};
 
@@ -321,16 +382,23 @@ where
 
 	/// Start warp sync as soon as we have enough peers.
 	fn try_to_start_warp_sync(&mut self) {
-		let Phase::WaitingForPeers { warp_sync_provider } = &self.phase else { return };
+		let Phase::WaitingForPeers { warp_sync_provider, checkpoint } = &mut self.phase else {
+			return
+		};
 
 		if self.peers.len() < self.min_peers_to_start_warp_sync {
 			return
 		}
 
+		let (set_id, authorities, last_hash) = match checkpoint.take() {
+			Some(checkpoint) =>
+				(checkpoint.set_id, checkpoint.authorities, checkpoint.header.hash()),
+			None => (0, warp_sync_provider.current_authorities(), self.client.info().genesis_hash),
+		};
 		self.phase = Phase::WarpProof {
-			set_id: 0,
-			authorities: warp_sync_provider.current_authorities(),
-			last_hash: self.client.info().genesis_hash,
+			set_id,
+			authorities,
+			last_hash,
 			warp_sync_provider: Arc::clone(warp_sync_provider),
 		};
 		trace!(target: LOG_TARGET, "Started warp sync with {} peers.", self.peers.len());
@@ -746,11 +814,12 @@ mod test {
 	use sp_blockchain::{BlockStatus, Error as BlockchainError, HeaderBackend, Info};
 	use sp_consensus_grandpa::{AuthorityList, SetId};
 	use sp_core::H256;
-	use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
+	use sp_runtime::traits::{BlakeTwo256, Block as BlockT, Header as HeaderT, NumberFor};
 	use std::{io::ErrorKind, sync::Arc};
 	use substrate_test_runtime_client::{
 		runtime::{Block, Hash},
-		BlockBuilderExt, DefaultTestClientBuilderExt, TestClientBuilder, TestClientBuilderExt,
+		BlockBuilderExt, DefaultTestClientBuilderExt, Ed25519Keyring, TestClientBuilder,
+		TestClientBuilderExt,
 	};
 
 	mockall::mock! {
@@ -949,6 +1018,110 @@ mod test {
 		assert!(matches!(warp_sync.phase, Phase::WarpProof { .. }))
 	}
 
+	#[test]
+	fn warp_sync_with_checkpoint_starts_from_checkpoint() {
+		let client = mock_client_without_state();
+		let mut provider = MockWarpSyncProvider::<Block>::new();
+		provider.expect_current_authorities().never();
+		let header = <Block as BlockT>::Header::new(
+			100,
+			Default::default(),
+			Default::default(),
+			Default::default(),
+			Default::default(),
+		);
+		let checkpoint =
+			WarpSyncCheckpoint::<Block> { header: header.clone(), set_id: 5, authorities: vec![] };
+		let checkpoint =
+			WarpSyncCheckpoint::decode_and_verify(&checkpoint.encode(), checkpoint.hash().as_ref())
+				.unwrap();
+		let config = WarpSyncConfig::WithCheckpoint { provider: Arc::new(provider), checkpoint };
+		let mut warp_sync = WarpSync::new(
+			Arc::new(client),
+			config,
+			None,
+			Arc::new(MockBlockDownloader::new()),
+			None,
+		);
+
+		for best_number in 1..11 {
+			warp_sync.add_peer(PeerId::random(), Hash::random(), best_number);
+		}
+
+		// Proofs are requested starting from the checkpoint with its authority set.
+		let Phase::WarpProof { set_id, last_hash, .. } = &warp_sync.phase else {
+			panic!("Warp sync should be downloading proofs");
+		};
+		assert_eq!(*set_id, 5);
+		assert_eq!(*last_hash, header.hash());
+	}
+
+	#[test]
+	fn warp_sync_checkpoint_with_untrusted_hash_is_rejected() {
+		let checkpoint = WarpSyncCheckpoint::<Block> {
+			header: <Block as BlockT>::Header::new(
+				100,
+				Default::default(),
+				Default::default(),
+				Default::default(),
+				Default::default(),
+			),
+			set_id: 5,
+			authorities: Default::default(),
+		};
+
+		assert!(WarpSyncCheckpoint::<Block>::decode_and_verify(
+			&checkpoint.encode(),
+			Hash::random().as_ref()
+		)
+		.is_err());
+		// The header hash alone is not trusted.
+		assert!(WarpSyncCheckpoint::<Block>::decode_and_verify(
+			&checkpoint.encode(),
+			checkpoint.header.hash().as_ref()
+		)
+		.is_err());
+		assert!(WarpSyncCheckpoint::<Block>::decode_and_verify(
+			&[1, 2, 3],
+			BlakeTwo256::hash(&[1, 2, 3]).as_ref()
+		)
+		.is_err());
+	}
+
+	#[test]
+	fn warp_sync_checkpoint_with_tampered_authorities_is_rejected() {
+		let checkpoint = WarpSyncCheckpoint::<Block> {
+			header: <Block as BlockT>::Header::new(
+				100,
+				Default::default(),
+				Default::default(),
+				Default::default(),
+				Default::default(),
+			),
+			set_id: 5,
+			authorities: vec![(Ed25519Keyring::Alice.public().into(), 1)],
+		};
+		let trusted_hash = checkpoint.hash();
+		assert_eq!(
+			WarpSyncCheckpoint::<Block>::decode_and_verify(
+				&checkpoint.encode(),
+				trusted_hash.as_ref()
+			),
+			Ok(checkpoint.clone())
+		);
+
+		// Same header, but other authorities.
+		let tampered = WarpSyncCheckpoint::<Block> {
+			authorities: vec![(Ed25519Keyring::Bob.public().into(), 1)],
+			..checkpoint
+		};
+		assert!(WarpSyncCheckpoint::<Block>::decode_and_verify(
+			&tampered.encode(),
+			trusted_hash.as_ref()
+		)
+		.is_err());
+	}
+
 	#[test]
 	fn no_peer_is_scheduled_if_no_peers_connected() {
 		let client = mock_client_without_state();
@@ -125,6 +125,7 @@ impl<TBlock: BlockT> RequestHandler<TBlock> {
 	pub async fn run(mut self) {
 		while let Some(request) = self.request_receiver.next().await {
 			let IncomingRequest { peer, payload, pending_response } = request;
+			let _span = tracing::info_span!("handle_warp_proof_request", %peer).entered();
 
 			match self.handle_request(payload, pending_response) {
 				Ok(()) => {
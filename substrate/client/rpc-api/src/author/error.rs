@@ -83,6 +83,10 @@ const POOL_NO_TAGS: i32 = POOL_INVALID_TX + 9;
 const POOL_INVALID_BLOCK_ID: i32 = POOL_INVALID_TX + 10;
 /// The pool is not accepting future transactions.
 const POOL_FUTURE_TX: i32 = POOL_INVALID_TX + 11;
+/// Transaction does not bump the priority enough to replace existing one in the pool.
+const POOL_TOO_LOW_PRIORITY_BUMP: i32 = POOL_INVALID_TX + 12;
+/// The transaction was not included to the pool because of the per-sender limits.
+const POOL_SENDER_LIMIT_EXCEEDED: i32 = POOL_INVALID_TX + 13;
 /// Other error.
 const OTHER_ERR: i32 = BASE_ERROR + 40;
 
@@ -140,6 +144,12 @@ impl From<Error> for ErrorObjectOwned {
 				format!("Priority is too low: ({} vs {})", old, new),
 				Some("The transaction has too low priority to replace another transaction already in the pool.")
 			),
+			Error::Pool(PoolError::TooLowPriorityBump { new, required, .. }) => ErrorObject::owned(
+				POOL_TOO_LOW_PRIORITY_BUMP,
+				format!("Priority bump is too low: ({} vs {})", new, required),
+				Some("The transaction does not bump the priority enough to replace another \
+				transaction already in the pool.")
+			),
 			Error::Pool(PoolError::CycleDetected) =>
 				ErrorObject::owned(
 				POOL_CYCLE_DETECTED,
@@ -175,6 +185,11 @@ impl From<Error> for ErrorObjectOwned {
 					None::<()>,
 				)
 			},
+			Error::Pool(PoolError::SenderLimitExceeded) => ErrorObject::owned(
+				POOL_SENDER_LIMIT_EXCEEDED,
+				"Sender Limit Exceeded",
+				Some("The transaction couldn't enter the pool because of the per-sender limit"),
+			),
 			Error::UnsafeRpcCalled(e) => e.into(),
 			other => ErrorObject::owned(
 				OTHER_ERR,
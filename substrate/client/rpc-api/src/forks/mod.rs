@@ -0,0 +1,126 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Substrate forks API, reporting the competing forks known to the node and the reorganizations
+//! of its best chain.
+
+pub mod error;
+
+use error::Error;
+use jsonrpsee::proc_macros::rpc;
+use serde::{Deserialize, Serialize};
+
+/// Fork-choice data of a block, as seen by the consensus engine.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkChoice {
+	/// Weight of the chain ending at the block.
+	///
+	/// For BABE this is the number of blocks of the chain authored in a primary slot.
+	pub weight: u64,
+	/// Slot the block was authored in, if the consensus engine is slot based.
+	pub slot: Option<u64>,
+	/// Index of the authority which authored the block in the authority set of its epoch, if the
+	/// consensus engine exposes it.
+	pub author: Option<u32>,
+}
+
+/// Provides the fork-choice data of the blocks to the forks API.
+pub trait ForkChoiceProvider<Hash>: Send + Sync {
+	/// Fork-choice data of the block `hash`, `None` if it isn't known.
+	fn fork_choice(&self, hash: Hash) -> Option<ForkChoice>;
+}
+
+/// A leaf of the block tree, the head of a fork.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Leaf<Hash, Number> {
+	/// Hash of the leaf.
+	pub hash: Hash,
+	/// Number of the leaf.
+	pub number: Number,
+	/// Whether the leaf is the best block.
+	pub is_best: bool,
+	/// Last block the fork shares with the best chain.
+	pub common_ancestor: Hash,
+	/// Number of blocks of the fork after the common ancestor, `0` for the best block.
+	pub fork_length: u32,
+	/// Fork-choice data of the leaf, if a fork-choice provider is configured.
+	pub fork_choice: Option<ForkChoice>,
+}
+
+/// The leaf set of the node returned by the `forks_getLeaves` RPC.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Leaves<Hash, Number> {
+	/// Hash of the best block.
+	pub best_hash: Hash,
+	/// Number of the best block.
+	pub best_number: Number,
+	/// Hash of the last finalized block.
+	pub finalized_hash: Hash,
+	/// Number of the last finalized block.
+	pub finalized_number: Number,
+	/// Number of blocks of the best chain which aren't finalized yet.
+	pub finality_lag: Number,
+	/// All the leaves, including the best block.
+	pub leaves: Vec<Leaf<Hash, Number>>,
+}
+
+/// A reorganization of the best chain sent by the `forks_subscribeReorgs` subscription.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reorg<Hash, Number> {
+	/// Hash of the previous best block.
+	pub from: Hash,
+	/// Number of the previous best block.
+	pub from_number: Number,
+	/// Hash of the new best block.
+	pub to: Hash,
+	/// Number of the new best block.
+	pub to_number: Number,
+	/// Hash of the last block shared by both chains.
+	pub common_ancestor: Hash,
+	/// Number of the last block shared by both chains.
+	pub common_ancestor_number: Number,
+	/// Blocks removed from the best chain, from the previous best block towards the common
+	/// ancestor.
+	pub retracted: Vec<Hash>,
+	/// Blocks added to the best chain, from the common ancestor towards the new best block.
+	pub enacted: Vec<Hash>,
+}
+
+/// Substrate forks API.
+#[rpc(client, server)]
+pub trait ForksApi<Hash, Number> {
+	/// Get the leaves of the block tree, with their fork-choice data and their fork from the
+	/// best chain.
+	///
+	/// This method is unsafe, the cost of computing the fork of each leaf grows with the number
+	/// of leaves.
+	#[method(name = "forks_getLeaves", with_extensions)]
+	async fn leaves(&self) -> Result<Leaves<Hash, Number>, Error>;
+
+	/// Subscribe to the reorganizations of the best chain.
+	#[subscription(
+		name = "forks_subscribeReorgs" => "forks_reorg",
+		unsubscribe = "forks_unsubscribeReorgs",
+		item = Reorg<Hash, Number>,
+	)]
+	fn subscribe_reorgs(&self);
+}
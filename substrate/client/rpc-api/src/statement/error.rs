@@ -32,6 +32,12 @@ pub enum Error {
 	/// Call to an unsafe RPC was denied.
 	#[error(transparent)]
 	UnsafeRpcCalled(#[from] crate::policy::UnsafeRpcError),
+	/// The topic filter can't match any statement.
+	#[error("Invalid topic filter: {0}")]
+	InvalidTopicFilter(String),
+	/// The connection reached its limit of statement subscriptions.
+	#[error("Too many statement subscriptions on the connection, at most {0} are allowed")]
+	TooManySubscriptions(usize),
 }
 
 /// Base error code for all statement errors.
@@ -46,6 +52,10 @@ impl From<Error> for ErrorObjectOwned {
 				None::<()>,
 			),
 			Error::UnsafeRpcCalled(e) => e.into(),
+			e @ Error::InvalidTopicFilter(_) =>
+				ErrorObject::owned(BASE_ERROR + 2, e.to_string(), None::<()>),
+			e @ Error::TooManySubscriptions(_) =>
+				ErrorObject::owned(BASE_ERROR + 3, e.to_string(), None::<()>),
 		}
 	}
 }
@@ -19,10 +19,21 @@
 //! Substrate Statement Store RPC API.
 
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use serde::{Deserialize, Serialize};
 use sp_core::Bytes;
 
 pub mod error;
 
+/// Topics a statement must include to be sent by `statement_subscribe`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TopicFilter {
+	/// Statements including all the topics. Matches every statement when empty.
+	MatchAll(Vec<[u8; 32]>),
+	/// Statements including at least one of the topics.
+	MatchAny(Vec<[u8; 32]>),
+}
+
 /// Substrate statement RPC API
 #[rpc(client, server)]
 pub trait StatementApi {
@@ -92,4 +103,19 @@ pub trait StatementApi {
 	/// Remove a statement from the store.
 	#[method(name = "statement_remove")]
 	fn remove(&self, statement_hash: [u8; 32]) -> RpcResult<()>;
+
+	/// Subscribe to the statements newly accepted by the store which match `filter`, sent
+	/// SCALE-encoded.
+	///
+	/// Without `dest` only statements with no `DecryptionKey` field are sent, as with
+	/// `statement_broadcastsStatement`. Otherwise only the statements whose decryption key is
+	/// identified as `dest` are sent, as with `statement_postedStatement`.
+	///
+	/// The subscription is dropped if the client doesn't keep up with the new statements.
+	#[subscription(
+		name = "statement_subscribe" => "statement_statement",
+		unsubscribe = "statement_unsubscribe",
+		item = Bytes,
+	)]
+	fn subscribe(&self, filter: TopicFilter, dest: Option<[u8; 32]>);
 }
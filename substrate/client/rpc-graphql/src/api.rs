@@ -0,0 +1,46 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! API trait of the GraphQL service.
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use serde::{Deserialize, Serialize};
+
+/// A GraphQL request, as sent in the body of a GraphQL over HTTP `POST` request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphQlRequest {
+	/// The GraphQL document.
+	pub query: String,
+	/// Name of the operation of the document to execute.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub operation_name: Option<String>,
+	/// Values of the variables of the operation.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub variables: Option<serde_json::Value>,
+}
+
+#[rpc(client, server)]
+pub trait GraphQlApi {
+	/// Execute a GraphQL request over the chain data.
+	///
+	/// Returns the GraphQL response, which reports the errors of the request in its `errors`
+	/// field.
+	#[method(name = "graphql_query", blocking)]
+	fn graphql_query(&self, request: GraphQlRequest) -> RpcResult<serde_json::Value>;
+}
@@ -0,0 +1,181 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Access to the chain data, independent of the block and client types.
+
+use crate::{metadata::ChainMetadata, Error};
+use codec::{DecodeAll, Encode};
+use log::debug;
+use parking_lot::Mutex;
+use sc_client_api::{Backend, BlockBackend, StorageKey, StorageProvider};
+use sp_api::{Core, Metadata, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	traits::{Block as BlockT, Hash, HashingFor, Header as HeaderT},
+	SaturatedConversion,
+};
+use std::{collections::HashMap, marker::PhantomData, sync::Arc};
+
+const LOG_TARGET: &str = "rpc-graphql";
+
+/// Header of a block, with the hashes SCALE encoded.
+#[derive(Debug, Clone)]
+pub(crate) struct BlockHeader {
+	pub hash: Vec<u8>,
+	pub number: u64,
+	pub parent_hash: Vec<u8>,
+	pub state_root: Vec<u8>,
+	pub extrinsics_root: Vec<u8>,
+}
+
+/// The chain data served by the GraphQL schema.
+pub(crate) trait ChainData: Send + Sync {
+	/// Hash of the best block, or of the last finalized block if `finalized` is set.
+	fn head(&self, finalized: bool) -> Vec<u8>;
+
+	/// Hash of the block `number` of the best chain, or of the finalized chain if `finalized`
+	/// is set.
+	fn hash(&self, number: u64, finalized: bool) -> Result<Option<Vec<u8>>, Error>;
+
+	/// Header of the block `hash`.
+	fn header(&self, hash: &[u8]) -> Result<Option<BlockHeader>, Error>;
+
+	/// Whether the block `header` is part of the finalized chain.
+	fn is_finalized(&self, header: &BlockHeader) -> Result<bool, Error>;
+
+	/// SCALE encoded extrinsics of the block `hash`.
+	fn extrinsics(&self, hash: &[u8]) -> Result<Vec<Vec<u8>>, Error>;
+
+	/// Hash of a SCALE encoded extrinsic.
+	fn extrinsic_hash(&self, extrinsic: &[u8]) -> Vec<u8>;
+
+	/// Value of the storage `key` at the block `hash`.
+	fn storage(&self, hash: &[u8], key: &[u8]) -> Result<Option<Vec<u8>>, Error>;
+
+	/// Metadata of the runtime of the block `hash`.
+	fn metadata(&self, hash: &[u8]) -> Result<Arc<ChainMetadata>, Error>;
+}
+
+/// [`ChainData`] of a client.
+pub(crate) struct Chain<Block, BE, Client> {
+	client: Arc<Client>,
+	/// Metadata of the runtimes seen so far, by spec version.
+	metadata: Mutex<HashMap<u32, Arc<ChainMetadata>>>,
+	_phantom: PhantomData<fn() -> (Block, BE)>,
+}
+
+impl<Block, BE, Client> Chain<Block, BE, Client> {
+	/// Create a new [`Chain`].
+	pub fn new(client: Arc<Client>) -> Self {
+		Self { client, metadata: Default::default(), _phantom: PhantomData }
+	}
+}
+
+impl<Block, BE, Client> Chain<Block, BE, Client>
+where
+	Block: BlockT,
+	Client: HeaderBackend<Block>,
+{
+	fn block_hash(hash: &[u8]) -> Result<Block::Hash, Error> {
+		Block::Hash::decode_all(&mut &hash[..]).map_err(|_| {
+			let hash = array_bytes::bytes2hex("0x", hash);
+			Error::InvalidQuery(format!("Invalid block hash {hash}"))
+		})
+	}
+
+	fn best_or_finalized(&self, finalized: bool) -> (Block::Hash, u64) {
+		let info = self.client.info();
+		if finalized {
+			(info.finalized_hash, info.finalized_number.saturated_into())
+		} else {
+			(info.best_hash, info.best_number.saturated_into())
+		}
+	}
+}
+
+impl<Block, BE, Client> ChainData for Chain<Block, BE, Client>
+where
+	Block: BlockT,
+	BE: Backend<Block>,
+	Client: HeaderBackend<Block>
+		+ BlockBackend<Block>
+		+ StorageProvider<Block, BE>
+		+ ProvideRuntimeApi<Block>
+		+ Send
+		+ Sync,
+	Client::Api: Core<Block> + Metadata<Block>,
+{
+	fn head(&self, finalized: bool) -> Vec<u8> {
+		self.best_or_finalized(finalized).0.encode()
+	}
+
+	fn hash(&self, number: u64, finalized: bool) -> Result<Option<Vec<u8>>, Error> {
+		if number > self.best_or_finalized(finalized).1 {
+			return Ok(None)
+		}
+		// The canonical chain is the chain of the best block.
+		Ok(self.client.hash(number.saturated_into())?.map(|hash| hash.encode()))
+	}
+
+	fn header(&self, hash: &[u8]) -> Result<Option<BlockHeader>, Error> {
+		let Some(header) = self.client.header(Self::block_hash(hash)?)? else { return Ok(None) };
+		Ok(Some(BlockHeader {
+			hash: header.hash().encode(),
+			number: (*header.number()).saturated_into(),
+			parent_hash: header.parent_hash().encode(),
+			state_root: header.state_root().encode(),
+			extrinsics_root: header.extrinsics_root().encode(),
+		}))
+	}
+
+	fn is_finalized(&self, header: &BlockHeader) -> Result<bool, Error> {
+		Ok(self.hash(header.number, true)?.as_ref() == Some(&header.hash))
+	}
+
+	fn extrinsics(&self, hash: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+		let body = self.client.block_body(Self::block_hash(hash)?)?;
+		Ok(body.unwrap_or_default().iter().map(Encode::encode).collect())
+	}
+
+	fn extrinsic_hash(&self, extrinsic: &[u8]) -> Vec<u8> {
+		HashingFor::<Block>::hash(extrinsic).encode()
+	}
+
+	fn storage(&self, hash: &[u8], key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+		let value = self.client.storage(Self::block_hash(hash)?, &StorageKey(key.to_vec()))?;
+		Ok(value.map(|value| value.0))
+	}
+
+	fn metadata(&self, hash: &[u8]) -> Result<Arc<ChainMetadata>, Error> {
+		let hash = Self::block_hash(hash)?;
+		let api = self.client.runtime_api();
+		let spec_version = api.version(hash)?.spec_version;
+		if let Some(metadata) = self.metadata.lock().get(&spec_version) {
+			return Ok(metadata.clone())
+		}
+
+		debug!(target: LOG_TARGET, "Loading metadata of spec version {spec_version}");
+		let metadata = match api.metadata_at_version(hash, 15) {
+			Ok(Some(metadata)) => metadata,
+			_ => api.metadata(hash)?,
+		};
+		let metadata = Arc::new(ChainMetadata::from_metadata(&metadata)?);
+		self.metadata.lock().insert(spec_version, metadata.clone());
+		Ok(metadata)
+	}
+}
@@ -0,0 +1,265 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Decoding of SCALE encoded values into JSON using the type registry of the runtime metadata.
+
+use crate::Error;
+use codec::{Compact, Decode};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive, Variant};
+use serde_json::{Map, Value};
+
+/// Decodes SCALE encoded values of the types of a registry into JSON.
+///
+/// Composites with named fields become objects, newtypes become their inner value, unit variants
+/// become their name and other variants become an object keyed by the variant name. Byte sequences
+/// and arrays become hex strings. Integers of 64 and 128 bits become decimal strings and wider
+/// integers become hex strings, so they are not truncated by JSON parsers.
+pub(crate) struct ValueDecoder<'a>(pub &'a PortableRegistry);
+
+impl<'a> ValueDecoder<'a> {
+	/// Decode a value of type `ty`.
+	pub fn value(&self, ty: u32, input: &mut &[u8]) -> Result<Value, Error> {
+		match self.resolve(ty)? {
+			TypeDef::Composite(composite) => self.fields(&composite.fields, input),
+			TypeDef::Variant(_) => {
+				let (variant, fields) = self.variant(ty, input)?;
+				if variant.fields.is_empty() {
+					Ok(Value::String(variant.name.clone()))
+				} else {
+					Ok(Value::Object(Map::from_iter([(variant.name.clone(), fields)])))
+				}
+			},
+			TypeDef::Sequence(sequence) => {
+				let len = Compact::<u32>::decode(input)?.0;
+				self.many(sequence.type_param.id, len, input)
+			},
+			TypeDef::Array(array) => self.many(array.type_param.id, array.len, input),
+			TypeDef::Tuple(tuple) if tuple.fields.is_empty() => Ok(Value::Null),
+			TypeDef::Tuple(tuple) =>
+				tuple.fields.iter().map(|field| self.value(field.id, input)).collect(),
+			TypeDef::Primitive(primitive) => primitive_value(primitive, input),
+			TypeDef::Compact(compact) => self.compact(compact.type_param.id, input),
+			TypeDef::BitSequence(bits) => {
+				let store_bits = match self.resolve(bits.bit_store_type.id)? {
+					TypeDef::Primitive(primitive) if primitive_size(primitive) > 0 =>
+						primitive_size(primitive) * 8,
+					_ => return Err(Error::Metadata("Unsupported bit store type".into())),
+				};
+				let len = Compact::<u32>::decode(input)?.0 as usize;
+				Ok(hex(take(input, len.div_ceil(store_bits) * store_bits / 8)?))
+			},
+		}
+	}
+
+	/// Decode a variant of the enum `ty`, returning the variant and its decoded fields.
+	pub fn variant(
+		&self,
+		ty: u32,
+		input: &mut &[u8],
+	) -> Result<(&'a Variant<PortableForm>, Value), Error> {
+		let TypeDef::Variant(def) = self.resolve(ty)? else {
+			return Err(Error::Metadata(format!("Type {ty} is not an enum")))
+		};
+		let index = u8::decode(input)?;
+		let variant = def
+			.variants
+			.iter()
+			.find(|variant| variant.index == index)
+			.ok_or_else(|| Error::Metadata(format!("Unknown variant {index} of type {ty}")))?;
+		Ok((variant, self.fields(&variant.fields, input)?))
+	}
+
+	/// Decode a variant of an outer enum wrapping the enum of a pallet, such as `RuntimeCall` or
+	/// `RuntimeEvent`, returning the name of the pallet, the inner variant and its fields.
+	pub fn outer_variant(
+		&self,
+		ty: u32,
+		input: &mut &[u8],
+	) -> Result<(&'a str, &'a Variant<PortableForm>, Value), Error> {
+		let TypeDef::Variant(def) = self.resolve(ty)? else {
+			return Err(Error::Metadata(format!("Type {ty} is not an enum")))
+		};
+		let index = u8::decode(input)?;
+		let pallet = def
+			.variants
+			.iter()
+			.find(|variant| variant.index == index)
+			.ok_or_else(|| Error::Metadata(format!("Unknown variant {index} of type {ty}")))?;
+		let [field] = &pallet.fields[..] else {
+			return Err(Error::Metadata(format!("Variant `{}` is not a newtype", pallet.name)))
+		};
+		let (variant, fields) = self.variant(field.ty.id, input)?;
+		Ok((&pallet.name, variant, fields))
+	}
+
+	fn fields(&self, fields: &[Field<PortableForm>], input: &mut &[u8]) -> Result<Value, Error> {
+		match fields {
+			[] => Ok(Value::Null),
+			[field] if field.name.is_none() => self.value(field.ty.id, input),
+			fields if fields.iter().all(|field| field.name.is_some()) => fields
+				.iter()
+				.map(|field| {
+					let name = field.name.clone().expect("All fields are named; qed");
+					Ok((name, self.value(field.ty.id, input)?))
+				})
+				.collect::<Result<Map<_, _>, Error>>()
+				.map(Value::Object),
+			fields => fields.iter().map(|field| self.value(field.ty.id, input)).collect(),
+		}
+	}
+
+	fn many(&self, ty: u32, len: u32, input: &mut &[u8]) -> Result<Value, Error> {
+		if let TypeDef::Primitive(TypeDefPrimitive::U8) = self.resolve(ty)? {
+			return Ok(hex(take(input, len as usize)?))
+		}
+		(0..len).map(|_| self.value(ty, input)).collect()
+	}
+
+	/// Decode a compact integer, possibly wrapped in newtypes such as `Perbill`.
+	fn compact(&self, mut ty: u32, input: &mut &[u8]) -> Result<Value, Error> {
+		let primitive = loop {
+			match self.resolve(ty)? {
+				TypeDef::Primitive(primitive) => break primitive,
+				TypeDef::Composite(composite) if composite.fields.len() == 1 =>
+					ty = composite.fields[0].ty.id,
+				_ => return Err(Error::Metadata(format!("Type {ty} can't be compact"))),
+			}
+		};
+		let value = Compact::<u128>::decode(input)?.0;
+		Ok(match primitive {
+			TypeDefPrimitive::U8 | TypeDefPrimitive::U16 | TypeDefPrimitive::U32 =>
+				(value as u64).into(),
+			_ => value.to_string().into(),
+		})
+	}
+
+	fn resolve(&self, ty: u32) -> Result<&'a TypeDef<PortableForm>, Error> {
+		self.0
+			.resolve(ty)
+			.map(|ty| &ty.type_def)
+			.ok_or_else(|| Error::Metadata(format!("Unknown type {ty}")))
+	}
+}
+
+fn primitive_value(primitive: &TypeDefPrimitive, input: &mut &[u8]) -> Result<Value, Error> {
+	Ok(match primitive {
+		TypeDefPrimitive::Bool => bool::decode(input)?.into(),
+		TypeDefPrimitive::Char => char::from_u32(u32::decode(input)?)
+			.ok_or_else(|| codec::Error::from("Invalid char"))?
+			.to_string()
+			.into(),
+		TypeDefPrimitive::Str => String::decode(input)?.into(),
+		TypeDefPrimitive::U8 => u8::decode(input)?.into(),
+		TypeDefPrimitive::U16 => u16::decode(input)?.into(),
+		TypeDefPrimitive::U32 => u32::decode(input)?.into(),
+		TypeDefPrimitive::U64 => u64::decode(input)?.to_string().into(),
+		TypeDefPrimitive::U128 => u128::decode(input)?.to_string().into(),
+		TypeDefPrimitive::I8 => i8::decode(input)?.into(),
+		TypeDefPrimitive::I16 => i16::decode(input)?.into(),
+		TypeDefPrimitive::I32 => i32::decode(input)?.into(),
+		TypeDefPrimitive::I64 => i64::decode(input)?.to_string().into(),
+		TypeDefPrimitive::I128 => i128::decode(input)?.to_string().into(),
+		TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => {
+			// Displayed big endian, like the integer it represents.
+			let mut bytes = take(input, 32)?.to_vec();
+			bytes.reverse();
+			hex(&bytes)
+		},
+	})
+}
+
+/// Size of a fixed width primitive, `0` for strings.
+fn primitive_size(primitive: &TypeDefPrimitive) -> usize {
+	match primitive {
+		TypeDefPrimitive::Bool | TypeDefPrimitive::U8 | TypeDefPrimitive::I8 => 1,
+		TypeDefPrimitive::U16 | TypeDefPrimitive::I16 => 2,
+		TypeDefPrimitive::Char | TypeDefPrimitive::U32 | TypeDefPrimitive::I32 => 4,
+		TypeDefPrimitive::U64 | TypeDefPrimitive::I64 => 8,
+		TypeDefPrimitive::U128 | TypeDefPrimitive::I128 => 16,
+		TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => 32,
+		TypeDefPrimitive::Str => 0,
+	}
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], Error> {
+	if input.len() < len {
+		return Err(codec::Error::from("Not enough data").into())
+	}
+	let (bytes, rest) = input.split_at(len);
+	*input = rest;
+	Ok(bytes)
+}
+
+fn hex(bytes: &[u8]) -> Value {
+	array_bytes::bytes2hex("0x", bytes).into()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::{CompactAs, Encode};
+	use scale_info::{meta_type, Registry, TypeInfo};
+	use serde_json::json;
+
+	#[derive(Encode, CompactAs, TypeInfo)]
+	struct Perbill(u32);
+
+	#[derive(Encode, TypeInfo)]
+	enum Call {
+		#[codec(index = 3)]
+		Transfer {
+			dest: [u8; 4],
+			#[codec(compact)]
+			value: u128,
+			#[codec(compact)]
+			fee: Perbill,
+		},
+		Remark(Vec<u8>, String),
+		Noop,
+	}
+
+	fn decode(values: Vec<Call>) -> Result<Value, Error> {
+		let mut registry = Registry::new();
+		let ty = registry.register_type(&meta_type::<Vec<Call>>()).id;
+		let registry: PortableRegistry = registry.into();
+		ValueDecoder(&registry).value(ty, &mut &values.encode()[..])
+	}
+
+	#[test]
+	fn values_are_decoded_to_json() {
+		let calls = vec![
+			Call::Transfer { dest: [1, 2, 3, 4], value: u128::MAX, fee: Perbill(10) },
+			Call::Remark(vec![0xff], "remark".into()),
+			Call::Noop,
+		];
+		assert_eq!(
+			decode(calls).unwrap(),
+			json!([
+				{
+					"Transfer": {
+						"dest": "0x01020304",
+						"value": u128::MAX.to_string(),
+						"fee": 10,
+					}
+				},
+				{ "Remark": ["0xff", "remark"] },
+				"Noop",
+			]),
+		);
+	}
+}
@@ -0,0 +1,112 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! GraphQL queries over the chain data.
+//!
+//! [`GraphQl`] implements the `graphql_query` RPC method, which executes a GraphQL request over
+//! the blocks, extrinsics, events and storage of the best and the finalized chains. Extrinsics,
+//! events and storage values are decoded into JSON using the type registry of the runtime
+//! metadata of each block.
+//!
+//! The service is optional: nodes serve it by merging [`GraphQl::into_rpc`] into their RPC
+//! module. The RPC server proxies `POST /graphql` HTTP requests to `graphql_query`, so GraphQL
+//! requests are served by the same listener and are subject to the same rate limits as the other
+//! RPC calls.
+
+#![warn(missing_docs)]
+
+mod api;
+mod chain;
+mod decode;
+mod metadata;
+mod schema;
+
+#[cfg(test)]
+mod tests;
+
+pub use api::{GraphQlApiClient, GraphQlApiServer, GraphQlRequest};
+pub use schema::MAX_BLOCKS_PER_QUERY;
+
+use async_graphql::Variables;
+use chain::Chain;
+use jsonrpsee::core::RpcResult;
+use sc_client_api::{Backend, BlockBackend, StorageProvider};
+use schema::ChainSchema;
+use sp_api::{Core, Metadata, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+use std::sync::Arc;
+
+/// Errors of the GraphQL service, reported in the `errors` of the GraphQL responses.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// Blockchain error.
+	#[error(transparent)]
+	Blockchain(#[from] sp_blockchain::Error),
+	/// Runtime API error.
+	#[error(transparent)]
+	RuntimeApi(#[from] sp_api::ApiError),
+	/// Decoding error.
+	#[error("Failed to decode: {0}")]
+	Codec(#[from] codec::Error),
+	/// The runtime metadata can't be used to decode the chain data.
+	#[error("Invalid runtime metadata: {0}")]
+	Metadata(String),
+	/// The query is invalid.
+	#[error("Invalid query: {0}")]
+	InvalidQuery(String),
+}
+
+/// The GraphQL service.
+pub struct GraphQl {
+	schema: ChainSchema,
+}
+
+impl GraphQl {
+	/// Create a new [`GraphQl`] serving the chain data of `client`.
+	pub fn new<Block, BE, Client>(client: Arc<Client>) -> Self
+	where
+		Block: BlockT,
+		BE: Backend<Block> + 'static,
+		Client: HeaderBackend<Block>
+			+ BlockBackend<Block>
+			+ StorageProvider<Block, BE>
+			+ ProvideRuntimeApi<Block>
+			+ Send
+			+ Sync
+			+ 'static,
+		Client::Api: Core<Block> + Metadata<Block>,
+	{
+		Self { schema: schema::build(Arc::new(Chain::<Block, BE, Client>::new(client))) }
+	}
+}
+
+impl GraphQlApiServer for GraphQl {
+	fn graphql_query(&self, request: GraphQlRequest) -> RpcResult<serde_json::Value> {
+		let GraphQlRequest { query, operation_name, variables } = request;
+		let mut request = async_graphql::Request::new(query)
+			.variables(Variables::from_json(variables.unwrap_or_default()));
+		if let Some(operation_name) = operation_name {
+			request = request.operation_name(operation_name);
+		}
+
+		// The resolvers read the database synchronously and the method is blocking.
+		let response = futures::executor::block_on(self.schema.execute(request));
+		Ok(serde_json::to_value(response).expect("GraphQL responses serialize to JSON; qed"))
+	}
+}
@@ -0,0 +1,272 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Runtime metadata used to locate and decode storage entries, events and extrinsics.
+
+use crate::{decode::ValueDecoder, Error};
+use codec::{Compact, Decode};
+use frame_metadata::{
+	v14::{PalletStorageMetadata, StorageEntryModifier, StorageEntryType, StorageHasher},
+	RuntimeMetadata, RuntimeMetadataPrefixed,
+};
+use scale_info::{form::PortableForm, PortableRegistry, TypeDef};
+use serde_json::Value;
+use sp_core::hashing::{blake2_128, blake2_256, twox_128, twox_256, twox_64};
+use std::collections::HashMap;
+
+/// A storage entry of a pallet.
+pub(crate) struct StorageEntry {
+	prefix: Vec<u8>,
+	hashers: Vec<StorageHasher>,
+	value_ty: u32,
+	default: Option<Vec<u8>>,
+}
+
+impl StorageEntry {
+	/// The storage key of the entry for the SCALE encoded `keys`, one per hasher of the entry.
+	pub fn key(&self, keys: &[Vec<u8>]) -> Result<Vec<u8>, Error> {
+		if keys.len() != self.hashers.len() {
+			return Err(Error::InvalidQuery(format!(
+				"Expected {} storage keys, got {}",
+				self.hashers.len(),
+				keys.len()
+			)))
+		}
+		let mut storage_key = self.prefix.clone();
+		for (hasher, key) in self.hashers.iter().zip(keys) {
+			match hasher {
+				StorageHasher::Blake2_128 => storage_key.extend(blake2_128(key)),
+				StorageHasher::Blake2_256 => storage_key.extend(blake2_256(key)),
+				StorageHasher::Blake2_128Concat =>
+					storage_key.extend(blake2_128(key).iter().chain(key)),
+				StorageHasher::Twox128 => storage_key.extend(twox_128(key)),
+				StorageHasher::Twox256 => storage_key.extend(twox_256(key)),
+				StorageHasher::Twox64Concat => storage_key.extend(twox_64(key).iter().chain(key)),
+				StorageHasher::Identity => storage_key.extend(key),
+			}
+		}
+		Ok(storage_key)
+	}
+}
+
+/// An event decoded from `System::Events`.
+pub(crate) struct DecodedEvent {
+	pub phase: Value,
+	pub pallet: String,
+	pub name: String,
+	pub fields: Value,
+	pub topics: Vec<String>,
+}
+
+/// A decoded extrinsic.
+pub(crate) struct DecodedExtrinsic {
+	pub signer: Option<Value>,
+	pub pallet: String,
+	pub call: String,
+	pub args: Value,
+}
+
+/// Types of the parts of an extrinsic.
+struct ExtrinsicTypes {
+	address: u32,
+	call: u32,
+	signature: u32,
+	extra: u32,
+}
+
+/// The runtime metadata of a runtime version.
+pub(crate) struct ChainMetadata {
+	registry: PortableRegistry,
+	storage: HashMap<(String, String), StorageEntry>,
+	extrinsic: Option<ExtrinsicTypes>,
+}
+
+impl ChainMetadata {
+	/// Create from the SCALE encoded runtime metadata.
+	pub fn from_metadata(metadata: &[u8]) -> Result<Self, Error> {
+		let metadata = RuntimeMetadataPrefixed::decode(&mut &metadata[..])?;
+		let (registry, pallets, extrinsic) = match metadata.1 {
+			RuntimeMetadata::V14(metadata) => {
+				// The parts of the extrinsic are type parameters of `UncheckedExtrinsic`.
+				let params = metadata
+					.types
+					.resolve(metadata.extrinsic.ty.id)
+					.map(|ty| ty.type_params.clone())
+					.unwrap_or_default();
+				let param = |name: &str| {
+					let param = params.iter().find(|param| param.name == name)?;
+					param.ty.as_ref().map(|ty| ty.id)
+				};
+				let extrinsic = (|| {
+					Some(ExtrinsicTypes {
+						address: param("Address")?,
+						call: param("Call")?,
+						signature: param("Signature")?,
+						extra: param("Extra")?,
+					})
+				})();
+				let pallets = metadata.pallets.into_iter().filter_map(|p| p.storage).collect();
+				(metadata.types, pallets, extrinsic)
+			},
+			RuntimeMetadata::V15(metadata) => {
+				let extrinsic = ExtrinsicTypes {
+					address: metadata.extrinsic.address_ty.id,
+					call: metadata.extrinsic.call_ty.id,
+					signature: metadata.extrinsic.signature_ty.id,
+					extra: metadata.extrinsic.extra_ty.id,
+				};
+				let pallets = metadata.pallets.into_iter().filter_map(|p| p.storage).collect();
+				(metadata.types, pallets, Some(extrinsic))
+			},
+			_ => return Err(Error::Metadata("Unsupported metadata version".into())),
+		};
+		Ok(Self { registry, storage: storage_entries(pallets), extrinsic })
+	}
+
+	/// The storage entry `entry` of `pallet`.
+	pub fn storage_entry(&self, pallet: &str, entry: &str) -> Result<&StorageEntry, Error> {
+		self.storage.get(&(pallet.to_string(), entry.to_string())).ok_or_else(|| {
+			Error::InvalidQuery(format!("Unknown storage entry `{pallet}::{entry}`"))
+		})
+	}
+
+	/// Decode the `value` of a storage entry, falling back to the default value of the entry.
+	pub fn decode_storage(
+		&self,
+		entry: &StorageEntry,
+		value: Option<Vec<u8>>,
+	) -> Result<Option<Value>, Error> {
+		value
+			.or_else(|| entry.default.clone())
+			.map(|value| ValueDecoder(&self.registry).value(entry.value_ty, &mut &value[..]))
+			.transpose()
+	}
+
+	/// Decode the SCALE encoded `System::Events`.
+	pub fn decode_events(&self, mut input: &[u8]) -> Result<Vec<DecodedEvent>, Error> {
+		let input = &mut input;
+		let decoder = ValueDecoder(&self.registry);
+		let events_ty = self.storage_entry("System", "Events")?.value_ty;
+		let record_ty = match self.registry.resolve(events_ty).map(|ty| &ty.type_def) {
+			Some(TypeDef::Sequence(sequence)) => sequence.type_param.id,
+			_ => return Err(Error::Metadata("`System::Events` is not a sequence".into())),
+		};
+		let fields = match self.registry.resolve(record_ty).map(|ty| &ty.type_def) {
+			Some(TypeDef::Composite(composite)) => &composite.fields,
+			_ => return Err(Error::Metadata("`EventRecord` is not a composite".into())),
+		};
+
+		let len = Compact::<u32>::decode(input)?.0;
+		let mut events = Vec::with_capacity(len as usize);
+		for _ in 0..len {
+			let (mut phase, mut event, mut topics) = (Value::Null, None, Vec::new());
+			for field in fields {
+				match field.name.as_deref() {
+					Some("phase") => phase = decoder.value(field.ty.id, input)?,
+					Some("event") => event = Some(decoder.outer_variant(field.ty.id, input)?),
+					Some("topics") => match decoder.value(field.ty.id, input)? {
+						Value::Array(values) => topics = values
+							.into_iter()
+							.filter_map(|topic| topic.as_str().map(Into::into))
+							.collect(),
+						_ => return Err(Error::Metadata("Event topics are not a sequence".into())),
+					},
+					_ => {
+						decoder.value(field.ty.id, input)?;
+					},
+				}
+			}
+			let (pallet, variant, fields) =
+				event.ok_or_else(|| Error::Metadata("`EventRecord` has no event".into()))?;
+			events.push(DecodedEvent {
+				phase,
+				pallet: pallet.into(),
+				name: variant.name.clone(),
+				fields,
+				topics,
+			});
+		}
+		Ok(events)
+	}
+
+	/// Decode a SCALE encoded extrinsic.
+	pub fn decode_extrinsic(&self, mut input: &[u8]) -> Result<DecodedExtrinsic, Error> {
+		let input = &mut input;
+		let decoder = ValueDecoder(&self.registry);
+		let types = self
+			.extrinsic
+			.as_ref()
+			.ok_or_else(|| Error::Metadata("Unknown extrinsic types".into()))?;
+
+		let _len = Compact::<u32>::decode(input)?;
+		let signer = match u8::decode(input)? {
+			// Unsigned and bare extrinsics.
+			0b0000_0100 | 0b0000_0101 => None,
+			0b1000_0100 => {
+				let signer = decoder.value(types.address, input)?;
+				decoder.value(types.signature, input)?;
+				decoder.value(types.extra, input)?;
+				Some(signer)
+			},
+			version => return Err(Error::Metadata(format!("Unsupported extrinsic {version:#x}"))),
+		};
+		let (pallet, call, args) = decoder.outer_variant(types.call, input)?;
+		Ok(DecodedExtrinsic { signer, pallet: pallet.into(), call: call.name.clone(), args })
+	}
+}
+
+fn storage_entries(
+	pallets: Vec<PalletStorageMetadata<PortableForm>>,
+) -> HashMap<(String, String), StorageEntry> {
+	let mut entries = HashMap::new();
+	for pallet in pallets {
+		for entry in pallet.entries {
+			let prefix =
+				[twox_128(pallet.prefix.as_bytes()), twox_128(entry.name.as_bytes())].concat();
+			let (hashers, value_ty) = match entry.ty {
+				StorageEntryType::Plain(ty) => (Vec::new(), ty.id),
+				StorageEntryType::Map { hashers, value, .. } => (hashers, value.id),
+			};
+			let default = match entry.modifier {
+				StorageEntryModifier::Default => Some(entry.default),
+				StorageEntryModifier::Optional => None,
+			};
+			let storage_entry = StorageEntry { prefix, hashers, value_ty, default };
+			entries.insert((pallet.prefix.clone(), entry.name), storage_entry);
+		}
+	}
+	entries
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn storage_keys_are_hashed() {
+		let entry = StorageEntry {
+			prefix: vec![0; 32],
+			hashers: vec![StorageHasher::Twox64Concat, StorageHasher::Identity],
+			value_ty: 0,
+			default: None,
+		};
+		let key = entry.key(&[vec![1, 2], vec![3]]).unwrap();
+		assert_eq!(key, [&[0; 32][..], &twox_64(&[1, 2]), &[1, 2, 3]].concat());
+		assert!(entry.key(&[vec![1, 2]]).is_err());
+	}
+}
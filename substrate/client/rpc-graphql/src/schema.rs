@@ -0,0 +1,279 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The GraphQL schema over the chain data.
+
+use crate::{
+	chain::{BlockHeader, ChainData},
+	Error,
+};
+use async_graphql::{
+	Context, EmptyMutation, EmptySubscription, Json, Object, Result, Schema, SimpleObject,
+};
+use serde_json::Value;
+use sp_core::hashing::twox_128;
+use std::sync::Arc;
+
+/// The maximum number of blocks returned by a `blocks` query.
+pub const MAX_BLOCKS_PER_QUERY: u64 = 100;
+
+/// The maximum depth of a query.
+const MAX_DEPTH: usize = 8;
+
+/// The maximum complexity of a query, the number of fields it selects.
+const MAX_COMPLEXITY: usize = 512;
+
+/// The GraphQL schema.
+pub(crate) type ChainSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+/// Build the schema serving the data of `chain`.
+pub(crate) fn build(chain: Arc<dyn ChainData>) -> ChainSchema {
+	Schema::build(Query, EmptyMutation, EmptySubscription)
+		.data(chain)
+		.limit_depth(MAX_DEPTH)
+		.limit_complexity(MAX_COMPLEXITY)
+		.finish()
+}
+
+fn chain(ctx: &Context<'_>) -> Arc<dyn ChainData> {
+	ctx.data_unchecked::<Arc<dyn ChainData>>().clone()
+}
+
+fn parse_hex(hex: &str) -> Result<Vec<u8>, Error> {
+	array_bytes::hex2bytes(hex).map_err(|_| Error::InvalidQuery(format!("Invalid hex `{hex}`")))
+}
+
+fn hex(bytes: &[u8]) -> String {
+	array_bytes::bytes2hex("0x", bytes)
+}
+
+pub(crate) struct Query;
+
+#[Object]
+impl Query {
+	/// The best block, or the last finalized block if `finalized` is set.
+	async fn head(&self, ctx: &Context<'_>, #[graphql(default)] finalized: bool) -> Result<Block> {
+		let chain = chain(ctx);
+		let hash = chain.head(finalized);
+		Block::load(chain, &hash)?
+			.ok_or_else(|| async_graphql::Error::new("The head block is not in the database"))
+	}
+
+	/// A block, by hash or by number.
+	///
+	/// Blocks are looked up by number on the best chain, or on the finalized chain if `finalized`
+	/// is set. Blocks looked up by hash are only returned when they are finalized if `finalized`
+	/// is set.
+	async fn block(
+		&self,
+		ctx: &Context<'_>,
+		hash: Option<String>,
+		number: Option<u64>,
+		#[graphql(default)] finalized: bool,
+	) -> Result<Option<Block>> {
+		let chain = chain(ctx);
+		let hash = match (hash, number) {
+			(Some(hash), None) => parse_hex(&hash)?,
+			(None, Some(number)) => match chain.hash(number, finalized)? {
+				Some(hash) => hash,
+				None => return Ok(None),
+			},
+			_ => return Err("Exactly one of `hash` and `number` must be given".into()),
+		};
+		let Some(block) = Block::load(chain.clone(), &hash)? else { return Ok(None) };
+		if finalized && !chain.is_finalized(&block.header)? {
+			return Ok(None)
+		}
+		Ok(Some(block))
+	}
+
+	/// The blocks `from..=to` of the best chain, or of the finalized chain if `finalized` is set.
+	///
+	/// At most 100 blocks are returned, and none past the head of the chain.
+	async fn blocks(
+		&self,
+		ctx: &Context<'_>,
+		from: u64,
+		to: u64,
+		#[graphql(default)] finalized: bool,
+	) -> Result<Vec<Block>> {
+		if from > to {
+			return Err("`from` must not be greater than `to`".into())
+		}
+		let chain = chain(ctx);
+		let mut blocks = Vec::new();
+		for number in from..=to.min(from.saturating_add(MAX_BLOCKS_PER_QUERY - 1)) {
+			let Some(hash) = chain.hash(number, finalized)? else { break };
+			blocks.extend(Block::load(chain.clone(), &hash)?);
+		}
+		Ok(blocks)
+	}
+}
+
+/// A block.
+pub(crate) struct Block {
+	chain: Arc<dyn ChainData>,
+	header: BlockHeader,
+}
+
+impl Block {
+	fn load(chain: Arc<dyn ChainData>, hash: &[u8]) -> Result<Option<Self>, Error> {
+		Ok(chain.header(hash)?.map(|header| Block { chain, header }))
+	}
+}
+
+#[Object]
+impl Block {
+	/// Hash of the block.
+	async fn hash(&self) -> String {
+		hex(&self.header.hash)
+	}
+
+	/// Number of the block.
+	async fn number(&self) -> u64 {
+		self.header.number
+	}
+
+	/// Hash of the parent of the block.
+	async fn parent_hash(&self) -> String {
+		hex(&self.header.parent_hash)
+	}
+
+	/// Root of the state trie after the block.
+	async fn state_root(&self) -> String {
+		hex(&self.header.state_root)
+	}
+
+	/// Root of the trie of the extrinsics of the block.
+	async fn extrinsics_root(&self) -> String {
+		hex(&self.header.extrinsics_root)
+	}
+
+	/// Whether the block is part of the finalized chain.
+	async fn is_finalized(&self) -> Result<bool> {
+		Ok(self.chain.is_finalized(&self.header)?)
+	}
+
+	/// Extrinsics of the block.
+	async fn extrinsics(&self) -> Result<Vec<Extrinsic>> {
+		let metadata = self.chain.metadata(&self.header.hash)?;
+		let extrinsics = self.chain.extrinsics(&self.header.hash)?;
+		Ok(extrinsics
+			.into_iter()
+			.enumerate()
+			.map(|(index, bytes)| {
+				// Extrinsics that can't be decoded are returned without their decoded parts.
+				let decoded = metadata.decode_extrinsic(&bytes).ok();
+				Extrinsic {
+					index: index as u32,
+					hash: hex(&self.chain.extrinsic_hash(&bytes)),
+					bytes: hex(&bytes),
+					signer: decoded.as_ref().and_then(|e| e.signer.clone()).map(Json),
+					pallet: decoded.as_ref().map(|e| e.pallet.clone()),
+					call: decoded.as_ref().map(|e| e.call.clone()),
+					args: decoded.map(|e| Json(e.args)),
+				}
+			})
+			.collect())
+	}
+
+	/// Events emitted by the block, optionally only those of `pallet` named `name`.
+	async fn events(&self, pallet: Option<String>, name: Option<String>) -> Result<Vec<Event>> {
+		let key = [twox_128(b"System"), twox_128(b"Events")].concat();
+		let Some(events) = self.chain.storage(&self.header.hash, &key)? else {
+			return Ok(Vec::new())
+		};
+		let metadata = self.chain.metadata(&self.header.hash)?;
+		Ok(metadata
+			.decode_events(&events)?
+			.into_iter()
+			.enumerate()
+			.filter(|(_, event)| pallet.as_ref().map_or(true, |pallet| *pallet == event.pallet))
+			.filter(|(_, event)| name.as_ref().map_or(true, |name| *name == event.name))
+			.map(|(index, event)| Event {
+				index: index as u32,
+				extrinsic_index: event
+					.phase
+					.get("ApplyExtrinsic")
+					.and_then(Value::as_u64)
+					.map(|index| index as u32),
+				phase: Json(event.phase),
+				pallet: event.pallet,
+				name: event.name,
+				fields: Json(event.fields),
+				topics: event.topics,
+			})
+			.collect())
+	}
+
+	/// The decoded value of the storage `entry` of `pallet` at the block.
+	///
+	/// Map entries take one hex encoded SCALE key per hasher of the entry. Entries without a value
+	/// are `null`, unless they have a default value.
+	async fn storage(
+		&self,
+		pallet: String,
+		entry: String,
+		#[graphql(default)] keys: Vec<String>,
+	) -> Result<Option<Json<Value>>> {
+		let metadata = self.chain.metadata(&self.header.hash)?;
+		let entry = metadata.storage_entry(&pallet, &entry)?;
+		let keys = keys.iter().map(|key| parse_hex(key)).collect::<Result<Vec<_>, _>>()?;
+		let value = self.chain.storage(&self.header.hash, &entry.key(&keys)?)?;
+		Ok(metadata.decode_storage(entry, value)?.map(Json))
+	}
+}
+
+/// An extrinsic of a block.
+#[derive(SimpleObject)]
+pub(crate) struct Extrinsic {
+	/// Index of the extrinsic in the block.
+	index: u32,
+	/// Hash of the extrinsic.
+	hash: String,
+	/// The SCALE encoded extrinsic.
+	bytes: String,
+	/// The decoded address of the signer, `null` for unsigned extrinsics.
+	signer: Option<Json<Value>>,
+	/// Name of the pallet of the call.
+	pallet: Option<String>,
+	/// Name of the call.
+	call: Option<String>,
+	/// The decoded arguments of the call.
+	args: Option<Json<Value>>,
+}
+
+/// An event emitted by a block.
+#[derive(SimpleObject)]
+pub(crate) struct Event {
+	/// Index of the event in the block.
+	index: u32,
+	/// Index of the extrinsic that emitted the event, `null` for events emitted while
+	/// initializing or finalizing the block.
+	extrinsic_index: Option<u32>,
+	/// The decoded phase of the block in which the event was emitted.
+	phase: Json<Value>,
+	/// Name of the pallet that emitted the event.
+	pallet: String,
+	/// Name of the event.
+	name: String,
+	/// The decoded fields of the event.
+	fields: Json<Value>,
+	/// Hex encoded topics of the event.
+	topics: Vec<String>,
+}
@@ -0,0 +1,262 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	chain::{BlockHeader, ChainData},
+	metadata::ChainMetadata,
+	schema, Error, GraphQl, GraphQlApiServer, GraphQlRequest, MAX_BLOCKS_PER_QUERY,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+const BEST: u64 = 150;
+const FINALIZED: u64 = 100;
+/// Number of the block of a fork that isn't part of the best chain.
+const FORK: u64 = 101;
+
+/// Hash of the block `number`, of the fork if `fork` is set.
+fn block_hash(number: u64, fork: bool) -> Vec<u8> {
+	let mut hash = vec![0; 32];
+	hash[..8].copy_from_slice(&number.to_le_bytes());
+	hash[31] = fork as u8;
+	hash
+}
+
+fn hex(bytes: &[u8]) -> String {
+	array_bytes::bytes2hex("0x", bytes)
+}
+
+/// A chain of `BEST` blocks, of which `FINALIZED` are finalized, with a fork at `FORK`.
+///
+/// Every block has a single extrinsic and no metadata.
+struct TestChain;
+
+impl ChainData for TestChain {
+	fn head(&self, finalized: bool) -> Vec<u8> {
+		block_hash(if finalized { FINALIZED } else { BEST }, false)
+	}
+
+	fn hash(&self, number: u64, finalized: bool) -> Result<Option<Vec<u8>>, Error> {
+		let head = if finalized { FINALIZED } else { BEST };
+		Ok((number <= head).then(|| block_hash(number, false)))
+	}
+
+	fn header(&self, hash: &[u8]) -> Result<Option<BlockHeader>, Error> {
+		if hash.len() != 32 {
+			return Err(Error::InvalidQuery(format!("Invalid block hash {}", hex(hash))))
+		}
+		let number = u64::from_le_bytes(hash[..8].try_into().unwrap());
+		let fork = hash[31] == 1;
+		let known = if fork { number == FORK } else { number <= BEST };
+		if !known || hash != block_hash(number, fork) {
+			return Ok(None)
+		}
+		Ok(Some(BlockHeader {
+			hash: hash.to_vec(),
+			number,
+			parent_hash: block_hash(number.saturating_sub(1), false),
+			state_root: vec![1; 32],
+			extrinsics_root: vec![2; 32],
+		}))
+	}
+
+	fn is_finalized(&self, header: &BlockHeader) -> Result<bool, Error> {
+		Ok(header.number <= FINALIZED && header.hash == block_hash(header.number, false))
+	}
+
+	fn extrinsics(&self, _hash: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+		Ok(vec![vec![4, 1, 2]])
+	}
+
+	fn extrinsic_hash(&self, extrinsic: &[u8]) -> Vec<u8> {
+		extrinsic.iter().rev().copied().collect()
+	}
+
+	fn storage(&self, _hash: &[u8], _key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+		Ok(None)
+	}
+
+	fn metadata(&self, _hash: &[u8]) -> Result<Arc<ChainMetadata>, Error> {
+		Err(Error::Metadata("no metadata".into()))
+	}
+}
+
+fn graphql() -> GraphQl {
+	GraphQl { schema: schema::build(Arc::new(TestChain)) }
+}
+
+fn query(query: &str) -> Value {
+	let request = GraphQlRequest { query: query.into(), operation_name: None, variables: None };
+	graphql().graphql_query(request).unwrap()
+}
+
+fn errors(response: &Value) -> Vec<&str> {
+	let errors = response["errors"].as_array().expect("the query fails");
+	errors.iter().map(|error| error["message"].as_str().unwrap()).collect()
+}
+
+#[test]
+fn head_is_the_best_or_finalized_block() {
+	let response = query(
+		"{
+			best: head { number hash parentHash isFinalized }
+			finalized: head(finalized: true) { number isFinalized }
+		}",
+	);
+	assert_eq!(
+		response,
+		json!({
+			"data": {
+				"best": {
+					"number": BEST,
+					"hash": hex(&block_hash(BEST, false)),
+					"parentHash": hex(&block_hash(BEST - 1, false)),
+					"isFinalized": false,
+				},
+				"finalized": { "number": FINALIZED, "isFinalized": true },
+			}
+		})
+	);
+}
+
+#[test]
+fn blocks_are_looked_up_by_number_or_hash() {
+	let request = GraphQlRequest {
+		query: "
+			query ByNumber($number: Int!, $finalized: Boolean!) {
+				block(number: $number, finalized: $finalized) { hash }
+			}
+			query ByHash($hash: String!, $finalized: Boolean!) {
+				block(hash: $hash, finalized: $finalized) { number isFinalized }
+			}
+		"
+		.into(),
+		operation_name: Some("ByNumber".into()),
+		variables: Some(json!({ "number": 10, "finalized": true })),
+	};
+	let response = graphql().graphql_query(request.clone()).unwrap();
+	assert_eq!(response, json!({ "data": { "block": { "hash": hex(&block_hash(10, false)) } } }));
+
+	// Blocks past the head of the chain aren't returned.
+	let mut past_finalized = request.clone();
+	past_finalized.variables = Some(json!({ "number": FINALIZED + 1, "finalized": true }));
+	let response = graphql().graphql_query(past_finalized).unwrap();
+	assert_eq!(response, json!({ "data": { "block": null } }));
+	let response = query(&format!("{{ block(number: {}) {{ number }} }}", BEST + 1));
+	assert_eq!(response, json!({ "data": { "block": null } }));
+
+	let by_hash = |hash: &[u8], finalized: bool| {
+		let mut request = request.clone();
+		request.operation_name = Some("ByHash".into());
+		request.variables = Some(json!({ "hash": hex(hash), "finalized": finalized }));
+		graphql().graphql_query(request).unwrap()
+	};
+	assert_eq!(
+		by_hash(&block_hash(FINALIZED, false), true),
+		json!({ "data": { "block": { "number": FINALIZED, "isFinalized": true } } })
+	);
+	assert_eq!(
+		by_hash(&block_hash(FORK, true), false),
+		json!({ "data": { "block": { "number": FORK, "isFinalized": false } } })
+	);
+	assert_eq!(by_hash(&block_hash(FORK, true), true), json!({ "data": { "block": null } }));
+	assert_eq!(by_hash(&block_hash(BEST + 1, false), false), json!({ "data": { "block": null } }));
+}
+
+#[test]
+fn blocks_ranges_are_capped() {
+	let numbers = |response: Value| -> Vec<u64> {
+		let blocks = response["data"]["blocks"].as_array().unwrap().clone();
+		blocks.iter().map(|block| block["number"].as_u64().unwrap()).collect()
+	};
+
+	let response = query("{ blocks(from: 0, to: 1000) { number } }");
+	assert_eq!(numbers(response), (0..MAX_BLOCKS_PER_QUERY).collect::<Vec<_>>());
+	let response = query(&format!("{{ blocks(from: {}, to: 1000) {{ number }} }}", BEST - 1));
+	assert_eq!(numbers(response), vec![BEST - 1, BEST]);
+	let response = query("{ blocks(from: 99, to: 1000, finalized: true) { number } }");
+	assert_eq!(numbers(response), vec![99, FINALIZED]);
+	let response = query("{ blocks(from: 5, to: 5) { number } }");
+	assert_eq!(numbers(response), vec![5]);
+
+	let response = query("{ blocks(from: 2, to: 1) { number } }");
+	assert_eq!(errors(&response), ["`from` must not be greater than `to`"]);
+}
+
+#[test]
+fn invalid_queries_are_reported_as_errors() {
+	let response = query("{ block { number } }");
+	assert_eq!(errors(&response), ["Exactly one of `hash` and `number` must be given"]);
+	let response = query("{ block(number: 1, hash: \"0x00\") { number } }");
+	assert_eq!(errors(&response), ["Exactly one of `hash` and `number` must be given"]);
+
+	let response = query("{ block(hash: \"0xzz\") { number } }");
+	assert_eq!(errors(&response), ["Invalid query: Invalid hex `0xzz`"]);
+	let response = query("{ block(hash: \"0x0102\") { number } }");
+	assert_eq!(errors(&response), ["Invalid query: Invalid block hash 0x0102"]);
+
+	// Errors of a field only discard the block it belongs to.
+	let response = query(
+		"{ a: block(number: 2) { number extrinsics { hash } } b: block(number: 1) { number } }",
+	);
+	assert_eq!(errors(&response), ["Invalid runtime metadata: no metadata"]);
+	assert_eq!(response["data"], json!({ "a": null, "b": { "number": 1 } }));
+
+	// No metadata is needed when there is no event.
+	let response = query("{ head { events { name } } }");
+	assert_eq!(response, json!({ "data": { "head": { "events": [] } } }));
+
+	assert!(!errors(&query("{ head { number")).is_empty());
+	assert!(!errors(&query("{ head { unknownField } }")).is_empty());
+}
+
+#[test]
+fn query_depth_is_limited() {
+	let response = query(
+		"{ __schema { types { fields { type {
+			ofType { ofType { ofType { ofType { name } } } }
+		} } } } }",
+	);
+	assert_eq!(errors(&response), ["Query is nested too deep."]);
+}
+
+#[tokio::test]
+async fn graphql_query_is_served_over_rpc() {
+	let api = graphql().into_rpc();
+
+	let request = json!({
+		"jsonrpc": "2.0",
+		"method": "graphql_query",
+		"params": [{ "query": "{ head { number } }" }],
+		"id": 1,
+	});
+	let (response, _) = api.raw_json_request(&request.to_string(), 1).await.unwrap();
+	let response = serde_json::from_str::<Value>(&response).unwrap();
+	assert_eq!(response["result"], json!({ "data": { "head": { "number": BEST } } }));
+
+	// Requests without a query are invalid params, reported as `400 Bad Request` on `/graphql`.
+	let request = json!({
+		"jsonrpc": "2.0",
+		"method": "graphql_query",
+		"params": [{ "variables": {} }],
+		"id": 1,
+	});
+	let (response, _) = api.raw_json_request(&request.to_string(), 1).await.unwrap();
+	let response = serde_json::from_str::<Value>(&response).unwrap();
+	assert_eq!(response["error"]["code"], -32602);
+}
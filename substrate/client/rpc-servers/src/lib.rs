@@ -23,7 +23,7 @@
 pub mod middleware;
 pub mod utils;
 
-use std::{error::Error as StdError, net::SocketAddr, time::Duration};
+use std::{error::Error as StdError, net::SocketAddr, sync::Arc, time::Duration};
 
 use jsonrpsee::{
 	core::BoxError,
@@ -32,6 +32,7 @@ use jsonrpsee::{
 	},
 	Methods, RpcModule,
 };
+use middleware::{ConnectionSubscriptions, SubscriptionMethods};
 use tower::Service;
 use utils::{
 	build_rpc_api, deny_unsafe, format_listen_addrs, get_proxy_ip, ListenAddrError, RpcSettings,
@@ -42,7 +43,10 @@ pub use jsonrpsee::{
 	core::id_providers::{RandomIntegerIdProvider, RandomStringIdProvider},
 	server::{middleware::rpc::RpcServiceBuilder, BatchRequestConfig},
 };
-pub use middleware::{Metrics, MiddlewareLayer, NodeHealthProxyLayer, RpcMetrics};
+pub use middleware::{
+	GraphQlProxyLayer, IpRateLimit, Metrics, MiddlewareLayer, NodeHealthProxyLayer,
+	RpcMethodCosts, RpcMetrics, SubscriptionQuota,
+};
 pub use utils::{RpcEndpoint, RpcMethods};
 
 const MEGABYTE: u32 = 1024 * 1024;
@@ -125,6 +129,7 @@ where
 		tokio_handle: tokio_handle.clone(),
 		stop_handle,
 	};
+	let subscription_methods = Arc::new(SubscriptionMethods::new(&cfg.methods));
 
 	let mut local_addrs = Vec::new();
 
@@ -157,14 +162,36 @@ where
 			host_filter,
 			cors,
 			rate_limit,
+			rate_limit_per_ip,
+			rate_limit_method_costs,
+			max_subscriptions_per_ip,
 		} = listener.rpc_settings();
 
+		// The per IP state is shared by all the connections of the endpoint.
+		let ip_rate_limit = rate_limit_per_ip.map(IpRateLimit::per_minute);
+		let subscription_quota = max_subscriptions_per_ip.map(SubscriptionQuota::new);
+
+		if let Some(ip_rate_limit) = ip_rate_limit.clone() {
+			let stop_handle = cfg.stop_handle.clone();
+			tokio_handle.spawn(async move {
+				let mut interval = tokio::time::interval(Duration::from_secs(60));
+				loop {
+					tokio::select! {
+						_ = interval.tick() => ip_rate_limit.retain_recent(),
+						_ = stop_handle.clone().shutdown() => break,
+					}
+				}
+			});
+		}
+
 		let http_middleware = tower::ServiceBuilder::new()
 			.option_layer(host_filter)
 			// Proxy `GET /health, /health/readiness` requests to the internal
 			// `system_health` method.
 			.layer(NodeHealthProxyLayer::default())
-			.layer(cors);
+			.layer(cors)
+			// Proxy `POST /graphql` requests to the `graphql_query` method.
+			.layer(GraphQlProxyLayer::new(max_payload_in_mb.saturating_mul(MEGABYTE)));
 
 		let mut builder = jsonrpsee::server::Server::builder()
 			.max_request_body_size(max_payload_in_mb.saturating_mul(MEGABYTE))
@@ -210,6 +237,10 @@ where
 				let cfg2 = cfg.clone();
 				let service_builder2 = service_builder.clone();
 				let rate_limit_whitelisted_ips2 = rate_limit_whitelisted_ips.clone();
+				let rate_limit_method_costs2 = rate_limit_method_costs.clone();
+				let ip_rate_limit2 = ip_rate_limit.clone();
+				let subscription_quota2 = subscription_quota.clone();
+				let subscription_methods2 = subscription_methods.clone();
 
 				let svc =
 					tower::service_fn(move |mut req: http::Request<hyper::body::Incoming>| {
@@ -222,36 +253,44 @@ where
 						let proxy_ip =
 							if rate_limit_trust_proxy_headers { get_proxy_ip(&req) } else { None };
 
-						let rate_limit_cfg = if rate_limit_whitelisted_ips2
-							.iter()
-							.any(|ips| ips.contains(proxy_ip.unwrap_or(ip)))
-						{
+						let client_ip = proxy_ip.unwrap_or(ip);
+
+						let is_trusted =
+							rate_limit_whitelisted_ips2.iter().any(|ips| ips.contains(client_ip));
+						if is_trusted {
 							log::debug!(target: "rpc", "ip={ip}, proxy_ip={:?} is trusted, disabling rate-limit", proxy_ip);
-							None
-						} else {
-							if !rate_limit_whitelisted_ips2.is_empty() {
-								log::debug!(target: "rpc", "ip={ip}, proxy_ip={:?} is not trusted, rate-limit enabled", proxy_ip);
-							}
-							rate_limit
-						};
+						} else if !rate_limit_whitelisted_ips2.is_empty() {
+							log::debug!(target: "rpc", "ip={ip}, proxy_ip={:?} is not trusted, rate-limit enabled", proxy_ip);
+						}
 
 						let is_websocket = ws::is_upgrade_request(&req);
 						let transport_label = if is_websocket { "ws" } else { "http" };
 
-						let middleware_layer = match (metrics, rate_limit_cfg) {
-							(None, None) => None,
-							(Some(metrics), None) => Some(
-								MiddlewareLayer::new()
-									.with_metrics(Metrics::new(metrics, transport_label)),
-							),
-							(None, Some(rate_limit)) =>
-								Some(MiddlewareLayer::new().with_rate_limit_per_minute(rate_limit)),
-							(Some(metrics), Some(rate_limit)) => Some(
-								MiddlewareLayer::new()
-									.with_metrics(Metrics::new(metrics, transport_label))
-									.with_rate_limit_per_minute(rate_limit),
-							),
-						};
+						let mut middleware_layer = MiddlewareLayer::new()
+							.with_method_costs(rate_limit_method_costs2.clone());
+						if let Some(metrics) = metrics {
+							let metrics = Metrics::new(metrics, transport_label);
+							middleware_layer = middleware_layer.with_metrics(metrics);
+						}
+						if !is_trusted {
+							if let Some(rate_limit) = rate_limit {
+								middleware_layer =
+									middleware_layer.with_rate_limit_per_minute(rate_limit);
+							}
+							if let Some(ip_rate_limit) = ip_rate_limit2.clone() {
+								middleware_layer =
+									middleware_layer.with_ip_rate_limit(client_ip, ip_rate_limit);
+							}
+							if let Some(quota) = subscription_quota2.clone() {
+								let methods = subscription_methods2.clone();
+								let subscriptions =
+									ConnectionSubscriptions::new(client_ip, quota, methods);
+								middleware_layer =
+									middleware_layer.with_subscriptions(subscriptions);
+							}
+						}
+						let middleware_layer =
+							middleware_layer.is_enabled().then_some(middleware_layer);
 
 						let rpc_middleware = RpcServiceBuilder::new()
 							.rpc_logger(1024)
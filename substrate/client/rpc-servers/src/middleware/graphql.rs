@@ -0,0 +1,288 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Middleware for handling the `/graphql` endpoint.
+
+use std::{
+	error::Error,
+	future::Future,
+	pin::Pin,
+	task::{Context, Poll},
+};
+
+use futures::future::FutureExt;
+use http::{HeaderValue, Method, StatusCode, Uri};
+use http_body_util::{BodyExt, Limited};
+use jsonrpsee::{
+	server::{HttpBody, HttpRequest, HttpResponse},
+	types::{ErrorCode, Response as RpcResponse, ResponsePayload},
+};
+use tower::Service;
+
+const RPC_GRAPHQL_METHOD: &str = "graphql_query";
+const HEADER_VALUE_JSON: HeaderValue = HeaderValue::from_static("application/json; charset=utf-8");
+
+/// Layer that applies [`GraphQlProxy`] which proxies the `/graphql` endpoint.
+#[derive(Debug, Clone)]
+pub struct GraphQlProxyLayer {
+	max_request_size: u32,
+}
+
+impl GraphQlProxyLayer {
+	/// Creates a new [`GraphQlProxyLayer`] accepting requests up to `max_request_size` bytes.
+	pub fn new(max_request_size: u32) -> Self {
+		Self { max_request_size }
+	}
+}
+
+impl<S> tower::Layer<S> for GraphQlProxyLayer {
+	type Service = GraphQlProxy<S>;
+
+	fn layer(&self, service: S) -> Self::Service {
+		GraphQlProxy { service, max_request_size: self.max_request_size }
+	}
+}
+
+/// Middleware that proxies `POST /graphql` requests to the `graphql_query` method.
+///
+/// The GraphQL request is wrapped in a JSON-RPC call so it goes through the RPC middlewares,
+/// such as the rate limits, like any other call. The result of the call is returned as the body
+/// of the response. Requests are rejected with `404 Not Found` when the node doesn't serve the
+/// `graphql_query` method.
+#[derive(Clone)]
+pub struct GraphQlProxy<S> {
+	service: S,
+	max_request_size: u32,
+}
+
+impl<S> tower::Service<HttpRequest> for GraphQlProxy<S>
+where
+	S: Service<HttpRequest, Response = HttpResponse> + Clone + Send + 'static,
+	S::Error: Into<Box<dyn Error + Send + Sync>> + 'static,
+	S::Future: Send + 'static,
+{
+	type Response = S::Response;
+	type Error = Box<dyn Error + Send + Sync + 'static>;
+	type Future =
+		Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.service.poll_ready(cx).map_err(Into::into)
+	}
+
+	fn call(&mut self, req: HttpRequest) -> Self::Future {
+		if req.uri().path() != "/graphql" {
+			let fut = self.service.call(req);
+			return async move { fut.await.map_err(Into::into) }.boxed()
+		}
+		if req.method() != Method::POST {
+			return async { Ok(graphql_error(StatusCode::METHOD_NOT_ALLOWED, "Expected POST")) }
+				.boxed()
+		}
+
+		// The service that was polled ready is used for the call, leaving a clone in its place.
+		let clone = self.service.clone();
+		let mut service = std::mem::replace(&mut self.service, clone);
+		let max_request_size = self.max_request_size as usize;
+
+		async move {
+			let (mut parts, body) = req.into_parts();
+			let body = match Limited::new(body, max_request_size).collect().await {
+				Ok(body) => body.to_bytes(),
+				Err(_) =>
+					return Ok(graphql_error(StatusCode::PAYLOAD_TOO_LARGE, "Request too large")),
+			};
+			// Parsing the request first ensures it can't alter the call it is wrapped in.
+			let Ok(request) = serde_json::from_slice::<serde_json::Value>(&body) else {
+				return Ok(graphql_error(StatusCode::BAD_REQUEST, "Invalid JSON request"))
+			};
+			let call = serde_json::json!({
+				"jsonrpc": "2.0",
+				"method": RPC_GRAPHQL_METHOD,
+				"params": [request],
+				"id": 0,
+			});
+
+			// Precautionary remove the URI.
+			parts.uri = Uri::from_static("/");
+			parts.headers.insert(http::header::CONTENT_TYPE, HEADER_VALUE_JSON);
+			parts.headers.insert(http::header::ACCEPT, HEADER_VALUE_JSON);
+			let req = HttpRequest::from_parts(parts, HttpBody::from(call.to_string()));
+
+			let res = service.call(req).await.map_err(Into::into)?;
+			let bytes = res.into_body().collect().await?.to_bytes();
+			let rp = serde_json::from_slice::<RpcResponse<serde_json::Value>>(&bytes)?;
+			Ok(match rp.payload {
+				ResponsePayload::Success(result) =>
+					http_response(StatusCode::OK, serde_json::to_string(&result)?),
+				ResponsePayload::Error(err) => {
+					let status = match ErrorCode::from(err.code()) {
+						ErrorCode::MethodNotFound => StatusCode::NOT_FOUND,
+						ErrorCode::InvalidParams | ErrorCode::ParseError => StatusCode::BAD_REQUEST,
+						// Rate limit and subscription quota exceeded.
+						ErrorCode::ServerError(-32999 | -32998) => StatusCode::TOO_MANY_REQUESTS,
+						_ => StatusCode::INTERNAL_SERVER_ERROR,
+					};
+					graphql_error(status, err.message())
+				},
+			})
+		}
+		.boxed()
+	}
+}
+
+fn http_response<S: Into<HttpBody>>(status_code: StatusCode, body: S) -> HttpResponse {
+	HttpResponse::builder()
+		.status(status_code)
+		.header(http::header::CONTENT_TYPE, HEADER_VALUE_JSON)
+		.body(body.into())
+		.expect("Header is valid; qed")
+}
+
+/// A GraphQL response reporting a request error.
+fn graphql_error(status_code: StatusCode, message: &str) -> HttpResponse {
+	let body = serde_json::json!({ "errors": [{ "message": message }] });
+	http_response(status_code, body.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::{json, Value};
+	use std::convert::Infallible;
+	use tower::{Layer, ServiceExt};
+
+	const MAX_REQUEST_SIZE: u32 = 256;
+
+	/// RPC server answering `graphql_query` calls according to the `query` of the request.
+	///
+	/// Successful calls return the URI, the content type and the params of the call they
+	/// received. Other requests are answered with their path.
+	async fn rpc_server(req: HttpRequest) -> Result<HttpResponse, Infallible> {
+		let uri = req.uri().to_string();
+		let content_type = req.headers().get(http::header::CONTENT_TYPE).cloned();
+		let body = req.into_body().collect().await.unwrap().to_bytes();
+		let call = serde_json::from_slice::<Value>(&body).ok();
+		let Some(call) = call.filter(|call| call["method"] == RPC_GRAPHQL_METHOD) else {
+			return Ok(http_response(StatusCode::OK, format!("passed through {uri}")))
+		};
+
+		let error = |code: i32| json!({ "code": code, "message": format!("error {code}") });
+		let response = match call["params"][0]["query"].as_str() {
+			Some("{ head { number } }") => json!({
+				"result": {
+					"uri": uri,
+					"contentType": content_type.unwrap().to_str().unwrap(),
+					"params": call["params"],
+				}
+			}),
+			Some("not found") => json!({ "error": error(-32601) }),
+			Some("invalid params") => json!({ "error": error(-32602) }),
+			Some("rate limited") => json!({ "error": error(-32999) }),
+			Some("subscriptions exceeded") => json!({ "error": error(-32998) }),
+			_ => json!({ "error": error(-32603) }),
+		};
+		let mut response = response.as_object().unwrap().clone();
+		response.insert("jsonrpc".into(), "2.0".into());
+		response.insert("id".into(), call["id"].clone());
+		Ok(http_response(StatusCode::OK, Value::from(response).to_string()))
+	}
+
+	fn send(method: Method, path: &str, body: &str) -> (StatusCode, String) {
+		let proxy = GraphQlProxyLayer::new(MAX_REQUEST_SIZE).layer(tower::service_fn(rpc_server));
+		let req = HttpRequest::builder()
+			.method(method)
+			.uri(path)
+			.body(HttpBody::from(body.to_owned()))
+			.unwrap();
+		futures::executor::block_on(async {
+			let res = proxy.oneshot(req).await.unwrap();
+			let status = res.status();
+			let body = res.into_body().collect().await.unwrap().to_bytes();
+			(status, String::from_utf8(body.to_vec()).unwrap())
+		})
+	}
+
+	fn post(request: &Value) -> (StatusCode, Value) {
+		let (status, body) = send(Method::POST, "/graphql", &request.to_string());
+		(status, serde_json::from_str(&body).unwrap())
+	}
+
+	fn error_message(body: &str) -> String {
+		let body = serde_json::from_str::<Value>(body).unwrap();
+		body["errors"][0]["message"].as_str().unwrap().to_owned()
+	}
+
+	#[test]
+	fn other_paths_are_passed_through() {
+		let call = json!({ "jsonrpc": "2.0", "method": "system_health", "id": 0 });
+		assert_eq!(send(Method::POST, "/", &call.to_string()).1, "passed through /");
+		assert_eq!(send(Method::GET, "/graphql/x", "").1, "passed through /graphql/x");
+		assert_eq!(send(Method::GET, "/health", "").1, "passed through /health");
+	}
+
+	#[test]
+	fn requests_are_wrapped_in_a_graphql_query_call() {
+		let request = json!({
+			"query": "{ head { number } }",
+			"operationName": "Head",
+			"variables": { "finalized": true },
+		});
+		let (status, body) = post(&request);
+		assert_eq!(status, StatusCode::OK);
+		assert_eq!(
+			body,
+			json!({
+				"uri": "/",
+				"contentType": "application/json; charset=utf-8",
+				"params": [request],
+			})
+		);
+	}
+
+	#[test]
+	fn invalid_requests_are_rejected() {
+		let (status, body) = send(Method::GET, "/graphql", "");
+		assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+		assert_eq!(error_message(&body), "Expected POST");
+
+		let (status, body) = send(Method::POST, "/graphql", "{ head { number } }");
+		assert_eq!(status, StatusCode::BAD_REQUEST);
+		assert_eq!(error_message(&body), "Invalid JSON request");
+
+		let query = "x".repeat(MAX_REQUEST_SIZE as usize);
+		let (status, body) = send(Method::POST, "/graphql", &json!({ "query": query }).to_string());
+		assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+		assert_eq!(error_message(&body), "Request too large");
+	}
+
+	#[test]
+	fn call_errors_are_mapped_to_status_codes() {
+		for (query, status) in [
+			("not found", StatusCode::NOT_FOUND),
+			("invalid params", StatusCode::BAD_REQUEST),
+			("rate limited", StatusCode::TOO_MANY_REQUESTS),
+			("subscriptions exceeded", StatusCode::TOO_MANY_REQUESTS),
+			("internal error", StatusCode::INTERNAL_SERVER_ERROR),
+		] {
+			let (actual, body) = post(&json!({ "query": query }));
+			assert_eq!(actual, status, "{query}");
+			assert!(body["errors"][0]["message"].as_str().unwrap().starts_with("error -32"));
+		}
+	}
+}
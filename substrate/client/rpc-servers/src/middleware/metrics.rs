@@ -51,6 +51,8 @@ pub struct RpcMetrics {
 	calls_started: CounterVec<U64>,
 	/// Number of calls completed.
 	calls_finished: CounterVec<U64>,
+	/// Number of calls rejected by the rate limits and quotas.
+	calls_throttled: CounterVec<U64>,
 	/// Number of Websocket sessions opened.
 	ws_sessions_opened: Option<Counter<U64>>,
 	/// Number of Websocket sessions closed.
@@ -95,6 +97,16 @@ impl RpcMetrics {
 					)?,
 					metrics_registry,
 				)?,
+				calls_throttled: register(
+					CounterVec::new(
+						Opts::new(
+							"substrate_rpc_calls_throttled",
+							"Number of RPC calls rejected by rate limits and subscription quotas",
+						),
+						&["protocol", "method", "limit"],
+					)?,
+					metrics_registry,
+				)?,
 				ws_sessions_opened: register(
 					Counter::new(
 						"substrate_rpc_sessions_opened",
@@ -152,6 +164,17 @@ impl RpcMetrics {
 			.inc();
 	}
 
+	pub(crate) fn on_throttled(&self, req: &Request, limit: &str, transport_label: &'static str) {
+		log::debug!(
+			target: "rpc_metrics",
+			"[{transport_label}] {} call rejected by the {limit} limit",
+			req.method_name(),
+		);
+		self.calls_throttled
+			.with_label_values(&[transport_label, req.method_name(), limit])
+			.inc();
+	}
+
 	pub(crate) fn on_response(
 		&self,
 		req: &Request,
@@ -215,6 +238,10 @@ impl Metrics {
 		self.inner.on_call(req, self.transport_label)
 	}
 
+	pub(crate) fn on_throttled(&self, req: &Request, limit: &str) {
+		self.inner.on_throttled(req, limit, self.transport_label)
+	}
+
 	pub(crate) fn on_response(
 		&self,
 		req: &Request,
@@ -19,22 +19,25 @@
 //! JSON-RPC specific middleware.
 
 use std::{
+	net::IpAddr,
 	num::NonZeroU32,
 	time::{Duration, Instant},
 };
 
 use futures::future::{BoxFuture, FutureExt};
-use governor::{clock::Clock, Jitter};
+use governor::Jitter;
 use jsonrpsee::{
 	server::middleware::rpc::RpcServiceT,
 	types::{ErrorObject, Id, Request},
 	MethodResponse,
 };
 
+mod graphql;
 mod metrics;
 mod node_health;
 mod rate_limit;
 
+pub use graphql::*;
 pub use metrics::*;
 pub use node_health::*;
 pub use rate_limit::*;
@@ -46,6 +49,9 @@ const MAX_RETRIES: usize = 10;
 #[derive(Debug, Clone, Default)]
 pub struct MiddlewareLayer {
 	rate_limit: Option<RateLimit>,
+	ip_rate_limit: Option<(IpAddr, IpRateLimit)>,
+	method_costs: RpcMethodCosts,
+	subscriptions: Option<ConnectionSubscriptions>,
 	metrics: Option<Metrics>,
 }
 
@@ -57,12 +63,35 @@ impl MiddlewareLayer {
 
 	/// Enable new rate limit middleware enforced per minute.
 	pub fn with_rate_limit_per_minute(self, n: NonZeroU32) -> Self {
-		Self { rate_limit: Some(RateLimit::per_minute(n)), metrics: self.metrics }
+		Self { rate_limit: Some(RateLimit::per_minute(n)), ..self }
+	}
+
+	/// Enable the rate limit shared by the connections of `ip`.
+	pub fn with_ip_rate_limit(self, ip: IpAddr, rate_limit: IpRateLimit) -> Self {
+		Self { ip_rate_limit: Some((ip, rate_limit)), ..self }
+	}
+
+	/// Set the cost of the methods counted against the rate limits.
+	pub fn with_method_costs(self, method_costs: RpcMethodCosts) -> Self {
+		Self { method_costs, ..self }
+	}
+
+	/// Enable the subscription quota shared by the connections of an IP address.
+	pub(crate) fn with_subscriptions(self, subscriptions: ConnectionSubscriptions) -> Self {
+		Self { subscriptions: Some(subscriptions), ..self }
 	}
 
 	/// Enable metrics middleware.
 	pub fn with_metrics(self, metrics: Metrics) -> Self {
-		Self { rate_limit: self.rate_limit, metrics: Some(metrics) }
+		Self { metrics: Some(metrics), ..self }
+	}
+
+	/// Whether any of the middlewares is enabled.
+	pub(crate) fn is_enabled(&self) -> bool {
+		self.rate_limit.is_some() ||
+			self.ip_rate_limit.is_some() ||
+			self.subscriptions.is_some() ||
+			self.metrics.is_some()
 	}
 
 	/// Register a new websocket connection.
@@ -73,6 +102,7 @@ impl MiddlewareLayer {
 	/// Register that a websocket connection was closed.
 	pub fn ws_disconnect(&self, now: Instant) {
 		self.metrics.as_ref().map(|m| m.ws_disconnect(now));
+		self.subscriptions.as_ref().map(|s| s.release_all());
 	}
 }
 
@@ -80,12 +110,12 @@ impl<S> tower::Layer<S> for MiddlewareLayer {
 	type Service = Middleware<S>;
 
 	fn layer(&self, service: S) -> Self::Service {
-		Middleware { service, rate_limit: self.rate_limit.clone(), metrics: self.metrics.clone() }
+		Middleware { service, layer: self.clone() }
 	}
 }
 
-/// JSON-RPC middleware that handles metrics
-/// and rate-limiting.
+/// JSON-RPC middleware that handles metrics,
+/// rate-limiting and subscription quotas.
 ///
 /// These are part of the same middleware
 /// because the metrics needs to know whether
@@ -93,8 +123,7 @@ impl<S> tower::Layer<S> for MiddlewareLayer {
 /// it will impact the roundtrip for a call.
 pub struct Middleware<S> {
 	service: S,
-	rate_limit: Option<RateLimit>,
-	metrics: Option<Metrics>,
+	layer: MiddlewareLayer,
 }
 
 impl<'a, S> RpcServiceT<'a> for Middleware<S>
@@ -106,45 +135,100 @@ where
 	fn call(&self, req: Request<'a>) -> Self::Future {
 		let now = Instant::now();
 
-		self.metrics.as_ref().map(|m| m.on_call(&req));
+		self.layer.metrics.as_ref().map(|m| m.on_call(&req));
 
 		let service = self.service.clone();
-		let rate_limit = self.rate_limit.clone();
-		let metrics = self.metrics.clone();
+		let MiddlewareLayer { rate_limit, ip_rate_limit, method_costs, subscriptions, metrics } =
+			self.layer.clone();
 
 		async move {
+			let cost = method_costs.cost(req.method_name());
 			let mut is_rate_limited = false;
 
 			if let Some(limit) = rate_limit.as_ref() {
-				let mut attempts = 0;
-				let jitter = Jitter::up_to(MAX_JITTER);
-
-				loop {
-					if attempts >= MAX_RETRIES {
-						return reject_too_many_calls(req.id);
-					}
-
-					if let Err(rejected) = limit.inner.check() {
-						tokio::time::sleep(jitter + rejected.wait_time_from(limit.clock.now()))
-							.await;
-					} else {
-						break;
-					}
+				match throttle(|| limit.check(cost)).await {
+					Some(delayed) => is_rate_limited |= delayed,
+					None => {
+						metrics.as_ref().map(|m| m.on_throttled(&req, "connection"));
+						return reject_too_many_calls(req.id)
+					},
+				}
+			}
 
-					is_rate_limited = true;
-					attempts += 1;
+			if let Some((ip, limit)) = ip_rate_limit.as_ref() {
+				match throttle(|| limit.check(ip, cost)).await {
+					Some(delayed) => is_rate_limited |= delayed,
+					None => {
+						metrics.as_ref().map(|m| m.on_throttled(&req, "ip"));
+						return reject_too_many_calls(req.id)
+					},
 				}
 			}
 
+			let subscriptions = subscriptions.as_ref();
+			let is_subscribe = subscriptions.is_some_and(|s| s.is_subscribe(req.method_name()));
+			if is_subscribe && !subscriptions.is_some_and(|s| s.try_subscribe()) {
+				metrics.as_ref().map(|m| m.on_throttled(&req, "subscriptions"));
+				return reject_too_many_subscriptions(req.id)
+			}
+
 			let rp = service.call(req.clone()).await;
 			metrics.as_ref().map(|m| m.on_response(&req, &rp, is_rate_limited, now));
 
+			if let Some(subscriptions) = subscriptions {
+				if is_subscribe {
+					subscriptions.on_subscribed(subscription_id(&rp));
+				} else if subscriptions.is_unsubscribe(req.method_name()) && rp.is_success() {
+					// The subscription is released even if the server already closed it.
+					if let Some(id) = unsubscribed_id(&req) {
+						subscriptions.on_unsubscribed(&id);
+					}
+				}
+			}
+
 			rp
 		}
 		.boxed()
 	}
 }
 
+/// Wait until `check` allows a call.
+///
+/// Returns whether the call was delayed, or `None` if the call isn't allowed.
+async fn throttle(check: impl Fn() -> Capacity) -> Option<bool> {
+	let jitter = Jitter::up_to(MAX_JITTER);
+
+	for attempt in 0..MAX_RETRIES {
+		match check() {
+			Capacity::Available => return Some(attempt > 0),
+			Capacity::After(wait) => tokio::time::sleep(jitter + wait).await,
+			Capacity::Exceeded => return None,
+		}
+	}
+	None
+}
+
+/// The JSON encoded id of the subscription opened by a subscription call.
+fn subscription_id(rp: &MethodResponse) -> Option<String> {
+	if !rp.is_success() {
+		return None
+	}
+	let response: serde_json::Value = serde_json::from_str(rp.as_result()).ok()?;
+	response.get("result").map(ToString::to_string)
+}
+
+/// The JSON encoded id of the subscription closed by an unsubscription call.
+fn unsubscribed_id(req: &Request) -> Option<String> {
+	req.params().one::<serde_json::Value>().ok().map(|id| id.to_string())
+}
+
 fn reject_too_many_calls(id: Id) -> MethodResponse {
 	MethodResponse::error(id, ErrorObject::owned(-32999, "RPC rate limit exceeded", None::<()>))
 }
+
+fn reject_too_many_subscriptions(id: Id) -> MethodResponse {
+	MethodResponse::error(
+		id,
+		ErrorObject::owned(-32998, "RPC subscription quota exceeded", None::<()>),
+	)
+}
@@ -19,20 +19,40 @@
 //! RPC rate limit.
 
 use governor::{
-	clock::{DefaultClock, QuantaClock},
+	clock::{Clock, DefaultClock, QuantaClock},
 	middleware::NoOpMiddleware,
-	state::{InMemoryState, NotKeyed},
+	state::{keyed::DefaultKeyedStateStore, InMemoryState, NotKeyed},
 	Quota,
 };
-use std::{num::NonZeroU32, sync::Arc};
+use jsonrpsee::{core::server::MethodCallback, Methods};
+use parking_lot::Mutex;
+use std::{
+	collections::{HashMap, HashSet},
+	net::IpAddr,
+	num::NonZeroU32,
+	sync::Arc,
+	time::Duration,
+};
 
 type RateLimitInner = governor::RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>;
+type IpRateLimitInner =
+	governor::RateLimiter<IpAddr, DefaultKeyedStateStore<IpAddr>, DefaultClock, NoOpMiddleware>;
+
+/// Whether a rate limit allows a call.
+pub(crate) enum Capacity {
+	/// The call is allowed.
+	Available,
+	/// The call is allowed after waiting.
+	After(Duration),
+	/// The cost of the call exceeds the limit, it's never allowed.
+	Exceeded,
+}
 
 /// Rate limit.
 #[derive(Debug, Clone)]
 pub struct RateLimit {
-	pub(crate) inner: Arc<RateLimitInner>,
-	pub(crate) clock: QuantaClock,
+	inner: Arc<RateLimitInner>,
+	clock: QuantaClock,
 }
 
 impl RateLimit {
@@ -44,4 +64,236 @@ impl RateLimit {
 			clock,
 		}
 	}
+
+	/// Check whether a call of the given `cost` is allowed.
+	pub(crate) fn check(&self, cost: NonZeroU32) -> Capacity {
+		match self.inner.check_n(cost) {
+			Ok(Ok(())) => Capacity::Available,
+			Ok(Err(not_until)) => Capacity::After(not_until.wait_time_from(self.clock.now())),
+			Err(_) => Capacity::Exceeded,
+		}
+	}
+}
+
+/// Rate limit shared by all the connections of an IP address.
+///
+/// The state of an IP address is kept after its connections are closed, so reconnecting doesn't
+/// reset the limit.
+#[derive(Debug, Clone)]
+pub struct IpRateLimit {
+	inner: Arc<IpRateLimitInner>,
+	clock: QuantaClock,
+}
+
+impl IpRateLimit {
+	/// Create a new `IpRateLimit` per minute.
+	pub fn per_minute(n: NonZeroU32) -> Self {
+		let clock = QuantaClock::default();
+		Self {
+			inner: Arc::new(IpRateLimitInner::dashmap_with_clock(Quota::per_minute(n), &clock)),
+			clock,
+		}
+	}
+
+	/// Check whether a call of the given `cost` from `ip` is allowed.
+	pub(crate) fn check(&self, ip: &IpAddr, cost: NonZeroU32) -> Capacity {
+		match self.inner.check_key_n(ip, cost) {
+			Ok(Ok(())) => Capacity::Available,
+			Ok(Err(not_until)) => Capacity::After(not_until.wait_time_from(self.clock.now())),
+			Err(_) => Capacity::Exceeded,
+		}
+	}
+
+	/// Forget the IP addresses whose limit is fully replenished.
+	pub(crate) fn retain_recent(&self) {
+		self.inner.retain_recent();
+		self.inner.shrink_to_fit();
+	}
+}
+
+/// Cost of the RPC methods, counted against the rate limits.
+///
+/// Methods without a configured cost cost `1`.
+#[derive(Debug, Clone, Default)]
+pub struct RpcMethodCosts(Arc<HashMap<String, NonZeroU32>>);
+
+impl RpcMethodCosts {
+	/// Cost of a call to `method`.
+	pub fn cost(&self, method: &str) -> NonZeroU32 {
+		self.0.get(method).copied().unwrap_or(NonZeroU32::MIN)
+	}
+}
+
+impl FromIterator<(String, NonZeroU32)> for RpcMethodCosts {
+	fn from_iter<I: IntoIterator<Item = (String, NonZeroU32)>>(iter: I) -> Self {
+		Self(Arc::new(iter.into_iter().collect()))
+	}
+}
+
+/// Maximum number of subscriptions opened by the connections of an IP address.
+#[derive(Debug, Clone)]
+pub struct SubscriptionQuota {
+	max: u32,
+	open: Arc<Mutex<HashMap<IpAddr, u32>>>,
+}
+
+impl SubscriptionQuota {
+	/// Create a new `SubscriptionQuota`.
+	pub fn new(max: u32) -> Self {
+		Self { max, open: Default::default() }
+	}
+
+	fn try_acquire(&self, ip: IpAddr) -> bool {
+		let mut open = self.open.lock();
+		let count = open.entry(ip).or_default();
+		if *count >= self.max {
+			return false
+		}
+		*count += 1;
+		true
+	}
+
+	fn release(&self, ip: IpAddr, n: u32) {
+		let mut open = self.open.lock();
+		if let Some(count) = open.get_mut(&ip) {
+			*count = count.saturating_sub(n);
+			if *count == 0 {
+				open.remove(&ip);
+			}
+		}
+	}
+}
+
+/// Names of the methods opening and closing subscriptions.
+#[derive(Debug, Default)]
+pub(crate) struct SubscriptionMethods {
+	subscribe: HashSet<String>,
+	unsubscribe: HashSet<String>,
+}
+
+impl SubscriptionMethods {
+	pub(crate) fn new(methods: &Methods) -> Self {
+		let mut this = Self::default();
+		for name in methods.method_names() {
+			match methods.method(name) {
+				Some(MethodCallback::Subscription(_)) => {
+					this.subscribe.insert(name.into());
+				},
+				Some(MethodCallback::Unsubscription(_)) => {
+					this.unsubscribe.insert(name.into());
+				},
+				_ => {},
+			}
+		}
+		this
+	}
+}
+
+/// Subscriptions opened by a connection, counted against the [`SubscriptionQuota`] of its IP.
+///
+/// The subscriptions are tracked by id. A subscription is released when its id is unsubscribed,
+/// including when the server already closed it, or when the connection is closed.
+#[derive(Debug, Clone)]
+pub(crate) struct ConnectionSubscriptions {
+	ip: IpAddr,
+	quota: SubscriptionQuota,
+	methods: Arc<SubscriptionMethods>,
+	/// The JSON encoded ids of the open subscriptions.
+	open: Arc<Mutex<HashSet<String>>>,
+}
+
+impl ConnectionSubscriptions {
+	pub(crate) fn new(
+		ip: IpAddr,
+		quota: SubscriptionQuota,
+		methods: Arc<SubscriptionMethods>,
+	) -> Self {
+		Self { ip, quota, methods, open: Default::default() }
+	}
+
+	pub(crate) fn is_subscribe(&self, method: &str) -> bool {
+		self.methods.subscribe.contains(method)
+	}
+
+	pub(crate) fn is_unsubscribe(&self, method: &str) -> bool {
+		self.methods.unsubscribe.contains(method)
+	}
+
+	/// Reserve a subscription before calling a subscription method.
+	pub(crate) fn try_subscribe(&self) -> bool {
+		self.quota.try_acquire(self.ip)
+	}
+
+	/// Keep the subscription reserved by [`Self::try_subscribe`] under the id of the opened
+	/// subscription, or release it if no subscription was opened.
+	pub(crate) fn on_subscribed(&self, id: Option<String>) {
+		if !id.is_some_and(|id| self.open.lock().insert(id)) {
+			self.quota.release(self.ip, 1);
+		}
+	}
+
+	/// Release the subscription with the given id, closed by an unsubscription method.
+	pub(crate) fn on_unsubscribed(&self, id: &str) {
+		if self.open.lock().remove(id) {
+			self.quota.release(self.ip, 1);
+		}
+	}
+
+	/// Release all the subscriptions of the connection.
+	pub(crate) fn release_all(&self) {
+		let open = std::mem::take(&mut *self.open.lock());
+		self.quota.release(self.ip, open.len() as u32);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn subscription_quota_is_shared_by_connections() {
+		let ip = IpAddr::from([127, 0, 0, 1]);
+		let quota = SubscriptionQuota::new(2);
+		let methods = Arc::new(SubscriptionMethods::default());
+		let first = ConnectionSubscriptions::new(ip, quota.clone(), methods.clone());
+		let second = ConnectionSubscriptions::new(ip, quota.clone(), methods);
+
+		assert!(first.try_subscribe());
+		first.on_subscribed(Some("1".into()));
+		assert!(second.try_subscribe());
+		second.on_subscribed(Some("1".into()));
+		assert!(!second.try_subscribe());
+
+		// Unsubscribing twice or unknown ids doesn't release the other connections.
+		first.on_unsubscribed("1");
+		first.on_unsubscribed("1");
+		first.on_unsubscribed("2");
+		assert!(second.try_subscribe());
+		second.on_subscribed(None);
+		assert!(first.try_subscribe());
+		first.on_subscribed(Some("2".into()));
+		assert!(!first.try_subscribe());
+
+		second.release_all();
+		assert!(first.try_subscribe());
+		let other_ip = IpAddr::from([127, 0, 0, 2]);
+		assert!(ConnectionSubscriptions::new(other_ip, quota, Default::default()).try_subscribe());
+	}
+
+	#[test]
+	fn costs_are_counted_against_the_ip_rate_limit() {
+		let limit = IpRateLimit::per_minute(NonZeroU32::new(10).unwrap());
+		let ip = IpAddr::from([127, 0, 0, 1]);
+		let cost = NonZeroU32::new(4).unwrap();
+
+		assert!(matches!(limit.check(&ip, cost), Capacity::Available));
+		assert!(matches!(limit.check(&ip, cost), Capacity::Available));
+		assert!(matches!(limit.check(&ip, cost), Capacity::After(_)));
+		assert!(matches!(limit.check(&IpAddr::from([127, 0, 0, 2]), cost), Capacity::Available));
+		assert!(matches!(limit.check(&ip, NonZeroU32::new(11).unwrap()), Capacity::Exceeded));
+
+		let costs: RpcMethodCosts = [("state_getKeysPaged".into(), cost)].into_iter().collect();
+		assert_eq!(costs.cost("state_getKeysPaged"), cost);
+		assert_eq!(costs.cost("system_health"), NonZeroU32::MIN);
+	}
 }
@@ -18,7 +18,7 @@
 
 //! Substrate RPC server utils.
 
-use crate::BatchRequestConfig;
+use crate::{BatchRequestConfig, RpcMethodCosts};
 use std::{
 	error::Error as StdError,
 	net::{IpAddr, SocketAddr},
@@ -88,8 +88,11 @@ pub(crate) struct RpcSettings {
 	pub(crate) max_buffer_capacity_per_connection: u32,
 	pub(crate) rpc_methods: RpcMethods,
 	pub(crate) rate_limit: Option<NonZeroU32>,
+	pub(crate) rate_limit_per_ip: Option<NonZeroU32>,
+	pub(crate) rate_limit_method_costs: RpcMethodCosts,
 	pub(crate) rate_limit_trust_proxy_headers: bool,
 	pub(crate) rate_limit_whitelisted_ips: Vec<IpNetwork>,
+	pub(crate) max_subscriptions_per_ip: Option<u32>,
 	pub(crate) cors: CorsLayer,
 	pub(crate) host_filter: Option<HostFilterLayer>,
 }
@@ -113,10 +116,16 @@ pub struct RpcEndpoint {
 	pub max_buffer_capacity_per_connection: u32,
 	/// Rate limit per minute.
 	pub rate_limit: Option<NonZeroU32>,
+	/// Rate limit per minute shared by all the connections of an IP address.
+	pub rate_limit_per_ip: Option<NonZeroU32>,
+	/// Cost of the methods counted against the rate limits.
+	pub rate_limit_method_costs: RpcMethodCosts,
 	/// Whether to trust proxy headers for rate limiting.
 	pub rate_limit_trust_proxy_headers: bool,
 	/// Whitelisted IPs for rate limiting.
 	pub rate_limit_whitelisted_ips: Vec<IpNetwork>,
+	/// Maximum number of subscriptions shared by all the connections of an IP address.
+	pub max_subscriptions_per_ip: Option<u32>,
 	/// CORS.
 	pub cors: Option<Vec<String>>,
 	/// RPC methods to expose.
@@ -158,8 +167,11 @@ impl RpcEndpoint {
 				max_buffer_capacity_per_connection: self.max_buffer_capacity_per_connection,
 				rpc_methods: self.rpc_methods,
 				rate_limit: self.rate_limit,
+				rate_limit_per_ip: self.rate_limit_per_ip,
+				rate_limit_method_costs: self.rate_limit_method_costs,
 				rate_limit_trust_proxy_headers: self.rate_limit_trust_proxy_headers,
 				rate_limit_whitelisted_ips: self.rate_limit_whitelisted_ips,
+				max_subscriptions_per_ip: self.max_subscriptions_per_ip,
 				host_filter,
 				cors,
 			},
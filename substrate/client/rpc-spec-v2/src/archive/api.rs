@@ -21,7 +21,7 @@
 use crate::{
 	archive::{
 		error::{Error, Infallible},
-		types::MethodResult,
+		types::{ArchiveEventsQuery, ArchiveEventsResult, MethodResult},
 	},
 	common::events::{
 		ArchiveStorageDiffEvent, ArchiveStorageDiffItem, ArchiveStorageEvent, StorageQuery,
@@ -131,3 +131,20 @@ pub trait ArchiveApi<Hash> {
 		previous_hash: Option<Hash>,
 	);
 }
+
+/// Queries of the events emitted by finalized blocks.
+///
+/// Only available on archive nodes running the event indexer.
+#[rpc(client, server)]
+pub trait ArchiveEventsApi {
+	/// Find the events emitted by a range of finalized blocks.
+	///
+	/// Returns the position of the matching events, ordered by their position in the chain. The
+	/// events themselves can be read from the `System::Events` storage of the returned blocks.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "archive_v1_events")]
+	fn archive_v1_events(&self, query: ArchiveEventsQuery) -> Result<ArchiveEventsResult, Error>;
+}
@@ -0,0 +1,114 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! API implementation for the `archive` event queries.
+
+use crate::{
+	archive::{
+		error::Error as ArchiveError,
+		types::{ArchiveEvent, ArchiveEventPosition, ArchiveEventsQuery, ArchiveEventsResult},
+		ArchiveEventsApiServer,
+	},
+	hex_string,
+};
+use sc_client_api::AuxStore;
+use sc_event_index::{EventCursor, EventFilter, EventIndex, EventQuery};
+use sp_runtime::traits::Block as BlockT;
+use std::sync::Arc;
+
+/// Maximum number of events returned by a single call.
+const MAX_EVENTS_PER_QUERY: u32 = 1000;
+
+/// An API for the events indexed by [`sc_event_index::run_event_indexer`].
+pub struct ArchiveEvents<Block: BlockT, Client> {
+	/// Index of the events.
+	index: EventIndex<Block, Client>,
+}
+
+impl<Block: BlockT, Client: AuxStore> ArchiveEvents<Block, Client> {
+	/// Create a new [`ArchiveEvents`] reading the event index of `client`.
+	pub fn new(client: Arc<Client>) -> Self {
+		Self { index: EventIndex::new(client) }
+	}
+}
+
+impl<Block, Client> ArchiveEventsApiServer for ArchiveEvents<Block, Client>
+where
+	Block: BlockT + 'static,
+	Client: AuxStore + Send + Sync + 'static,
+{
+	fn archive_v1_events(
+		&self,
+		query: ArchiveEventsQuery,
+	) -> Result<ArchiveEventsResult, ArchiveError> {
+		let hex = |value: String| {
+			array_bytes::hex2bytes(&value).map_err(|_| ArchiveError::InvalidParam(value))
+		};
+		let filter = match (query.pallet, query.event, query.topic, query.account) {
+			(Some(pallet), variant, None, None) => EventFilter::Pallet { pallet, variant },
+			(None, None, Some(topic), None) => EventFilter::Topic(hex(topic)?),
+			(None, None, None, Some(account)) => EventFilter::Account(hex(account)?),
+			_ =>
+				return Err(ArchiveError::InvalidParam(
+					"Exactly one of `pallet`, `topic` or `account` must be provided".into(),
+				)),
+		};
+		if query.from_block > query.to_block {
+			return Err(ArchiveError::InvalidParam(format!(
+				"Invalid block range: {}..={}",
+				query.from_block, query.to_block
+			)))
+		}
+		let limit = match query.limit.unwrap_or(MAX_EVENTS_PER_QUERY) {
+			0 => return Err(ArchiveError::InvalidParam("Limit must not be zero".into())),
+			limit => limit.min(MAX_EVENTS_PER_QUERY),
+		};
+
+		let page = self
+			.index
+			.query(&EventQuery {
+				filter,
+				from: query.from_block,
+				to: query.to_block,
+				start: query.start.map(|start| EventCursor {
+					block_number: start.block_number,
+					event_index: start.event_index,
+				}),
+				limit: limit as usize,
+			})
+			.map_err(|e| ArchiveError::EventIndex(e.to_string()))?;
+
+		Ok(ArchiveEventsResult {
+			events: page
+				.events
+				.into_iter()
+				.map(|event| ArchiveEvent {
+					block_number: event.block_number,
+					block_hash: hex_string(&event.block_hash.as_ref()),
+					event_index: event.event_index,
+				})
+				.collect(),
+			next: page.next.map(|next| ArchiveEventPosition {
+				block_number: next.block_number,
+				event_index: next.event_index,
+			}),
+			indexed_height: page.last_indexed,
+			pending_blocks: page.pending,
+		})
+	}
+}
@@ -25,6 +25,7 @@
 #[cfg(test)]
 mod tests;
 
+mod archive_events;
 mod archive_storage;
 mod types;
 
@@ -32,6 +33,10 @@ pub mod api;
 pub mod archive;
 pub mod error;
 
-pub use api::ArchiveApiServer;
+pub use api::{ArchiveApiServer, ArchiveEventsApiServer};
 pub use archive::Archive;
-pub use types::{MethodResult, MethodResultErr, MethodResultOk};
+pub use archive_events::ArchiveEvents;
+pub use types::{
+	ArchiveEvent, ArchiveEventPosition, ArchiveEventsQuery, ArchiveEventsResult, MethodResult,
+	MethodResultErr, MethodResultOk,
+};
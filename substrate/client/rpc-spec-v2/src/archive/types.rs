@@ -60,6 +60,73 @@ pub struct MethodResultErr {
 	pub error: String,
 }
 
+/// The events to look for with `archive_v1_events`.
+///
+/// Exactly one of `pallet`, optionally narrowed down by `event`, `topic` or `account` must be
+/// provided.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEventsQuery {
+	/// Name of the pallet that emitted the events.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub pallet: Option<String>,
+	/// Name of the event variant of `pallet`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub event: Option<String>,
+	/// Hexadecimal-encoded SCALE-encoded topic of the events.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub topic: Option<String>,
+	/// Hexadecimal-encoded SCALE-encoded account found in the fields of the events.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub account: Option<String>,
+	/// Height of the first block to look at.
+	pub from_block: u64,
+	/// Height of the last block to look at, inclusive.
+	pub to_block: u64,
+	/// Position returned by a previous call to continue from.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub start: Option<ArchiveEventPosition>,
+	/// Maximum number of events to return.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub limit: Option<u32>,
+}
+
+/// Position of an event in the chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEventPosition {
+	/// Height of the block that emitted the event.
+	pub block_number: u64,
+	/// Index of the event in the events of the block.
+	pub event_index: u32,
+}
+
+/// An event returned by `archive_v1_events`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEvent {
+	/// Height of the block that emitted the event.
+	pub block_number: u64,
+	/// Hexadecimal-encoded hash of the block that emitted the event.
+	pub block_hash: String,
+	/// Index of the event in the events of the block.
+	pub event_index: u32,
+}
+
+/// The result of `archive_v1_events`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEventsResult {
+	/// Matching events ordered by their position in the chain.
+	pub events: Vec<ArchiveEvent>,
+	/// Position to pass as `start` to fetch the next page, `null` if there are no more events.
+	pub next: Option<ArchiveEventPosition>,
+	/// Height of the last indexed block, events of later blocks are not returned.
+	pub indexed_height: Option<u64>,
+	/// Heights of the blocks of the range whose events couldn't be indexed yet.
+	pub pending_blocks: Vec<u64>,
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -20,7 +20,7 @@
 //!
 //! Errors are interpreted as transaction events for subscriptions.
 
-use crate::transaction::event::{TransactionError, TransactionEvent};
+use crate::transaction::event::{TransactionDropped, TransactionError, TransactionEvent};
 use jsonrpsee::types::error::ErrorObject;
 use sc_transaction_pool_api::error::Error as PoolError;
 use sp_runtime::transaction_validity::InvalidTransaction;
@@ -73,6 +73,13 @@ impl<Hash> From<Error> for TransactionEvent<Hash> {
 						old, new
 					),
 				}),
+			Error::Pool(PoolError::TooLowPriorityBump { old, new, required }) =>
+				TransactionEvent::Invalid(TransactionError {
+					error: format!(
+						"The priority bump of the transaction is too low (pool {}, {} < {})",
+						old, new, required
+					),
+				}),
 			Error::Pool(PoolError::CycleDetected) => TransactionEvent::Invalid(TransactionError {
 				error: "The transaction contains a cyclic dependency".into(),
 			}),
@@ -96,6 +103,11 @@ impl<Hash> From<Error> for TransactionEvent<Hash> {
 				TransactionEvent::Invalid(TransactionError {
 					error: "The pool is not accepting future transactions".into(),
 				}),
+			Error::Pool(PoolError::SenderLimitExceeded) =>
+				TransactionEvent::Dropped(TransactionDropped {
+					error: "The transaction could not enter the pool because of the sender limit"
+						.into(),
+				}),
 		}
 	}
 }
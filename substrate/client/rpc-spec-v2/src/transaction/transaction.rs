@@ -176,8 +176,8 @@ fn handle_event<Hash: Clone, BlockHash: Clone>(
 			})),
 		TransactionStatus::Finalized((hash, index)) =>
 			Some(TransactionEvent::Finalized(TransactionBlock { hash, index })),
-		TransactionStatus::Usurped(_) => Some(TransactionEvent::Invalid(TransactionError {
-			error: "Extrinsic was rendered invalid by another extrinsic".into(),
+		TransactionStatus::Usurped(_) => Some(TransactionEvent::Dropped(TransactionDropped {
+			error: "Extrinsic was replaced by another extrinsic with a higher priority".into(),
 		})),
 		TransactionStatus::Dropped => Some(TransactionEvent::Dropped(TransactionDropped {
 			error: "Extrinsic dropped from the pool due to exceeding limits".into(),
@@ -0,0 +1,150 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Implementation of the [`ForksApiServer`] trait reporting the forks of the block tree and the
+//! reorganizations of the best chain.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+	utils::{spawn_subscription_task, BoundedVecDeque, PendingSubscription},
+	SubscriptionTaskExecutor,
+};
+use futures::{future, StreamExt};
+use jsonrpsee::{core::async_trait, Extensions, PendingSubscriptionSink};
+use sc_client_api::{BlockImportNotification, BlockchainEvents};
+use sc_rpc_api::{check_if_safe, forks::error::Error};
+use sp_blockchain::{tree_route, HeaderBackend, HeaderMetadata};
+use sp_consensus::SelectChain;
+use sp_runtime::traits::{Block as BlockT, Header, NumberFor, Saturating};
+use std::{iter, marker::PhantomData, sync::Arc};
+
+pub use sc_rpc_api::forks::{ForkChoice, ForkChoiceProvider, ForksApiServer, Leaf, Leaves, Reorg};
+
+/// Forks API.
+pub struct Forks<Block: BlockT, Client, SC> {
+	client: Arc<Client>,
+	select_chain: SC,
+	fork_choice: Option<Box<dyn ForkChoiceProvider<Block::Hash>>>,
+	executor: SubscriptionTaskExecutor,
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block: BlockT, Client, SC> Forks<Block, Client, SC> {
+	/// Create a new Forks API.
+	///
+	/// The leaves are reported with the data of `fork_choice`, if any.
+	pub fn new(
+		client: Arc<Client>,
+		select_chain: SC,
+		fork_choice: Option<Box<dyn ForkChoiceProvider<Block::Hash>>>,
+		executor: SubscriptionTaskExecutor,
+	) -> Self {
+		Self { client, select_chain, fork_choice, executor, _phantom: PhantomData }
+	}
+}
+
+#[async_trait]
+impl<Block, Client, SC> ForksApiServer<Block::Hash, NumberFor<Block>> for Forks<Block, Client, SC>
+where
+	Block: BlockT + 'static,
+	Client: HeaderBackend<Block>
+		+ HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ BlockchainEvents<Block>
+		+ 'static,
+	SC: SelectChain<Block> + 'static,
+{
+	async fn leaves(
+		&self,
+		ext: &Extensions,
+	) -> Result<Leaves<Block::Hash, NumberFor<Block>>, Error> {
+		check_if_safe(ext)?;
+
+		let hashes = self.select_chain.leaves().await.map_err(|e| Error::Leaves(Box::new(e)))?;
+		let info = self.client.info();
+
+		let leaves = hashes
+			.into_iter()
+			.map(|hash| {
+				let route = tree_route(&*self.client, info.best_hash, hash)
+					.map_err(|e| Error::Client(Box::new(e)))?;
+				let number = route.last().map_or(info.best_number, |block| block.number);
+				Ok(Leaf {
+					hash,
+					number,
+					is_best: hash == info.best_hash,
+					common_ancestor: route.common_block().hash,
+					fork_length: route.enacted().len() as u32,
+					fork_choice: self.fork_choice.as_ref().and_then(|f| f.fork_choice(hash)),
+				})
+			})
+			.collect::<Result<_, Error>>()?;
+
+		Ok(Leaves {
+			best_hash: info.best_hash,
+			best_number: info.best_number,
+			finalized_hash: info.finalized_hash,
+			finalized_number: info.finalized_number,
+			finality_lag: info.best_number.saturating_sub(info.finalized_number),
+			leaves,
+		})
+	}
+
+	fn subscribe_reorgs(&self, pending: PendingSubscriptionSink) {
+		let stream = self
+			.client
+			.import_notification_stream()
+			.filter_map(|notification| future::ready(reorg(&notification)));
+
+		spawn_subscription_task(
+			&self.executor,
+			PendingSubscription::from(pending).pipe_from_stream(stream, BoundedVecDeque::default()),
+		);
+	}
+}
+
+/// The reorganization caused by the import of a block, if any.
+fn reorg<Block: BlockT>(
+	notification: &BlockImportNotification<Block>,
+) -> Option<Reorg<Block::Hash, NumberFor<Block>>> {
+	if !notification.is_new_best {
+		return None
+	}
+
+	// The tree route goes from the previous best block to the parent of the new one.
+	let route = notification.tree_route.as_ref()?;
+	let from = route.retracted().first()?;
+	let common = route.common_block();
+
+	Some(Reorg {
+		from: from.hash,
+		from_number: from.number,
+		to: notification.hash,
+		to_number: *notification.header.number(),
+		common_ancestor: common.hash,
+		common_ancestor_number: common.number,
+		retracted: route.retracted().iter().map(|block| block.hash).collect(),
+		enacted: route
+			.enacted()
+			.iter()
+			.map(|block| block.hash)
+			.chain(iter::once(notification.hash))
+			.collect(),
+	})
+}
@@ -0,0 +1,162 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::{
+	testing::{test_executor, timeout_secs},
+	DenyUnsafe,
+};
+use assert_matches::assert_matches;
+use jsonrpsee::core::EmptyServerParams as EmptyParams;
+use sc_block_builder::BlockBuilderBuilder;
+use sp_consensus::BlockOrigin;
+use substrate_test_runtime_client::{
+	prelude::*,
+	runtime::{Block, H256},
+	Client,
+};
+
+struct TestForkChoice(H256);
+
+impl ForkChoiceProvider<H256> for TestForkChoice {
+	fn fork_choice(&self, hash: H256) -> Option<ForkChoice> {
+		(hash == self.0).then_some(ForkChoice { weight: 2, slot: Some(42), author: Some(1) })
+	}
+}
+
+async fn import_block(client: &Arc<Client<Backend>>, parent: H256, fork: bool) -> H256 {
+	let mut builder = BlockBuilderBuilder::new(&**client)
+		.on_parent_block(parent)
+		.fetch_parent_block_number(&**client)
+		.unwrap()
+		.build()
+		.unwrap();
+	if fork {
+		builder.push_storage_change(vec![1], Some(vec![1])).unwrap();
+	}
+	let block = builder.build().unwrap().block;
+	let hash = block.hash();
+	client.import(BlockOrigin::Own, block).await.unwrap();
+	hash
+}
+
+#[tokio::test]
+async fn should_return_leaves() {
+	let (client, select_chain) = TestClientBuilder::new().build_with_longest_chain();
+	let client = Arc::new(client);
+	let genesis = client.genesis_hash();
+
+	let a1 = import_block(&client, genesis, false).await;
+	let a2 = import_block(&client, a1, false).await;
+	let b1 = import_block(&client, genesis, true).await;
+
+	let mut api = Forks::<Block, _, _>::new(
+		client.clone(),
+		select_chain,
+		Some(Box::new(TestForkChoice(a2))),
+		test_executor(),
+	)
+	.into_rpc();
+	api.extensions_mut().insert(DenyUnsafe::No);
+
+	let res: Leaves<H256, u64> = api.call("forks_getLeaves", EmptyParams::new()).await.unwrap();
+	assert_eq!(res.best_hash, a2);
+	assert_eq!(res.best_number, 2);
+	assert_eq!(res.finalized_hash, genesis);
+	assert_eq!(res.finalized_number, 0);
+	assert_eq!(res.finality_lag, 2);
+	assert_eq!(res.leaves.len(), 2);
+
+	let best = res.leaves.iter().find(|leaf| leaf.hash == a2).unwrap();
+	assert_eq!(
+		best,
+		&Leaf {
+			hash: a2,
+			number: 2,
+			is_best: true,
+			common_ancestor: a2,
+			fork_length: 0,
+			fork_choice: Some(ForkChoice { weight: 2, slot: Some(42), author: Some(1) }),
+		}
+	);
+
+	let fork = res.leaves.iter().find(|leaf| leaf.hash == b1).unwrap();
+	assert_eq!(
+		fork,
+		&Leaf {
+			hash: b1,
+			number: 1,
+			is_best: false,
+			common_ancestor: genesis,
+			fork_length: 1,
+			fork_choice: None,
+		}
+	);
+}
+
+#[tokio::test]
+async fn deny_unsafe_works() {
+	let (client, select_chain) = TestClientBuilder::new().build_with_longest_chain();
+	let mut api =
+		Forks::<Block, _, _>::new(Arc::new(client), select_chain, None, test_executor()).into_rpc();
+	api.extensions_mut().insert(DenyUnsafe::Yes);
+
+	let request = r#"{"jsonrpc":"2.0","method":"forks_getLeaves","params":[],"id":1}"#;
+	let (resp, _) = api.raw_json_request(request, 1).await.expect("Raw calls should succeed");
+
+	assert_eq!(
+		resp,
+		r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32601,"message":"RPC call is unsafe to be called externally"}}"#
+	);
+}
+
+#[tokio::test]
+async fn should_notify_about_reorgs() {
+	let (client, select_chain) = TestClientBuilder::new().build_with_longest_chain();
+	let client = Arc::new(client);
+	let genesis = client.genesis_hash();
+
+	let api =
+		Forks::<Block, _, _>::new(client.clone(), select_chain, None, test_executor()).into_rpc();
+	let mut sub =
+		api.subscribe_unbounded("forks_subscribeReorgs", EmptyParams::new()).await.unwrap();
+
+	// Neither extending the best chain nor importing a shorter fork is a reorg.
+	let a1 = import_block(&client, genesis, false).await;
+	let b1 = import_block(&client, genesis, true).await;
+	let b2 = import_block(&client, b1, false).await;
+
+	let (reorg, _) =
+		timeout_secs(10, sub.next::<Reorg<H256, u64>>()).await.unwrap().unwrap().unwrap();
+	assert_eq!(
+		reorg,
+		Reorg {
+			from: a1,
+			from_number: 1,
+			to: b2,
+			to_number: 2,
+			common_ancestor: genesis,
+			common_ancestor_number: 0,
+			retracted: vec![a1],
+			enacted: vec![b1, b2],
+		}
+	);
+
+	sub.close();
+	assert_matches!(timeout_secs(10, sub.next::<Reorg<H256, u64>>()).await, Ok(None));
+}
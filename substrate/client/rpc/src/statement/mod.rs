@@ -18,26 +18,124 @@
 
 //! Substrate statement store API.
 
+#[cfg(test)]
+mod tests;
+
+use crate::{
+	utils::{spawn_subscription_task, BoundedVecDeque, PendingSubscription},
+	SubscriptionTaskExecutor,
+};
 use codec::{Decode, Encode};
+use futures::{future, stream::BoxStream, Stream, StreamExt};
 use jsonrpsee::{
 	core::{async_trait, RpcResult},
-	Extensions,
+	ConnectionId, Extensions, PendingSubscriptionSink,
 };
+use parking_lot::Mutex;
 /// Re-export the API for backward compatibility.
-pub use sc_rpc_api::statement::{error::Error, StatementApiServer};
+pub use sc_rpc_api::statement::{error::Error, StatementApiServer, TopicFilter};
 use sp_core::Bytes;
-use sp_statement_store::{StatementSource, SubmitResult};
-use std::sync::Arc;
+use sp_statement_store::{
+	DecryptionKey, Hash, Statement, StatementSource, SubmitResult, MAX_TOPICS,
+};
+use std::{
+	collections::{hash_map::Entry, HashMap},
+	sync::Arc,
+};
+
+/// Maximum number of `statement_subscribe` subscriptions of a connection.
+const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = 16;
+
+/// Number of statements buffered for a subscription before it is dropped.
+const SUBSCRIPTION_BUFFER_SIZE: usize = 1024;
+
+/// Returns a new stream of the hashes and the statements newly accepted by the store.
+type SubscribeStatements = Box<dyn Fn() -> BoxStream<'static, (Hash, Statement)> + Send + Sync>;
 
 /// Statement store API
 pub struct StatementStore {
 	store: Arc<dyn sp_statement_store::StatementStore>,
+	subscribe_statements: SubscribeStatements,
+	executor: SubscriptionTaskExecutor,
+	subscriptions: Subscriptions,
 }
 
 impl StatementStore {
 	/// Create new instance of Offchain API.
-	pub fn new(store: Arc<dyn sp_statement_store::StatementStore>) -> Self {
-		StatementStore { store }
+	///
+	/// `subscribe_statements` is called for every `statement_subscribe` subscription and returns
+	/// a stream of the hashes and the statements newly accepted by `store`.
+	pub fn new<S>(
+		store: Arc<dyn sp_statement_store::StatementStore>,
+		subscribe_statements: impl Fn() -> S + Send + Sync + 'static,
+		executor: SubscriptionTaskExecutor,
+	) -> Self
+	where
+		S: Stream<Item = (Hash, Statement)> + Send + 'static,
+	{
+		StatementStore {
+			store,
+			subscribe_statements: Box::new(move || subscribe_statements().boxed()),
+			executor,
+			subscriptions: Default::default(),
+		}
+	}
+}
+
+/// Number of `statement_subscribe` subscriptions of each connection.
+#[derive(Clone, Default)]
+struct Subscriptions(Arc<Mutex<HashMap<ConnectionId, usize>>>);
+
+impl Subscriptions {
+	/// Reserve a subscription for the connection, `None` if it reached its limit.
+	fn reserve(&self, connection_id: ConnectionId) -> Option<ReservedSubscription> {
+		let mut subscriptions = self.0.lock();
+		let count = subscriptions.entry(connection_id).or_default();
+		if *count >= MAX_SUBSCRIPTIONS_PER_CONNECTION {
+			return None
+		}
+		*count += 1;
+		Some(ReservedSubscription { subscriptions: self.clone(), connection_id })
+	}
+}
+
+/// A subscription reserved for a connection, freed on drop.
+struct ReservedSubscription {
+	subscriptions: Subscriptions,
+	connection_id: ConnectionId,
+}
+
+impl Drop for ReservedSubscription {
+	fn drop(&mut self) {
+		if let Entry::Occupied(mut entry) = self.subscriptions.0.lock().entry(self.connection_id) {
+			*entry.get_mut() -= 1;
+			if *entry.get() == 0 {
+				entry.remove();
+			}
+		}
+	}
+}
+
+/// Check that the filter can match some statements.
+fn check_topic_filter(filter: &TopicFilter) -> Result<(), Error> {
+	match filter {
+		TopicFilter::MatchAll(topics) if topics.len() > MAX_TOPICS =>
+			Err(Error::InvalidTopicFilter(format!("statements have at most {MAX_TOPICS} topics"))),
+		TopicFilter::MatchAny(topics) if topics.is_empty() =>
+			Err(Error::InvalidTopicFilter("no topic to match".into())),
+		_ => Ok(()),
+	}
+}
+
+/// Whether the statement has the decryption key `dest` and the topics matching the filter.
+fn matches(filter: &TopicFilter, dest: Option<DecryptionKey>, statement: &Statement) -> bool {
+	if statement.decryption_key() != dest {
+		return false
+	}
+	let topics: Vec<_> = (0..MAX_TOPICS).map_while(|i| statement.topic(i)).collect();
+	match filter {
+		TopicFilter::MatchAll(filter) => filter.iter().all(|topic| topics.contains(topic)),
+		TopicFilter::MatchAny(filter) => filter.iter().any(|topic| topics.contains(topic)),
 	}
 }
 
@@ -141,4 +239,34 @@ impl StatementApiServer for StatementStore {
 	fn remove(&self, hash: [u8; 32]) -> RpcResult<()> {
 		Ok(self.store.remove(&hash).map_err(|e| Error::StatementStore(e.to_string()))?)
 	}
+
+	fn subscribe(
+		&self,
+		pending: PendingSubscriptionSink,
+		filter: TopicFilter,
+		dest: Option<[u8; 32]>,
+	) {
+		if let Err(e) = check_topic_filter(&filter) {
+			spawn_subscription_task(&self.executor, pending.reject(e));
+			return
+		}
+		let Some(reserved) = self.subscriptions.reserve(pending.connection_id()) else {
+			let e = Error::TooManySubscriptions(MAX_SUBSCRIPTIONS_PER_CONNECTION);
+			spawn_subscription_task(&self.executor, pending.reject(e));
+			return
+		};
+
+		let stream = (self.subscribe_statements)().filter_map(move |(_, statement)| {
+			let item = matches(&filter, dest, &statement).then(|| statement.encode().into());
+			future::ready(item)
+		});
+		let fut = async move {
+			PendingSubscription::from(pending)
+				.pipe_from_stream(stream, BoundedVecDeque::<Bytes>::new(SUBSCRIPTION_BUFFER_SIZE))
+				.await;
+			drop(reserved);
+		};
+
+		spawn_subscription_task(&self.executor, fut);
+	}
 }
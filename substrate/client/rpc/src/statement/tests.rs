@@ -0,0 +1,174 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use super::*;
+
+use crate::testing::{test_executor, timeout_secs};
+use assert_matches::assert_matches;
+use jsonrpsee::{MethodsError as RpcError, RpcModule};
+use sc_utils::notification::{NotificationSender, NotificationStream, TracingKeyStr};
+use sp_statement_store::{Hash, Result, Topic};
+
+struct TestStore;
+
+impl sp_statement_store::StatementStore for TestStore {
+	fn statements(&self) -> Result<Vec<(Hash, Statement)>> {
+		unimplemented!()
+	}
+
+	fn statement(&self, _hash: &Hash) -> Result<Option<Statement>> {
+		unimplemented!()
+	}
+
+	fn broadcasts(&self, _match_all_topics: &[Topic]) -> Result<Vec<Vec<u8>>> {
+		unimplemented!()
+	}
+
+	fn posted(&self, _match_all_topics: &[Topic], _dest: [u8; 32]) -> Result<Vec<Vec<u8>>> {
+		unimplemented!()
+	}
+
+	fn posted_clear(&self, _match_all_topics: &[Topic], _dest: [u8; 32]) -> Result<Vec<Vec<u8>>> {
+		unimplemented!()
+	}
+
+	fn broadcasts_stmt(&self, _match_all_topics: &[Topic]) -> Result<Vec<Vec<u8>>> {
+		unimplemented!()
+	}
+
+	fn posted_stmt(&self, _match_all_topics: &[Topic], _dest: [u8; 32]) -> Result<Vec<Vec<u8>>> {
+		unimplemented!()
+	}
+
+	fn posted_clear_stmt(
+		&self,
+		_match_all_topics: &[Topic],
+		_dest: [u8; 32],
+	) -> Result<Vec<Vec<u8>>> {
+		unimplemented!()
+	}
+
+	fn submit(&self, _statement: Statement, _source: StatementSource) -> SubmitResult {
+		unimplemented!()
+	}
+
+	fn remove(&self, _hash: &Hash) -> Result<()> {
+		unimplemented!()
+	}
+}
+
+#[derive(Clone)]
+struct TestTracingKey;
+
+impl TracingKeyStr for TestTracingKey {
+	const TRACING_KEY: &'static str = "mpsc_test_statement_notification_stream";
+}
+
+fn setup() -> (NotificationSender<(Hash, Statement)>, RpcModule<StatementStore>) {
+	let (sender, notifications) = NotificationStream::<_, TestTracingKey>::channel();
+	let subscribe = move || notifications.subscribe(100_000);
+	let api = StatementStore::new(Arc::new(TestStore), subscribe, test_executor());
+	(sender, api.into_rpc())
+}
+
+fn statement(topics: &[u8], dest: Option<u8>) -> Statement {
+	let mut statement = Statement::new();
+	for (i, topic) in topics.iter().enumerate() {
+		statement.set_topic(i, [*topic; 32]);
+	}
+	if let Some(dest) = dest {
+		statement.set_decryption_key([dest; 32]);
+	}
+	statement
+}
+
+fn notify(sender: &NotificationSender<(Hash, Statement)>, statement: &Statement) {
+	let _ = sender.notify(|| Ok::<_, ()>((statement.hash(), statement.clone())));
+}
+
+#[tokio::test]
+async fn subscription_sends_matching_statements() {
+	let (sender, api) = setup();
+	let filter = TopicFilter::MatchAll(vec![[1; 32], [2; 32]]);
+	let mut match_all = api
+		.subscribe_unbounded("statement_subscribe", (filter, None::<[u8; 32]>))
+		.await
+		.unwrap();
+	let filter = TopicFilter::MatchAny(vec![[1; 32], [2; 32]]);
+	let mut match_any = api
+		.subscribe_unbounded("statement_subscribe", (filter, Some([7u8; 32])))
+		.await
+		.unwrap();
+
+	let statements = [
+		statement(&[1], None),
+		statement(&[2, 1, 3], None),
+		statement(&[2], Some(7)),
+		statement(&[1, 2], Some(8)),
+		statement(&[3], Some(7)),
+		statement(&[3, 1], Some(7)),
+	];
+	for statement in &statements {
+		notify(&sender, statement);
+	}
+
+	let (item, _) = timeout_secs(10, match_all.next::<Bytes>()).await.unwrap().unwrap().unwrap();
+	assert_eq!(item, statements[1].encode().into());
+	for expected in [&statements[2], &statements[5]] {
+		let next = timeout_secs(10, match_any.next::<Bytes>()).await.unwrap();
+		assert_eq!(next.unwrap().unwrap().0, expected.encode().into());
+	}
+}
+
+#[tokio::test]
+async fn subscription_rejects_unmatchable_filters() {
+	let (_sender, api) = setup();
+
+	let filter = TopicFilter::MatchAll(vec![[1; 32]; MAX_TOPICS + 1]);
+	let sub = api.subscribe_unbounded("statement_subscribe", (filter, None::<[u8; 32]>)).await;
+	assert_matches!(sub, Err(RpcError::JsonRpc(err)) if err.code() == 7002);
+
+	let filter = TopicFilter::MatchAny(Vec::new());
+	let sub = api.subscribe_unbounded("statement_subscribe", (filter, None::<[u8; 32]>)).await;
+	assert_matches!(sub, Err(RpcError::JsonRpc(err)) if err.code() == 7002);
+}
+
+#[tokio::test]
+async fn subscriptions_are_limited_per_connection() {
+	let (_sender, api) = setup();
+	let params = (TopicFilter::MatchAll(Vec::new()), None::<[u8; 32]>);
+
+	let mut subs = Vec::new();
+	for _ in 0..MAX_SUBSCRIPTIONS_PER_CONNECTION {
+		subs.push(api.subscribe_unbounded("statement_subscribe", params.clone()).await.unwrap());
+	}
+	let sub = api.subscribe_unbounded("statement_subscribe", params.clone()).await;
+	assert_matches!(sub, Err(RpcError::JsonRpc(err)) if err.code() == 7003);
+
+	// Closing a subscription frees a slot for the connection.
+	drop(subs.pop());
+	let sub = timeout_secs(10, async {
+		loop {
+			match api.subscribe_unbounded("statement_subscribe", params.clone()).await {
+				Ok(sub) => break sub,
+				Err(_) => tokio::time::sleep(std::time::Duration::from_millis(10)).await,
+			}
+		}
+	})
+	.await;
+	assert!(sub.is_ok());
+}
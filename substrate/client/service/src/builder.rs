@@ -64,7 +64,7 @@ use sc_network_sync::{
 		SyncingStrategy,
 	},
 	warp_request_handler::RequestHandler as WarpSyncRequestHandler,
-	SyncingService, WarpSyncConfig,
+	SyncingService, WarpSyncCheckpoint, WarpSyncConfig,
 };
 use sc_rpc::{
 	author::AuthorApiServer,
@@ -90,7 +90,7 @@ use sp_consensus::block_validation::{
 };
 use sp_core::traits::{CodeExecutor, SpawnNamed};
 use sp_keystore::KeystorePtr;
-use sp_runtime::traits::{Block as BlockT, BlockIdTo, NumberFor, Zero};
+use sp_runtime::traits::{Block as BlockT, BlockIdTo, Header as HeaderT, NumberFor, Zero};
 use sp_storage::{ChildInfo, ChildType, PrefixedStorageKey};
 use std::{
 	str::FromStr,
@@ -1415,6 +1415,24 @@ where
 		return Err("Warp sync enabled, but no warp sync provider configured.".into())
 	}
 
+	let warp_sync_config = match (warp_sync_config, &net_config.network_config.warp_sync_checkpoint)
+	{
+		(Some(WarpSyncConfig::WithProvider(provider)), Some(checkpoint_config)) => {
+			let encoded = std::fs::read(&checkpoint_config.path)?;
+			let checkpoint =
+				WarpSyncCheckpoint::decode_and_verify(&encoded, &checkpoint_config.trusted_hash)?;
+			info!(
+				"Using warp sync checkpoint #{} ({}).",
+				checkpoint.header.number(),
+				checkpoint.header.hash(),
+			);
+			Some(WarpSyncConfig::WithCheckpoint { provider, checkpoint })
+		},
+		(_, Some(_)) =>
+			return Err("Warp sync checkpoint requires a warp sync provider.".into()),
+		(warp_sync_config, None) => warp_sync_config,
+	};
+
 	if client.requires_full_sync() {
 		match net_config.network_config.sync_mode {
 			SyncMode::LightState { .. } =>
@@ -1440,7 +1458,8 @@ where
 	net_config.add_request_response_protocol(state_request_protocol_config);
 
 	let (warp_sync_protocol_config, warp_sync_protocol_name) = match warp_sync_config.as_ref() {
-		Some(WarpSyncConfig::WithProvider(warp_with_provider)) => {
+		Some(WarpSyncConfig::WithProvider(warp_with_provider)) |
+		Some(WarpSyncConfig::WithCheckpoint { provider: warp_with_provider, .. }) => {
 			// Allow both outgoing and incoming requests.
 			let (handler, protocol_config) = WarpSyncRequestHandler::new::<_, Net>(
 				protocol_id,
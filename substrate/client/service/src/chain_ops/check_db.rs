@@ -0,0 +1,284 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Integrity check of the blocks and the states kept in the database.
+
+use crate::error::Error;
+use log::info;
+use sc_client_api::{backend::Backend, StateBackend, TrieCacheContext};
+use sp_blockchain::{Backend as _, HeaderBackend};
+use sp_core::storage::{well_known_keys, ChildInfo};
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT, NumberFor, One, Zero},
+	SaturatedConversion,
+};
+use sp_state_machine::IterArgs;
+use std::fmt;
+
+/// Number of checked blocks after which the progress is logged.
+const PROGRESS_INTERVAL: u64 = 10_000;
+
+/// An inconsistency found in the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DbIssue<B: BlockT> {
+	/// No canonical block is known at this height.
+	MissingCanonicalHash(NumberFor<B>),
+	/// The header of a canonical block is missing, can't be decoded or doesn't link to its
+	/// parent.
+	InvalidHeader(NumberFor<B>, B::Hash, String),
+	/// The body of a block is missing while the bodies of older blocks are kept, or can't be
+	/// decoded.
+	InvalidBody(NumberFor<B>, B::Hash, String),
+	/// The justifications of a block can't be decoded or belong to a block that isn't finalized.
+	InvalidJustifications(NumberFor<B>, B::Hash, String),
+	/// The state of a block was discarded while it should be kept, or some of its trie nodes are
+	/// missing.
+	IncompleteState(NumberFor<B>, B::Hash, String),
+	/// A leaf that doesn't lead to the canonical chain, or that forks off below the last finalized
+	/// block.
+	Orphan(B::Hash, String),
+}
+
+impl<B: BlockT> DbIssue<B> {
+	/// The number of the canonical block the issue was found at, `None` for orphans.
+	pub fn number(&self) -> Option<NumberFor<B>> {
+		match self {
+			Self::MissingCanonicalHash(number) |
+			Self::InvalidHeader(number, ..) |
+			Self::InvalidBody(number, ..) |
+			Self::InvalidJustifications(number, ..) |
+			Self::IncompleteState(number, ..) => Some(*number),
+			Self::Orphan(..) => None,
+		}
+	}
+}
+
+impl<B: BlockT> fmt::Display for DbIssue<B> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::MissingCanonicalHash(number) =>
+				write!(f, "#{number}: no canonical block at this height"),
+			Self::InvalidHeader(number, hash, reason) =>
+				write!(f, "#{number} ({hash}): invalid header: {reason}"),
+			Self::InvalidBody(number, hash, reason) =>
+				write!(f, "#{number} ({hash}): invalid body: {reason}"),
+			Self::InvalidJustifications(number, hash, reason) =>
+				write!(f, "#{number} ({hash}): invalid justifications: {reason}"),
+			Self::IncompleteState(number, hash, reason) =>
+				write!(f, "#{number} ({hash}): incomplete state: {reason}"),
+			Self::Orphan(hash, reason) => write!(f, "Orphan leaf {hash}: {reason}"),
+		}
+	}
+}
+
+/// The result of [`check_db`].
+#[derive(Debug)]
+pub struct DbCheckReport<B: BlockT> {
+	/// Number of canonical blocks that were checked.
+	pub checked_blocks: u64,
+	/// Number of states whose tries were walked.
+	pub walked_states: u64,
+	/// The issues found, canonical blocks from the highest to the lowest followed by orphans.
+	pub issues: Vec<DbIssue<B>>,
+	/// The highest canonical block such that neither it nor any of its ancestors have issues.
+	///
+	/// `None` if the genesis block has issues.
+	pub last_valid: Option<(NumberFor<B>, B::Hash)>,
+}
+
+/// Check the integrity of the database.
+///
+/// Walks the canonical chain from the best block down to genesis, checking that every block has
+/// a header linking to its parent, a body unless it was pruned and readable justifications. The
+/// state of the last finalized block and of the blocks above it must be kept. The tries of the
+/// states of the last finalized block and of the `state_depth` best canonical blocks are walked
+/// to ensure none of their nodes are missing. Finally, the leaves are checked to lead back to the
+/// canonical chain.
+///
+/// Finalized blocks whose bodies are missing are only reported when the body of an older block
+/// is kept, as block pruning and warp sync don't keep the bodies of old blocks.
+pub fn check_db<B, BA>(backend: &BA, state_depth: u64) -> Result<DbCheckReport<B>, Error>
+where
+	B: BlockT,
+	BA: Backend<B>,
+{
+	let blockchain = backend.blockchain();
+	let info = blockchain.info();
+	let best: u64 = info.best_number.saturated_into();
+	let finalized: u64 = info.finalized_number.saturated_into();
+	// Blocks of the gap left by warp sync haven't been downloaded yet.
+	let gap = info.block_gap.map(|gap| (gap.start, gap.end));
+	let in_gap = |number| gap.is_some_and(|(start, end)| start <= number && number <= end);
+
+	let mut report =
+		DbCheckReport { checked_blocks: 0, walked_states: 0, issues: Vec::new(), last_valid: None };
+	// Finalized blocks without a body that are only reported if an older body is found.
+	let mut missing_bodies = Vec::new();
+	// The header of the canonical block above the current one.
+	let mut child: Option<B::Header> = None;
+
+	info!("Checking the database from the best block #{}", info.best_number);
+	for n in (0..=best).rev() {
+		let number: NumberFor<B> = n.saturated_into();
+		if in_gap(number) {
+			child = None;
+			continue
+		}
+		report.checked_blocks += 1;
+		if report.checked_blocks % PROGRESS_INTERVAL == 0 {
+			info!("Checked {} blocks, now at #{}", report.checked_blocks, number);
+		}
+
+		let Some(hash) = blockchain.hash(number)? else {
+			// The canonical hash is read from the header, so a missing header is told apart by
+			// the hash the child links to.
+			let issue = match child.take() {
+				Some(child) if matches!(blockchain.header(*child.parent_hash()), Ok(None)) =>
+					DbIssue::InvalidHeader(number, *child.parent_hash(), "missing".into()),
+				_ => DbIssue::MissingCanonicalHash(number),
+			};
+			report.issues.push(issue);
+			continue
+		};
+		let is_finalized = n <= finalized;
+
+		if let Some(child) = child.take() {
+			if *child.parent_hash() != hash {
+				let reason = format!("parent {} is not canonical", child.parent_hash());
+				report.issues.push(DbIssue::InvalidHeader(*child.number(), child.hash(), reason));
+			}
+		}
+		match blockchain.header(hash) {
+			Ok(Some(header)) if header.hash() != hash || *header.number() != number => {
+				let reason = format!("found #{} ({})", header.number(), header.hash());
+				report.issues.push(DbIssue::InvalidHeader(number, hash, reason));
+			},
+			Ok(Some(header)) => child = Some(header),
+			Ok(None) => report.issues.push(DbIssue::InvalidHeader(number, hash, "missing".into())),
+			Err(e) => report.issues.push(DbIssue::InvalidHeader(number, hash, e.to_string())),
+		}
+
+		match blockchain.body(hash) {
+			// The genesis body is kept when the following bodies are not downloaded by warp sync.
+			Ok(Some(_)) if !number.is_zero() => report.issues.append(&mut missing_bodies),
+			Ok(Some(_)) => {},
+			Ok(None) if is_finalized =>
+				missing_bodies.push(DbIssue::InvalidBody(number, hash, "missing".into())),
+			Ok(None) => report.issues.push(DbIssue::InvalidBody(number, hash, "missing".into())),
+			Err(e) => report.issues.push(DbIssue::InvalidBody(number, hash, e.to_string())),
+		}
+
+		match blockchain.justifications(hash) {
+			Ok(Some(_)) if !is_finalized => {
+				let reason = "the block is not finalized".into();
+				report.issues.push(DbIssue::InvalidJustifications(number, hash, reason));
+			},
+			Ok(_) => {},
+			Err(e) =>
+				report.issues.push(DbIssue::InvalidJustifications(number, hash, e.to_string())),
+		}
+
+		let has_state = backend.have_state_at(hash, number);
+		if n >= finalized && !has_state {
+			let reason = "the state was discarded".into();
+			report.issues.push(DbIssue::IncompleteState(number, hash, reason));
+		}
+		if has_state && (n == finalized || best - n < state_depth) {
+			info!("Walking the state of block #{} ({})", number, hash);
+			report.walked_states += 1;
+			if let Err(reason) = walk_state(backend, hash) {
+				report.issues.push(DbIssue::IncompleteState(number, hash, reason));
+			}
+		}
+	}
+
+	report.issues.sort_by(|a, b| b.number().cmp(&a.number()));
+	for leaf in blockchain.leaves()? {
+		if let Some(reason) = orphan_reason(blockchain, leaf, info.finalized_number)? {
+			report.issues.push(DbIssue::Orphan(leaf, reason));
+		}
+	}
+
+	report.last_valid = match report.issues.iter().filter_map(DbIssue::number).min() {
+		None => Some((info.best_number, info.best_hash)),
+		Some(number) if number.is_zero() => None,
+		Some(number) => {
+			let number = number - One::one();
+			blockchain.hash(number)?.map(|hash| (number, hash))
+		},
+	};
+	Ok(report)
+}
+
+/// Read every key and value of the tries of the state of the block `hash`.
+fn walk_state<B, BA>(backend: &BA, hash: B::Hash) -> Result<(), String>
+where
+	B: BlockT,
+	BA: Backend<B>,
+{
+	let state = backend.state_at(hash, TrieCacheContext::Untrusted).map_err(|e| e.to_string())?;
+	let mut child_tries = Vec::new();
+	for pair in state.pairs(IterArgs::default()).map_err(|e| e.to_string())? {
+		let (key, _) = pair.map_err(|e| e.to_string())?;
+		if let Some(storage_key) =
+			key.strip_prefix(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX)
+		{
+			child_tries.push(ChildInfo::new_default(storage_key));
+		}
+	}
+	for child_info in child_tries {
+		let mut args = IterArgs::default();
+		args.child_info = Some(child_info.clone());
+		for pair in state.pairs(args).map_err(|e| e.to_string())? {
+			pair.map_err(|e| format!("child trie {:?}: {e}", child_info.storage_key()))?;
+		}
+	}
+	Ok(())
+}
+
+/// Why the `leaf` is an orphan, `None` if its ancestry leads to the canonical chain.
+fn orphan_reason<B, BC>(
+	blockchain: &BC,
+	leaf: B::Hash,
+	finalized: NumberFor<B>,
+) -> Result<Option<String>, Error>
+where
+	B: BlockT,
+	BC: HeaderBackend<B>,
+{
+	let Some(mut header) = blockchain.header(leaf)? else {
+		return Ok(Some("missing header".into()))
+	};
+	loop {
+		let number = *header.number();
+		if blockchain.hash(number)? == Some(header.hash()) {
+			return Ok(None)
+		}
+		if number <= finalized {
+			return Ok(Some(format!("forks off below the finalized block at #{number}")))
+		}
+		let parent = *header.parent_hash();
+		header = match blockchain.header(parent)? {
+			Some(parent) => parent,
+			None => {
+				let reason = format!("missing ancestor #{} ({parent})", number - One::one());
+				return Ok(Some(reason))
+			},
+		};
+	}
+}
@@ -19,7 +19,7 @@
 use crate::error::Error;
 use log::info;
 use sc_client_api::{Backend, UsageProvider};
-use sp_runtime::traits::{Block as BlockT, NumberFor, Zero};
+use sp_runtime::traits::{Block as BlockT, NumberFor, Saturating, Zero};
 use std::sync::Arc;
 
 /// Performs a revert of `blocks` blocks.
@@ -50,3 +50,34 @@ where
 	}
 	Ok(())
 }
+
+/// Reverts the chain down to the block `number`, reverting finalized blocks if needed.
+pub fn revert_chain_to<B, BA, C>(
+	client: Arc<C>,
+	backend: Arc<BA>,
+	number: NumberFor<B>,
+) -> Result<(), Error>
+where
+	B: BlockT,
+	C: UsageProvider<B>,
+	BA: Backend<B>,
+{
+	let info = client.usage_info().chain;
+	let blocks = info.best_number.saturating_sub(number);
+	let revert_finalized = number < info.finalized_number;
+	let reverted = backend.revert(blocks, revert_finalized)?;
+
+	let info = client.usage_info().chain;
+	info!(
+		"Reverted {} blocks. Best: #{} ({}), finalized: #{} ({})",
+		reverted.0, info.best_number, info.best_hash, info.finalized_number, info.finalized_hash,
+	);
+	// The backend stops reverting at the first block whose parent has no state.
+	if info.best_number != number {
+		return Err(Error::Other(format!(
+			"Failed to revert to #{number}, the best block is #{}",
+			info.best_number
+		)))
+	}
+	Ok(())
+}
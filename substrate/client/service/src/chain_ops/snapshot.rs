@@ -0,0 +1,447 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Binary snapshots of the state of a finalized block.
+//!
+//! A snapshot starts with [`SNAPSHOT_MAGIC`] followed by a sequence of records. Every record is
+//! the little endian `u32` length of its SCALE encoded payload, the payload and the
+//! `blake2_256` hash of the payload. The first record holds the genesis hash of the chain, the
+//! header and the justifications of the block, followed by any number of key/value chunks of the
+//! top trie and the child tries and an end record holding the number of chunks written.
+
+use crate::error::Error;
+use codec::{Decode, Encode};
+use futures::{future, prelude::*};
+use futures_timer::Delay;
+use log::{info, warn};
+use sc_client_api::{BlockBackend, HeaderBackend, StorageProvider};
+use sc_consensus::{
+	import_queue::{BlockImportError, BlockImportStatus, ImportQueue, IncomingBlock, Link},
+	ImportedState,
+};
+use sp_consensus::BlockOrigin;
+use sp_core::{
+	hashing::blake2_256,
+	storage::{well_known_keys, ChildInfo},
+};
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT, NumberFor, Zero},
+	Justifications,
+};
+use sp_state_machine::{KeyValueStates, KeyValueStorageLevel};
+use std::{
+	io::{Read, Write},
+	pin::Pin,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+	task::Poll,
+	time::Duration,
+};
+
+/// Magic bytes every snapshot starts with, the last byte being the format version.
+pub const SNAPSHOT_MAGIC: [u8; 8] = *b"SUBSNAP\x01";
+
+/// Number of key/value bytes after which a chunk is written.
+const CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// Maximum length of a single record we accept when reading a snapshot.
+const MAX_RECORD_SIZE: usize = 256 * 1024 * 1024;
+
+/// Number of milliseconds to wait until next poll.
+const DELAY_TIME: u64 = 200;
+
+/// The trie a chunk of key/values belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
+enum SnapshotTrie {
+	/// The top trie.
+	Top,
+	/// A default child trie.
+	Child {
+		/// The prefixed storage key of the child trie.
+		storage_key: Vec<u8>,
+		/// The root of the child trie as stored in the top trie.
+		root: Vec<u8>,
+	},
+}
+
+/// A record of a snapshot.
+#[derive(Encode, Decode)]
+enum SnapshotRecord<B: BlockT> {
+	/// The block the snapshot was taken at.
+	Block { genesis_hash: B::Hash, header: B::Header, justifications: Option<Justifications> },
+	/// Key/values of a trie.
+	Chunk { trie: SnapshotTrie, key_values: Vec<(Vec<u8>, Vec<u8>)> },
+	/// The end of the snapshot.
+	End { chunks: u64 },
+}
+
+/// Writes [`SnapshotRecord`]s to the output.
+struct SnapshotWriter<W, B> {
+	output: W,
+	chunks: u64,
+	trie: SnapshotTrie,
+	key_values: Vec<(Vec<u8>, Vec<u8>)>,
+	size: usize,
+	_phantom: std::marker::PhantomData<B>,
+}
+
+impl<W: Write, B: BlockT> SnapshotWriter<W, B> {
+	fn new(mut output: W) -> Result<Self, Error> {
+		output.write_all(&SNAPSHOT_MAGIC)?;
+		Ok(Self {
+			output,
+			chunks: 0,
+			trie: SnapshotTrie::Top,
+			key_values: Vec::new(),
+			size: 0,
+			_phantom: Default::default(),
+		})
+	}
+
+	fn write(&mut self, record: &SnapshotRecord<B>) -> Result<(), Error> {
+		let payload = record.encode();
+		let len = u32::try_from(payload.len())
+			.map_err(|_| Error::Other("Snapshot record too large".into()))?;
+		self.output.write_all(&len.to_le_bytes())?;
+		self.output.write_all(&payload)?;
+		self.output.write_all(&blake2_256(&payload))?;
+		Ok(())
+	}
+
+	/// Switch to the given trie, flushing the key/values of the previous one.
+	fn start_trie(&mut self, trie: SnapshotTrie) -> Result<(), Error> {
+		self.flush()?;
+		self.trie = trie;
+		Ok(())
+	}
+
+	fn push(&mut self, key: Vec<u8>, value: Vec<u8>) -> Result<(), Error> {
+		self.size += key.len() + value.len();
+		self.key_values.push((key, value));
+		if self.size >= CHUNK_SIZE {
+			self.flush()?;
+		}
+		Ok(())
+	}
+
+	fn flush(&mut self) -> Result<(), Error> {
+		if self.key_values.is_empty() {
+			return Ok(())
+		}
+		let key_values = std::mem::take(&mut self.key_values);
+		self.write(&SnapshotRecord::Chunk { trie: self.trie.clone(), key_values })?;
+		self.chunks += 1;
+		self.size = 0;
+		Ok(())
+	}
+
+	fn finish(mut self) -> Result<u64, Error> {
+		self.flush()?;
+		let chunks = self.chunks;
+		self.write(&SnapshotRecord::End { chunks })?;
+		self.output.flush()?;
+		Ok(chunks)
+	}
+}
+
+/// Reads [`SnapshotRecord`]s from the input, verifying their checksums.
+struct SnapshotReader<R> {
+	input: R,
+	read_records: u64,
+}
+
+impl<R: Read> SnapshotReader<R> {
+	fn new(mut input: R) -> Result<Self, Error> {
+		let mut magic = [0u8; SNAPSHOT_MAGIC.len()];
+		input.read_exact(&mut magic)?;
+		if magic != SNAPSHOT_MAGIC {
+			return Err(Error::Other("Not a snapshot or unsupported snapshot version".into()))
+		}
+		Ok(Self { input, read_records: 0 })
+	}
+
+	fn read<B: BlockT>(&mut self) -> Result<SnapshotRecord<B>, Error> {
+		let mut len = [0u8; 4];
+		self.input.read_exact(&mut len)?;
+		let len = u32::from_le_bytes(len) as usize;
+		if len > MAX_RECORD_SIZE {
+			return Err(Error::Other(format!(
+				"Snapshot record #{} exceeds the maximum size",
+				self.read_records
+			)))
+		}
+		let mut payload = vec![0u8; len];
+		self.input.read_exact(&mut payload)?;
+		let mut checksum = [0u8; 32];
+		self.input.read_exact(&mut checksum)?;
+		if blake2_256(&payload) != checksum {
+			return Err(Error::Other(format!(
+				"Checksum mismatch of snapshot record #{}",
+				self.read_records
+			)))
+		}
+		let record = SnapshotRecord::decode(&mut &payload[..]).map_err(|e| {
+			Error::Other(format!("Error decoding snapshot record #{}: {}", self.read_records, e))
+		})?;
+		self.read_records += 1;
+		Ok(record)
+	}
+}
+
+/// Export the state of the finalized block `hash` together with its header and justifications
+/// as a binary snapshot.
+pub fn export_snapshot<B, BA, C>(
+	client: Arc<C>,
+	hash: B::Hash,
+	output: impl Write,
+) -> Result<(), Error>
+where
+	C: HeaderBackend<B> + BlockBackend<B> + StorageProvider<B, BA>,
+	B: BlockT,
+	BA: sc_client_api::backend::Backend<B>,
+{
+	let header = client
+		.header(hash)?
+		.ok_or_else(|| Error::Other(format!("Header for {} not found", hash)))?;
+	let number = *header.number();
+	let info = client.info();
+	if number > info.finalized_number || client.hash(number)? != Some(hash) {
+		return Err(Error::Other(format!("Block #{} ({}) is not finalized", number, hash)))
+	}
+	let justifications = client.justifications(hash)?;
+
+	info!("Exporting snapshot of block #{} ({})", number, hash);
+	let mut writer = SnapshotWriter::<_, B>::new(output)?;
+	let genesis_hash = info.genesis_hash;
+	writer.write(&SnapshotRecord::Block { genesis_hash, header, justifications })?;
+
+	let mut child_roots = Vec::new();
+	for (key, value) in client.storage_pairs(hash, None, None)? {
+		// Child trie roots are recomputed on import, their content is exported separately.
+		if key.0.starts_with(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+			child_roots.push((key.0, value.0));
+			continue
+		}
+		writer.push(key.0, value.0)?;
+	}
+
+	for (storage_key, root) in child_roots {
+		let child_info = ChildInfo::new_default(
+			&storage_key[well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX.len()..],
+		);
+		writer.start_trie(SnapshotTrie::Child { storage_key, root })?;
+		for child_key in client.child_storage_keys(hash, child_info.clone(), None, None)? {
+			if let Some(child_value) = client.child_storage(hash, &child_info, &child_key)? {
+				writer.push(child_key.0, child_value.0)?;
+			}
+		}
+	}
+
+	let chunks = writer.finish()?;
+	info!("🎉 Exported snapshot of block #{} in {} chunks", number, chunks);
+	Ok(())
+}
+
+/// Import a snapshot written by [`export_snapshot`] into an empty database of the same chain.
+///
+/// The state is imported the same way state sync does: the block is passed to the import queue
+/// together with its state and without being executed.
+pub fn import_snapshot<B, IQ, C>(
+	client: Arc<C>,
+	mut import_queue: IQ,
+	input: impl Read + Send + 'static,
+) -> Pin<Box<dyn Future<Output = Result<(), Error>> + Send>>
+where
+	C: HeaderBackend<B> + Send + Sync + 'static,
+	B: BlockT,
+	IQ: ImportQueue<B> + 'static,
+{
+	struct WaitLink {
+		processed: AtomicBool,
+		has_error: AtomicBool,
+	}
+
+	impl<B: BlockT> Link<B> for WaitLink {
+		fn blocks_processed(
+			&self,
+			_imported: usize,
+			_num_expected_blocks: usize,
+			results: Vec<(Result<BlockImportStatus<NumberFor<B>>, BlockImportError>, B::Hash)>,
+		) {
+			for (result, hash) in results {
+				if let Err(err) = result {
+					warn!("There was an error importing snapshot block {:?}: {}", hash, err);
+					self.has_error.store(true, Ordering::Release);
+				}
+			}
+			self.processed.store(true, Ordering::Release);
+		}
+	}
+
+	let info = client.info();
+	if !info.best_number.is_zero() {
+		return future::ready(Err(Error::Other(format!(
+			"Snapshots can only be imported into an empty database, best block is #{}",
+			info.best_number
+		))))
+		.boxed()
+	}
+
+	let block = match read_snapshot::<B>(input, info.genesis_hash) {
+		Ok(block) => block,
+		Err(e) => return future::ready(Err(e)).boxed(),
+	};
+	let number = *block.header.as_ref().expect("Set by `read_snapshot`; qed").number();
+	import_queue.service_ref().import_blocks(BlockOrigin::File, vec![block]);
+
+	let link = WaitLink { processed: AtomicBool::new(false), has_error: AtomicBool::new(false) };
+	let mut delay = Delay::new(Duration::from_millis(DELAY_TIME));
+
+	future::poll_fn(move |cx| {
+		import_queue.poll_actions(cx, &link);
+
+		if link.processed.load(Ordering::Acquire) {
+			if link.has_error.load(Ordering::Acquire) {
+				return Poll::Ready(Err(Error::Other(format!(
+					"Failed to import snapshot of block #{}",
+					number
+				))))
+			}
+			info!("🎉 Imported snapshot of block #{}", number);
+			return Poll::Ready(Ok(()))
+		}
+
+		// Wait for the delay, because we know the queue is lagging behind.
+		if Pin::new(&mut delay).poll(cx).is_ready() {
+			delay.reset(Duration::from_millis(DELAY_TIME));
+			cx.waker().wake_by_ref();
+		}
+		Poll::Pending
+	})
+	.boxed()
+}
+
+/// Read a snapshot of the chain with the given genesis into a block ready to be passed to the
+/// import queue.
+///
+/// Every chunk is kept as its own level of the imported state as it's read, the import merges the
+/// levels of the same trie.
+fn read_snapshot<B: BlockT>(
+	input: impl Read,
+	genesis_hash: B::Hash,
+) -> Result<IncomingBlock<B>, Error> {
+	let mut reader = SnapshotReader::new(input)?;
+	let (header, justifications) = match reader.read::<B>()? {
+		SnapshotRecord::Block { genesis_hash: snapshot_genesis_hash, header, justifications } => {
+			if snapshot_genesis_hash != genesis_hash {
+				return Err(Error::Other(format!(
+					"Snapshot of another chain, genesis is {} instead of {}",
+					snapshot_genesis_hash, genesis_hash
+				)))
+			}
+			(header, justifications)
+		},
+		_ => return Err(Error::Other("Snapshot does not start with a block".into())),
+	};
+
+	// Same layout as produced by state sync: the top trie has neither a root nor parent keys,
+	// child tries are referenced by their prefixed storage key in the top trie.
+	let mut levels = Vec::new();
+	loop {
+		match reader.read::<B>()? {
+			SnapshotRecord::Chunk { trie, key_values } => {
+				let (state_root, parent_storage_keys) = match trie {
+					SnapshotTrie::Top => (Vec::new(), Vec::new()),
+					SnapshotTrie::Child { storage_key, root } => (root, vec![storage_key]),
+				};
+				levels.push(KeyValueStorageLevel { state_root, parent_storage_keys, key_values });
+			},
+			SnapshotRecord::End { chunks } if chunks == levels.len() as u64 => break,
+			SnapshotRecord::End { chunks } =>
+				return Err(Error::Other(format!(
+					"Snapshot is truncated, read {} out of {} chunks",
+					levels.len(),
+					chunks
+				))),
+			SnapshotRecord::Block { .. } =>
+				return Err(Error::Other("Snapshot contains more than one block".into())),
+		}
+	}
+
+	let hash = header.hash();
+	info!("Read snapshot of block #{} ({}) in {} chunks", header.number(), hash, levels.len());
+	Ok(IncomingBlock {
+		hash,
+		header: Some(header),
+		body: None,
+		indexed_body: None,
+		justifications,
+		origin: None,
+		allow_missing_state: true,
+		import_existing: true,
+		skip_execution: true,
+		state: Some(ImportedState { block: hash, state: KeyValueStates(levels) }),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use substrate_test_runtime_client::runtime::Block;
+
+	fn write_snapshot(records: &[SnapshotRecord<Block>]) -> Vec<u8> {
+		let mut output = Vec::new();
+		let mut writer = SnapshotWriter::<_, Block>::new(&mut output).unwrap();
+		for record in records {
+			writer.write(record).unwrap();
+		}
+		output
+	}
+
+	#[test]
+	fn snapshot_records_roundtrip() {
+		let output = write_snapshot(&[
+			SnapshotRecord::Chunk {
+				trie: SnapshotTrie::Top,
+				key_values: vec![(b"key".to_vec(), b"value".to_vec())],
+			},
+			SnapshotRecord::End { chunks: 1 },
+		]);
+
+		let mut reader = SnapshotReader::new(&output[..]).unwrap();
+		assert!(matches!(
+			reader.read::<Block>().unwrap(),
+			SnapshotRecord::Chunk { trie: SnapshotTrie::Top, key_values }
+				if key_values == vec![(b"key".to_vec(), b"value".to_vec())]
+		));
+		assert!(matches!(reader.read::<Block>().unwrap(), SnapshotRecord::End { chunks: 1 }));
+	}
+
+	#[test]
+	fn corrupted_snapshot_is_rejected() {
+		let mut output = write_snapshot(&[SnapshotRecord::End { chunks: 0 }]);
+		let len = output.len();
+		output[len - 33] ^= 1;
+
+		let mut reader = SnapshotReader::new(&output[..]).unwrap();
+		assert!(reader.read::<Block>().is_err());
+		assert!(SnapshotReader::new(&b"not a snapshot"[..]).is_err());
+	}
+}
@@ -284,7 +284,8 @@ where
 				}
 			}
 
-			self.backend.commit_operation(op)?;
+			tracing::info_span!("commit_operation")
+				.in_scope(|| self.backend.commit_operation(op))?;
 
 			// We need to pin the block in the backend once
 			// for each notification. Once all notifications are
@@ -829,6 +830,9 @@ where
 			// We should enact state, but don't have any storage changes, so we need to execute the
 			// block.
 			(true, None, Some(ref body)) => {
+				let _execute_span =
+					tracing::info_span!("execute_block", number = %import_block.header.number())
+						.entered();
 				let mut runtime_api = self.runtime_api();
 				let call_context = CallContext::Onchain;
 				runtime_api.set_call_context(call_context);
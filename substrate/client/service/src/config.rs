@@ -34,7 +34,8 @@ pub use sc_network::{
 	Multiaddr,
 };
 pub use sc_rpc_server::{
-	IpNetwork, RpcEndpoint, RpcMethods, SubscriptionIdProvider as RpcSubscriptionIdProvider,
+	IpNetwork, RpcEndpoint, RpcMethodCosts, RpcMethods,
+	SubscriptionIdProvider as RpcSubscriptionIdProvider,
 };
 pub use sc_telemetry::TelemetryEndpoints;
 pub use sc_transaction_pool::TransactionPoolOptions;
@@ -336,10 +337,16 @@ pub struct RpcConfiguration {
 	pub batch_config: RpcBatchRequestConfig,
 	/// RPC rate limit per minute.
 	pub rate_limit: Option<NonZeroU32>,
+	/// RPC rate limit per minute shared by all the connections of an ip address.
+	pub rate_limit_per_ip: Option<NonZeroU32>,
+	/// Cost of the RPC methods counted against the rate limits.
+	pub rate_limit_method_costs: RpcMethodCosts,
 	/// RPC rate limit whitelisted ip addresses.
 	pub rate_limit_whitelisted_ips: Vec<IpNetwork>,
 	/// RPC rate limit trust proxy headers.
 	pub rate_limit_trust_proxy_headers: bool,
+	/// Maximum allowed subscriptions shared by all the rpc connections of an ip address.
+	pub max_subs_per_ip: Option<u32>,
 }
 
 /// Runtime executor configuration.
@@ -411,8 +411,11 @@ where
 				max_subscriptions_per_connection: rpc_configuration.max_subs_per_conn,
 				rpc_methods: rpc_configuration.methods.into(),
 				rate_limit: rpc_configuration.rate_limit,
+				rate_limit_per_ip: rpc_configuration.rate_limit_per_ip,
+				rate_limit_method_costs: rpc_configuration.rate_limit_method_costs.clone(),
 				rate_limit_trust_proxy_headers: rpc_configuration.rate_limit_trust_proxy_headers,
 				rate_limit_whitelisted_ips: rpc_configuration.rate_limit_whitelisted_ips.clone(),
+				max_subscriptions_per_ip: rpc_configuration.max_subs_per_ip,
 				retry_random_port: true,
 				is_optional: false,
 			},
@@ -427,8 +430,11 @@ where
 				max_subscriptions_per_connection: rpc_configuration.max_subs_per_conn,
 				rpc_methods: rpc_configuration.methods.into(),
 				rate_limit: rpc_configuration.rate_limit,
+				rate_limit_per_ip: rpc_configuration.rate_limit_per_ip,
+				rate_limit_method_costs: rpc_configuration.rate_limit_method_costs.clone(),
 				rate_limit_trust_proxy_headers: rpc_configuration.rate_limit_trust_proxy_headers,
 				rate_limit_whitelisted_ips: rpc_configuration.rate_limit_whitelisted_ips.clone(),
+				max_subscriptions_per_ip: rpc_configuration.max_subs_per_ip,
 				retry_random_port: true,
 				is_optional: true,
 			},
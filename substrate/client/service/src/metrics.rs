@@ -16,9 +16,13 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use futures::{select, FutureExt, StreamExt};
 use futures_timer::Delay;
-use prometheus_endpoint::{register, Gauge, GaugeVec, Opts, PrometheusError, Registry, U64};
-use sc_client_api::{ClientInfo, UsageProvider};
+use prometheus_endpoint::{
+	register, Counter, Gauge, GaugeVec, Histogram, HistogramOpts, Opts, PrometheusError, Registry,
+	U64,
+};
+use sc_client_api::{BlockImportNotification, BlockchainEvents, ClientInfo, UsageProvider};
 use sc_network::{config::Role, NetworkStatus, NetworkStatusProvider};
 use sc_network_sync::{SyncStatus, SyncStatusProvider};
 use sc_telemetry::{telemetry, TelemetryHandle, SUBSTRATE_INFO};
@@ -35,6 +39,9 @@ struct PrometheusMetrics {
 	// generic info
 	block_height: GaugeVec<U64>,
 	number_leaves: Gauge<U64>,
+	finality_lag: Gauge<U64>,
+	reorgs: Counter<U64>,
+	reorg_depth: Histogram,
 	ready_transactions_number: Gauge<U64>,
 
 	// I/O
@@ -96,6 +103,33 @@ impl PrometheusMetrics {
 				registry,
 			)?,
 
+			finality_lag: register(
+				Gauge::new(
+					"substrate_finality_lag",
+					"Number of blocks between the last finalized block and the best block",
+				)?,
+				registry,
+			)?,
+
+			reorgs: register(
+				Counter::new(
+					"substrate_reorgs_total",
+					"Number of reorganizations of the best chain",
+				)?,
+				registry,
+			)?,
+
+			reorg_depth: register(
+				Histogram::with_opts(
+					HistogramOpts::new(
+						"substrate_reorg_depth",
+						"Number of blocks retracted from the best chain by a reorganization",
+					)
+					.buckets(vec![1.0, 2.0, 3.0, 4.0, 6.0, 8.0, 16.0, 32.0, 64.0, 128.0]),
+				)?,
+				registry,
+			)?,
+
 			ready_transactions_number: register(
 				Gauge::new(
 					"substrate_ready_transactions_number",
@@ -170,6 +204,9 @@ impl MetricsService {
 	/// Returns a never-ending `Future` that performs the
 	/// metric and telemetry updates with information from
 	/// the given sources.
+	///
+	/// The reorganizations of the best chain are recorded as
+	/// the blocks are imported.
 	pub async fn run<TBl, TExPool, TCl, TNet, TSync>(
 		mut self,
 		client: Arc<TCl>,
@@ -178,17 +215,26 @@ impl MetricsService {
 		syncing: TSync,
 	) where
 		TBl: Block,
-		TCl: ProvideRuntimeApi<TBl> + UsageProvider<TBl>,
+		TCl: ProvideRuntimeApi<TBl> + UsageProvider<TBl> + BlockchainEvents<TBl>,
 		TExPool: MaintainedTransactionPool<Block = TBl, Hash = <TBl as Block>::Hash>,
 		TNet: NetworkStatusProvider,
 		TSync: SyncStatusProvider<TBl>,
 	{
 		let mut timer = Delay::new(Duration::from_secs(0));
 		let timer_interval = Duration::from_secs(5);
+		let mut import_notifications = client.import_notification_stream();
 
 		loop {
-			// Wait for the next tick of the timer.
-			(&mut timer).await;
+			// Wait for the next tick of the timer, recording the reorgs in the meantime.
+			select! {
+				notification = import_notifications.next() => {
+					if let Some(notification) = notification {
+						self.on_block_import(&notification);
+					}
+					continue
+				},
+				_ = (&mut timer).fuse() => {},
+			}
 
 			// Try to get the latest network information.
 			let net_status = network.status().await.ok();
@@ -204,6 +250,20 @@ impl MetricsService {
 		}
 	}
 
+	fn on_block_import<T: Block>(&self, notification: &BlockImportNotification<T>) {
+		let Some(metrics) = self.metrics.as_ref() else { return };
+		if !notification.is_new_best {
+			return
+		}
+
+		// The tree route goes from the previous best block to the parent of the new one.
+		let retracted = notification.tree_route.as_ref().map_or(0, |route| route.retracted().len());
+		if retracted > 0 {
+			metrics.reorgs.inc();
+			metrics.reorg_depth.observe(retracted as f64);
+		}
+	}
+
 	fn update<T: Block>(
 		&mut self,
 		info: &ClientInfo<T>,
@@ -237,6 +297,7 @@ impl MetricsService {
 		if let Some(metrics) = self.metrics.as_ref() {
 			metrics.block_height.with_label_values(&["finalized"]).set(finalized_number);
 			metrics.block_height.with_label_values(&["best"]).set(best_number);
+			metrics.finality_lag.set(best_number.saturating_sub(finalized_number));
 
 			if let Ok(leaves) = u64::try_from(info.chain.number_leaves) {
 				metrics.number_leaves.set(leaves);
@@ -0,0 +1,267 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use futures::executor::block_on;
+use sc_block_builder::BlockBuilderBuilder;
+use sc_client_api::{HeaderBackend, StorageProvider};
+use sc_client_db::{Backend, BlocksPruning, DatabaseSettings, DatabaseSource, DbHash, PruningMode};
+use sc_consensus::{BasicQueue, BlockImportParams, ForkChoiceStrategy, Verifier};
+use sc_service::{
+	chain_ops::{check_db, export_snapshot, import_snapshot, revert_chain_to, DbIssue},
+	Error,
+};
+use sp_consensus::BlockOrigin;
+use sp_core::testing::TaskExecutor;
+use sp_database::{ColumnId, Database, MemDb, Transaction};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use sp_storage::{ChildInfo, StorageData, StorageKey};
+use std::sync::Arc;
+use substrate_test_runtime_client::{
+	runtime::{currency::DOLLARS, Block, Header, Transfer},
+	BlockBuilderExt, ClientBlockImportExt, ClientExt, DefaultTestClientBuilderExt, Sr25519Keyring,
+	TestClient, TestClientBuilder, TestClientBuilderExt,
+};
+
+struct PassThroughVerifier;
+
+#[async_trait::async_trait]
+impl Verifier<Block> for PassThroughVerifier {
+	async fn verify(
+		&self,
+		mut block: BlockImportParams<Block>,
+	) -> Result<BlockImportParams<Block>, String> {
+		block.fork_choice = Some(ForkChoiceStrategy::LongestChain);
+		Ok(block)
+	}
+}
+
+/// A client whose genesis has `value` stored in a child trie.
+fn client(value: &[u8]) -> Arc<TestClient> {
+	let child_info = ChildInfo::new_default(b"child");
+	let builder = TestClientBuilder::new().add_extra_child_storage(&child_info, b"key", value);
+	Arc::new(builder.build())
+}
+
+fn import(client: &Arc<TestClient>, snapshot: &[u8]) -> Result<(), Error> {
+	let import_queue = BasicQueue::new(
+		PassThroughVerifier,
+		Box::new(client.clone()),
+		None,
+		&TaskExecutor::new(),
+		None,
+	);
+	block_on(import_snapshot(client.clone(), import_queue, std::io::Cursor::new(snapshot.to_vec())))
+}
+
+#[test]
+fn exported_snapshot_is_imported() {
+	let source = client(b"value");
+	let mut hash = source.info().genesis_hash;
+	for nonce in 0..2 {
+		let mut builder = BlockBuilderBuilder::new(&*source)
+			.on_parent_block(hash)
+			.with_parent_block_number(nonce)
+			.build()
+			.unwrap();
+		builder
+			.push_transfer(Transfer {
+				from: Sr25519Keyring::Alice.into(),
+				to: Sr25519Keyring::Ferdie.into(),
+				amount: 42 * DOLLARS,
+				nonce,
+			})
+			.unwrap();
+		let block = builder.build().unwrap().block;
+		hash = block.hash();
+		block_on(source.import(BlockOrigin::Own, block)).unwrap();
+	}
+
+	// Only finalized blocks are exported.
+	assert!(export_snapshot(source.clone(), hash, Vec::new()).is_err());
+	source.finalize_block(hash, None).unwrap();
+	let mut snapshot = Vec::new();
+	export_snapshot(source.clone(), hash, &mut snapshot).unwrap();
+
+	let target = client(b"value");
+	import(&target, &snapshot).unwrap();
+	assert_eq!(target.info().best_hash, hash);
+	assert_eq!(target.info().best_number, 2);
+	let pairs =
+		|client: &TestClient| client.storage_pairs(hash, None, None).unwrap().collect::<Vec<_>>();
+	assert_eq!(pairs(&target), pairs(&source));
+	assert_eq!(
+		target
+			.child_storage(hash, &ChildInfo::new_default(b"child"), &StorageKey(b"key".to_vec()))
+			.unwrap(),
+		Some(StorageData(b"value".to_vec()))
+	);
+
+	// The database isn't empty anymore.
+	assert!(import(&target, &snapshot).is_err());
+}
+
+#[test]
+fn snapshot_of_another_chain_is_rejected() {
+	let source = client(b"value");
+	let mut snapshot = Vec::new();
+	export_snapshot(source.clone(), source.info().genesis_hash, &mut snapshot).unwrap();
+
+	let target = client(b"other value");
+	assert!(matches!(
+		import(&target, &snapshot),
+		Err(Error::Other(message)) if message.starts_with("Snapshot of another chain")
+	));
+	assert_eq!(target.info().best_number, 0);
+
+	// Corrupted snapshots are rejected as well.
+	let len = snapshot.len();
+	snapshot[len - 1] ^= 1;
+	assert!(import(&client(b"value"), &snapshot).is_err());
+}
+
+// Columns of the database of `sc_client_db`.
+const STATE: ColumnId = 1;
+const KEY_LOOKUP: ColumnId = 3;
+const HEADER: ColumnId = 4;
+const BODY: ColumnId = 5;
+
+fn backend(db: Arc<dyn Database<DbHash>>, create: bool) -> Arc<Backend<Block>> {
+	let settings = DatabaseSettings {
+		trie_cache_maximum_size: None,
+		state_pruning: Some(PruningMode::ArchiveAll),
+		blocks_pruning: BlocksPruning::KeepAll,
+		source: DatabaseSource::Custom { db, require_create_flag: create },
+		metrics_registry: None,
+	};
+	Arc::new(Backend::new(settings, 0).unwrap())
+}
+
+/// A database with four blocks on top of genesis, up to #2 being finalized, and their headers.
+fn database() -> (Arc<dyn Database<DbHash>>, Vec<Header>) {
+	let db: Arc<dyn Database<DbHash>> = Arc::new(MemDb::default());
+	let client = TestClientBuilder::with_backend(backend(db.clone(), true)).build();
+	let mut headers = vec![client.header(client.info().genesis_hash).unwrap().unwrap()];
+	for nonce in 0..4 {
+		let parent = headers.last().unwrap();
+		let mut builder = BlockBuilderBuilder::new(&client)
+			.on_parent_block(parent.hash())
+			.with_parent_block_number(*parent.number())
+			.build()
+			.unwrap();
+		builder
+			.push_transfer(Transfer {
+				from: Sr25519Keyring::Alice.into(),
+				to: Sr25519Keyring::Ferdie.into(),
+				amount: DOLLARS,
+				nonce,
+			})
+			.unwrap();
+		let block = builder.build().unwrap().block;
+		block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+		headers.push(block.header);
+	}
+	client.finalize_block(headers[2].hash(), None).unwrap();
+	(db, headers)
+}
+
+/// The lookup key of the block `header` in the columns of `sc_client_db`.
+fn lookup_key(header: &Header) -> Vec<u8> {
+	let number = (*header.number() as u32).to_be_bytes();
+	number.iter().chain(header.hash().as_ref()).copied().collect()
+}
+
+fn remove(db: &Arc<dyn Database<DbHash>>, column: ColumnId, key: &[u8]) {
+	assert!(db.get(column, key).is_some());
+	let mut transaction = Transaction::new();
+	transaction.remove(column, key);
+	db.commit(transaction).unwrap();
+}
+
+/// Check the database and revert it to the last valid block.
+fn check_and_revert(
+	db: Arc<dyn Database<DbHash>>,
+	expected_issues: Vec<DbIssue<Block>>,
+	expected_last_valid: &Header,
+) -> Result<(), Error> {
+	// A fresh backend doesn't serve the removed entries from its caches.
+	let backend = backend(db, false);
+	let report = check_db(&*backend, 1).unwrap();
+	assert_eq!(report.checked_blocks, 5);
+	assert_eq!(report.issues, expected_issues);
+	assert_eq!(
+		report.last_valid,
+		Some((*expected_last_valid.number(), expected_last_valid.hash()))
+	);
+
+	let client = Arc::new(TestClientBuilder::with_backend(backend.clone()).build());
+	revert_chain_to(client.clone(), backend, *expected_last_valid.number())?;
+	assert_eq!(client.info().best_hash, expected_last_valid.hash());
+	Ok(())
+}
+
+#[test]
+fn check_db_accepts_valid_database() {
+	let (db, headers) = database();
+	let report = check_db(&*backend(db, false), 1).unwrap();
+	assert_eq!(report.checked_blocks, 5);
+	// The states of the finalized and the best block are walked.
+	assert_eq!(report.walked_states, 2);
+	assert!(report.issues.is_empty());
+	assert_eq!(report.last_valid, Some((4, headers[4].hash())));
+}
+
+#[test]
+fn check_db_reports_missing_canonical_hash() {
+	let (db, headers) = database();
+	remove(&db, KEY_LOOKUP, &3u32.to_be_bytes());
+
+	let issues = vec![DbIssue::MissingCanonicalHash(3)];
+	check_and_revert(db, issues, &headers[2]).unwrap();
+}
+
+#[test]
+fn check_db_reports_missing_header() {
+	let (db, headers) = database();
+	remove(&db, HEADER, &lookup_key(&headers[3]));
+
+	let issues = vec![DbIssue::InvalidHeader(3, headers[3].hash(), "missing".into())];
+	// The backend can't revert past a block without header.
+	assert!(matches!(
+		check_and_revert(db, issues, &headers[2]),
+		Err(Error::Other(message)) if message.starts_with("Failed to revert to #2")
+	));
+}
+
+#[test]
+fn check_db_reports_missing_body() {
+	let (db, headers) = database();
+	remove(&db, BODY, &lookup_key(&headers[4]));
+
+	let issues = vec![DbIssue::InvalidBody(4, headers[4].hash(), "missing".into())];
+	check_and_revert(db, issues, &headers[3]).unwrap();
+}
+
+#[test]
+fn check_db_reports_missing_state() {
+	let (db, headers) = database();
+	remove(&db, STATE, headers[4].state_root().as_ref());
+
+	let reason = "the state was discarded".into();
+	let issues = vec![DbIssue::IncompleteState(4, headers[4].hash(), reason)];
+	check_and_revert(db, issues, &headers[3]).unwrap();
+}
@@ -52,6 +52,7 @@ use substrate_test_runtime_client::{
 	TestClientBuilder, TestClientBuilderExt,
 };
 
+mod chain_ops;
 mod db;
 
 const TEST_ENGINE_ID: ConsensusEngineId = *b"TEST";
@@ -40,13 +40,16 @@
 //! # Pruning.
 //! See `RefWindow` for pruning algorithm details. `StateDb` prunes on each canonicalization until
 //! pruning constraints are satisfied.
+//!
+//! In the sparse mode the state of every canonical block whose number is a multiple of the
+//! checkpoint interval is additionally kept forever, on top of the pruning window.
 
 mod noncanonical;
 mod pruning;
 #[cfg(test)]
 mod test;
 
-use codec::Codec;
+use codec::{Codec, Decode, Encode};
 use log::trace;
 use noncanonical::NonCanonicalOverlay;
 use parking_lot::RwLock;
@@ -62,6 +65,8 @@ const PRUNING_MODE: &[u8] = b"mode";
 const PRUNING_MODE_ARCHIVE: &[u8] = b"archive";
 const PRUNING_MODE_ARCHIVE_CANON: &[u8] = b"archive_canonical";
 const PRUNING_MODE_CONSTRAINED: &[u8] = b"constrained";
+const PRUNING_MODE_SPARSE: &[u8] = b"sparse";
+const PRUNING_CHECKPOINT_INTERVAL: &[u8] = b"checkpoint_interval";
 pub(crate) const DEFAULT_MAX_BLOCK_CONSTRAINT: u32 = 256;
 
 /// Database value type.
@@ -145,6 +150,9 @@ pub enum StateDbError {
 	BlockUnavailable,
 	/// Block record is missing from the pruning window
 	BlockMissing,
+	/// Sparse pruning requires a database with native reference counting support (i.e. ParityDb),
+	/// it is unsupported when the references are counted by the state db (i.e. RocksDb).
+	SparsePruningUnsupported,
 }
 
 impl<E> From<StateDbError> for Error<E> {
@@ -196,6 +204,10 @@ impl fmt::Debug for StateDbError {
 				write!(f, "Trying to get a block record from db while it is not commit to db yet")
 			},
 			Self::BlockMissing => write!(f, "Block record is missing from the pruning window"),
+			Self::SparsePruningUnsupported => write!(
+				f,
+				"Sparse pruning requires a database with native reference counting (ParityDb)"
+			),
 		}
 	}
 }
@@ -235,6 +247,17 @@ pub enum PruningMode {
 	ArchiveAll,
 	/// Canonicalization discards non-canonical nodes. All the canonical nodes are kept in the DB.
 	ArchiveCanonical,
+	/// Maintain a pruning window and keep the state of every canonical block whose number is a
+	/// multiple of `checkpoint_interval` forever.
+	///
+	/// Requires a database with native reference counting support (i.e. ParityDb), see
+	/// [`StateDbError::SparsePruningUnsupported`].
+	Sparse {
+		/// Constraints of the pruning window.
+		constraints: Constraints,
+		/// Number of blocks between two checkpoints.
+		checkpoint_interval: u32,
+	},
 }
 
 impl PruningMode {
@@ -243,11 +266,27 @@ impl PruningMode {
 		PruningMode::Constrained(Constraints { max_blocks: Some(n) })
 	}
 
+	/// Create a mode that keeps given number of blocks and the state of every
+	/// `checkpoint_interval`-th block.
+	pub fn sparse_pruning(n: u32, checkpoint_interval: u32) -> PruningMode {
+		let constraints = Constraints { max_blocks: Some(n) };
+		PruningMode::Sparse { constraints, checkpoint_interval }
+	}
+
 	/// Is this an archive (either ArchiveAll or ArchiveCanonical) pruning mode?
 	pub fn is_archive(&self) -> bool {
 		match *self {
 			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => true,
-			PruningMode::Constrained(_) => false,
+			PruningMode::Constrained(_) | PruningMode::Sparse { .. } => false,
+		}
+	}
+
+	/// Is the state of the given canonical block kept forever by the sparse pruning mode?
+	pub fn is_checkpoint(&self, number: u64) -> bool {
+		match *self {
+			PruningMode::Sparse { checkpoint_interval, .. } =>
+				checkpoint_interval > 0 && number % checkpoint_interval as u64 == 0,
+			_ => false,
 		}
 	}
 
@@ -257,6 +296,7 @@ impl PruningMode {
 			PruningMode::ArchiveAll => PRUNING_MODE_ARCHIVE,
 			PruningMode::ArchiveCanonical => PRUNING_MODE_ARCHIVE_CANON,
 			PruningMode::Constrained(_) => PRUNING_MODE_CONSTRAINED,
+			PruningMode::Sparse { .. } => PRUNING_MODE_SPARSE,
 		}
 	}
 
@@ -265,6 +305,9 @@ impl PruningMode {
 			PRUNING_MODE_ARCHIVE => Some(Self::ArchiveAll),
 			PRUNING_MODE_ARCHIVE_CANON => Some(Self::ArchiveCanonical),
 			PRUNING_MODE_CONSTRAINED => Some(Self::Constrained(Default::default())),
+			// The checkpoint interval is stored separately, see `fetch_stored_pruning_mode`.
+			PRUNING_MODE_SPARSE =>
+				Some(Self::Sparse { constraints: Default::default(), checkpoint_interval: 0 }),
 			_ => None,
 		}
 	}
@@ -319,6 +362,17 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 		let pruning: Option<RefWindow<BlockHash, Key, D>> = match mode {
 			PruningMode::Constrained(Constraints { max_blocks }) =>
 				Some(RefWindow::new(db, max_blocks.unwrap_or(0), ref_counting)?),
+			PruningMode::Sparse { ref constraints, checkpoint_interval } => {
+				// The state db only counts references itself if the database doesn't.
+				if ref_counting {
+					return Err(StateDbError::SparsePruningUnsupported.into())
+				}
+				let max_blocks = constraints.max_blocks.unwrap_or(0);
+				Some(
+					RefWindow::new(db, max_blocks, ref_counting)?
+						.with_checkpoint_interval(checkpoint_interval)?,
+				)
+			},
 			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => None,
 		};
 
@@ -338,7 +392,9 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 				// write changes immediately
 				Ok(CommitSet { data: changeset, meta: Default::default() })
 			},
-			PruningMode::Constrained(_) | PruningMode::ArchiveCanonical => self
+			PruningMode::Constrained(_) |
+			PruningMode::Sparse { .. } |
+			PruningMode::ArchiveCanonical => self
 				.non_canonical
 				.insert(hash, number, parent_hash, changeset)
 				.map_err(Into::into),
@@ -380,7 +436,9 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 	fn is_pruned(&self, hash: &BlockHash, number: u64) -> IsPruned {
 		match self.mode {
 			PruningMode::ArchiveAll => IsPruned::NotPruned,
-			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) => {
+			PruningMode::ArchiveCanonical |
+			PruningMode::Constrained(_) |
+			PruningMode::Sparse { .. } => {
 				if self
 					.non_canonical
 					.last_canonicalized_block_number()
@@ -397,6 +455,9 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 						// We don't know for sure.
 						None => IsPruned::MaybePruned,
 						Some(pruning) => match pruning.have_block(hash, number) {
+							// The state of a checkpoint is kept, unless the block is not canonical.
+							HaveBlock::No if self.mode.is_checkpoint(number) =>
+								IsPruned::MaybePruned,
 							HaveBlock::No => IsPruned::Pruned,
 							HaveBlock::Yes => IsPruned::NotPruned,
 							HaveBlock::Maybe => IsPruned::MaybePruned,
@@ -408,8 +469,10 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 	}
 
 	fn prune(&mut self, commit: &mut CommitSet<Key>) -> Result<(), Error<D::Error>> {
-		if let (&mut Some(ref mut pruning), PruningMode::Constrained(constraints)) =
-			(&mut self.pruning, &self.mode)
+		if let (
+			&mut Some(ref mut pruning),
+			PruningMode::Constrained(constraints) | PruningMode::Sparse { constraints, .. },
+		) = (&mut self.pruning, &self.mode)
 		{
 			loop {
 				if pruning.window_size() <= constraints.max_blocks.unwrap_or(0) as u64 {
@@ -442,16 +505,18 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 	fn revert_one(&mut self) -> Option<CommitSet<Key>> {
 		match self.mode {
 			PruningMode::ArchiveAll => Some(CommitSet::default()),
-			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) =>
-				self.non_canonical.revert_one(),
+			PruningMode::ArchiveCanonical |
+			PruningMode::Constrained(_) |
+			PruningMode::Sparse { .. } => self.non_canonical.revert_one(),
 		}
 	}
 
 	fn remove(&mut self, hash: &BlockHash) -> Option<CommitSet<Key>> {
 		match self.mode {
 			PruningMode::ArchiveAll => Some(CommitSet::default()),
-			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) =>
-				self.non_canonical.remove(hash),
+			PruningMode::ArchiveCanonical |
+			PruningMode::Constrained(_) |
+			PruningMode::Sparse { .. } => self.non_canonical.remove(hash),
 		}
 	}
 
@@ -461,11 +526,14 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 	{
 		match self.mode {
 			PruningMode::ArchiveAll => Ok(()),
-			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) => {
+			PruningMode::ArchiveCanonical |
+			PruningMode::Constrained(_) |
+			PruningMode::Sparse { .. } => {
 				let have_block = self.non_canonical.have_block(hash) ||
 					self.pruning.as_ref().map_or_else(
 						|| hint(),
 						|pruning| match pruning.have_block(hash, number) {
+							HaveBlock::No if self.mode.is_checkpoint(number) => hint(),
 							HaveBlock::No => false,
 							HaveBlock::Yes => true,
 							HaveBlock::Maybe => hint(),
@@ -531,6 +599,9 @@ pub struct StateDb<BlockHash: Hash, Key: Hash, D: MetaDb> {
 
 impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDb<BlockHash, Key, D> {
 	/// Create an instance of [`StateDb`].
+	///
+	/// `ref_counting` must be set if the database doesn't support reference counting natively,
+	/// the state db counts the references of the nodes itself then.
 	pub fn open(
 		db: D,
 		requested_mode: Option<PruningMode>,
@@ -556,18 +627,18 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDb<BlockHash, Key, D> {
 			(false, Some(stored), Some(requested)) => choose_pruning_mode(stored, requested)?,
 		};
 
-		let db_init_commit_set = if should_init {
-			let mut cs: CommitSet<Key> = Default::default();
-
+		let mut db_init_commit_set: CommitSet<Key> = Default::default();
+		if should_init {
 			let key = to_meta_key(PRUNING_MODE, &());
 			let value = selected_mode.id().to_owned();
 
-			cs.meta.inserted.push((key, value));
-
-			cs
-		} else {
-			Default::default()
-		};
+			db_init_commit_set.meta.inserted.push((key, value));
+		}
+		// The checkpoint interval may change between runs, so it is always stored.
+		if let PruningMode::Sparse { checkpoint_interval, .. } = selected_mode {
+			let key = to_meta_key(PRUNING_CHECKPOINT_INTERVAL, &());
+			db_init_commit_set.meta.inserted.push((key, checkpoint_interval.encode()));
+		}
 
 		let state_db =
 			StateDb { db: RwLock::new(StateDbSync::new(selected_mode, ref_counting, db)?) };
@@ -674,7 +745,15 @@ pub enum IsPruned {
 fn fetch_stored_pruning_mode<D: MetaDb>(db: &D) -> Result<Option<PruningMode>, Error<D::Error>> {
 	let meta_key_mode = to_meta_key(PRUNING_MODE, &());
 	if let Some(stored_mode) = db.get_meta(&meta_key_mode).map_err(Error::Db)? {
-		if let Some(mode) = PruningMode::from_id(&stored_mode) {
+		if let Some(mut mode) = PruningMode::from_id(&stored_mode) {
+			if let PruningMode::Sparse { ref mut checkpoint_interval, .. } = mode {
+				let meta_key_interval = to_meta_key(PRUNING_CHECKPOINT_INTERVAL, &());
+				let stored_interval =
+					db.get_meta(&meta_key_interval).map_err(Error::Db)?.ok_or_else(|| {
+						StateDbError::Metadata("Missing sparse pruning checkpoint interval".into())
+					})?;
+				*checkpoint_interval = u32::decode(&mut stored_interval.as_slice())?;
+			}
 			Ok(Some(mode))
 		} else {
 			Err(StateDbError::Metadata(format!(
@@ -698,6 +777,7 @@ fn choose_pruning_mode(
 			Ok(PruningMode::ArchiveCanonical),
 		(PruningMode::Constrained(_), PruningMode::Constrained(requested)) =>
 			Ok(PruningMode::Constrained(requested)),
+		(PruningMode::Sparse { .. }, requested @ PruningMode::Sparse { .. }) => Ok(requested),
 		(stored, requested) => Err(StateDbError::IncompatiblePruningModes { requested, stored }),
 	}
 }
@@ -847,6 +927,32 @@ mod tests {
 		assert!(db.data_eq(&make_db(&[1, 21, 3, 921, 922, 93, 94])));
 	}
 
+	#[test]
+	fn sparse_pruning_keeps_checkpoints() {
+		let (db, sdb) = make_test_db(PruningMode::sparse_pruning(0, 2));
+		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(1), 1), IsPruned::Pruned);
+		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(21), 2), IsPruned::MaybePruned);
+		assert!(db.data_eq(&make_db(&[1, 21, 3, 91, 921, 922, 93, 94])));
+	}
+
+	#[test]
+	fn sparse_pruning_stores_checkpoint_interval() {
+		let mut db = make_db(&[]);
+		let mode = PruningMode::sparse_pruning(256, 100);
+		let (state_db_init, _) =
+			StateDb::<H256, H256, TestDb>::open(db.clone(), Some(mode.clone()), false, true)
+				.unwrap();
+		db.commit(&state_db_init);
+
+		let (_, state_db) =
+			StateDb::<H256, H256, TestDb>::open(db.clone(), None, false, false).unwrap();
+		assert_eq!(state_db.pruning_mode(), mode);
+
+		// The references are counted by the state db, the database doesn't support it natively.
+		let result = StateDb::<H256, H256, TestDb>::open(make_db(&[]), Some(mode), true, true);
+		assert!(matches!(result, Err(Error::StateDb(StateDbError::SparsePruningUnsupported))));
+	}
+
 	#[test]
 	fn detects_incompatible_mode() {
 		let mut db = make_db(&[]);
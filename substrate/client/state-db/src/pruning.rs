@@ -23,6 +23,16 @@
 //! If a node is re-inserted into the window it gets removed from
 //! the death list.
 //! The changes are journaled in the DB.
+//!
+//! With a checkpoint interval set, deletions of nodes that are part of the state of the last
+//! checkpoint block are not journaled, so that the state of every checkpoint survives pruning.
+//! A node belongs to that state unless it was inserted after the checkpoint. The nodes inserted
+//! since the last checkpoint are journaled for every block until the next checkpoint, so that
+//! they can be restored after a restart. If that journal is incomplete, e.g. because the
+//! checkpoint interval changed, all deletions are conservatively treated as part of the checkpoint
+//! state until the next checkpoint. This relies on the reference counting of the database: a node
+//! that is re-inserted after being kept for a checkpoint has its deletion journaled again, which
+//! only drops the extra reference.
 
 use crate::{
 	noncanonical::LAST_CANONICAL, to_meta_key, CommitSet, Error, Hash, MetaDb, StateDbError,
@@ -34,6 +44,7 @@ use std::collections::{HashMap, HashSet, VecDeque};
 
 pub(crate) const LAST_PRUNED: &[u8] = b"last_pruned";
 const PRUNING_JOURNAL: &[u8] = b"pruning_journal";
+const CHECKPOINT_JOURNAL: &[u8] = b"checkpoint_journal";
 
 /// See module documentation.
 pub struct RefWindow<BlockHash: Hash, Key: Hash, D: MetaDb> {
@@ -42,6 +53,10 @@ pub struct RefWindow<BlockHash: Hash, Key: Hash, D: MetaDb> {
 	queue: DeathRowQueue<BlockHash, Key, D>,
 	/// Block number that is next to be pruned.
 	base: u64,
+	/// Number of blocks between two checkpoints whose state is never pruned.
+	checkpoint_interval: Option<u64>,
+	/// Nodes inserted since the last checkpoint, `None` if the last checkpoint is unknown.
+	inserted_since_checkpoint: Option<HashSet<Key>>,
 }
 
 /// `DeathRowQueue` used to keep track of blocks in the pruning window, there are two flavors:
@@ -279,6 +294,42 @@ fn to_journal_key(block: u64) -> Vec<u8> {
 	to_meta_key(PRUNING_JOURNAL, &block)
 }
 
+/// The changes of a block to the nodes inserted since the last checkpoint.
+#[derive(Encode, Decode)]
+struct CheckpointJournalRecord<Key: Hash> {
+	/// Nodes inserted by the block.
+	inserted: Vec<Key>,
+	/// Nodes inserted since the last checkpoint whose deletion was journaled by the block.
+	deleted: Vec<Key>,
+}
+
+fn to_checkpoint_journal_key(block: u64) -> Vec<u8> {
+	to_meta_key(CHECKPOINT_JOURNAL, &block)
+}
+
+/// Restore the nodes inserted since the last checkpoint before block `last` from the checkpoint
+/// journal. Returns `None` if the journal of any block since that checkpoint is missing.
+fn load_inserted_since_checkpoint<Key: Hash, D: MetaDb>(
+	db: &D,
+	last: u64,
+	interval: u64,
+) -> Result<Option<HashSet<Key>>, Error<D::Error>> {
+	let mut inserted = HashSet::new();
+	for block in (last - last % interval + 1)..=last {
+		let Some(record) = db.get_meta(&to_checkpoint_journal_key(block)).map_err(Error::Db)?
+		else {
+			trace!(target: LOG_TARGET, "Missing checkpoint journal of #{}", block);
+			return Ok(None)
+		};
+		let record: CheckpointJournalRecord<Key> = Decode::decode(&mut record.as_slice())?;
+		for key in record.deleted {
+			inserted.remove(&key);
+		}
+		inserted.extend(record.inserted);
+	}
+	Ok(Some(inserted))
+}
+
 /// The result return by `RefWindow::have_block`
 #[derive(Debug, PartialEq, Eq)]
 pub enum HaveBlock {
@@ -347,7 +398,20 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 			DeathRowQueue::new_db_backed(db, base, last, window_size)?
 		};
 
-		Ok(RefWindow { queue, base })
+		Ok(RefWindow { queue, base, checkpoint_interval: None, inserted_since_checkpoint: None })
+	}
+
+	/// Keep the state of every canonical block whose number is a multiple of `interval`.
+	///
+	/// The nodes inserted since the last checkpoint are restored from the checkpoint journal.
+	pub fn with_checkpoint_interval(mut self, interval: u32) -> Result<Self, Error<D::Error>> {
+		self.checkpoint_interval = (interval > 0).then_some(interval as u64);
+		if let (Some(interval), DeathRowQueue::DbBacked { db, last: Some(last), .. }) =
+			(self.checkpoint_interval, &self.queue)
+		{
+			self.inserted_since_checkpoint = load_inserted_since_checkpoint(db, *last, interval)?;
+		}
+		Ok(self)
 	}
 
 	pub fn window_size(&self) -> u64 {
@@ -429,21 +493,67 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 		} else {
 			Default::default()
 		};
-		let deleted = std::mem::take(&mut commit.data.deleted);
+		let mut deleted = std::mem::take(&mut commit.data.deleted);
+		if let Some(interval) = self.checkpoint_interval {
+			deleted = self.retain_checkpoint_state(number, interval, deleted, commit);
+		}
 		let journal_record = JournalRecord { hash: hash.clone(), inserted, deleted };
 		commit.meta.inserted.push((to_journal_key(number), journal_record.encode()));
 		self.queue.import(self.base, number, journal_record);
 		Ok(())
 	}
+
+	/// Filter out the deletions of the block `number` that would remove nodes of the state of the
+	/// last checkpoint and journal the nodes inserted since that checkpoint.
+	fn retain_checkpoint_state(
+		&mut self,
+		number: u64,
+		interval: u64,
+		deleted: Vec<Key>,
+		commit: &mut CommitSet<Key>,
+	) -> Vec<Key> {
+		let total = deleted.len();
+		let deleted = match self.inserted_since_checkpoint {
+			Some(ref mut inserted) => deleted.into_iter().filter(|k| inserted.remove(k)).collect(),
+			None => Vec::new(),
+		};
+		trace!(
+			target: LOG_TARGET,
+			"Retaining {} of {} deleted nodes of #{} for the last checkpoint",
+			total - deleted.len(),
+			total,
+			number,
+		);
+
+		if number % interval == 0 {
+			self.inserted_since_checkpoint = Some(HashSet::new());
+			let previous = number.saturating_sub(interval);
+			commit
+				.meta
+				.deleted
+				.extend((previous + 1..number).map(to_checkpoint_journal_key));
+		} else {
+			let inserted: Vec<Key> = commit.data.inserted.iter().map(|(k, _)| k.clone()).collect();
+			if let Some(ref mut inserted_since_checkpoint) = self.inserted_since_checkpoint {
+				inserted_since_checkpoint.extend(inserted.iter().cloned());
+			}
+			let record = CheckpointJournalRecord { inserted, deleted: deleted.clone() };
+			commit.meta.inserted.push((to_checkpoint_journal_key(number), record.encode()));
+		}
+		deleted
+	}
 }
 
 #[cfg(test)]
 mod tests {
-	use super::{to_journal_key, DeathRowQueue, HaveBlock, JournalRecord, RefWindow, LAST_PRUNED};
+	use super::{
+		to_checkpoint_journal_key, to_journal_key, DeathRowQueue, HaveBlock, JournalRecord,
+		RefWindow, LAST_PRUNED,
+	};
 	use crate::{
 		noncanonical::LAST_CANONICAL,
 		test::{make_commit, make_db, TestDb},
-		to_meta_key, CommitSet, Error, Hash, StateDbError, DEFAULT_MAX_BLOCK_CONSTRAINT,
+		to_meta_key, CommitSet, Error, Hash, MetaDb, StateDbError, DEFAULT_MAX_BLOCK_CONSTRAINT,
 	};
 	use codec::Encode;
 	use sp_core::H256;
@@ -659,6 +769,67 @@ mod tests {
 		commit.meta.inserted.push((to_meta_key(LAST_PRUNED, &()), block.encode()));
 	}
 
+	#[test]
+	fn checkpoint_state_survives() {
+		let mut db = make_db(&[1, 2, 3]);
+		let mut pruning: RefWindow<u64, H256, TestDb> = RefWindow::new(db.clone(), 0, false)
+			.unwrap()
+			.with_checkpoint_interval(2)
+			.unwrap();
+		let blocks: [(&[u64], &[u64]); 5] =
+			[(&[4], &[1]), (&[5], &[2]), (&[6], &[3, 5]), (&[7], &[6]), (&[8], &[7])];
+		for (number, (inserted, deleted)) in blocks.into_iter().enumerate() {
+			let number = number as u64;
+			let mut commit = make_commit(inserted, deleted);
+			pruning.note_canonical(&number, number, &mut commit).unwrap();
+			push_last_canonicalized(number, &mut commit);
+			db.commit(&commit);
+
+			let mut commit = CommitSet::default();
+			pruning.prune_one(&mut commit).unwrap();
+			db.commit(&commit);
+		}
+
+		// the state of the checkpoints #0 and #2 and of the last block is kept
+		assert!(db.data_eq(&make_db(&[1, 2, 3, 4, 6, 8])));
+		assert_eq!(pruning.base, 5);
+	}
+
+	#[test]
+	fn checkpoint_state_survives_restart() {
+		let mut db = make_db(&[1, 2, 3]);
+		let new_pruning = |db: &TestDb| -> RefWindow<u64, H256, TestDb> {
+			RefWindow::new(db.clone(), 0, false)
+				.unwrap()
+				.with_checkpoint_interval(2)
+				.unwrap()
+		};
+		let mut pruning = new_pruning(&db);
+		let blocks: [(&[u64], &[u64]); 5] =
+			[(&[4], &[1]), (&[5], &[2]), (&[6], &[3, 5]), (&[7], &[6]), (&[8], &[7])];
+		for (number, (inserted, deleted)) in blocks.into_iter().enumerate() {
+			let number = number as u64;
+			// restart after #3, whose inserted node is deleted by #4
+			if number == 4 {
+				pruning = new_pruning(&db);
+			}
+			let mut commit = make_commit(inserted, deleted);
+			pruning.note_canonical(&number, number, &mut commit).unwrap();
+			push_last_canonicalized(number, &mut commit);
+			db.commit(&commit);
+
+			let mut commit = CommitSet::default();
+			pruning.prune_one(&mut commit).unwrap();
+			db.commit(&commit);
+		}
+
+		// the node inserted by #3 is pruned although it was deleted after the restart
+		assert!(db.data_eq(&make_db(&[1, 2, 3, 4, 6, 8])));
+		assert_eq!(pruning.base, 5);
+		// the checkpoint journal is removed at the next checkpoint
+		assert!(db.get_meta(&to_checkpoint_journal_key(3)).unwrap().is_none());
+	}
+
 	#[test]
 	fn init_db_backed_queue() {
 		let mut db = make_db(&[]);
@@ -43,6 +43,11 @@
 //! explicitly with the `remove` function) the statement is marked as expired. Expired statements
 //! can't be added to the store for `Options::purge_after_sec` seconds. This is to prevent old
 //! statements from being propagated on the network.
+//!
+//! Notifications.
+//!
+//! Each statement newly accepted by `submit` is sent, together with its hash, to the streams
+//! returned by [`Store::statement_notification_stream`].
 
 #![warn(missing_docs)]
 #![warn(unused_extern_crates)]
@@ -55,6 +60,7 @@ use metrics::MetricsLink as PrometheusMetrics;
 use parking_lot::RwLock;
 use prometheus_endpoint::Registry as PrometheusRegistry;
 use sc_keystore::LocalKeystore;
+use sc_utils::notification::{NotificationSender, NotificationStream, TracingKeyStr};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_core::{crypto::UncheckedFrom, hexdisplay::HexDisplay, traits::SpawnNamed, Decode, Encode};
@@ -71,6 +77,18 @@ use std::{
 	sync::Arc,
 };
 
+/// Tracing key for the statement notification channels.
+#[derive(Clone)]
+pub struct StatementNotificationsTracingKey;
+
+impl TracingKeyStr for StatementNotificationsTracingKey {
+	const TRACING_KEY: &'static str = "mpsc_statement_notification_stream";
+}
+
+/// Stream of the hashes and the statements newly accepted by the store.
+pub type StatementNotificationStream =
+	NotificationStream<(Hash, Statement), StatementNotificationsTracingKey>;
+
 const KEY_VERSION: &[u8] = b"version".as_slice();
 const CURRENT_VERSION: u32 = 1;
 
@@ -206,6 +224,8 @@ pub struct Store {
 	// Used for testing
 	time_override: Option<u64>,
 	metrics: PrometheusMetrics,
+	notification_sender: NotificationSender<(Hash, Statement)>,
+	notification_stream: StatementNotificationStream,
 }
 
 enum IndexQuery {
@@ -566,6 +586,7 @@ impl Store {
 			validator.validate_statement(block, source, statement)
 		});
 
+		let (notification_sender, notification_stream) = StatementNotificationStream::channel();
 		let store = Store {
 			db,
 			index: RwLock::new(Index::new(options)),
@@ -573,6 +594,8 @@ impl Store {
 			keystore,
 			time_override: None,
 			metrics: PrometheusMetrics::new(prometheus),
+			notification_sender,
+			notification_stream,
 		};
 		store.populate()?;
 		Ok(store)
@@ -708,6 +731,11 @@ impl Store {
 		StatementStoreExt::new(self)
 	}
 
+	/// Returns a stream of the statements newly accepted by [`StatementStore::submit`].
+	pub fn statement_notification_stream(&self) -> StatementNotificationStream {
+		self.notification_stream.clone()
+	}
+
 	/// Return information of all known statements whose decryption key is identified as
 	/// `dest`. The key must be available to the client.
 	fn posted_clear_inner<R>(
@@ -939,6 +967,7 @@ impl StatementStore for Store {
 		self.metrics.report(|metrics| metrics.submitted_statements.inc());
 		let network_priority = NetworkPriority::High;
 		log::trace!(target: LOG_TARGET, "Statement submitted: {:?}", HexDisplay::from(&hash));
+		let _ = self.notification_sender.notify(|| Ok::<_, ()>((hash, statement)));
 		SubmitResult::New(network_priority)
 	}
 
@@ -1151,6 +1180,26 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn notifies_new_statements() {
+		use futures::StreamExt;
+
+		let (store, _temp) = test_store();
+		let mut notifications = store.statement_notification_stream().subscribe(100_000);
+		let statement0 = signed_statement(0);
+		let statement1 = signed_statement(1);
+		store.submit(statement0.clone(), StatementSource::Network);
+		// Known statements are not notified again.
+		store.submit(statement0.clone(), StatementSource::Network);
+		store.submit(statement1.clone(), StatementSource::Network);
+
+		let notified = futures::executor::block_on(notifications.take(2).collect::<Vec<_>>());
+		assert_eq!(
+			notified,
+			vec![(statement0.hash(), statement0), (statement1.hash(), statement1)]
+		);
+	}
+
 	#[test]
 	fn save_and_load_statements() {
 		let (store, temp) = test_store();
@@ -30,6 +30,7 @@
 
 pub mod block;
 pub mod logging;
+pub mod otlp;
 
 use rustc_hash::FxHashMap;
 use serde::ser::{Serialize, SerializeMap, Serializer};
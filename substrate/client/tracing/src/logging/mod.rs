@@ -66,6 +66,7 @@ pub enum Error {
 	SetGlobalDefaultError(#[from] tracing::subscriber::SetGlobalDefaultError),
 	DirectiveParseError(#[from] tracing_subscriber::filter::ParseError),
 	SetLoggerError(#[from] tracing_log::log_tracer::SetLoggerError),
+	OtlpError(#[from] crate::otlp::Error),
 }
 
 macro_rules! enable_log_reloading {
@@ -216,6 +217,7 @@ pub struct LoggerBuilder {
 	directives: String,
 	profiling: Option<(crate::TracingReceiver, String)>,
 	custom_profiler: Option<Box<dyn crate::TraceHandler>>,
+	otlp: Option<crate::otlp::OtlpConfig>,
 	log_reloading: bool,
 	force_colors: Option<bool>,
 	detailed_output: bool,
@@ -228,6 +230,7 @@ impl LoggerBuilder {
 			directives: directives.into(),
 			profiling: None,
 			custom_profiler: None,
+			otlp: None,
 			log_reloading: false,
 			force_colors: None,
 			detailed_output: false,
@@ -253,6 +256,12 @@ impl LoggerBuilder {
 		self
 	}
 
+	/// Export spans to an OpenTelemetry collector.
+	pub fn with_otlp(&mut self, config: crate::otlp::OtlpConfig) -> &mut Self {
+		self.otlp = Some(config);
+		self
+	}
+
 	/// Wether or not to disable log reloading.
 	pub fn with_log_reloading(&mut self, enabled: bool) -> &mut Self {
 		self.log_reloading = enabled;
@@ -280,6 +289,8 @@ impl LoggerBuilder {
 	///
 	/// This sets various global logging and tracing instances and thus may only be called once.
 	pub fn init(self) -> Result<()> {
+		let otlp = self.otlp.map(crate::otlp::OtlpLayer::new).transpose()?;
+
 		if let Some((tracing_receiver, profiling_targets)) = self.profiling {
 			if self.log_reloading {
 				let subscriber = prepare_subscriber(
@@ -296,7 +307,7 @@ impl LoggerBuilder {
 					.into_iter()
 					.for_each(|profiler| profiling.add_handler(profiler));
 
-				tracing::subscriber::set_global_default(subscriber.with(profiling))?;
+				tracing::subscriber::set_global_default(subscriber.with(profiling).with(otlp))?;
 
 				Ok(())
 			} else {
@@ -314,7 +325,7 @@ impl LoggerBuilder {
 					.into_iter()
 					.for_each(|profiler| profiling.add_handler(profiler));
 
-				tracing::subscriber::set_global_default(subscriber.with(profiling))?;
+				tracing::subscriber::set_global_default(subscriber.with(profiling).with(otlp))?;
 
 				Ok(())
 			}
@@ -327,7 +338,7 @@ impl LoggerBuilder {
 				|builder| enable_log_reloading!(builder),
 			)?;
 
-			tracing::subscriber::set_global_default(subscriber)?;
+			tracing::subscriber::set_global_default(subscriber.with(otlp))?;
 
 			Ok(())
 		} else {
@@ -339,7 +350,7 @@ impl LoggerBuilder {
 				|builder| builder,
 			)?;
 
-			tracing::subscriber::set_global_default(subscriber)?;
+			tracing::subscriber::set_global_default(subscriber.with(otlp))?;
 
 			Ok(())
 		}
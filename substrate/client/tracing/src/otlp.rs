@@ -0,0 +1,488 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Export of spans to OpenTelemetry collectors.
+//!
+//! [`OtlpLayer`] sends the spans of the configured targets to a collector speaking the OTLP/HTTP
+//! protocol with JSON encoding. Spans are batched and sent from a dedicated thread, so a slow
+//! collector doesn't slow down the node. Spans are dropped when the exporter can't keep up.
+//!
+//! Only spans enabled by the log filter are seen by the layer. The spans of the
+//! [`DEFAULT_TARGETS`] are emitted at the `info` level, so they are enabled by default.
+
+use crate::{parse_target, Values};
+use serde_json::{json, Value};
+use std::{
+	io::{self, BufRead, BufReader, Write},
+	net::{TcpStream, ToSocketAddrs},
+	sync::mpsc,
+	thread,
+	time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tracing::{
+	span::{Attributes, Id, Record},
+	Level, Subscriber,
+};
+use tracing_subscriber::{
+	layer::{Context, Layer},
+	registry::LookupSpan,
+};
+
+const LOG_TARGET: &str = "otlp";
+
+/// Default targets of the exported spans.
+///
+/// Covers the verification of blocks in the import queue, the runtime execution and state commit
+/// of imported blocks, block authorship and the handling of sync requests.
+pub const DEFAULT_TARGETS: &str =
+	"sc_consensus::import_queue,sc_service::client,sc_basic_authorship,sc_network_sync";
+
+/// Default URL of the collector.
+pub const DEFAULT_ENDPOINT: &str = "http://localhost:4318/v1/traces";
+
+/// Path used when the endpoint doesn't specify one.
+const DEFAULT_PATH: &str = "/v1/traces";
+
+/// Maximum number of finished spans waiting to be exported.
+const MAX_QUEUED_SPANS: usize = 8192;
+
+/// Maximum number of spans sent in one request.
+const MAX_BATCH_SIZE: usize = 512;
+
+/// Maximum time a finished span waits before being exported.
+const EXPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Timeout of the requests to the collector.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `SPAN_KIND_INTERNAL` of the OTLP protocol.
+const SPAN_KIND_INTERNAL: u8 = 1;
+
+/// Configuration of the [`OtlpLayer`].
+#[derive(Debug, Clone)]
+pub struct OtlpConfig {
+	/// URL of the collector, e.g. [`DEFAULT_ENDPOINT`].
+	///
+	/// Only `http` is supported, `https` endpoints are rejected. The path defaults to
+	/// `/v1/traces`.
+	pub endpoint: String,
+	/// Comma separated list of the targets of the exported spans, with an optional level:
+	/// "sc_consensus=debug,sc_network_sync".
+	pub targets: String,
+	/// Ratio of the traces that are exported, between `0.0` and `1.0`.
+	///
+	/// The decision is taken for the root span of a trace and inherited by its children.
+	pub sampling_ratio: f64,
+	/// Value of the `service.name` resource attribute.
+	pub service_name: String,
+}
+
+/// OTLP exporter errors.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// The endpoint couldn't be parsed.
+	#[error("Invalid OTLP endpoint `{0}`, expected `http://<host>:<port>[/<path>]`")]
+	InvalidEndpoint(String),
+	/// The endpoint requires TLS, which the exporter doesn't support.
+	#[error(
+		"Unsupported OTLP endpoint `{0}`: TLS isn't supported, use an `http://` endpoint, e.g. a \
+		local collector forwarding the spans"
+	)]
+	TlsNotSupported(String),
+	/// The exporter thread couldn't be started.
+	#[error("Failed to start the OTLP exporter: {0}")]
+	Io(#[from] io::Error),
+}
+
+/// Address of the collector.
+struct Endpoint {
+	host: String,
+	port: u16,
+	path: String,
+}
+
+impl Endpoint {
+	fn parse(url: &str) -> Result<Self, Error> {
+		if url.get(..8).is_some_and(|scheme| scheme.eq_ignore_ascii_case("https://")) {
+			return Err(Error::TlsNotSupported(url.into()))
+		}
+		let invalid = || Error::InvalidEndpoint(url.into());
+		let rest = url.strip_prefix("http://").ok_or_else(invalid)?;
+		let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+		let (host, port) = authority.rsplit_once(':').ok_or_else(invalid)?;
+		if host.is_empty() {
+			return Err(invalid())
+		}
+		let port = port.parse().map_err(|_| invalid())?;
+		let path = if path.is_empty() || path == "/" { DEFAULT_PATH } else { path };
+		Ok(Self { host: host.into(), port, path: path.into() })
+	}
+}
+
+/// Check that `url` is a valid endpoint for [`OtlpConfig::endpoint`].
+pub fn check_endpoint(url: &str) -> Result<(), Error> {
+	Endpoint::parse(url).map(|_| ())
+}
+
+/// Data of an open span, stored in the span extensions.
+struct OtlpSpan {
+	trace_id: u128,
+	span_id: u64,
+	parent_span_id: Option<u64>,
+	sampled: bool,
+	start: SystemTime,
+	values: Values,
+}
+
+/// A closed span waiting to be exported.
+struct FinishedSpan {
+	trace_id: u128,
+	span_id: u64,
+	parent_span_id: Option<u64>,
+	name: &'static str,
+	target: &'static str,
+	level: Level,
+	start: SystemTime,
+	end: SystemTime,
+	values: Values,
+}
+
+impl FinishedSpan {
+	fn to_json(&self) -> Value {
+		let mut attributes = vec![
+			attribute("code.namespace", json!({ "stringValue": self.target })),
+			attribute("level", json!({ "stringValue": self.level.to_string() })),
+		];
+		let values = &self.values;
+		attributes.extend(
+			values.bool_values.iter().map(|(k, v)| attribute(k, json!({ "boolValue": v }))),
+		);
+		attributes.extend(
+			values
+				.i64_values
+				.iter()
+				.map(|(k, v)| attribute(k, json!({ "intValue": v.to_string() }))),
+		);
+		attributes.extend(
+			values
+				.u64_values
+				.iter()
+				.map(|(k, v)| attribute(k, json!({ "intValue": v.to_string() }))),
+		);
+		attributes.extend(
+			values.string_values.iter().map(|(k, v)| attribute(k, json!({ "stringValue": v }))),
+		);
+
+		json!({
+			"traceId": format!("{:032x}", self.trace_id),
+			"spanId": format!("{:016x}", self.span_id),
+			"parentSpanId": self.parent_span_id.map(|id| format!("{id:016x}")).unwrap_or_default(),
+			"name": self.name,
+			"kind": SPAN_KIND_INTERNAL,
+			"startTimeUnixNano": unix_nanos(self.start).to_string(),
+			"endTimeUnixNano": unix_nanos(self.end).to_string(),
+			"attributes": attributes,
+		})
+	}
+}
+
+fn attribute(key: &str, value: Value) -> Value {
+	json!({ "key": key, "value": value })
+}
+
+fn unix_nanos(time: SystemTime) -> u128 {
+	time.duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or_default()
+}
+
+/// A [`Layer`] exporting spans to an OpenTelemetry collector.
+pub struct OtlpLayer {
+	targets: Vec<(String, Level)>,
+	sampling_ratio: f64,
+	sender: mpsc::SyncSender<FinishedSpan>,
+}
+
+impl OtlpLayer {
+	/// Create a new layer and start the exporter thread.
+	///
+	/// The exporter sends the remaining spans and stops once the layer is dropped.
+	pub fn new(config: OtlpConfig) -> Result<Self, Error> {
+		let endpoint = Endpoint::parse(&config.endpoint)?;
+		let (sender, receiver) = mpsc::sync_channel(MAX_QUEUED_SPANS);
+		let exporter = Exporter { endpoint, service_name: config.service_name };
+		thread::Builder::new()
+			.name("otlp-exporter".into())
+			.spawn(move || exporter.run(receiver))?;
+
+		Ok(Self {
+			targets: config.targets.split(',').map(parse_target).collect(),
+			sampling_ratio: config.sampling_ratio,
+			sender,
+		})
+	}
+
+	fn check_target(&self, target: &str, level: &Level) -> bool {
+		self.targets.iter().any(|t| target.starts_with(t.0.as_str()) && level <= &t.1)
+	}
+}
+
+impl<S> Layer<S> for OtlpLayer
+where
+	S: Subscriber + for<'span> LookupSpan<'span>,
+{
+	fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<S>) {
+		if !self.check_target(attrs.metadata().target(), attrs.metadata().level()) {
+			return
+		}
+		let Some(span) = ctx.span(id) else { return };
+
+		// Spans of other targets are skipped, the closest exported ancestor is the parent.
+		let parent = span.scope().skip(1).find_map(|parent| {
+			parent
+				.extensions()
+				.get::<OtlpSpan>()
+				.map(|parent| (parent.trace_id, parent.span_id, parent.sampled))
+		});
+		let (trace_id, parent_span_id, sampled) = match parent {
+			Some((trace_id, span_id, sampled)) => (trace_id, Some(span_id), sampled),
+			None =>
+				(rand::random::<u128>().max(1), None, rand::random::<f64>() < self.sampling_ratio),
+		};
+
+		let mut values = Values::default();
+		if sampled {
+			attrs.record(&mut values);
+		}
+		span.extensions_mut().insert(OtlpSpan {
+			trace_id,
+			span_id: rand::random::<u64>().max(1),
+			parent_span_id,
+			sampled,
+			start: SystemTime::now(),
+			values,
+		});
+	}
+
+	fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<S>) {
+		if let Some(span) = ctx.span(id) {
+			if let Some(s) = span.extensions_mut().get_mut::<OtlpSpan>().filter(|s| s.sampled) {
+				values.record(&mut s.values);
+			}
+		}
+	}
+
+	fn on_close(&self, id: Id, ctx: Context<S>) {
+		let Some(span) = ctx.span(&id) else { return };
+		let Some(data) = span.extensions_mut().remove::<OtlpSpan>() else { return };
+		if !data.sampled {
+			return
+		}
+
+		let metadata = span.metadata();
+		let finished = FinishedSpan {
+			trace_id: data.trace_id,
+			span_id: data.span_id,
+			parent_span_id: data.parent_span_id,
+			name: metadata.name(),
+			target: metadata.target(),
+			level: *metadata.level(),
+			start: data.start,
+			end: SystemTime::now(),
+			values: data.values,
+		};
+		// Drop the span if the exporter is lagging behind.
+		let _ = self.sender.try_send(finished);
+	}
+}
+
+/// Sends batches of finished spans to the collector.
+struct Exporter {
+	endpoint: Endpoint,
+	service_name: String,
+}
+
+impl Exporter {
+	fn run(self, receiver: mpsc::Receiver<FinishedSpan>) {
+		let mut batch = Vec::new();
+		let mut next_export = Instant::now() + EXPORT_INTERVAL;
+		loop {
+			let timeout = next_export.saturating_duration_since(Instant::now());
+			let disconnected = match receiver.recv_timeout(timeout) {
+				Ok(span) => {
+					batch.push(span);
+					if batch.len() < MAX_BATCH_SIZE && Instant::now() < next_export {
+						continue
+					}
+					false
+				},
+				Err(mpsc::RecvTimeoutError::Timeout) => false,
+				Err(mpsc::RecvTimeoutError::Disconnected) => true,
+			};
+
+			if !batch.is_empty() {
+				if let Err(e) = self.export(&batch) {
+					log::debug!(
+						target: LOG_TARGET,
+						"Failed to export {} spans: {}",
+						batch.len(),
+						e,
+					);
+				}
+				batch.clear();
+			}
+			if disconnected {
+				return
+			}
+			next_export = Instant::now() + EXPORT_INTERVAL;
+		}
+	}
+
+	/// Send `spans` in an `ExportTraceServiceRequest`.
+	fn export(&self, spans: &[FinishedSpan]) -> io::Result<()> {
+		let request = json!({
+			"resourceSpans": [{
+				"resource": {
+					"attributes": [
+						attribute("service.name", json!({ "stringValue": self.service_name })),
+					],
+				},
+				"scopeSpans": [{
+					"scope": {
+						"name": env!("CARGO_PKG_NAME"),
+						"version": env!("CARGO_PKG_VERSION"),
+					},
+					"spans": spans.iter().map(FinishedSpan::to_json).collect::<Vec<_>>(),
+				}],
+			}],
+		});
+		let body = serde_json::to_vec(&request)?;
+
+		let Endpoint { host, port, path } = &self.endpoint;
+		let address = (host.trim_start_matches('[').trim_end_matches(']'), *port)
+			.to_socket_addrs()?
+			.next()
+			.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Unknown collector host"))?;
+		let mut stream = TcpStream::connect_timeout(&address, REQUEST_TIMEOUT)?;
+		stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+		stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+
+		let head = format!(
+			"POST {path} HTTP/1.1\r\nHost: {host}:{port}\r\nContent-Type: application/json\r\n\
+			Content-Length: {}\r\nConnection: close\r\n\r\n",
+			body.len(),
+		);
+		stream.write_all(head.as_bytes())?;
+		stream.write_all(&body)?;
+
+		let mut status_line = String::new();
+		BufReader::new(stream).read_line(&mut status_line)?;
+		match status_line.split_whitespace().nth(1) {
+			Some(status) if status.starts_with('2') => Ok(()),
+			_ => Err(io::Error::other(format!("Unexpected response `{}`", status_line.trim()))),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{io::Read, net::TcpListener};
+	use tracing_subscriber::layer::SubscriberExt;
+
+	/// Read a request sent to the collector and return its JSON body.
+	fn read_request(stream: &mut TcpStream) -> Value {
+		let mut reader = BufReader::new(stream.try_clone().unwrap());
+		let mut content_length = 0;
+		loop {
+			let mut line = String::new();
+			reader.read_line(&mut line).unwrap();
+			if let Some(len) = line.to_lowercase().strip_prefix("content-length:") {
+				content_length = len.trim().parse().unwrap();
+			}
+			if line == "\r\n" {
+				break
+			}
+		}
+		let mut body = vec![0; content_length];
+		reader.read_exact(&mut body).unwrap();
+		stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n").unwrap();
+		serde_json::from_slice(&body).unwrap()
+	}
+
+	#[test]
+	fn spans_are_exported_to_the_collector() {
+		let collector = TcpListener::bind("127.0.0.1:0").unwrap();
+		let layer = OtlpLayer::new(OtlpConfig {
+			endpoint: format!("http://{}", collector.local_addr().unwrap()),
+			targets: "test_target".into(),
+			sampling_ratio: 1.0,
+			service_name: "test-node".into(),
+		})
+		.unwrap();
+
+		tracing::subscriber::with_default(tracing_subscriber::registry().with(layer), || {
+			let parent = tracing::info_span!(target: "test_target", "parent", number = 1u64);
+			let _enter = parent.enter();
+			tracing::info_span!(target: "other_target", "skipped").in_scope(|| {
+				tracing::info_span!(target: "test_target", "child").in_scope(|| {});
+			});
+		});
+
+		let (mut stream, _) = collector.accept().unwrap();
+		let request = read_request(&mut stream);
+		let resource = &request["resourceSpans"][0];
+		assert_eq!(
+			resource["resource"]["attributes"][0],
+			attribute("service.name", json!({ "stringValue": "test-node" })),
+		);
+
+		let spans = resource["scopeSpans"][0]["spans"].as_array().unwrap();
+		let [child, parent] = &spans[..] else { panic!("Unexpected spans: {spans:?}") };
+		assert_eq!(child["name"], "child");
+		assert_eq!(parent["name"], "parent");
+		assert_eq!(child["traceId"], parent["traceId"]);
+		assert_eq!(child["parentSpanId"], parent["spanId"]);
+		assert_eq!(parent["parentSpanId"], "");
+		assert!(parent["attributes"]
+			.as_array()
+			.unwrap()
+			.contains(&attribute("number", json!({ "intValue": "1" }))));
+	}
+
+	#[test]
+	fn endpoints_are_parsed() {
+		let endpoint = Endpoint::parse("http://localhost:4318").unwrap();
+		assert_eq!((endpoint.host.as_str(), endpoint.port), ("localhost", 4318));
+		assert_eq!(endpoint.path, DEFAULT_PATH);
+
+		let endpoint = Endpoint::parse("http://[::1]:4000/traces").unwrap();
+		assert_eq!((endpoint.host.as_str(), endpoint.port), ("[::1]", 4000));
+		assert_eq!(endpoint.path, "/traces");
+
+		assert!(matches!(
+			Endpoint::parse("https://localhost:4318"),
+			Err(Error::TlsNotSupported(_))
+		));
+		assert!(matches!(
+			Endpoint::parse("HTTPS://localhost:4318"),
+			Err(Error::TlsNotSupported(_))
+		));
+		assert!(matches!(Endpoint::parse("localhost:4318"), Err(Error::InvalidEndpoint(_))));
+		assert!(Endpoint::parse("http://localhost").is_err());
+		assert!(Endpoint::parse("http://:4318").is_err());
+	}
+}
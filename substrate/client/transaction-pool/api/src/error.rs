@@ -55,6 +55,16 @@ pub enum Error {
 		/// Transaction entering the pool.
 		new: Priority,
 	},
+	#[error("Priority bump too low to replace a transaction ({} < {})", new, required)]
+	TooLowPriorityBump {
+		/// Transaction already in the pool.
+		old: Priority,
+		/// Transaction entering the pool.
+		new: Priority,
+		/// Minimal priority required to replace the transaction already in the pool.
+		required: Priority,
+	},
+
 	#[error("Transaction with cyclic dependency")]
 	CycleDetected,
 
@@ -69,6 +79,9 @@ pub enum Error {
 
 	#[error("The pool is not accepting future transactions")]
 	RejectedFutureTransaction,
+
+	#[error("Transaction couldn't enter the pool because of the per-sender limit")]
+	SenderLimitExceeded,
 }
 
 impl Error {
@@ -87,7 +100,9 @@ impl Error {
 			// The node might be lagging behind, or during a warp sync.
 			Error::InvalidBlockId(_) |
 			// The pool is configured to not accept future transactions.
-			Error::RejectedFutureTransaction => {
+			Error::RejectedFutureTransaction |
+			// The sender has too many transactions in the pool at the moment.
+			Error::SenderLimitExceeded => {
 				true
 			}
 			_ => false
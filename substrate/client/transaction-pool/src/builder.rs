@@ -20,8 +20,11 @@
 
 use crate::{
 	common::api::FullChainApi,
-	fork_aware_txpool::ForkAwareTxPool as ForkAwareFullPool,
-	graph::{base_pool::Transaction, ChainApi, ExtrinsicFor, ExtrinsicHash, IsValidator, Options},
+	fork_aware_txpool::{ForkAwareTxPool as ForkAwareFullPool, MempoolJournalOptions},
+	graph::{
+		base_pool::{SenderLimit, Transaction},
+		ChainApi, ExtrinsicFor, ExtrinsicHash, IsValidator, Options,
+	},
 	single_state_txpool::BasicPool as SingleStateFullPool,
 	TransactionPoolWrapper, LOG_TARGET,
 };
@@ -29,7 +32,7 @@ use prometheus_endpoint::Registry as PrometheusRegistry;
 use sc_transaction_pool_api::{LocalTransactionPool, MaintainedTransactionPool};
 use sp_core::traits::SpawnEssentialNamed;
 use sp_runtime::traits::Block as BlockT;
-use std::{marker::PhantomData, sync::Arc, time::Duration};
+use std::{marker::PhantomData, path::Path, sync::Arc, time::Duration};
 
 /// The type of transaction pool.
 #[derive(Debug, Clone)]
@@ -45,11 +48,16 @@ pub enum TransactionPoolType {
 pub struct TransactionPoolOptions {
 	txpool_type: TransactionPoolType,
 	options: Options,
+	mempool_journal: Option<MempoolJournalOptions>,
 }
 
 impl Default for TransactionPoolOptions {
 	fn default() -> Self {
-		Self { txpool_type: TransactionPoolType::SingleState, options: Default::default() }
+		Self {
+			txpool_type: TransactionPoolType::SingleState,
+			options: Default::default(),
+			mempool_journal: None,
+		}
 	}
 }
 
@@ -81,7 +89,40 @@ impl TransactionPoolOptions {
 			Duration::from_secs(30 * 60)
 		};
 
-		TransactionPoolOptions { options, txpool_type }
+		TransactionPoolOptions { options, txpool_type, mempool_journal: None }
+	}
+
+	/// Enables the on-disk journal of the mempool.
+	///
+	/// Only supported by the [`TransactionPoolType::ForkAware`] pool.
+	pub fn with_mempool_journal(mut self, mempool_journal: MempoolJournalOptions) -> Self {
+		self.mempool_journal = Some(mempool_journal);
+		self
+	}
+
+	/// Limits the number and total size of the transactions of a single sender.
+	///
+	/// See [`SenderLimit`] for how the sender of a transaction is determined.
+	pub fn with_sender_limit(mut self, sender_limit: SenderLimit) -> Self {
+		self.options.sender_limit = Some(sender_limit);
+		self
+	}
+
+	/// Sets the minimal priority bump (in percent) required for a transaction to replace the
+	/// ones providing the same tags.
+	pub fn with_replacement_bump(mut self, percent: u64) -> Self {
+		self.options.replacement_bump = percent;
+		self
+	}
+
+	/// Resolves a relative path of the mempool journal against the given base directory.
+	pub fn resolve_journal_path(mut self, base_path: &Path) -> Self {
+		if let Some(ref mut mempool_journal) = self.mempool_journal {
+			if mempool_journal.path.is_relative() {
+				mempool_journal.path = base_path.join(&mempool_journal.path);
+			}
+		}
+		self
 	}
 
 	/// Creates predefined options for benchmarking
@@ -98,8 +139,11 @@ impl TransactionPoolOptions {
 				},
 				reject_future_transactions: false,
 				ban_time: Duration::from_secs(30 * 60),
+				sender_limit: None,
+				replacement_bump: 0,
 			},
 			txpool_type: TransactionPoolType::SingleState,
+			mempool_journal: None,
 		}
 	}
 }
@@ -229,8 +273,17 @@ where
 			txpool_type = ?self.options.txpool_type,
 			ready = ?self.options.options.ready,
 			future = ?self.options.options.future,
+			mempool_journal = ?self.options.mempool_journal.as_ref().map(|journal| &journal.path),
 			"Creating transaction pool"
 		);
+		if self.options.mempool_journal.is_some() &&
+			matches!(self.options.txpool_type, TransactionPoolType::SingleState)
+		{
+			tracing::warn!(
+				target: LOG_TARGET,
+				"The mempool journal is only supported by the fork-aware transaction pool, ignoring"
+			);
+		}
 		TransactionPoolWrapper::<Block, Client>(match self.options.txpool_type {
 			TransactionPoolType::SingleState => Box::new(SingleStateFullPool::new_full(
 				self.options.options,
@@ -245,6 +298,7 @@ where
 				self.prometheus,
 				self.spawner,
 				self.client,
+				self.options.mempool_journal,
 			)),
 		})
 	}
@@ -21,11 +21,14 @@
 use super::{
 	dropped_watcher::{MultiViewDroppedWatcherController, StreamOfDropped},
 	import_notification_sink::MultiViewImportNotificationSink,
+	mempool_journal::{MempoolJournal, MempoolJournalOptions},
 	metrics::{EventsMetricsCollector, MetricsLink as PrometheusMetrics},
 	multi_view_listener::MultiViewListener,
-	tx_mem_pool::{InsertionInfo, TxMemPool, TXMEMPOOL_TRANSACTION_LIMIT_MULTIPLIER},
+	tx_mem_pool::{
+		InsertionInfo, MempoolRevalidation, TxMemPool, TXMEMPOOL_TRANSACTION_LIMIT_MULTIPLIER,
+	},
 	view::View,
-	view_store::ViewStore,
+	view_store::{ViewStore, ViewStoreSubmitOutcome},
 };
 use crate::{
 	api::FullChainApi,
@@ -140,6 +143,9 @@ where
 	/// Intermediate buffer for the incoming transaction.
 	mempool: Arc<TxMemPool<ChainApi, Block>>,
 
+	/// Optional on-disk journal of the `mempool`, allowing it to survive restarts.
+	mempool_journal: Option<Arc<MempoolJournal>>,
+
 	/// The store for all the views.
 	view_store: Arc<ViewStore<ChainApi, Block>>,
 
@@ -205,6 +211,7 @@ where
 			finalized_hash,
 			Options::default().ready,
 			Options::default().future,
+			None,
 			usize::MAX,
 			finality_timeout_threshold,
 		)
@@ -218,6 +225,7 @@ where
 		finalized_hash: Block::Hash,
 		ready_limits: crate::PoolLimit,
 		future_limits: crate::PoolLimit,
+		sender_limit: Option<crate::SenderLimit>,
 		mempool_max_transactions_count: usize,
 		finality_timeout_threshold: Option<usize>,
 	) -> (Self, ForkAwareTxPoolTask) {
@@ -227,13 +235,16 @@ where
 		let (import_notification_sink, import_notification_sink_task) =
 			MultiViewImportNotificationSink::new_with_worker();
 
-		let mempool = Arc::from(TxMemPool::new(
-			pool_api.clone(),
-			listener.clone(),
-			Default::default(),
-			mempool_max_transactions_count,
-			ready_limits.total_bytes + future_limits.total_bytes,
-		));
+		let mempool = Arc::from(
+			TxMemPool::new(
+				pool_api.clone(),
+				listener.clone(),
+				Default::default(),
+				mempool_max_transactions_count,
+				ready_limits.total_bytes + future_limits.total_bytes,
+			)
+			.with_sender_limit(sender_limit.clone()),
+		);
 
 		let (dropped_stream_controller, dropped_stream) =
 			MultiViewDroppedWatcherController::<ChainApi>::new();
@@ -257,11 +268,17 @@ where
 		}
 		.boxed();
 
-		let options = Options { ready: ready_limits, future: future_limits, ..Default::default() };
+		let options = Options {
+			ready: ready_limits,
+			future: future_limits,
+			sender_limit,
+			..Default::default()
+		};
 
 		(
 			Self {
 				mempool,
+				mempool_journal: None,
 				api: pool_api,
 				view_store,
 				ready_poll: Arc::from(Mutex::from(ReadyPoll::new())),
@@ -338,6 +355,9 @@ where
 	///
 	/// The txpool essential tasks (including a revalidation worker) are spawned using provided
 	/// spawner.
+	///
+	/// If the `mempool_journal` is provided, the transactions journaled before the restart are
+	/// restored into the mempool and revalidated at the best block in the background.
 	pub fn new_with_background_worker(
 		options: Options,
 		is_validator: IsValidator,
@@ -346,6 +366,7 @@ where
 		spawner: impl SpawnEssentialNamed,
 		best_block_hash: Block::Hash,
 		finalized_hash: Block::Hash,
+		mempool_journal: Option<MempoolJournalOptions>,
 	) -> Self {
 		let metrics = PrometheusMetrics::new(prometheus);
 		let (events_metrics_collector, event_metrics_task) =
@@ -357,17 +378,21 @@ where
 
 		let (revalidation_queue, revalidation_task) =
 			revalidation_worker::RevalidationQueue::new_with_worker();
+		let revalidation_queue = Arc::from(revalidation_queue);
 
 		let (import_notification_sink, import_notification_sink_task) =
 			MultiViewImportNotificationSink::new_with_worker();
 
-		let mempool = Arc::from(TxMemPool::new(
-			pool_api.clone(),
-			listener.clone(),
-			metrics.clone(),
-			TXMEMPOOL_TRANSACTION_LIMIT_MULTIPLIER * options.total_count(),
-			options.ready.total_bytes + options.future.total_bytes,
-		));
+		let mempool = Arc::from(
+			TxMemPool::new(
+				pool_api.clone(),
+				listener.clone(),
+				metrics.clone(),
+				TXMEMPOOL_TRANSACTION_LIMIT_MULTIPLIER * options.total_count(),
+				options.ready.total_bytes + options.future.total_bytes,
+			)
+			.with_sender_limit(options.sender_limit.clone()),
+		);
 
 		let (dropped_stream_controller, dropped_stream) =
 			MultiViewDroppedWatcherController::<ChainApi>::new();
@@ -382,7 +407,31 @@ where
 			import_notification_sink.clone(),
 		);
 
+		let mempool_journal =
+			mempool_journal.map(|options| Arc::new(MempoolJournal::new(options, metrics.clone())));
+		let restored_revalidation = mempool_journal.as_ref().and_then(|journal| {
+			Self::restore_mempool_journal(journal, &mempool, &pool_api, &metrics, best_block_hash)
+				.map(|best_block| {
+					let revalidation_queue = revalidation_queue.clone();
+					let mempool = mempool.clone();
+					let view_store = view_store.clone();
+					async move {
+						revalidation_queue
+							.revalidate_mempool(
+								mempool,
+								view_store,
+								best_block,
+								MempoolRevalidation::Unvalidated,
+							)
+							.await
+					}
+				})
+		});
+
 		let combined_tasks = async move {
+			if let Some(restored_revalidation) = restored_revalidation {
+				restored_revalidation.await;
+			}
 			tokio::select! {
 				_ = listener_task => {}
 				_ = revalidation_task => {},
@@ -396,6 +445,7 @@ where
 
 		Self {
 			mempool,
+			mempool_journal,
 			api: pool_api,
 			view_store,
 			ready_poll: Arc::from(Mutex::from(ReadyPoll::new())),
@@ -403,7 +453,7 @@ where
 				best_block_hash,
 				finalized_hash,
 			))),
-			revalidation_queue: Arc::from(revalidation_queue),
+			revalidation_queue,
 			import_notification_sink,
 			options,
 			metrics,
@@ -414,6 +464,51 @@ where
 		}
 	}
 
+	/// Restores the transactions from the mempool journal into the mempool.
+	///
+	/// Returns the best block at which the restored transactions shall be revalidated, or `None`
+	/// if nothing was restored.
+	fn restore_mempool_journal(
+		journal: &MempoolJournal,
+		mempool: &TxMemPool<ChainApi, Block>,
+		pool_api: &ChainApi,
+		metrics: &PrometheusMetrics,
+		best_block_hash: Block::Hash,
+	) -> Option<HashAndNumber<Block>> {
+		let journaled = journal.restore::<ChainApi, Block>();
+		let journaled_count = journaled.len();
+		let restored_count = mempool.extend_restored(journaled);
+		metrics.report(|metrics| {
+			metrics.journal_restored_txs.inc_by(restored_count as _);
+			metrics.journal_discarded_txs.inc_by((journaled_count - restored_count) as _);
+		});
+		debug!(
+			target: LOG_TARGET,
+			journaled_count,
+			restored_count,
+			path = ?journal.path(),
+			"fatp::restore_mempool_journal"
+		);
+
+		if restored_count == 0 {
+			return None;
+		}
+
+		match pool_api.block_id_to_number(&BlockId::Hash(best_block_hash)) {
+			Ok(Some(number)) => Some(HashAndNumber { hash: best_block_hash, number }),
+			result => {
+				warn!(
+					target: LOG_TARGET,
+					?best_block_hash,
+					?result,
+					"fatp::restore_mempool_journal: could not resolve best block number, \
+					restored transactions will be revalidated with the views"
+				);
+				None
+			},
+		}
+	}
+
 	/// Get access to the underlying api
 	pub fn api(&self) -> &ChainApi {
 		&self.api
@@ -797,7 +892,8 @@ where
 		// view_store submission error.
 		//
 		// - If the view_store submission is successful, the transaction priority is updated in the
-		// mempool.
+		// mempool. The transaction is then accounted to its sender, and removed from the pool if
+		// the sender exceeds its limit.
 		//
 		// Finally, it collects the hashes of updated transactions or submission errors (either
 		// from the mempool or view_store) into a returned vector.
@@ -813,9 +909,10 @@ where
 				})
 			})
 			.map(|r| {
-				r.map(|r| {
+				r.and_then(|r| {
 					mempool.update_transaction_priority(&r);
-					r.hash()
+					self.enforce_sender_limit(&r)?;
+					Ok(r.hash())
 				})
 			})
 			.collect::<Vec<_>>())
@@ -877,9 +974,10 @@ where
 			.inspect_err(|_| {
 				self.mempool.remove_transactions(&[insertion.hash]);
 			})
-			.map(|mut outcome| {
+			.and_then(|mut outcome| {
 				self.mempool.update_transaction_priority(&outcome);
-				outcome.expect_watcher()
+				self.enforce_sender_limit(&outcome)?;
+				Ok(outcome.expect_watcher())
 			})
 	}
 
@@ -1371,7 +1469,15 @@ where
 			.zip(hashes)
 			.map(|(result, tx_hash)| {
 				result
-					.map(|outcome| self.mempool.update_transaction_priority(&outcome.into()))
+					.map(|outcome| {
+						let outcome: ViewStoreSubmitOutcome<ChainApi> = outcome.into();
+						self.mempool.update_transaction_priority(&outcome);
+						if self.enforce_sender_limit(&outcome).is_err() {
+							view.remove_subtree(&[tx_hash], true, |listener, tx_hash| {
+								listener.limits_enforced(&tx_hash);
+							});
+						}
+					})
 					.or_else(|_| Err(tx_hash))
 			})
 			.collect::<Vec<_>>();
@@ -1613,6 +1719,7 @@ where
 					self.mempool.clone(),
 					self.view_store.clone(),
 					HashAndNumber { hash: finalized_hash, number: finalized_number },
+					MempoolRevalidation::Periodic,
 				)
 				.await;
 		} else {
@@ -1623,6 +1730,11 @@ where
 			);
 		}
 
+		if let Some(ref journal) = self.mempool_journal {
+			self.revalidation_queue
+				.flush_mempool_journal(self.mempool.clone(), journal.clone());
+		}
+
 		self.ready_poll.lock().remove_cancelled();
 
 		debug!(
@@ -1737,6 +1849,33 @@ where
 
 		return Ok(insertion_info)
 	}
+
+	/// Accounts the transaction submitted to the views to its sender.
+	///
+	/// The views only enforce the per-sender limit on the transactions they know. If the sender
+	/// exceeds its limit across the whole mempool, the transaction (along with its subtree) is
+	/// removed from the views and from the mempool, and `SenderLimitExceeded` is returned.
+	fn enforce_sender_limit(
+		&self,
+		outcome: &ViewStoreSubmitOutcome<ChainApi>,
+	) -> Result<(), TxPoolApiError> {
+		self.mempool.ensure_sender_limit(outcome).inspect_err(|_| {
+			let tx_hash = outcome.hash();
+			self.view_store
+				.listener
+				.transaction_dropped(DroppedTransaction::new_enforced_by_limts(tx_hash));
+			let removed = self
+				.view_store
+				.remove_transaction_subtree(tx_hash, |listener, removed_tx_hash| {
+					listener.limits_enforced(&removed_tx_hash);
+				})
+				.into_iter()
+				.map(|tx| tx.hash)
+				.chain(std::iter::once(tx_hash))
+				.collect::<Vec<_>>();
+			self.mempool.remove_transactions(&removed);
+		})
+	}
 }
 
 #[async_trait]
@@ -1857,12 +1996,15 @@ where
 	<Block as BlockT>::Hash: std::marker::Unpin,
 {
 	/// Create new fork aware transaction pool for a full node with the provided api.
+	///
+	/// The mempool is persisted across restarts if `mempool_journal` is provided.
 	pub fn new_full(
 		options: Options,
 		is_validator: IsValidator,
 		prometheus: Option<&PrometheusRegistry>,
 		spawner: impl SpawnEssentialNamed,
 		client: Arc<Client>,
+		mempool_journal: Option<MempoolJournalOptions>,
 	) -> Self {
 		let pool_api = Arc::new(FullChainApi::new(client.clone(), prometheus, &spawner));
 		let pool = Self::new_with_background_worker(
@@ -1873,12 +2015,26 @@ where
 			spawner,
 			client.usage_info().chain.best_hash,
 			client.usage_info().chain.finalized_hash,
+			mempool_journal,
 		);
 
 		pool
 	}
 }
 
+impl<ChainApi, Block> Drop for ForkAwareTxPool<ChainApi, Block>
+where
+	Block: BlockT,
+	ChainApi: graph::ChainApi<Block = Block> + 'static,
+{
+	fn drop(&mut self) {
+		// The size of the written journal is bounded by `MempoolJournalOptions::max_bytes`.
+		if let Some(ref journal) = self.mempool_journal {
+			journal.flush(&self.mempool);
+		}
+	}
+}
+
 #[cfg(test)]
 mod reduce_multiview_result_tests {
 	use super::*;
@@ -0,0 +1,431 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! On-disk journal of the [`TxMemPool`].
+//!
+//! The journal allows the *mempool* to survive node restarts. Its content (the body of every
+//! transaction together with its source, priority and insertion time) is periodically flushed to
+//! a single file. The file is rewritten from scratch on every flush, so it never grows beyond the
+//! configured limits.
+//!
+//! On startup the journal is read, stale entries are discarded, and the remaining transactions are
+//! inserted into the *mempool* as unwatched transactions. They are then revalidated against the
+//! best block by the [`RevalidationQueue`].
+//!
+//! [`RevalidationQueue`]: super::revalidation_worker::RevalidationQueue
+
+use std::{
+	fs, io,
+	path::{Path, PathBuf},
+	sync::Arc,
+	time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use codec::{Decode, Encode};
+use parking_lot::Mutex;
+use sc_transaction_pool_api::{TransactionPriority, TransactionSource};
+use sp_runtime::traits::Block as BlockT;
+use tracing::{debug, info, warn};
+
+use super::{
+	metrics::MetricsLink as PrometheusMetrics,
+	tx_mem_pool::{TxInMemPool, TxMemPool},
+};
+use crate::{
+	graph::{self, ExtrinsicFor},
+	LOG_TARGET,
+};
+
+/// The version of the journal file format.
+const JOURNAL_VERSION: u8 = 1;
+
+/// Configuration of the mempool journal.
+#[derive(Debug, Clone)]
+pub struct MempoolJournalOptions {
+	/// The path of the journal file.
+	///
+	/// A relative path is resolved against the node's data directory, see
+	/// [`resolve_journal_path`][crate::TransactionPoolOptions::resolve_journal_path].
+	pub path: PathBuf,
+	/// The maximum number of transactions kept in the journal.
+	///
+	/// Transactions with the highest priority (and then the most recent ones) are kept.
+	pub max_count: usize,
+	/// The maximum total size in bytes of the transactions kept in the journal.
+	///
+	/// Transactions are kept in the same order as for [`Self::max_count`]. This also bounds the
+	/// size of the journal file written when the pool is dropped.
+	pub max_bytes: usize,
+	/// The maximum age of a journaled transaction.
+	///
+	/// Older transactions are discarded when the journal is restored.
+	pub max_age: Duration,
+	/// The minimum interval between two consecutive flushes of the journal.
+	pub flush_interval: Duration,
+}
+
+impl MempoolJournalOptions {
+	/// Creates the journal options for the given path with the default limits.
+	pub fn new(path: impl Into<PathBuf>) -> Self {
+		Self {
+			path: path.into(),
+			max_count: 8192,
+			max_bytes: 20 * 1024 * 1024,
+			max_age: Duration::from_secs(60 * 60),
+			flush_interval: Duration::from_secs(30),
+		}
+	}
+}
+
+/// A single transaction as stored in the journal file.
+#[derive(Encode, Decode)]
+struct JournalEntry {
+	/// The encoded body of the transaction.
+	extrinsic: Vec<u8>,
+	/// The original source of the transaction.
+	source: TransactionSource,
+	/// The priority of the transaction, if known.
+	priority: Option<TransactionPriority>,
+	/// The insertion time of the transaction in milliseconds since the Unix epoch.
+	inserted_at: u64,
+}
+
+/// A transaction restored from the journal, ready to be inserted into the mempool.
+pub(super) struct JournaledTransaction<Xt> {
+	/// The transaction body.
+	pub(super) xt: Xt,
+	/// The original source of the transaction.
+	pub(super) source: TransactionSource,
+	/// The priority of the transaction, if known.
+	pub(super) priority: Option<TransactionPriority>,
+	/// How long ago the transaction was inserted into the mempool.
+	pub(super) age: Duration,
+}
+
+/// The on-disk journal of the [`TxMemPool`][super::tx_mem_pool::TxMemPool].
+pub(super) struct MempoolJournal {
+	/// Externally provided journal options.
+	options: MempoolJournalOptions,
+	/// The time of the most recent flush.
+	last_flush: Mutex<Option<Instant>>,
+	/// Prometheus's metrics endpoint.
+	metrics: PrometheusMetrics,
+}
+
+impl MempoolJournal {
+	/// Creates a new journal instance.
+	pub(super) fn new(options: MempoolJournalOptions, metrics: PrometheusMetrics) -> Self {
+		Self { options, last_flush: Default::default(), metrics }
+	}
+
+	/// Returns the path of the journal file.
+	pub(super) fn path(&self) -> &Path {
+		&self.options.path
+	}
+
+	/// Reads the journal file and returns the transactions which shall be restored.
+	///
+	/// Entries which are older than the configured maximum age, or which cannot be decoded, are
+	/// discarded. A missing or corrupted journal yields no transactions.
+	pub(super) fn restore<ChainApi, Block>(
+		&self,
+	) -> Vec<JournaledTransaction<ExtrinsicFor<ChainApi>>>
+	where
+		Block: BlockT,
+		ChainApi: graph::ChainApi<Block = Block> + 'static,
+	{
+		let entries = match self.read() {
+			Ok(entries) => entries,
+			Err(error) if error.kind() == io::ErrorKind::NotFound => return Vec::new(),
+			Err(error) => {
+				warn!(
+					target: LOG_TARGET,
+					%error,
+					path = ?self.options.path,
+					"mempool_journal: failed to read the journal, starting with an empty mempool"
+				);
+				return Vec::new();
+			},
+		};
+
+		let now = unix_time_millis(SystemTime::now());
+		let total = entries.len();
+		let restored = self
+			.limit(entries)
+			.into_iter()
+			.filter_map(|entry| {
+				let age = Duration::from_millis(now.saturating_sub(entry.inserted_at));
+				if age > self.options.max_age {
+					return None;
+				}
+				let xt = <Block as BlockT>::Extrinsic::decode(&mut &entry.extrinsic[..]).ok()?;
+				Some(JournaledTransaction {
+					xt: Arc::from(xt),
+					source: entry.source,
+					priority: entry.priority,
+					age,
+				})
+			})
+			.collect::<Vec<_>>();
+
+		let discarded = total - restored.len();
+		self.metrics
+			.report(|metrics| metrics.journal_discarded_txs.inc_by(discarded as _));
+		info!(
+			target: LOG_TARGET,
+			restored = restored.len(),
+			discarded,
+			path = ?self.options.path,
+			"mempool_journal: restored transactions"
+		);
+
+		restored
+	}
+
+	/// Flushes the content of the mempool to the journal if the flush interval has elapsed since
+	/// the previous flush.
+	pub(super) fn maybe_flush<ChainApi, Block>(&self, mempool: &TxMemPool<ChainApi, Block>)
+	where
+		Block: BlockT,
+		ChainApi: graph::ChainApi<Block = Block> + 'static,
+	{
+		let due = self
+			.last_flush
+			.lock()
+			.map_or(true, |last_flush| last_flush.elapsed() >= self.options.flush_interval);
+		if due {
+			self.flush(mempool);
+		}
+	}
+
+	/// Unconditionally flushes the content of the mempool to the journal.
+	///
+	/// Errors are logged, the previous journal file is kept intact on failure.
+	pub(super) fn flush<ChainApi, Block>(&self, mempool: &TxMemPool<ChainApi, Block>)
+	where
+		Block: BlockT,
+		ChainApi: graph::ChainApi<Block = Block> + 'static,
+	{
+		let start = Instant::now();
+		*self.last_flush.lock() = Some(start);
+
+		let entries = self.entries(mempool.journal_snapshot());
+		let count = entries.len();
+		match self.write(entries) {
+			Ok(()) => {
+				let duration = start.elapsed();
+				self.metrics.report(|metrics| {
+					metrics.journal_persisted_txs.set(count as _);
+					metrics.journal_flush_duration.observe(duration.as_secs_f64());
+				});
+				debug!(target: LOG_TARGET, count, ?duration, "mempool_journal: flushed");
+			},
+			Err(error) => {
+				warn!(
+					target: LOG_TARGET,
+					%error,
+					path = ?self.options.path,
+					"mempool_journal: failed to write the journal"
+				);
+			},
+		}
+	}
+
+	/// Converts the mempool transactions into journal entries obeying the configured limits.
+	///
+	/// Watched transactions are journaled as well, however they are restored as unwatched ones.
+	fn entries<ChainApi, Block>(
+		&self,
+		transactions: Vec<Arc<TxInMemPool<ChainApi, Block>>>,
+	) -> Vec<JournalEntry>
+	where
+		Block: BlockT,
+		ChainApi: graph::ChainApi<Block = Block> + 'static,
+	{
+		let now = Instant::now();
+		let wall_clock_now = SystemTime::now();
+
+		let mut entries = transactions
+			.into_iter()
+			.map(|tx| {
+				let source = tx.source();
+				let inserted_at = source
+					.timestamp
+					.and_then(|timestamp| wall_clock_now.checked_sub(now.duration_since(timestamp)))
+					.unwrap_or(wall_clock_now);
+				JournalEntry {
+					extrinsic: tx.tx().encode(),
+					source: source.source,
+					priority: tx.priority(),
+					inserted_at: unix_time_millis(inserted_at),
+				}
+			})
+			.collect::<Vec<_>>();
+
+		// highest priority first, the most recent first within the same priority
+		entries.sort_by(|a, b| {
+			b.priority.cmp(&a.priority).then_with(|| b.inserted_at.cmp(&a.inserted_at))
+		});
+		self.limit(entries)
+	}
+
+	/// Keeps the leading entries fitting in the configured count and size limits.
+	fn limit(&self, mut entries: Vec<JournalEntry>) -> Vec<JournalEntry> {
+		let mut bytes = 0usize;
+		let count = entries
+			.iter()
+			.take(self.options.max_count)
+			.take_while(|entry| {
+				bytes = bytes.saturating_add(entry.extrinsic.len());
+				bytes <= self.options.max_bytes
+			})
+			.count();
+		entries.truncate(count);
+		entries
+	}
+
+	/// Reads and decodes the journal file.
+	fn read(&self) -> io::Result<Vec<JournalEntry>> {
+		let content = fs::read(&self.options.path)?;
+		let (version, entries) = <(u8, Vec<JournalEntry>)>::decode(&mut &content[..])
+			.map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+		if version != JOURNAL_VERSION {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				format!("unsupported journal version {version}"),
+			));
+		}
+		Ok(entries)
+	}
+
+	/// Atomically replaces the journal file with the given entries.
+	fn write(&self, entries: Vec<JournalEntry>) -> io::Result<()> {
+		if let Some(parent) = self.options.path.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		let tmp_path = self.options.path.with_extension("tmp");
+		fs::write(&tmp_path, (JOURNAL_VERSION, entries).encode())?;
+		fs::rename(&tmp_path, &self.options.path)
+	}
+}
+
+/// Converts the given time into milliseconds since the Unix epoch.
+fn unix_time_millis(time: SystemTime) -> u64 {
+	time.duration_since(UNIX_EPOCH)
+		.map_or(0, |duration| duration.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		common::tests::{uxt, TestApi},
+		graph::ChainApi,
+	};
+	use substrate_test_runtime::{AccountId, Block, Transfer, H256};
+	use substrate_test_runtime_client::Sr25519Keyring::Alice;
+
+	fn xt(nonce: u64) -> ExtrinsicFor<TestApi> {
+		Arc::from(uxt(Transfer {
+			from: Alice.into(),
+			to: AccountId::from_h256(H256::from_low_u64_be(2)),
+			amount: 5,
+			nonce,
+		}))
+	}
+
+	fn new_journal(dir: &Path, max_count: usize, max_age: Duration) -> MempoolJournal {
+		new_journal_with_bytes(dir, max_count, usize::MAX, max_age)
+	}
+
+	fn new_journal_with_bytes(
+		dir: &Path,
+		max_count: usize,
+		max_bytes: usize,
+		max_age: Duration,
+	) -> MempoolJournal {
+		MempoolJournal::new(
+			MempoolJournalOptions {
+				path: dir.join("txpool").join("journal"),
+				max_count,
+				max_bytes,
+				max_age,
+				flush_interval: Duration::ZERO,
+			},
+			Default::default(),
+		)
+	}
+
+	#[test]
+	fn journal_round_trip_works() {
+		let dir = tempfile::tempdir().unwrap();
+		let api = Arc::from(TestApi::default());
+		let mempool = TxMemPool::new_test(api.clone(), usize::MAX, usize::MAX);
+		let xts = (0..5).map(xt).collect::<Vec<_>>();
+		mempool.extend_unwatched(TransactionSource::External, &xts);
+
+		let journal = new_journal(dir.path(), usize::MAX, Duration::from_secs(60));
+		journal.flush(&mempool);
+
+		let restored = journal.restore::<TestApi, Block>();
+		assert_eq!(restored.len(), 5);
+		let mut restored_hashes =
+			restored.iter().map(|tx| api.hash_and_length(&tx.xt).0).collect::<Vec<_>>();
+		let mut expected_hashes =
+			xts.iter().map(|xt| api.hash_and_length(xt).0).collect::<Vec<_>>();
+		restored_hashes.sort();
+		expected_hashes.sort();
+		assert_eq!(restored_hashes, expected_hashes);
+		assert!(restored.iter().all(|tx| tx.source == TransactionSource::External));
+	}
+
+	#[test]
+	fn journal_obeys_limits() {
+		let dir = tempfile::tempdir().unwrap();
+		let api = Arc::from(TestApi::default());
+		let mempool = TxMemPool::new_test(api, usize::MAX, usize::MAX);
+		mempool.extend_unwatched(TransactionSource::External, &(0..5).map(xt).collect::<Vec<_>>());
+
+		let journal = new_journal(dir.path(), 3, Duration::from_secs(60));
+		journal.flush(&mempool);
+		assert_eq!(journal.restore::<TestApi, Block>().len(), 3);
+
+		let journal = new_journal(dir.path(), 3, Duration::ZERO);
+		std::thread::sleep(Duration::from_millis(5));
+		assert!(journal.restore::<TestApi, Block>().is_empty());
+
+		// the size limit applies when writing and when restoring
+		let xt_len = xt(0).encode().len();
+		let journal = new_journal_with_bytes(dir.path(), 3, 2 * xt_len, Duration::from_secs(60));
+		assert_eq!(journal.restore::<TestApi, Block>().len(), 2);
+		journal.flush(&mempool);
+		let journal = new_journal(dir.path(), usize::MAX, Duration::from_secs(60));
+		assert_eq!(journal.restore::<TestApi, Block>().len(), 2);
+	}
+
+	#[test]
+	fn missing_or_corrupted_journal_is_empty() {
+		let dir = tempfile::tempdir().unwrap();
+		let journal = new_journal(dir.path(), usize::MAX, Duration::from_secs(60));
+		assert!(journal.restore::<TestApi, Block>().is_empty());
+
+		fs::create_dir_all(journal.path().parent().unwrap()).unwrap();
+		fs::write(journal.path(), b"garbage").unwrap();
+		assert!(journal.restore::<TestApi, Block>().is_empty());
+	}
+}
@@ -83,6 +83,15 @@ pub struct Metrics {
 	pub view_revalidation_duration: Histogram,
 	/// Total number of the views created w/o cloning existing view.
 	pub non_cloned_views: Counter<U64>,
+	/// Total number of transactions restored from the mempool journal.
+	pub journal_restored_txs: Counter<U64>,
+	/// Total number of journaled transactions discarded on restore (stale, undecodable or over
+	/// the limit).
+	pub journal_discarded_txs: Counter<U64>,
+	/// Number of transactions written to the mempool journal by the most recent flush.
+	pub journal_persisted_txs: Gauge<U64>,
+	/// Histogram of mempool journal flush durations.
+	pub journal_flush_duration: Histogram,
 	/// Histograms to track the timing distribution of individual transaction pool events.
 	pub events_histograms: EventsHistograms,
 }
@@ -348,6 +357,35 @@ impl MetricsRegistrant for Metrics {
 				)?,
 				registry,
 			)?,
+			journal_restored_txs: register(
+				Counter::new(
+					"substrate_sub_txpool_journal_restored_txs_total",
+					"Total number of transactions restored from the mempool journal.",
+				)?,
+				registry,
+			)?,
+			journal_discarded_txs: register(
+				Counter::new(
+					"substrate_sub_txpool_journal_discarded_txs_total",
+					"Total number of journaled transactions discarded on restore.",
+				)?,
+				registry,
+			)?,
+			journal_persisted_txs: register(
+				Gauge::new(
+					"substrate_sub_txpool_journal_persisted_txs",
+					"Number of transactions written to the mempool journal by the last flush.",
+				)?,
+				registry,
+			)?,
+			journal_flush_duration: register(
+				Histogram::with_opts(histogram_opts!(
+					"substrate_sub_txpool_journal_flush_duration_seconds",
+					"Histogram of mempool journal flush durations.",
+					exponential_buckets(0.001, 2.0, 14).unwrap()
+				))?,
+				registry,
+			)?,
 			events_histograms: EventsHistograms::register(registry)?,
 		}))
 	}
@@ -275,6 +275,13 @@
 //! removed from the *mempool*, but not removed from the view. The [`Invalid`] event is not sent.
 //! This case is not likely to happen, however it may need some extra attention.
 //!
+//! ####  Mempool journal
+//! Optionally, the content of the *mempool* can be persisted in the [`MempoolJournal`]. The
+//! journal is flushed by the background worker on finalized blocks (at most once per configured
+//! interval) and when the pool is dropped. On startup the journaled transactions are inserted into
+//! the *mempool* as unwatched transactions and all of them are revalidated at the best block by the
+//! [`RevalidationQueue`].
+//!
 //! ### Networking
 //! The pool is exposing [`ImportNotificationStream`][`import_notification_stream`], the dedicated
 //! channel over which all ready transactions are notified. Internally this channel needs to merge
@@ -305,6 +312,12 @@
 //! while not referenced by the others), what means that transaction can also be
 //! [removed][`dropped_monitor_task`] from the *mempool*.
 //!
+//! The [limits][`Options`] may also cap the number and size of transactions of a single sender.
+//! A transaction exceeding the per-sender limit is rejected by the views, and as such it is also
+//! removed from the *mempool*. As views only know the transactions valid at their blocks, the
+//! *mempool* also [accounts][`mp::ensure_sender_limit`] every transaction accepted by the
+//! views to its sender, preventing a single account from filling it up across the forks.
+//!
 //!
 //! ## API Considerations
 //! Refer to github issue: <https://github.com/paritytech/polkadot-sdk/issues/5491>
@@ -321,6 +334,7 @@
 //! [`inactive_views`]: crate::fork_aware_txpool::view_store::ViewStore::inactive_views
 //! [`TxMemPool`]: crate::fork_aware_txpool::tx_mem_pool::TxMemPool
 //! [`mp::revalidate`]: crate::fork_aware_txpool::tx_mem_pool::TxMemPool::revalidate
+//! [`mp::ensure_sender_limit`]: crate::fork_aware_txpool::tx_mem_pool::TxMemPool::ensure_sender_limit
 //! [`batch_size`]: crate::fork_aware_txpool::tx_mem_pool::TXMEMPOOL_MAX_REVALIDATION_BATCH_SIZE
 //! [`TxInMemPool`]: crate::fork_aware_txpool::tx_mem_pool::TxInMemPool
 //! [`MultiViewListener`]: crate::fork_aware_txpool::multi_view_listener::MultiViewListener
@@ -363,12 +377,14 @@
 //! [`EnactmentState`]: crate::common::enactment_state::EnactmentState
 //! [`MultiViewImportNotificationSink`]: crate::fork_aware_txpool::import_notification_sink::MultiViewImportNotificationSink
 //! [`RevalidationQueue`]: crate::fork_aware_txpool::revalidation_worker::RevalidationQueue
+//! [`MempoolJournal`]: crate::fork_aware_txpool::mempool_journal::MempoolJournal
 //! [`StreamOfDropped`]: crate::fork_aware_txpool::dropped_watcher::StreamOfDropped
 //! [`Arc`]: std::sync::Arc
 
 mod dropped_watcher;
 pub(crate) mod fork_aware_txpool;
 mod import_notification_sink;
+mod mempool_journal;
 mod metrics;
 mod multi_view_listener;
 mod revalidation_worker;
@@ -377,6 +393,7 @@ mod view;
 mod view_store;
 
 pub use fork_aware_txpool::{ForkAwareTxPool, ForkAwareTxPoolTask};
+pub use mempool_journal::MempoolJournalOptions;
 
 mod stream_map_util {
 	use futures::Stream;
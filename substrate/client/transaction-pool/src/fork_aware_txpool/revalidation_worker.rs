@@ -28,7 +28,11 @@ use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnbound
 use sp_blockchain::HashAndNumber;
 use sp_runtime::traits::Block as BlockT;
 
-use super::{tx_mem_pool::TxMemPool, view_store::ViewStore};
+use super::{
+	mempool_journal::MempoolJournal,
+	tx_mem_pool::{MempoolRevalidation, TxMemPool},
+	view_store::ViewStore,
+};
 use futures::prelude::*;
 use tracing::{trace, warn};
 
@@ -45,7 +49,14 @@ where
 	/// Communication channels with maintain thread are also provided.
 	RevalidateView(Arc<View<Api>>, FinishRevalidationWorkerChannels<Api>),
 	/// Request to revalidated the given instance of the [`TxMemPool`] at provided block hash.
-	RevalidateMempool(Arc<TxMemPool<Api, Block>>, Arc<ViewStore<Api, Block>>, HashAndNumber<Block>),
+	RevalidateMempool(
+		Arc<TxMemPool<Api, Block>>,
+		Arc<ViewStore<Api, Block>>,
+		HashAndNumber<Block>,
+		MempoolRevalidation,
+	),
+	/// Request to flush the given instance of the [`TxMemPool`] to the [`MempoolJournal`].
+	FlushMempoolJournal(Arc<TxMemPool<Api, Block>>, Arc<MempoolJournal>),
 }
 
 /// The background revalidation worker.
@@ -85,7 +96,10 @@ where
 					mempool,
 					view_store,
 					finalized_hash_and_number,
-				) => mempool.revalidate(view_store, finalized_hash_and_number).await,
+					mode,
+				) => mempool.revalidate(view_store, finalized_hash_and_number, mode).await,
+				WorkerPayload::FlushMempoolJournal(mempool, journal) =>
+					journal.maybe_flush(&mempool),
 			};
 		}
 	}
@@ -169,10 +183,12 @@ where
 		mempool: Arc<TxMemPool<Api, Block>>,
 		view_store: Arc<ViewStore<Api, Block>>,
 		finalized_hash: HashAndNumber<Block>,
+		mode: MempoolRevalidation,
 	) {
 		trace!(
 			target: LOG_TARGET,
 			?finalized_hash,
+			?mode,
 			"Sent mempool to revalidation queue"
 		);
 
@@ -181,6 +197,7 @@ where
 				mempool,
 				view_store,
 				finalized_hash,
+				mode,
 			)) {
 				warn!(
 					target: LOG_TARGET,
@@ -189,7 +206,35 @@ where
 				);
 			}
 		} else {
-			mempool.revalidate(view_store, finalized_hash).await
+			mempool.revalidate(view_store, finalized_hash, mode).await
+		}
+	}
+
+	/// Flushes the given mempool instance to the journal, if the journal's flush interval has
+	/// elapsed.
+	///
+	/// If queue configured with background worker, this will return immediately.
+	/// If queue configured without background worker, this will return after the journal was
+	/// actually written.
+	///
+	/// Schedules execution of the [`MempoolJournal::maybe_flush`].
+	pub fn flush_mempool_journal(
+		&self,
+		mempool: Arc<TxMemPool<Api, Block>>,
+		journal: Arc<MempoolJournal>,
+	) {
+		if let Some(ref to_worker) = self.background {
+			if let Err(error) =
+				to_worker.unbounded_send(WorkerPayload::FlushMempoolJournal(mempool, journal))
+			{
+				warn!(
+					target: LOG_TARGET,
+					?error,
+					"Failed to update background worker"
+				);
+			}
+		} else {
+			journal.maybe_flush(&mempool)
 		}
 	}
 }
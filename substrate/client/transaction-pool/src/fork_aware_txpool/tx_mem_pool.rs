@@ -38,7 +38,7 @@ use std::{
 
 use futures::FutureExt;
 use itertools::Itertools;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use tracing::{debug, trace};
 
 use sc_transaction_pool_api::{TransactionPriority, TransactionSource};
@@ -51,11 +51,16 @@ use sp_runtime::{
 use crate::{
 	common::tracing_log_xt::log_xt_trace,
 	graph,
-	graph::{base_pool::TimedTransactionSource, tracked_map::Size, ExtrinsicFor, ExtrinsicHash},
+	graph::{
+		base_pool::{SenderLimit, SenderUsage, TimedTransactionSource},
+		tracked_map::Size,
+		ExtrinsicFor, ExtrinsicHash,
+	},
 	LOG_TARGET,
 };
 
 use super::{
+	mempool_journal::JournaledTransaction,
 	metrics::MetricsLink as PrometheusMetrics,
 	multi_view_listener::MultiViewListener,
 	view_store::{ViewStore, ViewStoreSubmitOutcome},
@@ -71,6 +76,17 @@ pub(crate) const TXMEMPOOL_MAX_REVALIDATION_BATCH_SIZE: usize = 1000;
 /// the view's total limit.
 pub const TXMEMPOOL_TRANSACTION_LIMIT_MULTIPLIER: usize = 4;
 
+/// Selects the transactions that are subject to the mempool revalidation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum MempoolRevalidation {
+	/// Revalidates a batch of transactions that were not revalidated within the last
+	/// [`TXMEMPOOL_REVALIDATION_PERIOD`] blocks.
+	Periodic,
+	/// Revalidates all transactions that were never revalidated, e.g. the ones restored from the
+	/// mempool journal.
+	Unvalidated,
+}
+
 /// Represents the transaction in the intermediary buffer.
 #[derive(Debug)]
 pub(crate) struct TxInMemPool<ChainApi, Block>
@@ -94,6 +110,9 @@ where
 	/// Priority of transaction at some block. It is assumed it will not be changed often. None if
 	/// not known.
 	priority: RwLock<Option<TransactionPriority>>,
+	/// Sender of transaction accounted to the per-sender limit. None if not known yet or if the
+	/// transaction is not subject to the limit.
+	sender: RwLock<Option<Vec<u8>>>,
 }
 
 impl<ChainApi, Block> TxInMemPool<ChainApi, Block>
@@ -154,6 +173,7 @@ where
 			validated_at: AtomicU64::new(0),
 			bytes,
 			priority: priority.into(),
+			sender: Default::default(),
 		}
 	}
 
@@ -224,6 +244,14 @@ where
 
 	/// Maximal size of encodings of all transactions in the memory pool.
 	max_transactions_total_bytes: usize,
+
+	/// Limits of the transactions of a single sender.
+	sender_limit: Option<SenderLimit>,
+
+	/// Number and total size of the transactions of each sender.
+	///
+	/// Always locked after `transactions`.
+	senders: Mutex<HashMap<Vec<u8>, SenderUsage>>,
 }
 
 /// Helper structure to encapsulate a result of [`TxMemPool::try_insert`].
@@ -265,12 +293,20 @@ where
 			metrics,
 			max_transactions_count,
 			max_transactions_total_bytes,
+			sender_limit: None,
+			senders: Default::default(),
 		}
 	}
 
+	/// Sets the limits applied to the transactions of a single sender.
+	pub(super) fn with_sender_limit(mut self, sender_limit: Option<SenderLimit>) -> Self {
+		self.sender_limit = sender_limit;
+		self
+	}
+
 	/// Creates a new `TxMemPool` instance for testing purposes.
 	#[cfg(test)]
-	fn new_test(
+	pub(super) fn new_test(
 		api: Arc<ChainApi>,
 		max_transactions_count: usize,
 		max_transactions_total_bytes: usize,
@@ -282,6 +318,8 @@ where
 			metrics: Default::default(),
 			max_transactions_count,
 			max_transactions_total_bytes,
+			sender_limit: None,
+			senders: Default::default(),
 		}
 	}
 
@@ -423,7 +461,9 @@ where
 		let source = new_tx.source();
 		transactions.insert(hash, Arc::from(new_tx));
 		for worst_hash in &to_be_removed {
-			transactions.remove(worst_hash);
+			if let Some(tx) = transactions.remove(worst_hash) {
+				self.release_sender(&tx);
+			}
 		}
 		debug_assert!(!self.is_limit_exceeded(transactions.len(), self.transactions.bytes()));
 
@@ -461,6 +501,32 @@ where
 		self.try_insert(hash, TxInMemPool::new_watched(source, xt.clone(), length))
 	}
 
+	/// Adds transactions restored from the mempool journal as unwatched transactions, not
+	/// exceeding the limit.
+	///
+	/// The original source, priority and insertion time of the transactions are preserved.
+	/// Returns the number of inserted transactions.
+	pub(super) fn extend_restored(
+		&self,
+		xts: impl IntoIterator<Item = JournaledTransaction<ExtrinsicFor<ChainApi>>>,
+	) -> usize {
+		xts.into_iter()
+			.map(|restored| {
+				let (hash, length) = self.api.hash_and_length(&restored.xt);
+				let mut tx = TxInMemPool::new_with_optional_priority(
+					false,
+					restored.source,
+					restored.xt,
+					length,
+					restored.priority,
+				);
+				tx.source.timestamp = Instant::now().checked_sub(restored.age);
+				self.try_insert(hash, tx)
+			})
+			.filter(Result::is_ok)
+			.count()
+	}
+
 	/// Clones and returns a `HashMap` of references to all transactions in the memory pool.
 	pub(super) fn clone_transactions(
 		&self,
@@ -473,17 +539,85 @@ where
 		log_xt_trace!(target: LOG_TARGET, tx_hashes, "mempool::remove_transaction");
 		let mut transactions = self.transactions.write();
 		for tx_hash in tx_hashes {
-			transactions.remove(tx_hash);
+			if let Some(tx) = transactions.remove(tx_hash) {
+				self.release_sender(&tx);
+			}
+		}
+	}
+
+	/// Accounts the transaction to its sender reported by the view store submission outcome.
+	///
+	/// Views only see the transactions valid at their blocks, so the per-sender limit is also
+	/// enforced across all the transactions of the memory pool. Transactions replaced by the
+	/// submitted one are not accounted.
+	///
+	/// Returns [`SenderLimitExceeded`][sc_transaction_pool_api::error::Error::SenderLimitExceeded]
+	/// if the sender would exceed the limit, the transaction shall be removed from the pool then.
+	pub(super) fn ensure_sender_limit(
+		&self,
+		outcome: &ViewStoreSubmitOutcome<ChainApi>,
+	) -> Result<(), sc_transaction_pool_api::error::Error> {
+		let (Some(sender_limit), Some(sender)) = (&self.sender_limit, outcome.sender()) else {
+			return Ok(())
+		};
+		let transactions = self.transactions.read();
+		let Some(tx) = transactions.get(&outcome.hash()) else { return Ok(()) };
+		let mut tx_sender = tx.sender.write();
+		if tx_sender.is_some() {
+			return Ok(())
+		}
+
+		let mut senders = self.senders.lock();
+		let usage = senders.get(sender).copied().unwrap_or_default();
+		let replaced = outcome
+			.replaced()
+			.iter()
+			.filter_map(|hash| transactions.get(hash))
+			.filter(|other| other.sender.read().as_deref() == Some(sender))
+			.fold(SenderUsage::default(), |replaced, other| replaced.add(other.bytes));
+		let count = (usage.count + 1).saturating_sub(replaced.count);
+		let bytes = (usage.bytes + tx.bytes).saturating_sub(replaced.bytes);
+
+		if sender_limit.limit.is_exceeded(count, bytes) {
+			trace!(
+				target: LOG_TARGET,
+				tx_hash = ?outcome.hash(),
+				count,
+				bytes,
+				"mempool::ensure_sender_limit exceeded"
+			);
+			return Err(sc_transaction_pool_api::error::Error::SenderLimitExceeded)
+		}
+
+		senders.insert(sender.to_vec(), usage.add(tx.bytes));
+		*tx_sender = Some(sender.to_vec());
+		Ok(())
+	}
+
+	/// Removes the transaction leaving the memory pool from the account of its sender.
+	fn release_sender(&self, tx: &TxInMemPool<ChainApi, Block>) {
+		let Some(sender) = tx.sender.read().clone() else { return };
+		let mut senders = self.senders.lock();
+		if let Some(usage) = senders.get_mut(&sender) {
+			*usage = usage.sub(tx.bytes);
+			if usage.count == 0 {
+				senders.remove(&sender);
+			}
 		}
 	}
 
-	/// Revalidates a batch of transactions against the provided finalized block.
+	/// Revalidates transactions selected by `mode` against the provided block.
 	///
 	/// Returns a vector of invalid transaction hashes.
-	async fn revalidate_inner(&self, finalized_block: HashAndNumber<Block>) -> Vec<Block::Hash> {
+	async fn revalidate_inner(
+		&self,
+		finalized_block: HashAndNumber<Block>,
+		mode: MempoolRevalidation,
+	) -> Vec<Block::Hash> {
 		trace!(
 			target: LOG_TARGET,
 			?finalized_block,
+			?mode,
 			"mempool::revalidate_inner"
 		);
 		let start = Instant::now();
@@ -491,18 +625,26 @@ where
 		let (count, input) = {
 			let transactions = self.transactions.clone_map();
 
+			let block_number = finalized_block.number.into().as_u64();
+			let batch_size = match mode {
+				MempoolRevalidation::Periodic => TXMEMPOOL_MAX_REVALIDATION_BATCH_SIZE,
+				MempoolRevalidation::Unvalidated => usize::MAX,
+			};
+
 			(
 				transactions.len(),
 				transactions
 					.into_iter()
 					.filter(|xt| {
-						let finalized_block_number = finalized_block.number.into().as_u64();
-						xt.1.validated_at.load(atomic::Ordering::Relaxed) +
-							TXMEMPOOL_REVALIDATION_PERIOD <
-							finalized_block_number
+						let validated_at = xt.1.validated_at.load(atomic::Ordering::Relaxed);
+						match mode {
+							MempoolRevalidation::Periodic =>
+								validated_at + TXMEMPOOL_REVALIDATION_PERIOD < block_number,
+							MempoolRevalidation::Unvalidated => validated_at == 0,
+						}
 					})
 					.sorted_by_key(|tx| tx.1.validated_at.load(atomic::Ordering::Relaxed))
-					.take(TXMEMPOOL_MAX_REVALIDATION_BATCH_SIZE),
+					.take(batch_size),
 			)
 		};
 
@@ -565,18 +707,23 @@ where
 		log_xt_trace!(target: LOG_TARGET, finalized_xts, "purged finalized transactions");
 		let mut transactions = self.transactions.write();
 		finalized_xts.iter().for_each(|t| {
-			transactions.remove(t);
+			if let Some(tx) = transactions.remove(t) {
+				self.release_sender(&tx);
+			}
 		});
 	}
 
 	/// Revalidates transactions in the memory pool against a given finalized block and removes
 	/// invalid ones.
+	///
+	/// The transactions which are revalidated are selected by `mode`.
 	pub(super) async fn revalidate(
 		&self,
 		view_store: Arc<ViewStore<ChainApi, Block>>,
 		finalized_block: HashAndNumber<Block>,
+		mode: MempoolRevalidation,
 	) {
-		let revalidated_invalid_hashes = self.revalidate_inner(finalized_block.clone()).await;
+		let revalidated_invalid_hashes = self.revalidate_inner(finalized_block.clone(), mode).await;
 
 		let mut invalid_hashes_subtrees =
 			revalidated_invalid_hashes.clone().into_iter().collect::<HashSet<_>>();
@@ -592,7 +739,9 @@ where
 		{
 			let mut transactions = self.transactions.write();
 			invalid_hashes_subtrees.iter().for_each(|tx_hash| {
-				transactions.remove(&tx_hash);
+				if let Some(tx) = transactions.remove(&tx_hash) {
+					self.release_sender(&tx);
+				}
 			});
 		};
 
@@ -639,6 +788,18 @@ where
 	}
 }
 
+impl<ChainApi, Block> TxMemPool<ChainApi, Block>
+where
+	Block: BlockT,
+	ChainApi: graph::ChainApi<Block = Block> + 'static,
+{
+	/// Returns references to all transactions in the memory pool, intended to be flushed to the
+	/// mempool journal.
+	pub(super) fn journal_snapshot(&self) -> Vec<Arc<TxInMemPool<ChainApi, Block>>> {
+		self.transactions.read().values().cloned().collect()
+	}
+}
+
 #[cfg(test)]
 mod tx_mem_pool_tests {
 	use substrate_test_runtime::{AccountId, Extrinsic, ExtrinsicBuilder, Transfer, H256};
@@ -993,4 +1154,52 @@ mod tx_mem_pool_tests {
 			sc_transaction_pool_api::error::Error::ImmediatelyDropped
 		));
 	}
+
+	#[test]
+	fn sender_limit_is_enforced() {
+		sp_tracing::try_init_simple();
+		let api = Arc::from(TestApi::default());
+		let sender_limit = SenderLimit {
+			limit: graph::base_pool::Limit { count: 2, total_bytes: usize::MAX },
+			nonce_len: 1,
+		};
+		let mempool = TxMemPool::new_test(api.clone(), usize::MAX, usize::MAX)
+			.with_sender_limit(Some(sender_limit));
+
+		let xts = (0..4).map(|x| Arc::from(uxt(x))).collect::<Vec<_>>();
+		let hashes = xts.iter().map(|xt| api.hash_and_length(xt).0).collect::<Vec<_>>();
+		let results = mempool.extend_unwatched(TransactionSource::External, &xts);
+		assert!(results.iter().all(Result::is_ok));
+
+		let sender = b"alice".to_vec();
+		let outcome = |i: usize, replaced: &[usize]| {
+			ViewStoreSubmitOutcome::<TestApi>::new(hashes[i], None)
+				.with_sender(Some(sender.clone()), replaced.iter().map(|r| hashes[*r]).collect())
+		};
+		let usage = || mempool.senders.lock().get(&sender).map(|usage| usage.count);
+
+		assert!(mempool.ensure_sender_limit(&outcome(0, &[])).is_ok());
+		assert!(mempool.ensure_sender_limit(&outcome(1, &[])).is_ok());
+		// Already accounted transactions are not accounted twice.
+		assert!(mempool.ensure_sender_limit(&outcome(1, &[])).is_ok());
+		assert_eq!(usage(), Some(2));
+
+		assert!(matches!(
+			mempool.ensure_sender_limit(&outcome(2, &[])).unwrap_err(),
+			sc_transaction_pool_api::error::Error::SenderLimitExceeded
+		));
+
+		// The replaced transaction leaves room for the replacement.
+		assert!(mempool.ensure_sender_limit(&outcome(2, &[1])).is_ok());
+		assert_eq!(usage(), Some(3));
+		mempool.remove_transactions(&[hashes[1]]);
+		assert_eq!(usage(), Some(2));
+
+		mempool.remove_transactions(&[hashes[0]]);
+		assert!(mempool.ensure_sender_limit(&outcome(3, &[])).is_ok());
+		assert_eq!(usage(), Some(2));
+
+		mempool.remove_transactions(&[hashes[2], hashes[3]]);
+		assert_eq!(usage(), None);
+	}
 }
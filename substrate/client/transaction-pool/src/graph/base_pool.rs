@@ -20,7 +20,13 @@
 //!
 //! For a more full-featured pool, have a look at the `pool` module.
 
-use std::{cmp::Ordering, collections::HashSet, fmt, hash, sync::Arc, time::Instant};
+use std::{
+	cmp::Ordering,
+	collections::{HashMap, HashSet},
+	fmt, hash,
+	sync::Arc,
+	time::Instant,
+};
 
 use crate::LOG_TARGET;
 use sc_transaction_pool_api::{error, InPoolTransaction, PoolStatus};
@@ -248,6 +254,11 @@ const RECENTLY_PRUNED_TAGS: usize = 2;
 #[derive(Clone, Debug)]
 pub struct BasePool<Hash: hash::Hash + Eq, Ex> {
 	reject_future_transactions: bool,
+	sender_limit: Option<SenderLimit>,
+	/// Number and total size of the ready and future transactions of each sender.
+	///
+	/// Only maintained when the sender limit is set.
+	senders: HashMap<Vec<u8>, SenderUsage>,
 	future: FutureTransactions<Hash, Ex>,
 	ready: ReadyTransactions<Hash, Ex>,
 	/// Store recently pruned tags (for last two invocations).
@@ -269,6 +280,8 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 	pub fn new(reject_future_transactions: bool) -> Self {
 		Self {
 			reject_future_transactions,
+			sender_limit: None,
+			senders: Default::default(),
 			future: Default::default(),
 			ready: Default::default(),
 			recently_pruned: Default::default(),
@@ -276,6 +289,29 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		}
 	}
 
+	/// Sets the limits applied to the transactions of a single sender.
+	pub fn set_sender_limit(&mut self, sender_limit: Option<SenderLimit>) {
+		self.sender_limit = sender_limit;
+		self.senders.clear();
+
+		let Some(sender_limit) = &self.sender_limit else { return };
+		let ready = self.ready.fold(Vec::new(), |mut ready, current| {
+			ready.push(current.transaction.transaction.clone());
+			ready
+		});
+		for tx in ready.iter().map(|tx| &**tx).chain(self.future.all()) {
+			if let Some(sender) = sender_limit.sender_of(tx) {
+				let usage = self.senders.entry(sender.to_vec()).or_default();
+				*usage = usage.add(tx.bytes);
+			}
+		}
+	}
+
+	/// Sets the minimal priority bump (in percent) required to replace ready transactions.
+	pub fn set_replacement_bump(&mut self, percent: u64) {
+		self.ready.set_replacement_bump(percent);
+	}
+
 	/// Clears buffer keeping recently pruned transaction.
 	pub fn clear_recently_pruned(&mut self) {
 		self.recently_pruned = Default::default();
@@ -315,6 +351,8 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 			return Err(error::Error::AlreadyImported(Box::new(tx.hash)))
 		}
 
+		self.ensure_sender_limit(&tx)?;
+
 		let tx = WaitingTransaction::new(tx, self.ready.provided_tags(), &self.recently_pruned);
 		trace!(
 			target: LOG_TARGET,
@@ -331,6 +369,7 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 			}
 
 			let hash = tx.transaction.hash.clone();
+			self.add_to_sender(&tx.transaction);
 			self.future.import(tx);
 			return Ok(Imported::Future { hash })
 		}
@@ -338,6 +377,70 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		self.import_to_ready(tx)
 	}
 
+	/// Makes sure that importing given transaction keeps its sender within the per-sender limit.
+	///
+	/// Ready transactions that would be replaced by the imported one (i.e. the ones providing any
+	/// of its tags) are not accounted.
+	fn ensure_sender_limit(&self, tx: &Transaction<Hash, Ex>) -> error::Result<()> {
+		let Some(sender_limit) = &self.sender_limit else { return Ok(()) };
+		let Some(sender) = sender_limit.sender_of(tx) else { return Ok(()) };
+
+		let usage = self.senders.get(sender).copied().unwrap_or_default();
+		let replaced = tx
+			.provides
+			.iter()
+			.filter_map(|tag| self.ready.provided_tags().get(tag))
+			.collect::<HashSet<_>>()
+			.into_iter()
+			.filter_map(|hash| self.ready.by_hash(hash))
+			.filter(|other| sender_limit.sender_of(&**other) == Some(sender))
+			.fold(SenderUsage::default(), |replaced, other| replaced.add(other.bytes));
+		let count = (usage.count + 1).saturating_sub(replaced.count);
+		let bytes = (usage.bytes + tx.bytes).saturating_sub(replaced.bytes);
+
+		if sender_limit.limit.is_exceeded(count, bytes) {
+			trace!(
+				target: LOG_TARGET,
+				tx_hash = ?tx.hash,
+				sender = %HexDisplay::from(&sender),
+				count,
+				bytes,
+				"Sender limit exceeded"
+			);
+			return Err(error::Error::SenderLimitExceeded)
+		}
+
+		Ok(())
+	}
+
+	/// Accounts the transaction entering the pool to its sender.
+	fn add_to_sender(&mut self, tx: &Transaction<Hash, Ex>) {
+		let Some(sender_limit) = &self.sender_limit else { return };
+		let Some(sender) = sender_limit.sender_of(tx) else { return };
+
+		let usage = self.senders.entry(sender.to_vec()).or_default();
+		*usage = usage.add(tx.bytes);
+	}
+
+	/// Removes the transactions leaving the pool from the accounts of their senders.
+	fn remove_from_senders<'a>(&mut self, txs: impl IntoIterator<Item = &'a Transaction<Hash, Ex>>)
+	where
+		Hash: 'a,
+		Ex: 'a,
+	{
+		let Some(sender_limit) = &self.sender_limit else { return };
+
+		for tx in txs {
+			let Some(sender) = sender_limit.sender_of(tx) else { continue };
+			if let Some(usage) = self.senders.get_mut(sender) {
+				*usage = usage.sub(tx.bytes);
+				if usage.count == 0 {
+					self.senders.remove(sender);
+				}
+			}
+		}
+	}
+
 	/// Imports transaction to ready queue.
 	///
 	/// NOTE the transaction has to have all requirements satisfied.
@@ -356,13 +459,17 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		// take first transaction from the list
 		while let Some(tx) = to_import.pop() {
 			// find transactions in Future that it unlocks
-			to_import.append(&mut self.future.satisfy_tags(&tx.transaction.provides));
+			let mut unlocked = self.future.satisfy_tags(&tx.transaction.provides);
+			self.remove_from_senders(unlocked.iter().map(|tx| &*tx.transaction));
+			to_import.append(&mut unlocked);
 
 			// import this transaction
 			let current_hash = tx.transaction.hash.clone();
 			let current_tx = tx.transaction.clone();
 			match self.ready.import(tx) {
 				Ok(mut replaced) => {
+					self.add_to_sender(&current_tx);
+					self.remove_from_senders(replaced.iter().map(|tx| &**tx));
 					if !first {
 						promoted.push(current_hash.clone());
 					}
@@ -373,7 +480,10 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 					// re-import them.
 					removed.append(&mut replaced);
 				},
-				Err(error @ error::Error::TooLowPriority { .. }) => {
+				Err(
+					error @ (error::Error::TooLowPriority { .. } |
+					error::Error::TooLowPriorityBump { .. }),
+				) => {
 					trace!(
 						target: LOG_TARGET,
 						tx_hash = ?current_tx.hash,
@@ -415,7 +525,8 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		if removed.iter().any(|tx| tx.hash == tx_hash) {
 			// We still need to remove all transactions that we promoted
 			// since they depend on each other and will never get to the best iterator.
-			self.ready.remove_subtree(&promoted);
+			let removed = self.ready.remove_subtree(&promoted);
+			self.remove_from_senders(removed.iter().map(|tx| &**tx));
 
 			trace!(
 				target: LOG_TARGET,
@@ -545,12 +656,15 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 	pub fn remove_subtree(&mut self, hashes: &[Hash]) -> Vec<Arc<Transaction<Hash, Ex>>> {
 		let mut removed = self.ready.remove_subtree(hashes);
 		removed.extend(self.future.remove(hashes));
+		self.remove_from_senders(removed.iter().map(|tx| &**tx));
 		removed
 	}
 
 	/// Removes and returns all transactions from the future queue.
 	pub fn clear_future(&mut self) -> Vec<Arc<Transaction<Hash, Ex>>> {
-		self.future.clear()
+		let removed = self.future.clear();
+		self.remove_from_senders(removed.iter().map(|tx| &**tx));
+		removed
 	}
 
 	/// Prunes transactions that provide given list of tags.
@@ -578,6 +692,14 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 			recently_pruned.insert(tag);
 		}
 
+		self.remove_from_senders(
+			futures_removed
+				.iter()
+				.chain(&pruned)
+				.map(|tx| &**tx)
+				.chain(to_import.iter().map(|tx| &*tx.transaction)),
+		);
+
 		let mut promoted = vec![];
 		let mut failed = vec![];
 		for tx in futures_removed {
@@ -630,6 +752,66 @@ impl Limit {
 	}
 }
 
+/// Limits of the transactions of a single sender.
+///
+/// The pool has no notion of accounts, so the sender of a transaction is derived from its first
+/// `provides` tag with the trailing `nonce_len` bytes stripped. This matches the
+/// `(AccountId, Nonce)` tags provided by the FRAME `CheckNonce` transaction extension. Only
+/// external transactions are subject to the limits.
+#[derive(Debug, Clone)]
+pub struct SenderLimit {
+	/// Maximal number and size of ready and future transactions of a single sender.
+	pub limit: Limit,
+	/// Length of the encoded nonce at the end of the first `provides` tag.
+	pub nonce_len: usize,
+}
+
+impl SenderLimit {
+	/// Returns the sender of the transaction or `None` if it is not subject to the limits.
+	fn sender_of<'a, Hash, Ex>(&self, tx: &'a Transaction<Hash, Ex>) -> Option<&'a [u8]> {
+		self.sender(tx.source.source, &tx.provides)
+	}
+
+	/// Returns the sender of a transaction with given source and `provides` tags or `None` if it
+	/// is not subject to the limits.
+	pub(crate) fn sender<'a>(
+		&self,
+		source: TransactionSource,
+		provides: &'a [Tag],
+	) -> Option<&'a [u8]> {
+		if source != TransactionSource::External {
+			return None
+		}
+
+		let tag = provides.first()?;
+		match tag.len().checked_sub(self.nonce_len) {
+			Some(len) if len > 0 => Some(&tag[..len]),
+			_ => None,
+		}
+	}
+}
+
+/// Number and total size of the transactions of a single sender.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct SenderUsage {
+	/// Number of transactions.
+	pub count: usize,
+	/// Total size of the transactions.
+	pub bytes: usize,
+}
+
+impl SenderUsage {
+	/// Returns the usage with a transaction of given size added.
+	pub(crate) fn add(self, bytes: usize) -> Self {
+		Self { count: self.count + 1, bytes: self.bytes + bytes }
+	}
+
+	/// Returns the usage with a transaction of given size removed.
+	pub(crate) fn sub(self, bytes: usize) -> Self {
+		Self { count: self.count.saturating_sub(1), bytes: self.bytes.saturating_sub(bytes) }
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -1226,6 +1408,91 @@ source: TimedTransactionSource { source: TransactionSource::External, timestamp:
 		}
 	}
 
+	fn sender_limited_pool() -> BasePool<Hash, Vec<u8>> {
+		let mut pool = pool();
+		pool.set_sender_limit(Some(SenderLimit {
+			limit: Limit { count: 2, total_bytes: usize::MAX },
+			nonce_len: 1,
+		}));
+		pool
+	}
+
+	fn sender_tx(sender: u8, nonce: u8) -> Transaction<Hash, Vec<u8>> {
+		Transaction {
+			data: vec![sender, nonce].into(),
+			hash: (sender as u64) << 8 | nonce as u64,
+			requires: if nonce > 0 { vec![vec![sender, nonce - 1]] } else { vec![] },
+			provides: vec![vec![sender, nonce]],
+			..default_tx().clone()
+		}
+	}
+
+	fn sender_count(pool: &BasePool<Hash, Vec<u8>>, sender: u8) -> usize {
+		pool.senders.get(&vec![sender]).map_or(0, |usage| usage.count)
+	}
+
+	#[test]
+	fn should_enforce_sender_limit() {
+		// given
+		let mut pool = sender_limited_pool();
+
+		// when
+		pool.import(sender_tx(1, 0)).unwrap();
+		pool.import(sender_tx(1, 1)).unwrap();
+		let err = pool.import(sender_tx(1, 2)).unwrap_err();
+
+		// then
+		assert!(matches!(err, error::Error::SenderLimitExceeded));
+		// replacements are not accounted
+		pool.import(Transaction { hash: 0xaa, priority: 10, ..sender_tx(1, 1) })
+			.unwrap();
+		// other senders are not affected
+		pool.import(sender_tx(2, 0)).unwrap();
+		// local transactions are not affected
+		pool.import(Transaction {
+			source: TimedTransactionSource::new_local(false),
+			..sender_tx(1, 2)
+		})
+		.unwrap();
+		assert_eq!(pool.ready().count(), 4);
+		assert_eq!(sender_count(&pool, 1), 2);
+		assert_eq!(sender_count(&pool, 2), 1);
+	}
+
+	#[test]
+	fn should_track_sender_usage() {
+		// given
+		let mut pool = sender_limited_pool();
+
+		// when
+		pool.import(sender_tx(1, 1)).unwrap();
+		assert_eq!(sender_count(&pool, 1), 1);
+		pool.import(sender_tx(1, 0)).unwrap();
+
+		// then
+		// the promotion of the future transaction doesn't change the usage
+		assert_eq!(pool.ready().count(), 2);
+		assert_eq!(sender_count(&pool, 1), 2);
+		assert!(matches!(pool.import(sender_tx(1, 2)), Err(error::Error::SenderLimitExceeded)));
+
+		// pruned transactions are released
+		pool.prune_tags(vec![vec![1, 0]]);
+		assert_eq!(sender_count(&pool, 1), 1);
+		pool.import(sender_tx(1, 2)).unwrap();
+		assert_eq!(sender_count(&pool, 1), 2);
+
+		// removed transactions are released
+		pool.remove_subtree(&[sender_tx(1, 1).hash]);
+		assert_eq!(sender_count(&pool, 1), 0);
+		assert!(pool.senders.is_empty());
+
+		// cleared future transactions are released
+		pool.import(sender_tx(1, 5)).unwrap();
+		assert_eq!(sender_count(&pool, 1), 1);
+		pool.clear_future();
+		assert!(pool.senders.is_empty());
+	}
+
 	#[test]
 	fn should_clear_future_queue() {
 		// given
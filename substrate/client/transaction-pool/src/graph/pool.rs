@@ -146,6 +146,11 @@ pub struct Options {
 	pub reject_future_transactions: bool,
 	/// How long the extrinsic is banned for.
 	pub ban_time: Duration,
+	/// Limits of the transactions of a single sender.
+	pub sender_limit: Option<base::SenderLimit>,
+	/// Minimal priority bump (in percent) required to replace transactions providing the same
+	/// tags.
+	pub replacement_bump: u64,
 }
 
 impl Default for Options {
@@ -155,6 +160,8 @@ impl Default for Options {
 			future: base::Limit { count: 512, total_bytes: 1 * 1024 * 1024 },
 			reject_future_transactions: false,
 			ban_time: Duration::from_secs(60 * 30),
+			sender_limit: None,
+			replacement_bump: 0,
 		}
 	}
 }
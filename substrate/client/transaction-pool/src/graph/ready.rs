@@ -26,7 +26,10 @@ use std::{
 use crate::LOG_TARGET;
 use sc_transaction_pool_api::error;
 use serde::Serialize;
-use sp_runtime::{traits::Member, transaction_validity::TransactionTag as Tag};
+use sp_runtime::{
+	traits::Member,
+	transaction_validity::{TransactionPriority as Priority, TransactionTag as Tag},
+};
 use tracing::trace;
 
 use super::{
@@ -118,6 +121,9 @@ pub struct ReadyTransactions<Hash: hash::Hash + Eq, Ex> {
 	/// Best transactions that are ready to be included to the block without any other previous
 	/// transaction.
 	best: BTreeSet<TransactionRef<Hash, Ex>>,
+	/// Minimal priority bump (in percent) required to replace transactions providing the same
+	/// tags.
+	replacement_bump: u64,
 }
 
 impl<Hash, Ex> tracked_map::Size for ReadyTx<Hash, Ex> {
@@ -133,11 +139,21 @@ impl<Hash: hash::Hash + Eq, Ex> Default for ReadyTransactions<Hash, Ex> {
 			provided_tags: Default::default(),
 			ready: Default::default(),
 			best: Default::default(),
+			replacement_bump: 0,
 		}
 	}
 }
 
 impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
+	/// Sets the minimal priority bump (in percent) required to replace transactions providing
+	/// the same tags.
+	///
+	/// With the default of `0` any transaction with a strictly higher priority is a valid
+	/// replacement.
+	pub fn set_replacement_bump(&mut self, percent: u64) {
+		self.replacement_bump = percent;
+	}
+
 	/// Borrows a map of tags that are provided by transactions in this queue.
 	pub fn provided_tags(&self) -> &HashMap<Tag, Hash> {
 		&self.provided_tags
@@ -412,7 +428,8 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 	///
 	/// In case that's true it determines if the priority of transactions that
 	/// we are about to replace is lower than the priority of the replacement transaction.
-	/// We remove/replace old transactions in case they have lower priority.
+	/// We remove/replace old transactions in case they have lower priority and the
+	/// replacement bumps it by at least the configured percentage.
 	///
 	/// In case replacement is successful returns a list of removed transactions
 	/// and a list of hashes that are still in pool and gets unlocked by the new transaction.
@@ -449,6 +466,16 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 				return Err(error::Error::TooLowPriority { old: old_priority, new: tx.priority })
 			}
 
+			// bail - the transaction does not bump the priority enough
+			let required = required_priority(old_priority, self.replacement_bump);
+			if tx.priority < required {
+				return Err(error::Error::TooLowPriorityBump {
+					old: old_priority,
+					new: tx.priority,
+					required,
+				})
+			}
+
 			// construct a list of unlocked transactions
 			let unlocks = {
 				let ready = self.ready.read();
@@ -578,6 +605,12 @@ impl<Hash: hash::Hash + Member, Ex> Iterator for BestIterator<Hash, Ex> {
 	}
 }
 
+/// Minimal priority of a transaction replacing others with a total priority of `old`.
+fn required_priority(old: Priority, bump_percent: u64) -> Priority {
+	let required = old as u128 * (100 + bump_percent as u128) / 100;
+	required.try_into().unwrap_or(Priority::MAX)
+}
+
 // See: https://github.com/rust-lang/rust/issues/40062
 fn remove_item<T: PartialEq>(vec: &mut Vec<T>, item: &T) {
 	if let Some(idx) = vec.iter().position(|i| i == item) {
@@ -639,6 +672,36 @@ mod tests {
 		assert_eq!(ready.get().count(), 1);
 	}
 
+	#[test]
+	fn should_require_priority_bump_to_replace_transaction() {
+		// given
+		let mut ready = ReadyTransactions::default();
+		ready.set_replacement_bump(10);
+		let mut tx1 = tx(1);
+		tx1.requires.clear();
+		tx1.priority = 100;
+		let mut tx2 = tx(2);
+		tx2.requires.clear();
+		tx2.priority = 109;
+		let mut tx3 = tx(3);
+		tx3.requires.clear();
+		tx3.priority = 110;
+		import(&mut ready, tx1).unwrap();
+
+		// when
+		let err = import(&mut ready, tx2).unwrap_err();
+		let replaced = import(&mut ready, tx3).unwrap();
+
+		// then
+		assert!(matches!(
+			err,
+			error::Error::TooLowPriorityBump { old: 100, new: 109, required: 110 }
+		));
+		assert_eq!(replaced.len(), 1);
+		assert_eq!(replaced[0].hash, 1);
+		assert_eq!(ready.get().map(|tx| tx.hash).collect::<Vec<_>>(), vec![3]);
+	}
+
 	#[test]
 	fn should_replace_multiple_transactions_correctly() {
 		// given
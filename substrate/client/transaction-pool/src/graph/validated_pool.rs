@@ -121,6 +121,12 @@ pub struct BaseSubmitOutcome<B: ChainApi, W> {
 
 	/// The priority of the transaction. Defaults to None if unknown.
 	priority: Option<TransactionPriority>,
+
+	/// The sender of the transaction if it is subject to the per-sender limit.
+	sender: Option<Vec<u8>>,
+
+	/// The transactions replaced by the submitted one.
+	replaced: Vec<ExtrinsicHash<B>>,
 }
 
 /// Type alias to outcome of submission to `ValidatedPool`.
@@ -130,7 +136,14 @@ pub type ValidatedPoolSubmitOutcome<B> =
 impl<B: ChainApi, W> BaseSubmitOutcome<B, W> {
 	/// Creates a new instance with given hash and priority.
 	pub fn new(hash: ExtrinsicHash<B>, priority: Option<TransactionPriority>) -> Self {
-		Self { hash, priority, watcher: None }
+		Self { hash, priority, watcher: None, sender: None, replaced: Vec::new() }
+	}
+
+	/// Sets the sender of the transaction and the transactions it replaced.
+	pub fn with_sender(mut self, sender: Option<Vec<u8>>, replaced: Vec<ExtrinsicHash<B>>) -> Self {
+		self.sender = sender;
+		self.replaced = replaced;
+		self
 	}
 
 	/// Sets the transaction watcher.
@@ -149,6 +162,16 @@ impl<B: ChainApi, W> BaseSubmitOutcome<B, W> {
 		self.hash
 	}
 
+	/// Provides the sender of submitted transaction, if it is subject to the per-sender limit.
+	pub fn sender(&self) -> Option<&[u8]> {
+		self.sender.as_deref()
+	}
+
+	/// Provides hashes of the transactions replaced by submitted transaction.
+	pub fn replaced(&self) -> &[ExtrinsicHash<B>] {
+		&self.replaced
+	}
+
 	/// Provides a watcher. Should only be called on outcomes of `submit_and_watch`. Otherwise will
 	/// panic (that would mean logical error in program).
 	pub fn expect_watcher(&mut self) -> W {
@@ -239,7 +262,9 @@ impl<B: ChainApi, L: EventHandler<B>> ValidatedPool<B, L> {
 		rotator: PoolRotator<ExtrinsicHash<B>>,
 		event_handler: Option<L>,
 	) -> Self {
-		let base_pool = base::BasePool::new(options.reject_future_transactions);
+		let mut base_pool = base::BasePool::new(options.reject_future_transactions);
+		base_pool.set_sender_limit(options.sender_limit.clone());
+		base_pool.set_replacement_bump(options.replacement_bump);
 		Self {
 			is_validator,
 			options,
@@ -315,6 +340,12 @@ impl<B: ChainApi, L: EventHandler<B>> ValidatedPool<B, L> {
 		match tx {
 			ValidatedTransaction::Valid(tx) => {
 				let priority = tx.priority;
+				let sender = self
+					.options
+					.sender_limit
+					.as_ref()
+					.and_then(|sender_limit| sender_limit.sender(tx.source.source, &tx.provides))
+					.map(<[u8]>::to_vec);
 				trace!(
 					target: LOG_TARGET,
 					tx_hash = ?tx.hash,
@@ -325,6 +356,11 @@ impl<B: ChainApi, L: EventHandler<B>> ValidatedPool<B, L> {
 				}
 
 				let imported = self.pool.write().import(tx)?;
+				let replaced = match imported {
+					base::Imported::Ready { ref removed, .. } =>
+						removed.iter().map(|tx| tx.hash).collect(),
+					base::Imported::Future { .. } => Vec::new(),
+				};
 
 				if let base::Imported::Ready { ref hash, .. } = imported {
 					let sinks = &mut self.import_notification_sinks.lock();
@@ -346,7 +382,8 @@ impl<B: ChainApi, L: EventHandler<B>> ValidatedPool<B, L> {
 
 				let mut event_dispatcher = self.event_dispatcher.write();
 				fire_events(&mut *event_dispatcher, &imported);
-				Ok(ValidatedPoolSubmitOutcome::new(*imported.hash(), Some(priority)))
+				Ok(ValidatedPoolSubmitOutcome::new(*imported.hash(), Some(priority))
+					.with_sender(sender, replaced))
 			},
 			ValidatedTransaction::Invalid(tx_hash, error) => {
 				trace!(
@@ -18,7 +18,7 @@
 
 //! Tests for fork-aware transaction pool.
 
-use sc_transaction_pool::{ChainApi, PoolLimit};
+use sc_transaction_pool::{ChainApi, PoolLimit, SenderLimit};
 use sc_transaction_pool_api::ChainEvent;
 use sp_runtime::transaction_validity::TransactionSource;
 use std::sync::Arc;
@@ -69,6 +69,7 @@ pub struct TestPoolBuilder {
 	use_default_limits: bool,
 	ready_limits: sc_transaction_pool::PoolLimit,
 	future_limits: sc_transaction_pool::PoolLimit,
+	sender_limit: Option<SenderLimit>,
 	mempool_max_transactions_count: usize,
 	finality_timeout_threshold: Option<usize>,
 }
@@ -80,6 +81,7 @@ impl Default for TestPoolBuilder {
 			use_default_limits: true,
 			ready_limits: PoolLimit { count: 8192, total_bytes: 20 * 1024 * 1024 },
 			future_limits: PoolLimit { count: 512, total_bytes: 1 * 1024 * 1024 },
+			sender_limit: None,
 			mempool_max_transactions_count: usize::MAX,
 			finality_timeout_threshold: None,
 		}
@@ -126,6 +128,12 @@ impl TestPoolBuilder {
 		self
 	}
 
+	pub fn with_sender_limit(mut self, sender_limit: SenderLimit) -> Self {
+		self.sender_limit = Some(sender_limit);
+		self.use_default_limits = false;
+		self
+	}
+
 	pub fn with_finality_timeout_threshold(mut self, threshold: usize) -> Self {
 		self.finality_timeout_threshold = Some(threshold);
 		self
@@ -160,6 +168,7 @@ impl TestPoolBuilder {
 				genesis_hash,
 				self.ready_limits,
 				self.future_limits,
+				self.sender_limit,
 				self.mempool_max_transactions_count,
 				self.finality_timeout_threshold,
 			)
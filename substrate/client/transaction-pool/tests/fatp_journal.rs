@@ -0,0 +1,104 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests of the mempool journal of the fork-aware transaction pool.
+
+pub mod fatp_common;
+
+use fatp_common::{new_best_block_event, LOG_TARGET, SOURCE};
+use futures::{executor::block_on, FutureExt};
+use sc_transaction_pool::{ForkAwareTxPool, MempoolJournalOptions, Options};
+use sc_transaction_pool_api::{MaintainedTransactionPool, TransactionPool};
+use sp_core::testing::TaskExecutor;
+use std::{
+	path::Path,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+use substrate_test_runtime_client::{
+	runtime::{Block, Hash},
+	Sr25519Keyring::*,
+};
+use substrate_test_runtime_transaction_pool::{uxt, TestApi};
+
+fn journaled_pool(
+	api: Arc<TestApi>,
+	best_block_hash: Hash,
+	journal: &Path,
+) -> ForkAwareTxPool<TestApi, Block> {
+	ForkAwareTxPool::new_with_background_worker(
+		Options::default(),
+		true.into(),
+		api,
+		None,
+		TaskExecutor::new(),
+		best_block_hash,
+		best_block_hash,
+		Some(MempoolJournalOptions::new(journal)),
+	)
+}
+
+/// Waits for the background worker to revalidate the restored transactions.
+fn wait_for_mempool_len(pool: &ForkAwareTxPool<TestApi, Block>, expected: usize) {
+	let deadline = Instant::now() + Duration::from_secs(10);
+	while pool.mempool_len().0 != expected {
+		assert!(Instant::now() < deadline, "mempool_len: {:?}", pool.mempool_len());
+		std::thread::sleep(Duration::from_millis(10));
+	}
+}
+
+#[test]
+fn fatp_journal_restart_drops_stale_and_invalid() {
+	sp_tracing::try_init_simple();
+
+	let dir = tempfile::tempdir().unwrap();
+	let journal = dir.path().join("mempool");
+	let api = Arc::new(TestApi::with_alice_nonce(200).enable_stale_check());
+
+	let header01 = api.push_block(1, vec![], true);
+	let pool = journaled_pool(api.clone(), header01.hash(), &journal);
+	block_on(pool.maintain(new_best_block_event(&pool, None, header01.hash())));
+
+	let xt0 = uxt(Alice, 200);
+	let xt1 = uxt(Alice, 201);
+	let xt2 = uxt(Bob, 0);
+	let results = block_on(pool.submit_at(
+		header01.hash(),
+		SOURCE,
+		vec![xt0.clone(), xt1.clone(), xt2.clone()],
+	))
+	.unwrap();
+	assert!(results.iter().all(Result::is_ok));
+	assert_eq!(pool.mempool_len(), (3, 0));
+
+	// Dropping the pool writes the journal.
+	drop(pool);
+	assert!(journal.exists());
+
+	// While the node is down `xt0` gets included and `xt2` becomes invalid.
+	let header02 = api.push_block_with_parent(header01.hash(), vec![], true);
+	api.set_nonce(header02.hash(), Alice.into(), 201);
+	api.add_invalid(&xt2);
+
+	let pool = journaled_pool(api.clone(), header02.hash(), &journal);
+	wait_for_mempool_len(&pool, 1);
+
+	block_on(pool.maintain(new_best_block_event(&pool, None, header02.hash())));
+	assert_pool_status!(header02.hash(), &pool, 1, 0);
+	assert_ready_iterator!(header02.hash(), pool, [xt1]);
+}
@@ -24,7 +24,7 @@ use fatp_common::{
 	finalized_block_event, invalid_hash, new_best_block_event, TestPoolBuilder, LOG_TARGET, SOURCE,
 };
 use futures::{executor::block_on, FutureExt};
-use sc_transaction_pool::ChainApi;
+use sc_transaction_pool::{ChainApi, PoolLimit, SenderLimit};
 use sc_transaction_pool_api::{
 	error::Error as TxPoolError, MaintainedTransactionPool, TransactionPool, TransactionStatus,
 };
@@ -830,3 +830,41 @@ fn fatp_limits_watcher_future_transactions_are_droped_when_view_is_dropped() {
 		assert_eq!(x_status, vec![TransactionStatus::Future]);
 	}
 }
+
+#[test]
+fn fatp_limits_sender_limit_is_enforced_across_views() {
+	sp_tracing::try_init_simple();
+
+	let sender_limit =
+		SenderLimit { limit: PoolLimit { count: 2, total_bytes: usize::MAX }, nonce_len: 1 };
+	let builder = TestPoolBuilder::new();
+	let (pool, api, _) = builder.with_sender_limit(sender_limit).build();
+
+	let header01a = api.push_block(1, vec![], true);
+	let header01b = api.push_block(1, vec![], true);
+	api.set_nonce(header01b.hash(), Alice.into(), 205);
+
+	let event = new_best_block_event(&pool, None, header01a.hash());
+	block_on(pool.maintain(event));
+	let event = new_best_block_event(&pool, None, header01b.hash());
+	block_on(pool.maintain(event));
+
+	let xt0 = uxt(Alice, 200);
+	let xt1 = uxt(Alice, 201);
+	let xt2 = uxt(Alice, 205);
+
+	// xt0 and xt1 are ready at header01a and stale at header01b.
+	let results = block_on(pool.submit_at(invalid_hash(), SOURCE, vec![xt0, xt1])).unwrap();
+	assert!(results.iter().all(Result::is_ok));
+	assert_pool_status!(header01a.hash(), &pool, 2, 0);
+	assert_pool_status!(header01b.hash(), &pool, 0, 0);
+
+	// xt2 is rejected by the limit at header01a, but ready at header01b. The sender has two
+	// transactions in the mempool already, so it is rejected by the mempool.
+	let result = block_on(pool.submit_one(invalid_hash(), SOURCE, xt2.clone()));
+	assert!(matches!(result.unwrap_err().0, TxPoolError::SenderLimitExceeded));
+
+	assert_pool_status!(header01a.hash(), &pool, 2, 0);
+	assert_pool_status!(header01b.hash(), &pool, 0, 0);
+	assert_eq!(pool.mempool_len(), (2, 0));
+}
@@ -878,5 +878,19 @@ benchmarks_instance_pallet! {
 		);
 	}
 
+	set_operator_approval {
+		let (collection, caller, _) = create_collection::<T, I>();
+		let operator: T::AccountId = account("operator", 0, SEED);
+		let operator_lookup = T::Lookup::unlookup(operator.clone());
+	}: _(SystemOrigin::Signed(caller.clone()), collection, operator_lookup, true)
+	verify {
+		assert_last_event::<T, I>(Event::OperatorApprovalSet {
+			collection,
+			owner: caller,
+			operator,
+			approved: true,
+		}.into());
+	}
+
 	impl_benchmark_test_suite!(Nfts, crate::mock::new_test_ext(), crate::mock::Test);
 }
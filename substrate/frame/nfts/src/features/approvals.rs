@@ -172,4 +172,55 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 
 		Ok(())
 	}
+
+	/// Approves or disapproves an operator to transfer any item of an owner within a collection.
+	///
+	/// Approving an `operator` reserves a deposit from the `owner` that is returned once the
+	/// approval is revoked. Revoking is possible even after the `collection` has been destroyed,
+	/// so that the deposit can always be reclaimed. Emits the `OperatorApprovalSet` event.
+	///
+	/// - `collection`: The collection the approval is scoped to.
+	/// - `owner`: The account whose items the `operator` may transfer.
+	/// - `operator`: The account that is approved or disapproved.
+	/// - `approved`: Whether the `operator` is approved.
+	pub(crate) fn do_set_operator_approval(
+		collection: T::CollectionId,
+		owner: T::AccountId,
+		operator: T::AccountId,
+		approved: bool,
+	) -> DispatchResult {
+		let key = (&collection, &owner, &operator);
+		if approved {
+			ensure!(
+				Self::is_pallet_feature_enabled(PalletFeature::Approvals),
+				Error::<T, I>::MethodDisabled
+			);
+			ensure!(
+				Collection::<T, I>::contains_key(&collection),
+				Error::<T, I>::UnknownCollection
+			);
+
+			if !CollectionOperators::<T, I>::contains_key(key) {
+				let deposit = T::DepositPerByte::get()
+					.saturating_mul((T::AccountId::max_encoded_len() as u32).into());
+				T::Currency::reserve(&owner, deposit)?;
+				CollectionOperators::<T, I>::insert(key, deposit);
+			}
+		} else if let Some(deposit) = CollectionOperators::<T, I>::take(key) {
+			T::Currency::unreserve(&owner, deposit);
+		}
+
+		Self::deposit_event(Event::OperatorApprovalSet { collection, owner, operator, approved });
+
+		Ok(())
+	}
+
+	/// Returns whether `operator` is approved to transfer any item of `owner` within `collection`.
+	pub fn is_approved_operator(
+		collection: &T::CollectionId,
+		owner: &T::AccountId,
+		operator: &T::AccountId,
+	) -> bool {
+		CollectionOperators::<T, I>::contains_key((collection, owner, operator))
+	}
 }
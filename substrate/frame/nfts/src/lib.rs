@@ -33,6 +33,7 @@ mod benchmarking;
 pub mod migration;
 #[cfg(test)]
 pub mod mock;
+pub mod precompiles;
 #[cfg(test)]
 mod tests;
 
@@ -414,6 +415,20 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	/// Operators approved by an owner to transfer any of its items within a collection, together
+	/// with the deposit reserved for the approval.
+	#[pallet::storage]
+	pub type CollectionOperators<T: Config<I>, I: 'static = ()> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, T::CollectionId>,
+			NMapKey<Blake2_128Concat, T::AccountId>, // owner
+			NMapKey<Blake2_128Concat, T::AccountId>, // operator
+		),
+		DepositBalanceOf<T, I>,
+		OptionQuery,
+	>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config<I>, I: 'static = ()> {
@@ -594,6 +609,14 @@ pub mod pallet {
 			attribute: PalletAttributes<T::CollectionId>,
 			value: BoundedVec<u8, T::ValueLimit>,
 		},
+		/// An `operator` was approved or disapproved to transfer any item of `owner` within the
+		/// `collection`.
+		OperatorApprovalSet {
+			collection: T::CollectionId,
+			owner: T::AccountId,
+			operator: T::AccountId,
+			approved: bool,
+		},
 	}
 
 	#[pallet::error]
@@ -1007,6 +1030,7 @@ pub mod pallet {
 		///
 		/// Origin must be Signed and the signing account must be either:
 		/// - the Owner of the `item`;
+		/// - an operator approved by the Owner of the `item` for the `collection`;
 		/// - the approved delegate for the `item` (in this case, the approval is reset).
 		///
 		/// Arguments:
@@ -1029,7 +1053,9 @@ pub mod pallet {
 			let dest = T::Lookup::lookup(dest)?;
 
 			Self::do_transfer(collection, item, dest, |_, details| {
-				if details.owner != origin {
+				if details.owner != origin &&
+					!Self::is_approved_operator(&collection, &details.owner, &origin)
+				{
 					let deadline =
 						details.approvals.get(&origin).ok_or(Error::<T, I>::NoPermission)?;
 					if let Some(d) = deadline {
@@ -1931,6 +1957,32 @@ pub mod pallet {
 			Self::validate_signature(&Encode::encode(&data), &signature, &signer)?;
 			Self::do_set_attributes_pre_signed(origin, data, signer)
 		}
+
+		/// Approve or disapprove an operator to transfer any item of the sender within a
+		/// collection.
+		///
+		/// Origin must be Signed. Approving an operator reserves a deposit from the sender that is
+		/// returned once the approval is revoked.
+		///
+		/// - `collection`: The collection the approval is scoped to.
+		/// - `operator`: The account that is approved or disapproved.
+		/// - `approved`: Whether the `operator` is approved.
+		///
+		/// Emits `OperatorApprovalSet` on success.
+		///
+		/// Weight: `O(1)`
+		#[pallet::call_index(39)]
+		#[pallet::weight(T::WeightInfo::set_operator_approval())]
+		pub fn set_operator_approval(
+			origin: OriginFor<T>,
+			collection: T::CollectionId,
+			operator: AccountIdLookupOf<T>,
+			approved: bool,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let operator = T::Lookup::lookup(operator)?;
+			Self::do_set_operator_approval(collection, origin, operator, approved)
+		}
 	}
 }
 
@@ -20,6 +20,7 @@
 use super::*;
 use crate as pallet_nfts;
 
+use crate::precompiles::{InlineIdConfig, ERC721};
 use frame_support::{
 	construct_runtime, derive_impl, parameter_types,
 	traits::{AsEnsureOriginWithArg, ConstU32, ConstU64},
@@ -38,6 +39,7 @@ construct_runtime!(
 		System: frame_system,
 		Balances: pallet_balances,
 		Nfts: pallet_nfts,
+		Revive: pallet_revive,
 	}
 );
 
@@ -58,6 +60,13 @@ impl pallet_balances::Config for Test {
 	type AccountStore = System;
 }
 
+#[derive_impl(pallet_revive::config_preludes::TestDefaultConfig)]
+impl pallet_revive::Config for Test {
+	type AddressMapper = pallet_revive::AccountId32Mapper<Self>;
+	type Currency = Balances;
+	type Precompiles = (ERC721<Self, InlineIdConfig<0x0121>>,);
+}
+
 parameter_types! {
 	pub storage Features: PalletFeatures = PalletFeatures::all_enabled();
 }
@@ -0,0 +1,709 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An ERC-721 precompile exposing the items of a collection to contracts of `pallet_revive`.
+//!
+//! Every collection is mapped to its own precompile address by a [`CollectionIdExtractor`].
+//! Transfer approvals of a single item map to the approvals of the pallet and operator approvals
+//! (`setApprovalForAll`) map to the [`crate::CollectionOperators`] of the pallet.
+
+use crate::{weights::WeightInfo, Account, Config, Item, ItemMetadataOf, PhantomData};
+use alloc::{string::String, vec::Vec};
+use ethereum_standards::{
+	IERC721,
+	IERC721::{IERC721Calls, IERC721Events},
+	IERC721Receiver,
+};
+use frame_support::{traits::Get, weights::Weight};
+use pallet_revive::precompiles::{
+	alloy::{
+		self,
+		primitives::{Address, FixedBytes, IntoLogData},
+		sol_types::{Revert, SolCall},
+	},
+	AddressMapper, AddressMatcher, Error, Ext, Precompile, RuntimeCosts, H160, H256, U256,
+};
+use sp_runtime::traits::BlockNumberProvider;
+
+/// Mean of extracting the collection id from the precompile address.
+pub trait CollectionIdExtractor {
+	type CollectionId;
+	/// Extracts the collection id from the address.
+	fn collection_id_from_address(address: &[u8; 20]) -> Result<Self::CollectionId, Error>;
+}
+
+/// The configuration of a pallet-nfts precompile.
+pub trait NftsPrecompileConfig {
+	/// The Address matcher used by the precompile.
+	const MATCHER: AddressMatcher;
+
+	/// The [`CollectionIdExtractor`] used by the precompile.
+	type CollectionIdExtractor: CollectionIdExtractor;
+}
+
+/// A `CollectionIdExtractor` that stores the collection id directly inside the address.
+pub struct InlineCollectionIdExtractor;
+
+impl CollectionIdExtractor for InlineCollectionIdExtractor {
+	type CollectionId = u32;
+	fn collection_id_from_address(addr: &[u8; 20]) -> Result<Self::CollectionId, Error> {
+		let bytes: [u8; 4] = addr[0..4].try_into().expect("slice is 4 bytes; qed");
+		let index = u32::from_be_bytes(bytes);
+		return Ok(index.into());
+	}
+}
+
+/// A precompile configuration that uses a prefix [`AddressMatcher`].
+pub struct InlineIdConfig<const PREFIX: u16>;
+
+impl<const P: u16> NftsPrecompileConfig for InlineIdConfig<P> {
+	const MATCHER: AddressMatcher = AddressMatcher::Prefix(core::num::NonZero::new(P).unwrap());
+	type CollectionIdExtractor = InlineCollectionIdExtractor;
+}
+
+/// An ERC721 precompile.
+pub struct ERC721<Runtime, PrecompileConfig, Instance = ()> {
+	_phantom: PhantomData<(Runtime, PrecompileConfig, Instance)>,
+}
+
+impl<Runtime, PrecompileConfig, Instance: 'static> Precompile
+	for ERC721<Runtime, PrecompileConfig, Instance>
+where
+	PrecompileConfig: NftsPrecompileConfig,
+	Runtime: crate::Config<Instance> + pallet_revive::Config,
+	<<PrecompileConfig as NftsPrecompileConfig>::CollectionIdExtractor as CollectionIdExtractor>::CollectionId:
+		Into<<Runtime as Config<Instance>>::CollectionId>,
+	alloy::primitives::U256: TryInto<<Runtime as Config<Instance>>::ItemId>,
+{
+	type T = Runtime;
+	type Interface = IERC721::IERC721Calls;
+	const MATCHER: AddressMatcher = PrecompileConfig::MATCHER;
+	const HAS_CONTRACT_INFO: bool = false;
+
+	fn call(
+		address: &[u8; 20],
+		input: &Self::Interface,
+		env: &mut impl Ext<T = Self::T>,
+	) -> Result<Vec<u8>, Error> {
+		let collection =
+			PrecompileConfig::CollectionIdExtractor::collection_id_from_address(address)?.into();
+
+		match input {
+			IERC721Calls::supportsInterface(call) => Self::supports_interface(call),
+			IERC721Calls::balanceOf(call) => Self::balance_of(collection, call, env),
+			IERC721Calls::ownerOf(call) => Self::owner_of(collection, call, env),
+			IERC721Calls::tokenURI(call) => Self::token_uri(collection, call, env),
+			IERC721Calls::safeTransferFrom_0(call) => Self::safe_transfer_from(
+				collection,
+				call.from,
+				call.to,
+				call.tokenId,
+				call.data.to_vec(),
+				env,
+			),
+			IERC721Calls::safeTransferFrom_1(call) => Self::safe_transfer_from(
+				collection,
+				call.from,
+				call.to,
+				call.tokenId,
+				Vec::new(),
+				env,
+			),
+			IERC721Calls::transferFrom(call) => {
+				Self::transfer_from(collection, call.from, call.to, call.tokenId, env)?;
+				return Ok(Vec::new());
+			},
+			IERC721Calls::approve(call) => Self::approve(collection, call, env),
+			IERC721Calls::setApprovalForAll(call) =>
+				Self::set_approval_for_all(collection, call, env),
+			IERC721Calls::getApproved(call) => Self::get_approved(collection, call, env),
+			IERC721Calls::isApprovedForAll(call) =>
+				Self::is_approved_for_all(collection, call, env),
+		}
+	}
+}
+
+const ERR_INVALID_CALLER: &str = "Invalid caller";
+const ERR_ITEM_ID_CONVERSION_FAILED: &str = "Item id conversion failed";
+const ERR_NONEXISTENT_TOKEN: &str = "ERC721: nonexistent token";
+const ERR_INVALID_OWNER: &str = "ERC721: invalid owner";
+const ERR_INCORRECT_OWNER: &str = "ERC721: incorrect owner";
+const ERR_INVALID_RECEIVER: &str = "ERC721: invalid receiver";
+const ERR_INSUFFICIENT_APPROVAL: &str = "ERC721: insufficient approval";
+
+/// The ERC-165 interface id of ERC-165 itself.
+const ERC165_INTERFACE_ID: [u8; 4] = [0x01, 0xff, 0xc9, 0xa7];
+
+/// The ERC-165 interface id of ERC-721.
+const ERC721_INTERFACE_ID: [u8; 4] = [0x80, 0xac, 0x58, 0xcd];
+
+impl<Runtime, PrecompileConfig, Instance: 'static> ERC721<Runtime, PrecompileConfig, Instance>
+where
+	PrecompileConfig: NftsPrecompileConfig,
+	Runtime: crate::Config<Instance> + pallet_revive::Config,
+	<<PrecompileConfig as NftsPrecompileConfig>::CollectionIdExtractor as CollectionIdExtractor>::CollectionId:
+		Into<<Runtime as Config<Instance>>::CollectionId>,
+	alloy::primitives::U256: TryInto<<Runtime as Config<Instance>>::ItemId>,
+{
+	/// Get the caller as an `H160` address.
+	fn caller(env: &mut impl Ext<T = Runtime>) -> Result<H160, Error> {
+		env.caller()
+			.account_id()
+			.map(<Runtime as pallet_revive::Config>::AddressMapper::to_address)
+			.map_err(|_| Error::Revert(Revert { reason: ERR_INVALID_CALLER.into() }))
+	}
+
+	/// Convert an Ethereum address to an account id.
+	fn to_account_id(address: Address) -> Runtime::AccountId {
+		<Runtime as pallet_revive::Config>::AddressMapper::to_account_id(
+			&address.into_array().into(),
+		)
+	}
+
+	/// Convert an account id to an Ethereum address.
+	fn to_address(account_id: &Runtime::AccountId) -> Address {
+		<Runtime as pallet_revive::Config>::AddressMapper::to_address(account_id).0.into()
+	}
+
+	/// Convert a `U256` token id to the item id type of the pallet.
+	fn to_item_id(
+		token_id: alloy::primitives::U256,
+	) -> Result<<Runtime as Config<Instance>>::ItemId, Error> {
+		token_id
+			.try_into()
+			.map_err(|_| Error::Revert(Revert { reason: ERR_ITEM_ID_CONVERSION_FAILED.into() }))
+	}
+
+	/// Charge the weight of reading `n` storage items.
+	fn charge_reads(env: &mut impl Ext<T = Runtime>, n: u64) -> Result<(), Error> {
+		env.charge(<Runtime as frame_system::Config>::DbWeight::get().reads(n))?;
+		Ok(())
+	}
+
+	/// Get the details of the given item, reverting if it doesn't exist.
+	fn item_details(
+		collection: &<Runtime as Config<Instance>>::CollectionId,
+		item: &<Runtime as Config<Instance>>::ItemId,
+	) -> Result<crate::ItemDetailsFor<Runtime, Instance>, Error> {
+		Item::<Runtime, Instance>::get(collection, item)
+			.ok_or_else(|| Error::Revert(Revert { reason: ERR_NONEXISTENT_TOKEN.into() }))
+	}
+
+	/// Whether `account` may transfer the item described by `details`.
+	///
+	/// This is the case for the owner, an approved operator of the owner and a delegate of the
+	/// item whose approval hasn't expired.
+	fn is_authorized(
+		collection: &<Runtime as Config<Instance>>::CollectionId,
+		details: &crate::ItemDetailsFor<Runtime, Instance>,
+		account: &Runtime::AccountId,
+	) -> bool {
+		if *account == details.owner ||
+			crate::Pallet::<Runtime, Instance>::is_approved_operator(
+				collection,
+				&details.owner,
+				account,
+			) {
+			return true;
+		}
+
+		let now = <Runtime as Config<Instance>>::BlockNumberProvider::current_block_number();
+		details
+			.approvals
+			.get(account)
+			.is_some_and(|deadline| deadline.map_or(true, |d| now <= d))
+	}
+
+	/// Deposit an event to the runtime.
+	fn deposit_event(env: &mut impl Ext<T = Runtime>, event: IERC721Events) -> Result<(), Error> {
+		let (topics, data) = event.into_log_data().split();
+		let topics = topics.into_iter().map(|v| H256(v.0)).collect::<Vec<_>>();
+		env.gas_meter_mut().charge(RuntimeCosts::DepositEvent {
+			num_topic: topics.len() as u32,
+			len: data.len() as u32,
+		})?;
+		env.deposit_event(topics, data.to_vec());
+		Ok(())
+	}
+
+	/// Execute the supports_interface call.
+	fn supports_interface(call: &IERC721::supportsInterfaceCall) -> Result<Vec<u8>, Error> {
+		let supported = [ERC165_INTERFACE_ID, ERC721_INTERFACE_ID]
+			.iter()
+			.any(|id| call.interfaceId == FixedBytes(*id));
+		return Ok(IERC721::supportsInterfaceCall::abi_encode_returns(&supported));
+	}
+
+	/// Execute the balance_of call.
+	///
+	/// The items of an account are enumerated, charging one storage read per item.
+	fn balance_of(
+		collection: <Runtime as Config<Instance>>::CollectionId,
+		call: &IERC721::balanceOfCall,
+		env: &mut impl Ext<T = Runtime>,
+	) -> Result<Vec<u8>, Error> {
+		if call.owner.is_zero() {
+			return Err(Error::Revert(Revert { reason: ERR_INVALID_OWNER.into() }));
+		}
+
+		let owner = Self::to_account_id(call.owner);
+		let mut balance = 0u64;
+		for _ in Account::<Runtime, Instance>::iter_key_prefix((&owner, &collection)) {
+			Self::charge_reads(env, 1)?;
+			balance += 1;
+		}
+
+		return Ok(IERC721::balanceOfCall::abi_encode_returns(&alloy::primitives::U256::from(
+			balance,
+		)));
+	}
+
+	/// Execute the owner_of call.
+	fn owner_of(
+		collection: <Runtime as Config<Instance>>::CollectionId,
+		call: &IERC721::ownerOfCall,
+		env: &mut impl Ext<T = Runtime>,
+	) -> Result<Vec<u8>, Error> {
+		Self::charge_reads(env, 1)?;
+		let details = Self::item_details(&collection, &Self::to_item_id(call.tokenId)?)?;
+		return Ok(IERC721::ownerOfCall::abi_encode_returns(&Self::to_address(&details.owner)));
+	}
+
+	/// Execute the token_uri call.
+	///
+	/// The metadata of the item is returned as is, or an empty string if it has none.
+	fn token_uri(
+		collection: <Runtime as Config<Instance>>::CollectionId,
+		call: &IERC721::tokenURICall,
+		env: &mut impl Ext<T = Runtime>,
+	) -> Result<Vec<u8>, Error> {
+		Self::charge_reads(env, 2)?;
+		let item = Self::to_item_id(call.tokenId)?;
+		Self::item_details(&collection, &item)?;
+
+		let uri = ItemMetadataOf::<Runtime, Instance>::get(&collection, &item)
+			.map(|metadata| String::from_utf8_lossy(&metadata.data).into_owned())
+			.unwrap_or_default();
+		return Ok(IERC721::tokenURICall::abi_encode_returns(&uri));
+	}
+
+	/// Transfer an item on behalf of the caller.
+	///
+	/// The caller must either own the item, be an approved delegate of the item or be an approved
+	/// operator of the owner.
+	fn transfer_from(
+		collection: <Runtime as Config<Instance>>::CollectionId,
+		from: Address,
+		to: Address,
+		token_id: alloy::primitives::U256,
+		env: &mut impl Ext<T = Runtime>,
+	) -> Result<(), Error> {
+		env.charge(<Runtime as Config<Instance>>::WeightInfo::transfer())?;
+		Self::charge_reads(env, 1)?;
+
+		if to.is_zero() {
+			return Err(Error::Revert(Revert { reason: ERR_INVALID_RECEIVER.into() }));
+		}
+
+		let caller = Self::caller(env)?;
+		let caller = <Runtime as pallet_revive::Config>::AddressMapper::to_account_id(&caller);
+		let owner = Self::to_account_id(from);
+		let item = Self::to_item_id(token_id)?;
+
+		let details = Self::item_details(&collection, &item)?;
+		if details.owner != owner {
+			return Err(Error::Revert(Revert { reason: ERR_INCORRECT_OWNER.into() }));
+		}
+		if !Self::is_authorized(&collection, &details, &caller) {
+			return Err(Error::Revert(Revert { reason: ERR_INSUFFICIENT_APPROVAL.into() }));
+		}
+
+		crate::Pallet::<Runtime, Instance>::do_transfer(
+			collection,
+			item,
+			Self::to_account_id(to),
+			|_, _| Ok(()),
+		)?;
+
+		Self::deposit_event(
+			env,
+			IERC721Events::Transfer(IERC721::Transfer { from, to, tokenId: token_id }),
+		)?;
+
+		Ok(())
+	}
+
+	/// Execute the safe_transfer_from call.
+	///
+	/// If `to` is a contract, its `onERC721Received` hook is called and has to return its own
+	/// selector. Otherwise the transfer is reverted.
+	fn safe_transfer_from(
+		collection: <Runtime as Config<Instance>>::CollectionId,
+		from: Address,
+		to: Address,
+		token_id: alloy::primitives::U256,
+		data: Vec<u8>,
+		env: &mut impl Ext<T = Runtime>,
+	) -> Result<Vec<u8>, Error> {
+		Self::transfer_from(collection, from, to, token_id, env)?;
+
+		let receiver = H160::from(to.into_array());
+		if env.is_contract(&receiver) {
+			let operator = Self::caller(env)?;
+			let input = IERC721Receiver::onERC721ReceivedCall {
+				operator: operator.0.into(),
+				from,
+				tokenId: token_id,
+				data: data.into(),
+			}
+			.abi_encode();
+
+			let accepted = env
+				.call(Weight::MAX, U256::MAX, &receiver, U256::zero(), input, true, false)
+				.is_ok() && {
+				let output = env.last_frame_output();
+				!output.did_revert() &&
+					output.data.get(..4) ==
+						Some(&IERC721Receiver::onERC721ReceivedCall::SELECTOR[..])
+			};
+
+			if !accepted {
+				return Err(Error::Revert(Revert { reason: ERR_INVALID_RECEIVER.into() }));
+			}
+		}
+
+		return Ok(Vec::new());
+	}
+
+	/// Execute the approve call.
+	///
+	/// ERC-721 only knows a single approved account per item, so all other approvals are cleared.
+	/// Approving the zero address only clears the approvals.
+	fn approve(
+		collection: <Runtime as Config<Instance>>::CollectionId,
+		call: &IERC721::approveCall,
+		env: &mut impl Ext<T = Runtime>,
+	) -> Result<Vec<u8>, Error> {
+		env.charge(<Runtime as Config<Instance>>::WeightInfo::clear_all_transfer_approvals())?;
+		env.charge(<Runtime as Config<Instance>>::WeightInfo::approve_transfer())?;
+
+		let caller = Self::caller(env)?;
+		let caller = <Runtime as pallet_revive::Config>::AddressMapper::to_account_id(&caller);
+		let item = Self::to_item_id(call.tokenId)?;
+		let owner = Self::item_details(&collection, &item)?.owner;
+
+		if caller != owner &&
+			!crate::Pallet::<Runtime, Instance>::is_approved_operator(&collection, &owner, &caller)
+		{
+			return Err(Error::Revert(Revert { reason: ERR_INSUFFICIENT_APPROVAL.into() }));
+		}
+
+		crate::Pallet::<Runtime, Instance>::do_clear_all_transfer_approvals(
+			None, collection, item,
+		)?;
+		if !call.to.is_zero() {
+			crate::Pallet::<Runtime, Instance>::do_approve_transfer(
+				None,
+				collection,
+				item,
+				Self::to_account_id(call.to),
+				None,
+			)?;
+		}
+
+		Self::deposit_event(
+			env,
+			IERC721Events::Approval(IERC721::Approval {
+				owner: Self::to_address(&owner),
+				approved: call.to,
+				tokenId: call.tokenId,
+			}),
+		)?;
+
+		return Ok(Vec::new());
+	}
+
+	/// Execute the set_approval_for_all call.
+	fn set_approval_for_all(
+		collection: <Runtime as Config<Instance>>::CollectionId,
+		call: &IERC721::setApprovalForAllCall,
+		env: &mut impl Ext<T = Runtime>,
+	) -> Result<Vec<u8>, Error> {
+		env.charge(<Runtime as Config<Instance>>::WeightInfo::set_operator_approval())?;
+
+		let owner = Self::caller(env)?;
+		crate::Pallet::<Runtime, Instance>::do_set_operator_approval(
+			collection,
+			<Runtime as pallet_revive::Config>::AddressMapper::to_account_id(&owner),
+			Self::to_account_id(call.operator),
+			call.approved,
+		)?;
+
+		Self::deposit_event(
+			env,
+			IERC721Events::ApprovalForAll(IERC721::ApprovalForAll {
+				owner: owner.0.into(),
+				operator: call.operator,
+				approved: call.approved,
+			}),
+		)?;
+
+		return Ok(Vec::new());
+	}
+
+	/// Execute the get_approved call.
+	///
+	/// Returns the first delegate of the item whose approval hasn't expired, or the zero address.
+	fn get_approved(
+		collection: <Runtime as Config<Instance>>::CollectionId,
+		call: &IERC721::getApprovedCall,
+		env: &mut impl Ext<T = Runtime>,
+	) -> Result<Vec<u8>, Error> {
+		Self::charge_reads(env, 1)?;
+		let details = Self::item_details(&collection, &Self::to_item_id(call.tokenId)?)?;
+
+		let now = <Runtime as Config<Instance>>::BlockNumberProvider::current_block_number();
+		let approved = details
+			.approvals
+			.iter()
+			.find(|(_, deadline)| deadline.map_or(true, |d| now <= d))
+			.map(|(delegate, _)| Self::to_address(delegate))
+			.unwrap_or_default();
+
+		return Ok(IERC721::getApprovedCall::abi_encode_returns(&approved));
+	}
+
+	/// Execute the is_approved_for_all call.
+	fn is_approved_for_all(
+		collection: <Runtime as Config<Instance>>::CollectionId,
+		call: &IERC721::isApprovedForAllCall,
+		env: &mut impl Ext<T = Runtime>,
+	) -> Result<Vec<u8>, Error> {
+		Self::charge_reads(env, 1)?;
+		let approved = crate::Pallet::<Runtime, Instance>::is_approved_operator(
+			&collection,
+			&Self::to_account_id(call.owner),
+			&Self::to_account_id(call.operator),
+		);
+		return Ok(IERC721::isApprovedForAllCall::abi_encode_returns(&approved));
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		mock::{new_test_ext, AccountId, Balances, Nfts, RuntimeEvent, RuntimeOrigin, System, Test},
+		precompiles::alloy::hex,
+		CollectionConfig, CollectionSetting, CollectionSettings, MintSettings,
+	};
+	use frame_support::{
+		assert_ok,
+		traits::{Currency, ReservableCurrency},
+		BoundedVec,
+	};
+	use pallet_revive::{DepositLimit, ExecReturnValue};
+
+	const PREFIX: u16 = 0x0121;
+
+	fn account(id: u8) -> AccountId {
+		<Test as pallet_revive::Config>::AddressMapper::to_fallback_account_id(&H160::repeat_byte(
+			id,
+		))
+	}
+
+	fn address(id: u8) -> Address {
+		H160::repeat_byte(id).0.into()
+	}
+
+	fn collection_address(collection: u32) -> H160 {
+		let mut address = [0u8; 20];
+		address[..4].copy_from_slice(&collection.to_be_bytes());
+		address[16..18].copy_from_slice(&PREFIX.to_be_bytes());
+		H160(address)
+	}
+
+	fn call(origin: u8, collection: u32, data: Vec<u8>) -> ExecReturnValue {
+		pallet_revive::Pallet::<Test>::bare_call(
+			RuntimeOrigin::signed(account(origin)),
+			collection_address(collection),
+			0u64,
+			Weight::MAX,
+			DepositLimit::UnsafeOnlyForDryRun,
+			data,
+		)
+		.result
+		.unwrap()
+	}
+
+	fn assert_contract_event(contract: H160, event: IERC721Events) {
+		let (topics, data) = event.into_log_data().split();
+		let topics = topics.into_iter().map(|v| H256(v.0)).collect::<Vec<_>>();
+		System::assert_has_event(RuntimeEvent::Revive(pallet_revive::Event::ContractEmitted {
+			contract,
+			data: data.to_vec(),
+			topics,
+		}));
+	}
+
+	/// Create collection `0` owned by account `1` with item `42` minted to account `1`.
+	fn setup() {
+		for id in 1..=3 {
+			Balances::make_free_balance_be(&account(id), 1_000);
+		}
+		let config = CollectionConfig {
+			settings: CollectionSettings::from_disabled(CollectionSetting::DepositRequired.into()),
+			max_supply: None,
+			mint_settings: MintSettings::default(),
+		};
+		assert_ok!(Nfts::force_create(RuntimeOrigin::root(), account(1), config));
+		assert_ok!(Nfts::mint(RuntimeOrigin::signed(account(1)), 0, 42, account(1), None));
+	}
+
+	#[test]
+	fn collection_id_extractor_works() {
+		let address: [u8; 20] =
+			hex::const_decode_to_array(b"0000053900000000000000000000000001210000").unwrap();
+		assert!(InlineIdConfig::<0x0121>::MATCHER.matches(&address));
+		assert_eq!(
+			<InlineIdConfig<0x0121> as NftsPrecompileConfig>::CollectionIdExtractor::collection_id_from_address(
+				&address
+			)
+			.unwrap(),
+			1337u32
+		);
+	}
+
+	#[test]
+	fn transfer_from_works() {
+		new_test_ext().execute_with(|| {
+			setup();
+			let token_id = alloy::primitives::U256::from(42);
+
+			let data = IERC721::ownerOfCall { tokenId: token_id }.abi_encode();
+			let ret = IERC721::ownerOfCall::abi_decode_returns(&call(1, 0, data).data).unwrap();
+			assert_eq!(ret, address(1));
+
+			// Only the owner or approved accounts may transfer the item.
+			let data =
+				IERC721::transferFromCall { from: address(1), to: address(2), tokenId: token_id }
+					.abi_encode();
+			assert!(call(2, 0, data.clone()).did_revert());
+			assert!(!call(1, 0, data).did_revert());
+
+			assert_contract_event(
+				collection_address(0),
+				IERC721Events::Transfer(IERC721::Transfer {
+					from: address(1),
+					to: address(2),
+					tokenId: token_id,
+				}),
+			);
+			assert_eq!(Nfts::owner(0, 42), Some(account(2)));
+
+			let data = IERC721::balanceOfCall { owner: address(2) }.abi_encode();
+			let ret = IERC721::balanceOfCall::abi_decode_returns(&call(1, 0, data).data).unwrap();
+			assert_eq!(ret, alloy::primitives::U256::from(1));
+
+			// The receiver of a safe transfer is called only if it's a contract.
+			let data = IERC721::safeTransferFrom_1Call {
+				from: address(2),
+				to: address(3),
+				tokenId: token_id,
+			}
+			.abi_encode();
+			assert!(!call(2, 0, data).did_revert());
+			assert_eq!(Nfts::owner(0, 42), Some(account(3)));
+		});
+	}
+
+	#[test]
+	fn approvals_work() {
+		new_test_ext().execute_with(|| {
+			setup();
+			let token_id = alloy::primitives::U256::from(42);
+
+			let data = IERC721::approveCall { to: address(2), tokenId: token_id }.abi_encode();
+			assert!(!call(1, 0, data).did_revert());
+			assert_contract_event(
+				collection_address(0),
+				IERC721Events::Approval(IERC721::Approval {
+					owner: address(1),
+					approved: address(2),
+					tokenId: token_id,
+				}),
+			);
+
+			let data = IERC721::getApprovedCall { tokenId: token_id }.abi_encode();
+			let ret = IERC721::getApprovedCall::abi_decode_returns(&call(1, 0, data).data).unwrap();
+			assert_eq!(ret, address(2));
+
+			// The approved account can transfer the item, which clears the approval.
+			let data =
+				IERC721::transferFromCall { from: address(1), to: address(3), tokenId: token_id }
+					.abi_encode();
+			assert!(!call(2, 0, data).did_revert());
+			assert_eq!(Nfts::owner(0, 42), Some(account(3)));
+
+			let data = IERC721::getApprovedCall { tokenId: token_id }.abi_encode();
+			let ret = IERC721::getApprovedCall::abi_decode_returns(&call(1, 0, data).data).unwrap();
+			assert_eq!(ret, Address::ZERO);
+
+			// An operator can transfer any item of the owner.
+			let data = IERC721::setApprovalForAllCall { operator: address(1), approved: true }
+				.abi_encode();
+			assert!(!call(3, 0, data).did_revert());
+			assert!(Balances::reserved_balance(&account(3)) > 0);
+
+			let data = IERC721::isApprovedForAllCall { owner: address(3), operator: address(1) }
+				.abi_encode();
+			let ret =
+				IERC721::isApprovedForAllCall::abi_decode_returns(&call(1, 0, data).data).unwrap();
+			assert!(ret);
+
+			let data =
+				IERC721::transferFromCall { from: address(3), to: address(1), tokenId: token_id }
+					.abi_encode();
+			assert!(!call(1, 0, data).did_revert());
+			assert_eq!(Nfts::owner(0, 42), Some(account(1)));
+
+			// Revoking the operator returns the deposit.
+			let data = IERC721::setApprovalForAllCall { operator: address(1), approved: false }
+				.abi_encode();
+			assert!(!call(3, 0, data).did_revert());
+			assert_eq!(Balances::reserved_balance(&account(3)), 0);
+		});
+	}
+
+	#[test]
+	fn token_uri_works() {
+		new_test_ext().execute_with(|| {
+			setup();
+			let metadata: BoundedVec<_, _> = b"ipfs://item".to_vec().try_into().unwrap();
+			assert_ok!(Nfts::set_metadata(RuntimeOrigin::signed(account(1)), 0, 42, metadata));
+
+			let data =
+				IERC721::tokenURICall { tokenId: alloy::primitives::U256::from(42) }.abi_encode();
+			let ret = IERC721::tokenURICall::abi_decode_returns(&call(1, 0, data).data).unwrap();
+			assert_eq!(ret, "ipfs://item");
+
+			let data =
+				IERC721::tokenURICall { tokenId: alloy::primitives::U256::from(43) }.abi_encode();
+			assert!(call(1, 0, data).did_revert());
+		});
+	}
+}
@@ -1827,6 +1827,78 @@ fn approval_lifecycle_works() {
 	});
 }
 
+#[test]
+fn operator_approval_works() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&account(2), 100);
+		assert_ok!(Nfts::force_create(
+			RuntimeOrigin::root(),
+			account(1),
+			default_collection_config()
+		));
+		assert_ok!(Nfts::force_mint(
+			RuntimeOrigin::signed(account(1)),
+			0,
+			42,
+			account(2),
+			default_item_config()
+		));
+		assert_ok!(Nfts::force_mint(
+			RuntimeOrigin::signed(account(1)),
+			0,
+			43,
+			account(2),
+			default_item_config()
+		));
+
+		assert_noop!(
+			Nfts::transfer(RuntimeOrigin::signed(account(3)), 0, 42, account(4)),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			Nfts::set_operator_approval(RuntimeOrigin::signed(account(2)), 1, account(3), true),
+			Error::<Test>::UnknownCollection
+		);
+
+		// An approved operator can transfer any item of the owner.
+		assert_ok!(Nfts::set_operator_approval(
+			RuntimeOrigin::signed(account(2)),
+			0,
+			account(3),
+			true
+		));
+		assert!(events().contains(&Event::<Test>::OperatorApprovalSet {
+			collection: 0,
+			owner: account(2),
+			operator: account(3),
+			approved: true,
+		}));
+		let deposit = CollectionOperators::<Test>::get((0, account(2), account(3))).unwrap();
+		assert_eq!(Balances::reserved_balance(&account(2)), deposit);
+		assert_ok!(Nfts::transfer(RuntimeOrigin::signed(account(3)), 0, 42, account(4)));
+		assert_eq!(Item::<Test>::get(0, 42).unwrap().owner, account(4));
+
+		// The approval only covers the items of the owner that approved the operator.
+		assert_noop!(
+			Nfts::transfer(RuntimeOrigin::signed(account(3)), 0, 42, account(3)),
+			Error::<Test>::NoPermission
+		);
+
+		// Revoking the approval returns the deposit.
+		assert_ok!(Nfts::set_operator_approval(
+			RuntimeOrigin::signed(account(2)),
+			0,
+			account(3),
+			false
+		));
+		assert_eq!(Balances::reserved_balance(&account(2)), 0);
+		assert_noop!(
+			Nfts::transfer(RuntimeOrigin::signed(account(3)), 0, 43, account(4)),
+			Error::<Test>::NoPermission
+		);
+	});
+}
+
 #[test]
 fn cancel_approval_works() {
 	new_test_ext().execute_with(|| {
@@ -514,12 +514,23 @@ fn expand_functions(def: &EnvDef) -> TokenStream2 {
 		// They will be mapped to variable names by the syscall specific code.
 		let (__a0__, __a1__, __a2__, __a3__, __a4__, __a5__) = memory.read_input_regs();
 
+		crate::tracing::if_tracing(|tracer| {
+			tracer.enter_ecall(
+				core::str::from_utf8(__syscall_symbol__).unwrap_or_default(),
+				memory.program_counter(),
+				&[__a0__, __a1__, __a2__, __a3__, __a4__, __a5__],
+				self.ext.gas_meter().gas_left(),
+			);
+		});
+
 		// Execute the syscall specific logic in a closure so that the gas metering code is always executed.
 		let result = (|| match __syscall_symbol__ {
 			#( #impls )*
 			_ => Err(TrapReason::SupervisorError(Error::<E::T>::InvalidSyscall.into()))
 		})();
 
+		crate::tracing::if_tracing(|tracer| tracer.exit_ecall(self.ext.gas_meter().gas_left()));
+
 		// Write gas from pallet-revive into polkavm after leaving the host function.
 		let gas = self.ext.gas_meter_mut().sync_to_executor(__gas_left_before__).map_err(TrapReason::from)?;
 		memory.set_gas(gas.into());
@@ -0,0 +1,376 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Build and verify the storage proofs returned by the `eth_getProof` endpoint of the
+//! pallet-revive Ethereum JSON-RPC server.
+//!
+//! An [`AccountProof`] carries Substrate trie proofs rather than Ethereum Merkle-Patricia proofs:
+//! - `accountProof` proves the [`original_account_key`], [`system_account_key`],
+//!   [`contract_info_key`] and child trie root entries of the state trie.
+//! - each `storageProof` proves the [`storage_slot_key`] entry of the contract's child trie,
+//!   including the path from the state root to the child trie root.
+//!
+//! Both are checked offline against the state root of a block header with
+//! [`verify_account_proof`].
+
+use codec::Decode;
+use frame_system::AccountInfo;
+use pallet_revive::evm::{AccountProof, H160, H256, U256};
+use sp_core::{crypto::AccountId32, storage::ChildInfo};
+use sp_crypto_hashing::{blake2_128, blake2_256, keccak_256, twox_128};
+use sp_runtime::traits::{BlakeTwo256, Header};
+use sp_state_machine::{read_child_proof_check, read_proof_check};
+use sp_trie::StorageProof;
+
+/// The name under which pallet-revive is declared in the runtime.
+pub const REVIVE_PALLET_NAME: &[u8] = b"Revive";
+
+/// The name under which frame-system is declared in the runtime.
+pub const SYSTEM_PALLET_NAME: &[u8] = b"System";
+
+/// Errors that can occur while verifying an [`AccountProof`].
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// The trie nodes do not prove the expected entries against the state root.
+	#[error("Invalid proof: {0}")]
+	InvalidProof(String),
+	/// A proven storage value could not be decoded.
+	#[error("Failed to decode the proven {0}: {1}")]
+	Decode(&'static str, codec::Error),
+	/// A field of the [`AccountProof`] does not match the proven state.
+	#[error("The {0} does not match the proven state")]
+	Mismatch(&'static str),
+}
+
+/// The state of an account proven by an [`AccountProof`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedAccount<Nonce, AccountData> {
+	/// The native account the address maps to.
+	pub account_id: AccountId32,
+	/// The `frame_system` account info, or `None` if the account does not exist.
+	pub account: Option<AccountInfo<Nonce, AccountData>>,
+	/// The code hash, or `None` if the account is not a contract.
+	pub code_hash: Option<H256>,
+	/// The value of the requested storage slots.
+	pub storage: Vec<(U256, U256)>,
+}
+
+/// The first fields of `pallet_revive::storage::ContractInfo`.
+#[derive(Decode)]
+struct ContractInfoPrefix {
+	trie_id: Vec<u8>,
+	code_hash: H256,
+}
+
+/// The hash of empty code, reported as the code hash of accounts that are not contracts.
+pub fn empty_code_hash() -> H256 {
+	H256(keccak_256(&[]))
+}
+
+/// The storage key prefix of the given storage item.
+pub fn storage_prefix(pallet: &[u8], item: &[u8]) -> Vec<u8> {
+	[twox_128(pallet), twox_128(item)].concat()
+}
+
+/// The key of the `OriginalAccount` entry of the given address.
+pub fn original_account_key(address: &H160) -> Vec<u8> {
+	[storage_prefix(REVIVE_PALLET_NAME, b"OriginalAccount").as_slice(), address.as_bytes()]
+		.concat()
+}
+
+/// The key of the `ContractInfoOf` entry of the given address.
+pub fn contract_info_key(address: &H160) -> Vec<u8> {
+	[storage_prefix(REVIVE_PALLET_NAME, b"ContractInfoOf").as_slice(), address.as_bytes()].concat()
+}
+
+/// The key of the `frame_system` `Account` entry of the given account.
+pub fn system_account_key(account_id: &AccountId32) -> Vec<u8> {
+	let account_id: &[u8] = account_id.as_ref();
+	[
+		storage_prefix(SYSTEM_PALLET_NAME, b"Account").as_slice(),
+		&blake2_128(account_id),
+		account_id,
+	]
+	.concat()
+}
+
+/// The child trie holding the storage of the contract with the given trie id.
+pub fn child_info(trie_id: &[u8]) -> ChildInfo {
+	ChildInfo::new_default(trie_id)
+}
+
+/// The key of the given storage slot in the contract's child trie.
+pub fn storage_slot_key(slot: &U256) -> Vec<u8> {
+	blake2_256(&slot.to_big_endian()).to_vec()
+}
+
+/// The account id an address maps to when no original account is registered for it.
+pub fn fallback_account_id(address: &H160) -> AccountId32 {
+	let mut account_id = [0xEE; 32];
+	account_id[..20].copy_from_slice(address.as_bytes());
+	account_id.into()
+}
+
+fn decode<T: Decode>(what: &'static str, value: &[u8]) -> Result<T, Error> {
+	T::decode(&mut &value[..]).map_err(|err| Error::Decode(what, err))
+}
+
+fn storage_proof(nodes: &[pallet_revive::evm::Bytes]) -> StorageProof {
+	StorageProof::new(nodes.iter().map(|node| node.0.clone()))
+}
+
+/// Read the given keys of the state trie from the account proof.
+fn read_state(
+	state_root: H256,
+	proof: &AccountProof,
+	keys: &[Vec<u8>],
+) -> Result<Vec<Option<Vec<u8>>>, Error> {
+	let mut values =
+		read_proof_check::<BlakeTwo256, _>(state_root, storage_proof(&proof.account_proof), keys)
+			.map_err(|err| Error::InvalidProof(err.to_string()))?;
+	Ok(keys.iter().map(|key| values.remove(key).flatten()).collect())
+}
+
+/// Verify an [`AccountProof`] against the state root of the given block header.
+pub fn verify_account_proof_at<Nonce, AccountData, H>(
+	header: &H,
+	proof: &AccountProof,
+) -> Result<VerifiedAccount<Nonce, AccountData>, Error>
+where
+	Nonce: Decode + Into<U256> + Copy,
+	AccountData: Decode,
+	H: Header<Hash = H256>,
+{
+	verify_account_proof(*header.state_root(), proof)
+}
+
+/// Verify an [`AccountProof`] against the given state root.
+///
+/// The nonce, code hash, storage hash and storage values of the proof are checked against the
+/// proven state. The balance is not, as the EVM balance is derived from the native account data
+/// by the runtime; it can be checked by the caller against [`VerifiedAccount::account`].
+pub fn verify_account_proof<Nonce, AccountData>(
+	state_root: H256,
+	proof: &AccountProof,
+) -> Result<VerifiedAccount<Nonce, AccountData>, Error>
+where
+	Nonce: Decode + Into<U256> + Copy,
+	AccountData: Decode,
+{
+	let address = proof.address;
+	let keys = [original_account_key(&address), contract_info_key(&address)];
+	let mut values = read_state(state_root, proof, &keys)?.into_iter();
+	let original_account = values.next().flatten();
+	let contract_info = values.next().flatten();
+
+	let account_id = match original_account {
+		Some(value) => decode("original account", &value)?,
+		None => fallback_account_id(&address),
+	};
+	let contract_info = contract_info
+		.map(|value| decode::<ContractInfoPrefix>("contract info", &value))
+		.transpose()?;
+
+	let mut keys = vec![system_account_key(&account_id)];
+	if let Some(info) = &contract_info {
+		keys.push(child_info(&info.trie_id).prefixed_storage_key().into_inner());
+	}
+	let mut values = read_state(state_root, proof, &keys)?.into_iter();
+
+	let account = values
+		.next()
+		.flatten()
+		.map(|value| decode::<AccountInfo<Nonce, AccountData>>("account info", &value))
+		.transpose()?;
+	let nonce = account.as_ref().map(|account| account.nonce.into()).unwrap_or_default();
+	if nonce != proof.nonce {
+		return Err(Error::Mismatch("nonce"))
+	}
+
+	let code_hash = contract_info.as_ref().map(|info| info.code_hash);
+	if code_hash.unwrap_or_else(empty_code_hash) != proof.code_hash {
+		return Err(Error::Mismatch("code hash"))
+	}
+
+	let storage_hash = values
+		.next()
+		.flatten()
+		.map(|root| decode::<H256>("storage root", &root))
+		.transpose()?
+		.unwrap_or_default();
+	if storage_hash != proof.storage_hash {
+		return Err(Error::Mismatch("storage hash"))
+	}
+
+	let mut storage = Vec::with_capacity(proof.storage_proof.len());
+	for slot in &proof.storage_proof {
+		let value = match &contract_info {
+			Some(info) => {
+				let key = storage_slot_key(&slot.key);
+				let mut values = read_child_proof_check::<BlakeTwo256, _>(
+					state_root,
+					storage_proof(&slot.proof),
+					&child_info(&info.trie_id),
+					[&key],
+				)
+				.map_err(|err| Error::InvalidProof(err.to_string()))?;
+				values
+					.remove(&key)
+					.flatten()
+					.map(|value| U256::from_big_endian(&value))
+					.unwrap_or_default()
+			},
+			None => U256::zero(),
+		};
+
+		if value != slot.value {
+			return Err(Error::Mismatch("storage value"))
+		}
+		storage.push((slot.key, value));
+	}
+
+	Ok(VerifiedAccount { account_id, account, code_hash, storage })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use pallet_revive::evm::{Bytes, StorageProof as SlotProof};
+	use sp_core::storage::{StateVersion, Storage, StorageChild};
+	use sp_state_machine::{prove_child_read, prove_read, Backend, InMemoryBackend};
+
+	const ADDRESS: H160 = H160([1u8; 20]);
+	const TRIE_ID: &[u8] = b"trie";
+	const CODE_HASH: H256 = H256([2u8; 32]);
+
+	fn slot_value(n: u64) -> U256 {
+		U256::from(n)
+	}
+
+	fn backend() -> InMemoryBackend<BlakeTwo256> {
+		let account = AccountInfo::<u32, u128> { nonce: 7, data: 1_000, ..Default::default() };
+		let contract_info = (TRIE_ID.to_vec(), CODE_HASH, 0u32, 0u32);
+		let child_info = child_info(TRIE_ID);
+
+		let storage = Storage {
+			top: [
+				(system_account_key(&fallback_account_id(&ADDRESS)), account.encode()),
+				(contract_info_key(&ADDRESS), contract_info.encode()),
+			]
+			.into(),
+			children_default: [(
+				child_info.storage_key().to_vec(),
+				StorageChild {
+					data: [(
+						storage_slot_key(&U256::one()),
+						slot_value(42).to_big_endian().to_vec(),
+					)]
+					.into(),
+					child_info,
+				},
+			)]
+			.into(),
+		};
+		(storage, StateVersion::V1).into()
+	}
+
+	fn account_proof(backend: InMemoryBackend<BlakeTwo256>, slots: &[u64]) -> AccountProof {
+		let child_info = child_info(TRIE_ID);
+		let child_root_key = child_info.prefixed_storage_key().into_inner();
+		let storage_hash = H256::decode(
+			&mut &backend.storage(&child_root_key).unwrap().unwrap()[..],
+		)
+		.unwrap();
+
+		let keys = [
+			original_account_key(&ADDRESS),
+			contract_info_key(&ADDRESS),
+			system_account_key(&fallback_account_id(&ADDRESS)),
+			child_root_key,
+		];
+		let account_proof = prove_read(backend.clone(), &keys).unwrap();
+
+		let storage_proof = slots
+			.iter()
+			.map(|slot| {
+				let key = U256::from(*slot);
+				let proof =
+					prove_child_read(backend.clone(), &child_info, [storage_slot_key(&key)])
+						.unwrap();
+				let value = backend
+					.child_storage(&child_info, &storage_slot_key(&key))
+					.unwrap()
+					.map(|value| U256::from_big_endian(&value))
+					.unwrap_or_default();
+				SlotProof { key, value, proof: proof.into_iter_nodes().map(Bytes).collect() }
+			})
+			.collect();
+
+		AccountProof {
+			address: ADDRESS,
+			balance: U256::zero(),
+			code_hash: CODE_HASH,
+			nonce: U256::from(7),
+			storage_hash,
+			account_proof: account_proof.into_iter_nodes().map(Bytes).collect(),
+			storage_proof,
+		}
+	}
+
+	#[test]
+	fn verify_account_proof_works() {
+		let backend = backend();
+		let state_root = *backend.root();
+		let proof = account_proof(backend, &[1, 2]);
+
+		let verified = verify_account_proof::<u32, u128>(state_root, &proof).unwrap();
+		assert_eq!(verified.account_id, fallback_account_id(&ADDRESS));
+		assert_eq!(verified.account.map(|account| account.data), Some(1_000));
+		assert_eq!(verified.code_hash, Some(CODE_HASH));
+		assert_eq!(
+			verified.storage,
+			vec![(U256::one(), slot_value(42)), (U256::from(2), U256::zero())]
+		);
+	}
+
+	#[test]
+	fn verify_account_proof_rejects_tampered_values() {
+		let backend = backend();
+		let state_root = *backend.root();
+		let proof = account_proof(backend, &[1]);
+
+		let mut tampered = proof.clone();
+		tampered.nonce = U256::from(8);
+		assert!(matches!(
+			verify_account_proof::<u32, u128>(state_root, &tampered),
+			Err(Error::Mismatch("nonce"))
+		));
+
+		let mut tampered = proof.clone();
+		tampered.storage_proof[0].value = slot_value(43);
+		assert!(matches!(
+			verify_account_proof::<u32, u128>(state_root, &tampered),
+			Err(Error::Mismatch("storage value"))
+		));
+
+		assert!(matches!(
+			verify_account_proof::<u32, u128>(H256::zero(), &proof),
+			Err(Error::InvalidProof(_))
+		));
+	}
+}
@@ -0,0 +1,139 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Ethereum pub-sub JSON-RPC methods.
+
+use crate::*;
+use futures::{stream, Stream, StreamExt};
+use jsonrpsee::{proc_macros::rpc, PendingSubscriptionSink};
+use sc_rpc::utils::{BoundedVecDeque, PendingSubscription};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// The kind of events an `eth_subscribe` subscription can listen to.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SubscriptionKind {
+	/// New best block headers.
+	NewHeads,
+	/// Logs included in new best blocks, matching the given filter.
+	Logs,
+	/// Hashes of transactions submitted through this server.
+	NewPendingTransactions,
+}
+
+/// An item sent to `eth_subscribe` subscribers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SubscriptionItem {
+	/// A new best block.
+	Block(Box<Block>),
+	/// A log matching the subscription filter.
+	Log(Log),
+	/// The hash of a pending transaction.
+	TransactionHash(H256),
+}
+
+/// Ethereum pub-sub JSON-RPC apis.
+#[rpc(server, client)]
+pub trait EthPubSubRpc {
+	/// Subscribe to new heads, logs or pending transactions.
+	///
+	/// ## References
+	///
+	/// - <https://geth.ethereum.org/docs/interacting-with-geth/rpc/pubsub>
+	#[subscription(
+		name = "eth_subscribe" => "eth_subscription",
+		unsubscribe = "eth_unsubscribe",
+		item = SubscriptionItem
+	)]
+	fn subscribe(&self, kind: SubscriptionKind, filter: Option<Filter>);
+}
+
+pub struct EthPubSubRpcServerImpl {
+	client: client::Client,
+}
+
+impl EthPubSubRpcServerImpl {
+	pub fn new(client: client::Client) -> Self {
+		Self { client }
+	}
+}
+
+/// Turn a broadcast receiver into a stream, skipping over the items missed by slow consumers.
+fn broadcast_stream<T: Clone + Send + 'static>(
+	receiver: broadcast::Receiver<T>,
+) -> impl Stream<Item = T> + Send {
+	stream::unfold(receiver, |mut receiver| async move {
+		loop {
+			match receiver.recv().await {
+				Ok(item) => return Some((item, receiver)),
+				Err(RecvError::Lagged(skipped)) => {
+					log::debug!(target: LOG_TARGET, "Subscriber lagged behind, skipped {skipped} items");
+				},
+				Err(RecvError::Closed) => return None,
+			}
+		}
+	})
+}
+
+impl EthPubSubRpcServer for EthPubSubRpcServerImpl {
+	fn subscribe(
+		&self,
+		pending: PendingSubscriptionSink,
+		kind: SubscriptionKind,
+		filter: Option<Filter>,
+	) {
+		let client = self.client.clone();
+		let stream = match kind {
+			SubscriptionKind::NewHeads =>
+				broadcast_stream(client.subscribe_new_heads())
+					.map(|block| SubscriptionItem::Block(Box::new((*block).clone())))
+					.boxed(),
+			SubscriptionKind::NewPendingTransactions =>
+				broadcast_stream(client.subscribe_pending_transactions())
+					.map(SubscriptionItem::TransactionHash)
+					.boxed(),
+			SubscriptionKind::Logs => {
+				let filter = filter.unwrap_or_default();
+				broadcast_stream(client.subscribe_new_heads())
+					.then(move |block| {
+						let client = client.clone();
+						let filter = Filter {
+							block_hash: Some(block.hash),
+							from_block: None,
+							to_block: None,
+							..filter.clone()
+						};
+						async move {
+							client.logs(Some(filter)).await.unwrap_or_else(|err| {
+								log::debug!(target: LOG_TARGET, "Failed to fetch logs for subscription: {err:?}");
+								Vec::new()
+							})
+						}
+					})
+					.flat_map(stream::iter)
+					.map(SubscriptionItem::Log)
+					.boxed()
+			},
+		};
+
+		tokio::spawn(
+			PendingSubscription::from(pending)
+				.pipe_from_stream(stream, BoundedVecDeque::default()),
+		);
+	}
+}
@@ -17,11 +17,12 @@
 //! The Ethereum JSON-RPC server.
 use crate::{
 	client::{connect, Client, SubscriptionType, SubstrateBlockNumber},
-	DebugRpcServer, DebugRpcServerImpl, EthRpcServer, EthRpcServerImpl, ReceiptExtractor,
-	ReceiptProvider, SubxtBlockInfoProvider, SystemHealthRpcServer, SystemHealthRpcServerImpl,
+	DebugRpcServer, DebugRpcServerImpl, EthPubSubRpcServer, EthPubSubRpcServerImpl, EthRpcServer,
+	EthRpcServerImpl, KvReceiptStore, ReceiptExtractor, ReceiptProvider, ReceiptStore,
+	SqliteReceiptStore, SubxtBlockInfoProvider, SystemHealthRpcServer, SystemHealthRpcServerImpl,
 	LOG_TARGET,
 };
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
 use futures::{pin_mut, FutureExt};
 use jsonrpsee::server::RpcModule;
 use sc_cli::{PrometheusParams, RpcParams, SharedParams, Signals};
@@ -29,6 +30,7 @@ use sc_service::{
 	config::{PrometheusConfig, RpcConfiguration},
 	start_rpc_servers, TaskManager,
 };
+use std::{path::Path, sync::Arc};
 
 // Default port if --prometheus-port is not specified
 const DEFAULT_PROMETHEUS_PORT: u16 = 9616;
@@ -38,6 +40,9 @@ const DEFAULT_RPC_PORT: u16 = 8545;
 
 const IN_MEMORY_DB: &str = "sqlite::memory:";
 
+// The database url prefix selecting the embedded parity-db key-value store.
+const PARITY_DB_PREFIX: &str = "parity-db:";
+
 // Parsed command instructions from the command line
 #[derive(Parser, Debug)]
 #[clap(author, about, version)]
@@ -57,6 +62,9 @@ pub struct CliCommand {
 	/// The database used to store Ethereum transaction hashes.
 	/// This is only useful if the node needs to act as an archive node and respond to Ethereum RPC
 	/// queries for transactions that are not in the in memory cache.
+	///
+	/// Use a `sqlite:` url for a SQLite database, or `parity-db:<path>` for an embedded
+	/// key-value store better suited to long histories.
 	#[clap(long, env = "DATABASE_URL", default_value = IN_MEMORY_DB)]
 	pub database_url: String,
 
@@ -75,6 +83,31 @@ pub struct CliCommand {
 	#[allow(missing_docs)]
 	#[clap(flatten)]
 	pub prometheus_params: PrometheusParams,
+
+	#[allow(missing_docs)]
+	#[clap(subcommand)]
+	pub subcommand: Option<SubCommand>,
+}
+
+/// Commands run instead of the JSON-RPC server.
+#[derive(Subcommand, Debug)]
+pub enum SubCommand {
+	/// Re-index the receipts of a range of blocks from the node, then exit.
+	///
+	/// This can be used to migrate to a new `--database-url`, or to repair an existing one.
+	Backfill(BackfillCmd),
+}
+
+/// The `backfill` command.
+#[derive(Args, Debug)]
+pub struct BackfillCmd {
+	/// The first block to re-index.
+	#[clap(long)]
+	pub from: SubstrateBlockNumber,
+
+	/// The last block to re-index. Defaults to the latest finalized block.
+	#[clap(long)]
+	pub to: Option<SubstrateBlockNumber>,
 }
 
 /// Initialize the logger
@@ -121,13 +154,17 @@ fn build_client(
 			api.clone(),
 			earliest_receipt_block).await?;
 
+		let store: Arc<dyn ReceiptStore> = match database_url.strip_prefix(PARITY_DB_PREFIX) {
+			Some(path) => Arc::new(KvReceiptStore::open(Path::new(path))?),
+			None => Arc::new(SqliteReceiptStore::new(database_url).await?),
+		};
+
 		let receipt_provider = ReceiptProvider::new(
-				database_url,
+				store,
 				block_provider.clone(),
 				receipt_extractor.clone(),
 				keep_latest_n_blocks,
-			)
-			.await?;
+			);
 
 		let client =
 			Client::new(api, rpc_client, rpc, block_provider, receipt_provider).await?;
@@ -155,6 +192,7 @@ pub fn run(cmd: CliCommand) -> anyhow::Result<()> {
 		earliest_receipt_block,
 		index_last_n_blocks,
 		shared_params,
+		subcommand,
 		..
 	} = cmd;
 
@@ -178,8 +216,11 @@ pub fn run(cmd: CliCommand) -> anyhow::Result<()> {
 		message_buffer_capacity: rpc_params.rpc_message_buffer_capacity_per_connection,
 		batch_config: rpc_params.rpc_batch_config()?,
 		rate_limit: rpc_params.rpc_rate_limit,
+		rate_limit_per_ip: rpc_params.rpc_rate_limit_per_ip,
+		rate_limit_method_costs: rpc_params.rpc_method_costs(),
 		rate_limit_whitelisted_ips: rpc_params.rpc_rate_limit_whitelisted_ips,
 		rate_limit_trust_proxy_headers: rpc_params.rpc_rate_limit_trust_proxy_headers,
+		max_subs_per_ip: rpc_params.rpc_max_subscriptions_per_ip,
 	};
 
 	let prometheus_config =
@@ -199,6 +240,12 @@ pub fn run(cmd: CliCommand) -> anyhow::Result<()> {
 		tokio_runtime.block_on(async { Signals::capture() })?,
 	)?;
 
+	if let Some(SubCommand::Backfill(BackfillCmd { from, to })) = subcommand {
+		let signals = tokio_runtime.block_on(async { Signals::capture() })?;
+		tokio_runtime.block_on(signals.run_until_signal(client.reindex_blocks(from, to).fuse()))?;
+		return Ok(());
+	}
+
 	// Prometheus metrics.
 	if let Some(PrometheusConfig { port, registry }) = prometheus_config.clone() {
 		task_manager.spawn_handle().spawn(
@@ -247,11 +294,13 @@ fn rpc_module(is_dev: bool, client: Client) -> Result<RpcModule<()>, sc_service:
 		.into_rpc();
 
 	let health_api = SystemHealthRpcServerImpl::new(client.clone()).into_rpc();
-	let debug_api = DebugRpcServerImpl::new(client).into_rpc();
+	let debug_api = DebugRpcServerImpl::new(client.clone()).into_rpc();
+	let pubsub_api = EthPubSubRpcServerImpl::new(client).into_rpc();
 
 	let mut module = RpcModule::new(());
 	module.merge(eth_api).map_err(|e| sc_service::Error::Application(e.into()))?;
 	module.merge(health_api).map_err(|e| sc_service::Error::Application(e.into()))?;
 	module.merge(debug_api).map_err(|e| sc_service::Error::Application(e.into()))?;
+	module.merge(pubsub_api).map_err(|e| sc_service::Error::Application(e.into()))?;
 	Ok(module)
 }
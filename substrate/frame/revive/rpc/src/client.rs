@@ -25,9 +25,10 @@ use storage_api::StorageApi;
 
 use crate::{
 	subxt_client::{self, revive::calls::types::EthTransact, SrcChainConfig},
-	BlockInfoProvider, BlockTag, FeeHistoryProvider, ReceiptProvider, SubxtBlockInfoProvider,
-	TracerType, TransactionInfo, LOG_TARGET,
+	BlockInfoProvider, BlockTag, FeeHistoryProvider, FilterChanges, FilterProvider,
+	ReceiptProvider, SubxtBlockInfoProvider, TracerType, TransactionInfo, LOG_TARGET,
 };
+use codec::Decode;
 use jsonrpsee::{
 	core::traits::ToRpcParams,
 	rpc_params,
@@ -35,12 +36,16 @@ use jsonrpsee::{
 };
 use pallet_revive::{
 	evm::{
-		decode_revert_reason, Block, BlockNumberOrTag, BlockNumberOrTagOrHash, FeeHistoryResult,
-		Filter, GenericTransaction, Log, ReceiptInfo, SyncingProgress, SyncingStatus, Trace,
-		TransactionSigned, TransactionTrace, H256, U256,
+		decode_revert_reason, AccountProof, Block, BlockNumberOrTag, BlockNumberOrTagOrHash, Bytes,
+		FeeHistoryResult, Filter, FilterResults, GenericTransaction, Log, ReceiptInfo,
+		StorageProof, SyncingProgress, SyncingStatus, Trace, TransactionSigned, TransactionTrace,
+		H160, H256, U256,
 	},
 	EthTransactError,
 };
+use pallet_revive_proof as proof;
+use sc_rpc_api::state::ReadProof;
+use sp_core::{crypto::AccountId32, storage::StorageKey};
 use sp_runtime::traits::Block as BlockT;
 use sp_weights::Weight;
 use std::{ops::Range, sync::Arc, time::Duration};
@@ -49,13 +54,17 @@ use subxt::{
 		legacy::{rpc_methods::SystemHealth, LegacyRpcMethods},
 		rpc::{
 			reconnecting_rpc_client::{ExponentialBackoff, RpcClient as ReconnectingRpcClient},
-			RpcClient,
+			rpc_params as subxt_rpc_params, RpcClient, RpcParams,
 		},
 	},
 	config::Header,
 	Config, OnlineClient,
 };
 use thiserror::Error;
+use tokio::sync::broadcast;
+
+/// The capacity of the new heads and pending transactions notification channels.
+const NOTIFICATION_CHANNEL_CAPACITY: usize = 256;
 
 /// The substrate block type.
 pub type SubstrateBlock = subxt::blocks::Block<SrcChainConfig, OnlineClient<SrcChainConfig>>;
@@ -98,6 +107,12 @@ pub enum ClientError {
 	/// A [`codec::Error`] wrapper error.
 	#[error(transparent)]
 	CodecError(#[from] codec::Error),
+	/// A [`serde_json::Error`] wrapper error.
+	#[error(transparent)]
+	JsonError(#[from] serde_json::Error),
+	/// A [`parity_db::Error`] wrapper error.
+	#[error(transparent)]
+	ParityDbError(#[from] parity_db::Error),
 	/// Transcact call failed.
 	#[error("contract reverted")]
 	TransactError(EthTransactError),
@@ -124,6 +139,15 @@ pub enum ClientError {
 	/// Failed to filter logs.
 	#[error("Failed to filter logs")]
 	LogFilterFailed(#[from] anyhow::Error),
+	/// The filter was not found, or has expired.
+	#[error("filter not found")]
+	FilterNotFound,
+	/// The maximum number of installed filters was reached.
+	#[error("too many filters installed")]
+	TooManyFilters,
+	/// The filter parameters are invalid.
+	#[error("invalid filter: {0}")]
+	InvalidFilter(String),
 }
 
 const REVERT_CODE: i32 = 3;
@@ -161,6 +185,9 @@ pub struct Client {
 	receipt_provider: ReceiptProvider,
 	block_provider: SubxtBlockInfoProvider,
 	fee_history_provider: FeeHistoryProvider,
+	filter_provider: FilterProvider,
+	new_heads: broadcast::Sender<Arc<Block>>,
+	pending_transactions: broadcast::Sender<H256>,
 	chain_id: u64,
 	max_block_weight: Weight,
 }
@@ -228,6 +255,9 @@ impl Client {
 			receipt_provider,
 			block_provider,
 			fee_history_provider: FeeHistoryProvider::default(),
+			filter_provider: FilterProvider::default(),
+			new_heads: broadcast::channel(NOTIFICATION_CHANNEL_CAPACITY).0,
+			pending_transactions: broadcast::channel(NOTIFICATION_CHANNEL_CAPACITY).0,
 			chain_id,
 			max_block_weight,
 		})
@@ -333,6 +363,12 @@ impl Client {
 			self.block_provider.update_latest(block, subscription_type).await;
 
 			self.fee_history_provider.update_fee_history(&evm_block, &receipts).await;
+
+			if matches!(subscription_type, SubscriptionType::BestBlocks) {
+				self.filter_provider.notify_new_block(evm_block.hash).await;
+				// An error only means that there are no subscribers.
+				let _ = self.new_heads.send(Arc::new(evm_block));
+			}
 			Ok(())
 		})
 		.await
@@ -356,6 +392,34 @@ impl Client {
 		Ok(())
 	}
 
+	/// Re-index the receipts of the blocks in the given inclusive range, replacing the receipts
+	/// already indexed for these blocks.
+	///
+	/// When `to` is `None`, the range ends at the latest finalized block.
+	pub async fn reindex_blocks(
+		&self,
+		from: SubstrateBlockNumber,
+		to: Option<SubstrateBlockNumber>,
+	) -> Result<(), ClientError> {
+		let to = match to {
+			Some(to) => to,
+			None => self.latest_finalized_block().await.number(),
+		};
+		if from > to {
+			log::warn!(target: LOG_TARGET, "🗄️ Nothing to re-index in range #{from}..=#{to}");
+			return Ok(());
+		}
+
+		log::info!(target: LOG_TARGET, "🗄️ Re-indexing blocks in range #{from}..=#{to}");
+		self.subscribe_past_blocks(from..to, |block| async move {
+			self.receipt_provider.reindex_block_receipts(&block).await
+		})
+		.await?;
+
+		log::info!(target: LOG_TARGET, "🗄️ Finished re-indexing blocks");
+		Ok(())
+	}
+
 	/// Get the block hash for the given block number or tag.
 	pub async fn block_hash_for_tag(
 		&self,
@@ -390,6 +454,97 @@ impl Client {
 		RuntimeApi::new(self.api.runtime_api().at(block_hash))
 	}
 
+	/// Get the account and storage proofs of the given contract or account, at the given block.
+	pub async fn get_proof(
+		&self,
+		address: H160,
+		storage_keys: Vec<U256>,
+		block_hash: H256,
+	) -> Result<AccountProof, ClientError> {
+		let runtime_api = self.runtime_api(block_hash);
+		let balance = runtime_api.balance(address).await?;
+		let nonce = runtime_api.nonce(address).await?;
+
+		let storage = self.api.storage().at(block_hash);
+		let account_id = match storage.fetch_raw(proof::original_account_key(&address)).await? {
+			Some(value) => AccountId32::decode(&mut &value[..])?,
+			None => proof::fallback_account_id(&address),
+		};
+
+		let contract_info = match self.storage_api(block_hash).get_contract_info(&address).await {
+			Ok(info) => Some(info),
+			Err(ClientError::ContractNotFound) => None,
+			Err(err) => return Err(err),
+		};
+		let child_info = contract_info.as_ref().map(|info| proof::child_info(&info.trie_id.0));
+
+		let mut keys = vec![
+			proof::original_account_key(&address),
+			proof::contract_info_key(&address),
+			proof::system_account_key(&account_id),
+		];
+		let mut storage_hash = H256::zero();
+		if let Some(child_info) = &child_info {
+			let child_root_key = child_info.prefixed_storage_key().into_inner();
+			if let Some(root) = storage.fetch_raw(child_root_key.clone()).await? {
+				storage_hash = H256::decode(&mut &root[..])?;
+			}
+			keys.push(child_root_key);
+		}
+
+		let keys: Vec<StorageKey> = keys.into_iter().map(StorageKey).collect();
+		let account_proof =
+			self.read_proof("state_getReadProof", subxt_rpc_params![keys, block_hash]).await?;
+
+		let mut storage_proof = Vec::with_capacity(storage_keys.len());
+		for key in storage_keys {
+			let Some(child_info) = &child_info else {
+				storage_proof.push(StorageProof { key, ..Default::default() });
+				continue;
+			};
+
+			let value = runtime_api
+				.get_storage(address, key.to_big_endian())
+				.await?
+				.map(|value| U256::from_big_endian(&value))
+				.unwrap_or_default();
+			let proof = self
+				.read_proof(
+					"state_getChildReadProof",
+					subxt_rpc_params![
+						child_info.prefixed_storage_key(),
+						vec![StorageKey(proof::storage_slot_key(&key))],
+						block_hash
+					],
+				)
+				.await?;
+			storage_proof.push(StorageProof { key, value, proof });
+		}
+
+		Ok(AccountProof {
+			address,
+			balance,
+			code_hash: contract_info
+				.map(|info| H256(info.code_hash.0))
+				.unwrap_or_else(proof::empty_code_hash),
+			nonce,
+			storage_hash,
+			account_proof,
+			storage_proof,
+		})
+	}
+
+	/// Request a storage proof from the node with the given method.
+	async fn read_proof(
+		&self,
+		method: &str,
+		params: RpcParams,
+	) -> Result<Vec<Bytes>, ClientError> {
+		let client = RpcClient::new(self.rpc_client.clone());
+		let read_proof: ReadProof<H256> = client.request(method, params).await?;
+		Ok(read_proof.proof.into_iter().map(|node| Bytes(node.0)).collect())
+	}
+
 	/// Get the latest finalized block.
 	pub async fn latest_finalized_block(&self) -> Arc<SubstrateBlock> {
 		self.block_provider.latest_finalized_block().await
@@ -410,6 +565,22 @@ impl Client {
 		Ok(hash)
 	}
 
+	/// Notify the filters and subscribers about a transaction submitted through this client.
+	pub async fn notify_pending_transaction(&self, hash: H256) {
+		self.filter_provider.notify_pending_transaction(hash).await;
+		let _ = self.pending_transactions.send(hash);
+	}
+
+	/// Subscribe to the new best blocks.
+	pub fn subscribe_new_heads(&self) -> broadcast::Receiver<Arc<Block>> {
+		self.new_heads.subscribe()
+	}
+
+	/// Subscribe to the hashes of the transactions submitted through this client.
+	pub fn subscribe_pending_transactions(&self) -> broadcast::Receiver<H256> {
+		self.pending_transactions.subscribe()
+	}
+
 	/// Get an EVM transaction receipt by hash.
 	pub async fn receipt(&self, tx_hash: &H256) -> Option<ReceiptInfo> {
 		self.receipt_provider.receipt_by_hash(tx_hash).await
@@ -690,6 +861,49 @@ impl Client {
 		Ok(logs)
 	}
 
+	/// Install a new log filter.
+	pub async fn new_filter(&self, filter: Filter) -> Result<U256, ClientError> {
+		let latest_block = self.block_provider.latest_block_number().await;
+		self.filter_provider.new_log_filter(filter, latest_block).await
+	}
+
+	/// Install a new block filter.
+	pub async fn new_block_filter(&self) -> Result<U256, ClientError> {
+		self.filter_provider.new_block_filter().await
+	}
+
+	/// Install a new pending transaction filter.
+	pub async fn new_pending_transaction_filter(&self) -> Result<U256, ClientError> {
+		self.filter_provider.new_pending_transaction_filter().await
+	}
+
+	/// Uninstall the filter with the given id.
+	pub async fn uninstall_filter(&self, id: U256) -> bool {
+		self.filter_provider.uninstall(id).await
+	}
+
+	/// Get the changes of the filter with the given id since it was last polled.
+	pub async fn filter_changes(&self, id: U256) -> Result<FilterResults, ClientError> {
+		let latest_block = self.block_provider.latest_block_number().await;
+		match self.filter_provider.poll(id, latest_block).await? {
+			FilterChanges::Hashes(hashes) => Ok(FilterResults::Hashes(hashes)),
+			FilterChanges::Logs { filter, from_block, to_block } => {
+				let filter = Filter {
+					from_block: Some(U256::from(from_block).into()),
+					to_block: Some(U256::from(to_block).into()),
+					..filter
+				};
+				Ok(FilterResults::Logs(self.logs(Some(filter)).await?))
+			},
+		}
+	}
+
+	/// Get all the logs matching the log filter with the given id.
+	pub async fn filter_logs(&self, id: U256) -> Result<Vec<Log>, ClientError> {
+		let filter = self.filter_provider.log_filter(id).await?;
+		self.logs(Some(filter)).await
+	}
+
 	pub async fn fee_history(
 		&self,
 		block_count: u32,
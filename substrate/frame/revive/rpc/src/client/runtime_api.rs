@@ -20,9 +20,12 @@ use crate::{
 	subxt_client::{self, SrcChainConfig},
 	ClientError, LOG_TARGET,
 };
+use codec::Encode;
 use pallet_revive::{
-	evm::{GenericTransaction, Trace, H160, U256},
-	EthTransactInfo,
+	evm::{
+		BlockOverrides, GenericTransaction, SimulatePayload, StateOverrides, Trace, H160, U256,
+	},
+	EthAccessListInfo, EthSimulatedBlock, EthTransactError, EthTransactInfo,
 };
 use subxt::OnlineClient;
 
@@ -74,6 +77,55 @@ impl RuntimeApi {
 		}
 	}
 
+	/// Dry run a transaction on top of the given state and block overrides, and returns the
+	/// [`EthTransactInfo`] for the transaction.
+	///
+	/// The runtime API is called by name, as it is not part of the metadata the client is
+	/// generated from.
+	pub async fn dry_run_with_overrides(
+		&self,
+		tx: GenericTransaction,
+		state_overrides: StateOverrides,
+		block_overrides: BlockOverrides,
+	) -> Result<EthTransactInfo<Balance>, ClientError> {
+		let params = (tx, state_overrides, block_overrides).encode();
+		let result: Result<EthTransactInfo<Balance>, EthTransactError> =
+			self.0.call_raw("ReviveApi_eth_transact_with_overrides", Some(&params)).await?;
+		result.map_err(|err| {
+			log::debug!(target: LOG_TARGET, "Dry run failed {err:?}");
+			ClientError::TransactError(err)
+		})
+	}
+
+	/// Dry run a sequence of transactions, each on top of the state left by the previous ones.
+	///
+	/// The runtime API is called by name, as it is not part of the metadata the client is
+	/// generated from.
+	pub async fn simulate(
+		&self,
+		payload: SimulatePayload,
+	) -> Result<Vec<EthSimulatedBlock<Balance>>, ClientError> {
+		let result: Result<Vec<EthSimulatedBlock<Balance>>, EthTransactError> =
+			self.0.call_raw("ReviveApi_eth_simulate", Some(&payload.encode())).await?;
+		result.map_err(|err| {
+			log::debug!(target: LOG_TARGET, "Simulation failed {err:?}");
+			ClientError::TransactError(err)
+		})
+	}
+
+	/// Dry run a transaction in access recording mode and returns the [`EthAccessListInfo`] for
+	/// the transaction.
+	///
+	/// The runtime API is called by name, as it is not part of the metadata the client is
+	/// generated from.
+	pub async fn access_list(
+		&self,
+		tx: GenericTransaction,
+	) -> Result<EthAccessListInfo<Balance>, ClientError> {
+		let info = self.0.call_raw("ReviveApi_eth_access_list", Some(&tx.encode())).await?;
+		Ok(info)
+	}
+
 	/// Get the nonce of the given address.
 	pub async fn nonce(&self, address: H160) -> Result<U256, ClientError> {
 		let address = address.0.into();
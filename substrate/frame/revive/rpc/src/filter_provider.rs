@@ -0,0 +1,281 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{client::SubstrateBlockNumber, BlockNumberOrTag, ClientError, LOG_TARGET};
+use pallet_revive::evm::Filter;
+use sp_core::{H256, U256};
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::{Duration, Instant},
+};
+use tokio::sync::Mutex;
+
+/// Filters that are not polled within this duration are uninstalled.
+const FILTER_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// The maximum number of filters that can be installed at the same time.
+const MAX_FILTERS: usize = 2048;
+
+/// The maximum number of hashes buffered by a filter between two polls.
+/// Older hashes are dropped when the limit is reached.
+const MAX_BUFFERED_HASHES: usize = 4096;
+
+/// The state of an installed filter.
+#[derive(Debug, Clone)]
+enum FilterKind {
+	/// Hashes of the new best blocks seen since the last poll.
+	Blocks(Vec<H256>),
+	/// Hashes of the transactions submitted since the last poll.
+	PendingTransactions(Vec<H256>),
+	/// A log filter, and the next block to query logs from.
+	Logs { filter: Filter, next_block: SubstrateBlockNumber },
+}
+
+#[derive(Debug)]
+struct InstalledFilter {
+	kind: FilterKind,
+	last_poll: Instant,
+}
+
+/// The changes of a filter since it was last polled.
+#[derive(Debug, PartialEq)]
+pub enum FilterChanges {
+	/// New block or transaction hashes.
+	Hashes(Vec<H256>),
+	/// Logs matching `filter` should be fetched for the given (inclusive) block range.
+	Logs { filter: Filter, from_block: SubstrateBlockNumber, to_block: SubstrateBlockNumber },
+}
+
+/// Keeps track of the filters installed with `eth_newFilter`, `eth_newBlockFilter` and
+/// `eth_newPendingTransactionFilter`.
+#[derive(Default, Clone)]
+pub struct FilterProvider {
+	filters: Arc<Mutex<HashMap<U256, InstalledFilter>>>,
+	next_id: Arc<AtomicU64>,
+}
+
+impl FilterProvider {
+	async fn install(&self, kind: FilterKind) -> Result<U256, ClientError> {
+		let mut filters = self.filters.lock().await;
+		Self::remove_expired(&mut filters);
+
+		if filters.len() >= MAX_FILTERS {
+			return Err(ClientError::TooManyFilters);
+		}
+
+		let id = U256::from(self.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+		filters.insert(id, InstalledFilter { kind, last_poll: Instant::now() });
+		log::debug!(target: LOG_TARGET, "Installed filter {id:?}");
+		Ok(id)
+	}
+
+	fn remove_expired(filters: &mut HashMap<U256, InstalledFilter>) {
+		filters.retain(|id, filter| {
+			let keep = filter.last_poll.elapsed() < FILTER_TIMEOUT;
+			if !keep {
+				log::debug!(target: LOG_TARGET, "Filter {id:?} expired");
+			}
+			keep
+		});
+	}
+
+	/// Install a filter notifying about new best blocks.
+	pub async fn new_block_filter(&self) -> Result<U256, ClientError> {
+		self.install(FilterKind::Blocks(Vec::new())).await
+	}
+
+	/// Install a filter notifying about new pending transactions.
+	pub async fn new_pending_transaction_filter(&self) -> Result<U256, ClientError> {
+		self.install(FilterKind::PendingTransactions(Vec::new())).await
+	}
+
+	/// Install a log filter. Changes are reported for blocks after `latest_block`.
+	pub async fn new_log_filter(
+		&self,
+		filter: Filter,
+		latest_block: SubstrateBlockNumber,
+	) -> Result<U256, ClientError> {
+		if filter.block_hash.is_some() {
+			return Err(ClientError::InvalidFilter("blockHash is not supported".to_string()));
+		}
+
+		let next_block = match filter.from_block {
+			Some(BlockNumberOrTag::U256(n)) =>
+				n.try_into().map_err(|_| ClientError::ConversionFailed)?,
+			_ => latest_block.saturating_add(1),
+		};
+		self.install(FilterKind::Logs { filter, next_block }).await
+	}
+
+	/// Uninstall the filter with the given id, returning `true` if it existed.
+	pub async fn uninstall(&self, id: U256) -> bool {
+		self.filters.lock().await.remove(&id).is_some()
+	}
+
+	/// Return the log filter with the given id.
+	pub async fn log_filter(&self, id: U256) -> Result<Filter, ClientError> {
+		let mut filters = self.filters.lock().await;
+		let installed = filters.get_mut(&id).ok_or(ClientError::FilterNotFound)?;
+		installed.last_poll = Instant::now();
+		match &installed.kind {
+			FilterKind::Logs { filter, .. } => Ok(filter.clone()),
+			_ => Err(ClientError::FilterNotFound),
+		}
+	}
+
+	/// Return the changes of the filter with the given id since it was last polled.
+	pub async fn poll(
+		&self,
+		id: U256,
+		latest_block: SubstrateBlockNumber,
+	) -> Result<FilterChanges, ClientError> {
+		let mut filters = self.filters.lock().await;
+		let installed = filters.get_mut(&id).ok_or(ClientError::FilterNotFound)?;
+		installed.last_poll = Instant::now();
+
+		let changes = match &mut installed.kind {
+			FilterKind::Blocks(hashes) | FilterKind::PendingTransactions(hashes) =>
+				FilterChanges::Hashes(core::mem::take(hashes)),
+			FilterKind::Logs { filter, next_block } => {
+				let to_block = match filter.to_block {
+					Some(BlockNumberOrTag::U256(n)) =>
+						n.try_into().unwrap_or(latest_block).min(latest_block),
+					_ => latest_block,
+				};
+
+				if *next_block > to_block {
+					return Ok(FilterChanges::Hashes(Vec::new()));
+				}
+
+				let from_block = *next_block;
+				*next_block = to_block.saturating_add(1);
+				FilterChanges::Logs { filter: filter.clone(), from_block, to_block }
+			},
+		};
+
+		Ok(changes)
+	}
+
+	/// Notify the block filters about a new best block.
+	pub async fn notify_new_block(&self, hash: H256) {
+		let mut filters = self.filters.lock().await;
+		Self::remove_expired(&mut filters);
+		for installed in filters.values_mut() {
+			if let FilterKind::Blocks(hashes) = &mut installed.kind {
+				push_bounded(hashes, hash);
+			}
+		}
+	}
+
+	/// Notify the pending transaction filters about a newly submitted transaction.
+	pub async fn notify_pending_transaction(&self, hash: H256) {
+		let mut filters = self.filters.lock().await;
+		for installed in filters.values_mut() {
+			if let FilterKind::PendingTransactions(hashes) = &mut installed.kind {
+				push_bounded(hashes, hash);
+			}
+		}
+	}
+}
+
+fn push_bounded(hashes: &mut Vec<H256>, hash: H256) {
+	if hashes.len() >= MAX_BUFFERED_HASHES {
+		hashes.remove(0);
+	}
+	hashes.push(hash);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[tokio::test]
+	async fn block_filter_returns_new_hashes_once() {
+		let provider = FilterProvider::default();
+		let id = provider.new_block_filter().await.unwrap();
+
+		provider.notify_new_block(H256::from([1u8; 32])).await;
+		provider.notify_new_block(H256::from([2u8; 32])).await;
+
+		assert_eq!(
+			provider.poll(id, 2).await.unwrap(),
+			FilterChanges::Hashes(vec![H256::from([1u8; 32]), H256::from([2u8; 32])])
+		);
+		assert_eq!(provider.poll(id, 2).await.unwrap(), FilterChanges::Hashes(vec![]));
+	}
+
+	#[tokio::test]
+	async fn pending_transaction_filter_ignores_blocks() {
+		let provider = FilterProvider::default();
+		let id = provider.new_pending_transaction_filter().await.unwrap();
+
+		provider.notify_new_block(H256::from([1u8; 32])).await;
+		provider.notify_pending_transaction(H256::from([2u8; 32])).await;
+
+		assert_eq!(
+			provider.poll(id, 1).await.unwrap(),
+			FilterChanges::Hashes(vec![H256::from([2u8; 32])])
+		);
+	}
+
+	#[tokio::test]
+	async fn log_filter_tracks_block_range() {
+		let provider = FilterProvider::default();
+		let id = provider.new_log_filter(Filter::default(), 10).await.unwrap();
+
+		assert_eq!(provider.poll(id, 10).await.unwrap(), FilterChanges::Hashes(vec![]));
+		assert_eq!(
+			provider.poll(id, 12).await.unwrap(),
+			FilterChanges::Logs { filter: Filter::default(), from_block: 11, to_block: 12 }
+		);
+		assert_eq!(
+			provider.poll(id, 13).await.unwrap(),
+			FilterChanges::Logs { filter: Filter::default(), from_block: 13, to_block: 13 }
+		);
+	}
+
+	#[tokio::test]
+	async fn log_filter_respects_to_block() {
+		let provider = FilterProvider::default();
+		let filter = Filter {
+			from_block: Some(U256::from(5).into()),
+			to_block: Some(U256::from(7).into()),
+			..Default::default()
+		};
+		let id = provider.new_log_filter(filter.clone(), 10).await.unwrap();
+
+		assert_eq!(
+			provider.poll(id, 10).await.unwrap(),
+			FilterChanges::Logs { filter, from_block: 5, to_block: 7 }
+		);
+		assert_eq!(provider.poll(id, 11).await.unwrap(), FilterChanges::Hashes(vec![]));
+	}
+
+	#[tokio::test]
+	async fn uninstall_removes_filter() {
+		let provider = FilterProvider::default();
+		let id = provider.new_block_filter().await.unwrap();
+
+		assert!(provider.uninstall(id).await);
+		assert!(!provider.uninstall(id).await);
+		assert!(matches!(provider.poll(id, 0).await, Err(ClientError::FilterNotFound)));
+	}
+}
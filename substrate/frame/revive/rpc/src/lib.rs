@@ -41,9 +41,15 @@ pub use block_info_provider::*;
 mod receipt_provider;
 pub use receipt_provider::*;
 
+mod receipt_store;
+pub use receipt_store::*;
+
 mod fee_history_provider;
 pub use fee_history_provider::*;
 
+mod filter_provider;
+pub use filter_provider::*;
+
 mod receipt_extractor;
 pub use receipt_extractor::*;
 
@@ -72,6 +78,27 @@ impl EthRpcServerImpl {
 		self.accounts = accounts;
 		self
 	}
+
+	/// Dry run the given transaction at the given block, on top of the given overrides.
+	///
+	/// Without overrides, the plain `eth_transact` runtime API is used, so that runtimes that
+	/// don't support overrides can still be dry-run against.
+	async fn dry_run(
+		&self,
+		hash: H256,
+		transaction: GenericTransaction,
+		state_overrides: Option<StateOverrides>,
+		block_overrides: Option<BlockOverrides>,
+	) -> Result<pallet_revive::EthTransactInfo<client::Balance>, ClientError> {
+		let runtime_api = self.client.runtime_api(hash);
+		let state_overrides = state_overrides.unwrap_or_default();
+		let block_overrides = block_overrides.unwrap_or_default();
+		if state_overrides.is_empty() && block_overrides == BlockOverrides::default() {
+			return runtime_api.dry_run(transaction).await
+		}
+
+		runtime_api.dry_run_with_overrides(transaction, state_overrides, block_overrides).await
+	}
 }
 
 /// The error type for the EVM RPC server.
@@ -149,10 +176,11 @@ impl EthRpcServer for EthRpcServerImpl {
 		&self,
 		transaction: GenericTransaction,
 		block: Option<BlockNumberOrTag>,
+		state_overrides: Option<StateOverrides>,
+		block_overrides: Option<BlockOverrides>,
 	) -> RpcResult<U256> {
 		let hash = self.client.block_hash_for_tag(block.unwrap_or_default().into()).await?;
-		let runtime_api = self.client.runtime_api(hash);
-		let dry_run = runtime_api.dry_run(transaction).await?;
+		let dry_run = self.dry_run(hash, transaction, state_overrides, block_overrides).await?;
 		Ok(dry_run.eth_gas)
 	}
 
@@ -160,13 +188,45 @@ impl EthRpcServer for EthRpcServerImpl {
 		&self,
 		transaction: GenericTransaction,
 		block: Option<BlockNumberOrTagOrHash>,
+		state_overrides: Option<StateOverrides>,
+		block_overrides: Option<BlockOverrides>,
 	) -> RpcResult<Bytes> {
 		let hash = self.client.block_hash_for_tag(block.unwrap_or_default()).await?;
-		let runtime_api = self.client.runtime_api(hash);
-		let dry_run = runtime_api.dry_run(transaction).await?;
+		let dry_run = self.dry_run(hash, transaction, state_overrides, block_overrides).await?;
 		Ok(dry_run.data.into())
 	}
 
+	async fn simulate_v1(
+		&self,
+		payload: SimulatePayload,
+		block: Option<BlockNumberOrTagOrHash>,
+	) -> RpcResult<Vec<SimulatedBlockResult>> {
+		let hash = self.client.block_hash_for_tag(block.unwrap_or_default()).await?;
+		let blocks = self.client.runtime_api(hash).simulate(payload).await?;
+		Ok(blocks.into_iter().map(simulated_block_result).collect())
+	}
+
+	async fn create_access_list(
+		&self,
+		transaction: GenericTransaction,
+		block: Option<BlockNumberOrTagOrHash>,
+	) -> RpcResult<AccessListResult> {
+		let hash = self.client.block_hash_for_tag(block.unwrap_or_default()).await?;
+		let runtime_api = self.client.runtime_api(hash);
+		let pallet_revive::EthAccessListInfo { access_list, result } =
+			runtime_api.access_list(transaction).await?;
+
+		let (gas_used, error) = match result {
+			Ok(dry_run) => (dry_run.eth_gas, None),
+			Err(err) => {
+				let err = ErrorObjectOwned::from(ClientError::TransactError(err));
+				(U256::zero(), Some(err.message().to_string()))
+			},
+		};
+
+		Ok(AccessListResult { access_list, error, gas_used })
+	}
+
 	async fn send_raw_transaction(&self, transaction: Bytes) -> RpcResult<H256> {
 		let hash = H256(keccak_256(&transaction.0));
 		let call = subxt_client::tx().revive().eth_transact(transaction.0);
@@ -176,6 +236,7 @@ impl EthRpcServer for EthRpcServerImpl {
 		})?;
 
 		log::debug!(target: LOG_TARGET, "send_raw_transaction hash: {hash:?}");
+		self.client.notify_pending_transaction(hash).await;
 		Ok(hash)
 	}
 
@@ -194,7 +255,7 @@ impl EthRpcServer for EthRpcServerImpl {
 			.ok_or(EthRpcError::AccountNotFound(from))?;
 
 		if transaction.gas.is_none() {
-			transaction.gas = Some(self.estimate_gas(transaction.clone(), None).await?);
+			transaction.gas = Some(self.estimate_gas(transaction.clone(), None, None, None).await?);
 		}
 
 		if transaction.gas_price.is_none() {
@@ -303,6 +364,26 @@ impl EthRpcServer for EthRpcServerImpl {
 		Ok(FilterResults::Logs(logs))
 	}
 
+	async fn get_filter_changes(&self, filter_id: U256) -> RpcResult<FilterResults> {
+		Ok(self.client.filter_changes(filter_id).await?)
+	}
+
+	async fn get_filter_logs(&self, filter_id: U256) -> RpcResult<FilterResults> {
+		let logs = self.client.filter_logs(filter_id).await?;
+		Ok(FilterResults::Logs(logs))
+	}
+
+	async fn get_proof(
+		&self,
+		address: H160,
+		storage_keys: Vec<U256>,
+		block: BlockNumberOrTagOrHash,
+	) -> RpcResult<AccountProof> {
+		let hash = self.client.block_hash_for_tag(block).await?;
+		let proof = self.client.get_proof(address, storage_keys, hash).await?;
+		Ok(proof)
+	}
+
 	async fn get_storage_at(
 		&self,
 		address: H160,
@@ -374,6 +455,22 @@ impl EthRpcServer for EthRpcServerImpl {
 		Ok(nonce)
 	}
 
+	async fn new_block_filter(&self) -> RpcResult<U256> {
+		Ok(self.client.new_block_filter().await?)
+	}
+
+	async fn new_filter(&self, filter: Filter) -> RpcResult<U256> {
+		Ok(self.client.new_filter(filter).await?)
+	}
+
+	async fn new_pending_transaction_filter(&self) -> RpcResult<U256> {
+		Ok(self.client.new_pending_transaction_filter().await?)
+	}
+
+	async fn uninstall_filter(&self, filter_id: U256) -> RpcResult<bool> {
+		Ok(self.client.uninstall_filter(filter_id).await)
+	}
+
 	async fn web3_client_version(&self) -> RpcResult<String> {
 		let git_revision = env!("GIT_REVISION");
 		let rustc_version = env!("RUSTC_VERSION");
@@ -392,3 +489,63 @@ impl EthRpcServer for EthRpcServerImpl {
 		Ok(result)
 	}
 }
+
+/// Convert a block simulated by the runtime into its `eth_simulateV1` representation.
+fn simulated_block_result(
+	block: pallet_revive::EthSimulatedBlock<client::Balance>,
+) -> SimulatedBlockResult {
+	let mut log_index = 0u32;
+	let mut gas_used = U256::zero();
+	let mut calls = Vec::with_capacity(block.calls.len());
+
+	for (transaction_index, call) in block.calls.into_iter().enumerate() {
+		let logs = call
+			.logs
+			.into_iter()
+			.map(|log| {
+				let log = Log {
+					address: log.address,
+					topics: log.topics,
+					data: Some(log.data),
+					block_number: block.number,
+					transaction_index: transaction_index.into(),
+					log_index: log_index.into(),
+					..Default::default()
+				};
+				log_index += 1;
+				log
+			})
+			.collect();
+
+		gas_used = gas_used.saturating_add(call.gas_used);
+		calls.push(match call.result {
+			Ok(info) => SimulatedCallResult {
+				status: U256::one(),
+				return_data: info.data.into(),
+				gas_used: call.gas_used,
+				logs,
+				error: None,
+			},
+			Err(err) => {
+				let data = match &err {
+					pallet_revive::EthTransactError::Data(data) => Some(Bytes(data.clone())),
+					pallet_revive::EthTransactError::Message(_) => None,
+				};
+				let err = ErrorObjectOwned::from(ClientError::TransactError(err));
+				SimulatedCallResult {
+					status: U256::zero(),
+					return_data: Default::default(),
+					gas_used: call.gas_used,
+					logs,
+					error: Some(SimulateCallError {
+						code: err.code(),
+						message: err.message().to_string(),
+						data,
+					}),
+				}
+			},
+		});
+	}
+
+	SimulatedBlockResult { number: block.number, timestamp: block.timestamp, gas_used, calls }
+}
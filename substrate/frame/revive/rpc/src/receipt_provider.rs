@@ -16,23 +16,22 @@
 // limitations under the License.
 use crate::{
 	client::{SubstrateBlock, SubstrateBlockNumber},
-	Address, AddressOrAddresses, BlockInfoProvider, BlockNumberOrTag, BlockTag, Bytes, ClientError,
-	FilterTopic, ReceiptExtractor, SubxtBlockInfoProvider, LOG_TARGET,
+	AddressOrAddresses, BlockInfoProvider, BlockNumberOrTag, BlockTag, ClientError, FilterTopic,
+	LogQuery, LogQueryBlocks, ReceiptExtractor, ReceiptStore, SubxtBlockInfoProvider, LOG_TARGET,
 };
 use pallet_revive::evm::{Filter, Log, ReceiptInfo, TransactionSigned};
 use sp_core::{H256, U256};
-use sqlx::{query, QueryBuilder, Row, Sqlite, SqlitePool};
 use std::{
 	collections::{BTreeMap, HashMap},
 	sync::Arc,
 };
 use tokio::sync::Mutex;
 
-/// ReceiptProvider stores transaction receipts and logs in a SQLite database.
+/// ReceiptProvider indexes transaction receipts and logs in a [`ReceiptStore`].
 #[derive(Clone)]
 pub struct ReceiptProvider<B: BlockInfoProvider = SubxtBlockInfoProvider> {
-	/// The database pool.
-	pool: SqlitePool,
+	/// The store used to index receipts and logs.
+	store: Arc<dyn ReceiptStore>,
 	/// The block provider used to fetch blocks, and reconstruct receipts.
 	block_provider: B,
 	/// A means to extract receipts from extrinsics.
@@ -63,66 +62,29 @@ impl BlockInfo for SubstrateBlock {
 }
 
 impl<B: BlockInfoProvider> ReceiptProvider<B> {
-	/// Create a new `ReceiptProvider` with the given database URL and block provider.
-	pub async fn new(
-		database_url: &str,
+	/// Create a new `ReceiptProvider` with the given store and block provider.
+	pub fn new(
+		store: Arc<dyn ReceiptStore>,
 		block_provider: B,
 		receipt_extractor: ReceiptExtractor,
 		keep_latest_n_blocks: Option<usize>,
-	) -> Result<Self, sqlx::Error> {
-		let pool = SqlitePool::connect(database_url).await?;
-		sqlx::migrate!().run(&pool).await?;
-		Ok(Self {
-			pool,
+	) -> Self {
+		Self {
+			store,
 			block_provider,
 			receipt_extractor,
 			keep_latest_n_blocks,
 			block_number_to_hash: Default::default(),
-		})
+		}
 	}
 
 	async fn fetch_row(&self, transaction_hash: &H256) -> Option<(H256, usize)> {
-		let transaction_hash = transaction_hash.as_ref();
-		let result = query!(
-			r#"
-			SELECT block_hash, transaction_index
-			FROM transaction_hashes
-			WHERE transaction_hash = $1
-			"#,
-			transaction_hash
-		)
-		.fetch_optional(&self.pool)
-		.await
-		.ok()??;
-
-		let block_hash = H256::from_slice(&result.block_hash[..]);
-		let transaction_index = result.transaction_index.try_into().ok()?;
-		Some((block_hash, transaction_index))
+		self.store.transaction_location(transaction_hash).await
 	}
 
 	/// Deletes older records from the database.
 	pub async fn remove(&self, block_hashes: &[H256]) -> Result<(), ClientError> {
-		if block_hashes.is_empty() {
-			return Ok(());
-		}
-		log::debug!(target: LOG_TARGET, "Removing block hashes: {block_hashes:?}");
-
-		let placeholders = vec!["?"; block_hashes.len()].join(", ");
-		let sql = format!("DELETE FROM transaction_hashes WHERE block_hash in ({placeholders})");
-		let mut delete_tx_query = sqlx::query(&sql);
-
-		let sql = format!("DELETE FROM logs WHERE block_hash in ({placeholders})");
-		let mut delete_logs_query = sqlx::query(&sql);
-
-		for block_hash in block_hashes {
-			delete_tx_query = delete_tx_query.bind(block_hash.as_ref());
-			delete_logs_query = delete_logs_query.bind(block_hash.as_ref());
-		}
-
-		let delete_transaction_hashes = delete_tx_query.execute(&self.pool);
-		let delete_logs = delete_logs_query.execute(&self.pool);
-		tokio::try_join!(delete_transaction_hashes, delete_logs)?;
-		Ok(())
+		self.store.remove(block_hashes).await
 	}
 
 	/// Check if the block is before the earliest block.
@@ -152,6 +114,13 @@ impl<B: BlockInfoProvider> ReceiptProvider<B> {
 		Ok(receipts)
 	}
 
+	/// Extract the receipts from the given block, replacing the receipts indexed for it.
+	pub async fn reindex_block_receipts(&self, block: &SubstrateBlock) -> Result<(), ClientError> {
+		let receipts = self.receipts_from_block(block).await?;
+		self.store.remove(&[block.hash()]).await?;
+		self.insert(block, &receipts).await
+	}
+
 	/// Insert receipts into the provider.
 	///
 	/// Note: Can be merged into `insert_block_receipts` once <https://github.com/paritytech/subxt/issues/1883> is fixed and subxt let
@@ -166,17 +135,7 @@ impl<B: BlockInfoProvider> ReceiptProvider<B> {
 		}
 
 		let block_hash = block.hash();
-		let block_hash_ref = block_hash.as_ref();
-		let block_number = block.number() as i64;
-
-		let result = sqlx::query!(
-			r#"SELECT EXISTS(SELECT 1 FROM transaction_hashes WHERE block_hash = $1) AS "exists!: bool""#,
-			block_hash_ref
-		)
-		.fetch_one(&self.pool)
-		.await?;
-
-		if result.exists {
+		if self.store.contains_block(&block_hash).await? {
 			return Ok(());
 		}
 
@@ -204,67 +163,11 @@ impl<B: BlockInfoProvider> ReceiptProvider<B> {
 			self.remove(&to_remove).await?;
 		}
 
-		for (_, receipt) in receipts {
-			let transaction_hash: &[u8] = receipt.transaction_hash.as_ref();
-			let transaction_index = receipt.transaction_index.as_u32() as i32;
-
-			query!(
-				r#"
-				INSERT OR REPLACE INTO transaction_hashes (transaction_hash, block_hash, transaction_index)
-				VALUES ($1, $2, $3)
-				"#,
-				transaction_hash,
-				block_hash_ref,
-				transaction_index
-			)
-			.execute(&self.pool)
-			.await?;
-
-			for log in &receipt.logs {
-				let log_index = log.log_index.as_u32() as i32;
-				let address: &[u8] = log.address.as_ref();
-
-				let topic_0 = log.topics.first().as_ref().map(|v| &v[..]);
-				let topic_1 = log.topics.get(1).as_ref().map(|v| &v[..]);
-				let topic_2 = log.topics.get(2).as_ref().map(|v| &v[..]);
-				let topic_3 = log.topics.get(3).as_ref().map(|v| &v[..]);
-				let data = log.data.as_ref().map(|v| &v.0[..]);
-
-				query!(
-					r#"
-					INSERT OR REPLACE INTO logs(
-						block_hash,
-						transaction_index,
-						log_index,
-						address,
-						block_number,
-						transaction_hash,
-						topic_0, topic_1, topic_2, topic_3,
-						data)
-					VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
-					"#,
-					block_hash_ref,
-					transaction_index,
-					log_index,
-					address,
-					block_number,
-					transaction_hash,
-					topic_0,
-					topic_1,
-					topic_2,
-					topic_3,
-					data
-				)
-				.execute(&self.pool)
-				.await?;
-			}
-		}
-		Ok(())
+		self.store.insert(block_hash, block.number(), receipts).await
 	}
 
 	/// Get logs that match the given filter.
 	pub async fn logs(&self, filter: Option<Filter>) -> anyhow::Result<Vec<Log>> {
-		let mut qb = QueryBuilder::<Sqlite>::new("SELECT logs.* FROM logs WHERE 1=1");
 		let filter = filter.unwrap_or_default();
 
 		let latest_block = U256::from(self.block_provider.latest_block_number().await);
@@ -279,7 +182,7 @@ impl<B: BlockInfoProvider> ReceiptProvider<B> {
 		let from_block = as_block_number(filter.from_block)?;
 		let to_block = as_block_number(filter.to_block)?;
 
-		match (from_block, to_block, filter.block_hash) {
+		let blocks = match (from_block, to_block, filter.block_hash) {
 			(Some(_), _, Some(_)) | (_, Some(_), Some(_)) => {
 				anyhow::bail!("block number and block hash cannot be used together");
 			},
@@ -290,124 +193,38 @@ impl<B: BlockInfoProvider> ReceiptProvider<B> {
 			(Some(from_block), Some(to_block), None) if from_block > to_block => {
 				anyhow::bail!("invalid block range params");
 			},
-			(Some(from_block), Some(to_block), None) if from_block == to_block => {
-				qb.push(" AND block_number = ").push_bind(from_block.as_u64() as i64);
-			},
-			(Some(from_block), Some(to_block), None) => {
-				qb.push(" AND block_number BETWEEN ")
-					.push_bind(from_block.as_u64() as i64)
-					.push(" AND ")
-					.push_bind(to_block.as_u64() as i64);
-			},
-			(Some(from_block), None, None) => {
-				qb.push(" AND block_number >= ").push_bind(from_block.as_u64() as i64);
-			},
-			(None, Some(to_block), None) => {
-				qb.push(" AND block_number <= ").push_bind(to_block.as_u64() as i64);
-			},
-			(None, None, Some(hash)) => {
-				qb.push(" AND block_hash = ").push_bind(hash.0.to_vec());
-			},
-			(None, None, None) => {
-				qb.push(" AND block_number = ").push_bind(latest_block.as_u64() as i64);
-			},
-		}
-
-		if let Some(addresses) = filter.address {
-			match addresses {
-				AddressOrAddresses::Address(addr) => {
-					qb.push(" AND address = ").push_bind(addr.0.to_vec());
-				},
-				AddressOrAddresses::Addresses(addrs) => {
-					qb.push(" AND address IN (");
-					let mut separated = qb.separated(", ");
-					for addr in addrs {
-						separated.push_bind(addr.0.to_vec());
-					}
-					separated.push_unseparated(")");
-				},
-			}
-		}
+			(None, None, Some(hash)) => LogQueryBlocks::Hash(hash),
+			(None, None, None) => LogQueryBlocks::Range(latest_block.as_u32(), latest_block.as_u32()),
+			(from_block, to_block, None) => LogQueryBlocks::Range(
+				from_block.unwrap_or_default().as_u32(),
+				to_block.unwrap_or(latest_block).as_u32(),
+			),
+		};
 
-		if let Some(topics) = filter.topics {
-			if topics.len() > 4 {
-				return Err(anyhow::anyhow!("exceed max topics"));
-			}
+		let addresses = filter.address.map(|addresses| match addresses {
+			AddressOrAddresses::Address(addr) => vec![addr],
+			AddressOrAddresses::Addresses(addrs) => addrs,
+		});
 
-			for (i, topic) in topics.into_iter().enumerate() {
-				match topic {
-					FilterTopic::Single(hash) => {
-						qb.push(format_args!(" AND topic_{i} = ")).push_bind(hash.0.to_vec());
-					},
-					FilterTopic::Multiple(hashes) => {
-						qb.push(format_args!(" AND topic_{i} IN ("));
-						let mut separated = qb.separated(", ");
-						for hash in hashes {
-							separated.push_bind(hash.0.to_vec());
-						}
-						separated.push_unseparated(")");
-					},
-				}
-			}
+		let topics = filter.topics.unwrap_or_default();
+		if topics.len() > 4 {
+			return Err(anyhow::anyhow!("exceed max topics"));
 		}
 
-		qb.push(" LIMIT 10000");
-
-		let logs = qb
-			.build()
-			.try_map(|row| {
-				let block_hash: Vec<u8> = row.try_get("block_hash")?;
-				let transaction_index: i64 = row.try_get("transaction_index")?;
-				let log_index: i64 = row.try_get("log_index")?;
-				let address: Vec<u8> = row.try_get("address")?;
-				let block_number: i64 = row.try_get("block_number")?;
-				let transaction_hash: Vec<u8> = row.try_get("transaction_hash")?;
-				let topic_0: Option<Vec<u8>> = row.try_get("topic_0")?;
-				let topic_1: Option<Vec<u8>> = row.try_get("topic_1")?;
-				let topic_2: Option<Vec<u8>> = row.try_get("topic_2")?;
-				let topic_3: Option<Vec<u8>> = row.try_get("topic_3")?;
-				let data: Option<Vec<u8>> = row.try_get("data")?;
-
-				let topics = [topic_0, topic_1, topic_2, topic_3]
-					.iter()
-					.filter_map(|t| t.as_ref().map(|t| H256::from_slice(t)))
-					.collect::<Vec<_>>();
-
-				Ok(Log {
-					address: Address::from_slice(&address),
-					block_hash: H256::from_slice(&block_hash),
-					block_number: U256::from(block_number as u64),
-					data: data.map(Bytes::from),
-					log_index: U256::from(log_index as u64),
-					topics,
-					transaction_hash: H256::from_slice(&transaction_hash),
-					transaction_index: U256::from(transaction_index as u64),
-					removed: None,
-				})
+		let topics = topics
+			.into_iter()
+			.map(|topic| match topic {
+				FilterTopic::Single(hash) => vec![hash],
+				FilterTopic::Multiple(hashes) => hashes,
 			})
-			.fetch_all(&self.pool)
-			.await?;
+			.collect();
 
-		Ok(logs)
+		self.store.logs(&LogQuery { blocks, addresses, topics }).await
 	}
 
 	/// Get the number of receipts per block.
 	pub async fn receipts_count_per_block(&self, block_hash: &H256) -> Option<usize> {
-		let block_hash = block_hash.as_ref();
-		let row = query!(
-			r#"
-            SELECT COUNT(*) as count
-            FROM transaction_hashes
-            WHERE block_hash = $1
-            "#,
-			block_hash
-		)
-		.fetch_one(&self.pool)
-		.await
-		.ok()?;
-
-		let count = row.count as usize;
-		Some(count)
+		self.store.receipts_count_per_block(block_hash).await
 	}
 
 	/// Return all transaction hashes for the given block hash.
@@ -415,25 +232,7 @@ impl<B: BlockInfoProvider> ReceiptProvider<B> {
 		&self,
 		block_hash: &H256,
 	) -> Option<HashMap<usize, H256>> {
-		let block_hash = block_hash.as_ref();
-		let rows = query!(
-			r#"
-		      SELECT transaction_index, transaction_hash
-		      FROM transaction_hashes
-		      WHERE block_hash = $1
-		      "#,
-			block_hash
-		)
-		.map(|row| {
-			let transaction_index = row.transaction_index as usize;
-			let transaction_hash = H256::from_slice(&row.transaction_hash);
-			(transaction_index, transaction_hash)
-		})
-		.fetch_all(&self.pool)
-		.await
-		.ok()?;
-
-		Some(rows.into_iter().collect())
+		self.store.block_transaction_hashes(block_hash).await
 	}
 
 	/// Get the receipt for the given block hash and transaction index.
@@ -466,21 +265,7 @@ impl<B: BlockInfoProvider> ReceiptProvider<B> {
 
 	/// Get the signed transaction for the given transaction hash.
 	pub async fn signed_tx_by_hash(&self, transaction_hash: &H256) -> Option<TransactionSigned> {
-		let transaction_hash = transaction_hash.as_ref();
-		let result = query!(
-			r#"
-			SELECT block_hash, transaction_index
-			FROM transaction_hashes
-			WHERE transaction_hash = $1
-			"#,
-			transaction_hash
-		)
-		.fetch_optional(&self.pool)
-		.await
-		.ok()??;
-
-		let block_hash = H256::from_slice(&result.block_hash[..]);
-		let transaction_index = result.transaction_index.try_into().ok()?;
+		let (block_hash, transaction_index) = self.fetch_row(transaction_hash).await?;
 
 		let block = self.block_provider.block_by_hash(&block_hash).await.ok()??;
 		let (signed_tx, _) = self
@@ -495,7 +280,10 @@ impl<B: BlockInfoProvider> ReceiptProvider<B> {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::test::{MockBlockInfo, MockBlockInfoProvider};
+	use crate::{
+		test::{MockBlockInfo, MockBlockInfoProvider},
+		SqliteReceiptStore,
+	};
 	use pallet_revive::evm::{ReceiptInfo, TransactionSigned};
 	use pretty_assertions::assert_eq;
 	use sp_core::{H160, H256};
@@ -519,18 +307,17 @@ mod tests {
 	}
 
 	async fn setup_sqlite_provider(pool: SqlitePool) -> ReceiptProvider<MockBlockInfoProvider> {
-		ReceiptProvider {
-			pool,
-			block_provider: MockBlockInfoProvider {},
-			receipt_extractor: ReceiptExtractor::new_mock(),
-			keep_latest_n_blocks: Some(10),
-			block_number_to_hash: Default::default(),
-		}
+		ReceiptProvider::new(
+			Arc::new(SqliteReceiptStore { pool }),
+			MockBlockInfoProvider {},
+			ReceiptExtractor::new_mock(),
+			Some(10),
+		)
 	}
 
 	#[sqlx::test]
 	async fn test_insert_remove(pool: SqlitePool) -> anyhow::Result<()> {
-		let provider = setup_sqlite_provider(pool).await;
+		let provider = setup_sqlite_provider(pool.clone()).await;
 		let block = MockBlockInfo { hash: H256::default(), number: 0 };
 		let receipts = vec![(
 			TransactionSigned::default(),
@@ -545,14 +332,14 @@ mod tests {
 		assert_eq!(row, Some((block.hash, 0)));
 
 		provider.remove(&[block.hash()]).await?;
-		assert_eq!(count(&provider.pool, "transaction_hashes", Some(block.hash())).await, 0);
-		assert_eq!(count(&provider.pool, "logs", Some(block.hash())).await, 0);
+		assert_eq!(count(&pool, "transaction_hashes", Some(block.hash())).await, 0);
+		assert_eq!(count(&pool, "logs", Some(block.hash())).await, 0);
 		Ok(())
 	}
 
 	#[sqlx::test]
 	async fn test_prune(pool: SqlitePool) -> anyhow::Result<()> {
-		let provider = setup_sqlite_provider(pool).await;
+		let provider = setup_sqlite_provider(pool.clone()).await;
 		let n = provider.keep_latest_n_blocks.unwrap();
 
 		for i in 0..2 * n {
@@ -572,8 +359,8 @@ mod tests {
 			)];
 			provider.insert(&block, &receipts).await?;
 		}
-		assert_eq!(count(&provider.pool, "transaction_hashes", None).await, n);
-		assert_eq!(count(&provider.pool, "logs", None).await, n);
+		assert_eq!(count(&pool, "transaction_hashes", None).await, n);
+		assert_eq!(count(&pool, "logs", None).await, n);
 		assert_eq!(provider.block_number_to_hash.lock().await.len(), n);
 
 		return Ok(());
@@ -581,7 +368,7 @@ mod tests {
 
 	#[sqlx::test]
 	async fn test_fork(pool: SqlitePool) -> anyhow::Result<()> {
-		let provider = setup_sqlite_provider(pool).await;
+		let provider = setup_sqlite_provider(pool.clone()).await;
 
 		for i in [1u8, 2u8] {
 			let block = MockBlockInfo { hash: H256::from([i; 32]), number: 1 };
@@ -600,8 +387,8 @@ mod tests {
 			)];
 			provider.insert(&block, &receipts).await?;
 		}
-		assert_eq!(count(&provider.pool, "transaction_hashes", None).await, 1);
-		assert_eq!(count(&provider.pool, "logs", None).await, 1);
+		assert_eq!(count(&pool, "transaction_hashes", None).await, 1);
+		assert_eq!(count(&pool, "logs", None).await, 1);
 		assert_eq!(
 			provider.block_number_to_hash.lock().await.clone(),
 			[(1, H256::from([2u8; 32]))].into(),
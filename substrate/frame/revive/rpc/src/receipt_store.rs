@@ -0,0 +1,98 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Storage backends used by the [`crate::ReceiptProvider`] to index receipts and logs.
+
+use crate::{client::SubstrateBlockNumber, Address, ClientError};
+use jsonrpsee::core::async_trait;
+use pallet_revive::evm::{Log, ReceiptInfo, TransactionSigned};
+use sp_core::H256;
+use std::collections::HashMap;
+
+mod kv;
+pub use kv::*;
+
+mod sqlite;
+pub use sqlite::*;
+
+/// The maximum number of logs returned by a single query.
+pub const MAX_LOGS_PER_QUERY: usize = 10_000;
+
+/// The blocks a [`LogQuery`] applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogQueryBlocks {
+	/// The blocks in the given inclusive range of block numbers.
+	Range(SubstrateBlockNumber, SubstrateBlockNumber),
+	/// The block with the given hash.
+	Hash(H256),
+}
+
+/// A validated log filter, executed by a [`ReceiptStore`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogQuery {
+	/// The blocks to search.
+	pub blocks: LogQueryBlocks,
+	/// When `Some`, only logs emitted by one of these addresses match.
+	pub addresses: Option<Vec<Address>>,
+	/// For each position, the topics that match at that position.
+	pub topics: Vec<Vec<H256>>,
+}
+
+impl LogQuery {
+	/// Returns whether the given log matches the address and topics of the query.
+	pub fn matches(&self, log: &Log) -> bool {
+		let address_matches =
+			self.addresses.as_ref().map_or(true, |addresses| addresses.contains(&log.address));
+
+		address_matches &&
+			self.topics.iter().enumerate().all(|(i, topics)| {
+				log.topics.get(i).is_some_and(|topic| topics.contains(topic))
+			})
+	}
+}
+
+/// A store indexing the transaction hashes and logs of the blocks seen by the RPC server.
+///
+/// Receipts themselves are not stored, they are reconstructed from the blocks by the
+/// [`crate::ReceiptProvider`].
+#[async_trait]
+pub trait ReceiptStore: Send + Sync {
+	/// Returns whether the receipts of the given block are indexed.
+	async fn contains_block(&self, block_hash: &H256) -> Result<bool, ClientError>;
+
+	/// Index the receipts of the given block.
+	async fn insert(
+		&self,
+		block_hash: H256,
+		block_number: SubstrateBlockNumber,
+		receipts: &[(TransactionSigned, ReceiptInfo)],
+	) -> Result<(), ClientError>;
+
+	/// Remove the indexed receipts of the given blocks.
+	async fn remove(&self, block_hashes: &[H256]) -> Result<(), ClientError>;
+
+	/// Get the block hash and transaction index of the given transaction.
+	async fn transaction_location(&self, transaction_hash: &H256) -> Option<(H256, usize)>;
+
+	/// Get the number of receipts indexed for the given block.
+	async fn receipts_count_per_block(&self, block_hash: &H256) -> Option<usize>;
+
+	/// Get the transaction hashes of the given block, indexed by transaction index.
+	async fn block_transaction_hashes(&self, block_hash: &H256) -> Option<HashMap<usize, H256>>;
+
+	/// Get the logs matching the given query, up to [`MAX_LOGS_PER_QUERY`].
+	async fn logs(&self, query: &LogQuery) -> anyhow::Result<Vec<Log>>;
+}
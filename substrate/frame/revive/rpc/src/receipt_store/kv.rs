@@ -0,0 +1,436 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{
+	client::SubstrateBlockNumber, ClientError, LogQuery, LogQueryBlocks, ReceiptStore, LOG_TARGET,
+	MAX_LOGS_PER_QUERY,
+};
+use codec::{Decode, Encode};
+use jsonrpsee::core::async_trait;
+use pallet_revive::evm::{Log, ReceiptInfo, TransactionSigned};
+use sp_core::{keccak_256, H256};
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+/// Maps a transaction hash to its [`TransactionLocation`].
+const COL_TRANSACTIONS: u8 = 0;
+/// Maps a block hash to its [`BlockEntry`].
+const COL_BLOCKS: u8 = 1;
+/// Maps a block hash to the JSON encoded logs of the block.
+const COL_LOGS: u8 = 2;
+/// Maps a big endian block number to the hash of the last block indexed at that height.
+///
+/// The column is B-tree indexed, so that block ranges are iterated in number order.
+const COL_BLOCK_NUMBERS: u8 = 3;
+const NUM_COLUMNS: u8 = 4;
+
+/// The location of a transaction.
+#[derive(Encode, Decode)]
+struct TransactionLocation {
+	block_hash: H256,
+	transaction_index: u32,
+}
+
+/// The index entry of a block.
+#[derive(Encode, Decode)]
+struct BlockEntry {
+	/// The number of the block.
+	number: SubstrateBlockNumber,
+	/// The hashes of the transactions of the block, ordered by transaction index.
+	transaction_hashes: Vec<H256>,
+	/// The bloom filter of the addresses and topics of all the logs of the block.
+	bloom: LogsBloom,
+}
+
+/// A 2048 bits bloom filter, computed the same way as the Ethereum `logsBloom`.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+struct LogsBloom([u8; 256]);
+
+impl Default for LogsBloom {
+	fn default() -> Self {
+		Self([0u8; 256])
+	}
+}
+
+impl LogsBloom {
+	/// The bit positions set by the given input.
+	fn bits(input: &[u8]) -> [usize; 3] {
+		let hash = keccak_256(input);
+		[0, 2, 4].map(|i| (hash[i + 1] as usize + ((hash[i] as usize) << 8)) & 0x7FF)
+	}
+
+	fn accrue(&mut self, input: &[u8]) {
+		for bit in Self::bits(input) {
+			self.0[256 - 1 - bit / 8] |= 1 << (bit % 8);
+		}
+	}
+
+	fn contains(&self, input: &[u8]) -> bool {
+		Self::bits(input)
+			.into_iter()
+			.all(|bit| self.0[256 - 1 - bit / 8] & (1 << (bit % 8)) != 0)
+	}
+
+	/// Returns whether a block with this bloom may contain logs matching the query.
+	fn may_match(&self, query: &LogQuery) -> bool {
+		let address_matches = query.addresses.as_ref().map_or(true, |addresses| {
+			addresses.iter().any(|address| self.contains(address.as_ref()))
+		});
+
+		address_matches &&
+			query
+				.topics
+				.iter()
+				.all(|topics| topics.iter().any(|topic| self.contains(topic.as_ref())))
+	}
+}
+
+/// A [`ReceiptStore`] backed by an embedded parity-db key-value database.
+///
+/// Every block entry carries a bloom filter of its logs, so that log queries over a block range
+/// only need to load the logs of the blocks that may contain matching entries.
+///
+/// Database accesses block, so they run on the blocking thread pool of the tokio runtime.
+#[derive(Clone)]
+pub struct KvReceiptStore {
+	db: Arc<parity_db::Db>,
+}
+
+impl KvReceiptStore {
+	/// Open or create the database at the given path.
+	pub fn open(path: &Path) -> Result<Self, ClientError> {
+		let mut options = parity_db::Options::with_columns(path, NUM_COLUMNS);
+		options.columns[COL_BLOCK_NUMBERS as usize].btree_index = true;
+		let db = parity_db::Db::open_or_create(&options)?;
+		Ok(Self { db: Arc::new(db) })
+	}
+
+	/// Run the given database operation on the blocking thread pool.
+	async fn blocking<R: Send + 'static>(&self, f: impl FnOnce(&Self) -> R + Send + 'static) -> R {
+		let this = self.clone();
+		match tokio::task::spawn_blocking(move || f(&this)).await {
+			Ok(result) => result,
+			Err(err) => std::panic::resume_unwind(err.into_panic()),
+		}
+	}
+
+	fn get<T: Decode>(&self, col: u8, key: &[u8]) -> Result<Option<T>, ClientError> {
+		self.db
+			.get(col, key)?
+			.map(|value| T::decode(&mut &value[..]))
+			.transpose()
+			.map_err(Into::into)
+	}
+
+	fn block_logs(&self, block_hash: &H256) -> anyhow::Result<Vec<Log>> {
+		match self.db.get(COL_LOGS, block_hash.as_ref())? {
+			Some(logs) => Ok(serde_json::from_slice(&logs)?),
+			None => Ok(Vec::new()),
+		}
+	}
+
+	/// Append the logs of the given block matching the query to `logs`, up to
+	/// [`MAX_LOGS_PER_QUERY`]. Returns `false` once the limit is reached.
+	fn collect_block_logs(
+		&self,
+		block_hash: &H256,
+		query: &LogQuery,
+		logs: &mut Vec<Log>,
+	) -> anyhow::Result<bool> {
+		let Some(entry) = self.get::<BlockEntry>(COL_BLOCKS, block_hash.as_ref())? else {
+			return Ok(true);
+		};
+
+		if !entry.bloom.may_match(query) {
+			return Ok(true);
+		}
+
+		for log in self.block_logs(block_hash)? {
+			if query.matches(&log) {
+				logs.push(log);
+			}
+
+			if logs.len() >= MAX_LOGS_PER_QUERY {
+				return Ok(false);
+			}
+		}
+
+		Ok(true)
+	}
+
+	fn logs_blocking(&self, query: &LogQuery) -> anyhow::Result<Vec<Log>> {
+		let mut logs = Vec::new();
+		match query.blocks {
+			LogQueryBlocks::Hash(block_hash) => {
+				self.collect_block_logs(&block_hash, query, &mut logs)?;
+			},
+			LogQueryBlocks::Range(from, to) => {
+				// Only the indexed blocks of the range are visited, in number order.
+				let mut iter = self.db.iter(COL_BLOCK_NUMBERS)?;
+				iter.seek(&from.to_be_bytes())?;
+				while let Some((number, block_hash)) = iter.next()? {
+					if number[..] > to.to_be_bytes()[..] {
+						break;
+					}
+
+					let block_hash = H256::decode(&mut &block_hash[..])?;
+					if !self.collect_block_logs(&block_hash, query, &mut logs)? {
+						break;
+					}
+				}
+			},
+		}
+
+		Ok(logs)
+	}
+}
+
+#[async_trait]
+impl ReceiptStore for KvReceiptStore {
+	async fn contains_block(&self, block_hash: &H256) -> Result<bool, ClientError> {
+		let block_hash = *block_hash;
+		self.blocking(move |this| Ok(this.db.get(COL_BLOCKS, block_hash.as_ref())?.is_some()))
+			.await
+	}
+
+	async fn insert(
+		&self,
+		block_hash: H256,
+		block_number: SubstrateBlockNumber,
+		receipts: &[(TransactionSigned, ReceiptInfo)],
+	) -> Result<(), ClientError> {
+		let mut bloom = LogsBloom::default();
+		let mut logs = Vec::new();
+		let mut transaction_hashes = Vec::with_capacity(receipts.len());
+		let mut changes = Vec::with_capacity(receipts.len() + 3);
+
+		for (_, receipt) in receipts {
+			changes.push((
+				COL_TRANSACTIONS,
+				receipt.transaction_hash.as_bytes().to_vec(),
+				Some(
+					TransactionLocation {
+						block_hash,
+						transaction_index: receipt.transaction_index.as_u32(),
+					}
+					.encode(),
+				),
+			));
+			transaction_hashes.push(receipt.transaction_hash);
+
+			for log in &receipt.logs {
+				bloom.accrue(log.address.as_ref());
+				for topic in &log.topics {
+					bloom.accrue(topic.as_ref());
+				}
+				logs.push(log.clone());
+			}
+		}
+
+		let entry = BlockEntry { number: block_number, transaction_hashes, bloom };
+		let logs = serde_json::to_vec(&logs)?;
+		changes.push((COL_BLOCKS, block_hash.as_bytes().to_vec(), Some(entry.encode())));
+		changes.push((COL_LOGS, block_hash.as_bytes().to_vec(), Some(logs)));
+		changes.push((
+			COL_BLOCK_NUMBERS,
+			block_number.to_be_bytes().to_vec(),
+			Some(block_hash.encode()),
+		));
+
+		self.blocking(move |this| Ok(this.db.commit(changes)?)).await
+	}
+
+	async fn remove(&self, block_hashes: &[H256]) -> Result<(), ClientError> {
+		if block_hashes.is_empty() {
+			return Ok(());
+		}
+		log::debug!(target: LOG_TARGET, "Removing block hashes: {block_hashes:?}");
+
+		let block_hashes = block_hashes.to_vec();
+		self.blocking(move |this| {
+			let mut changes = Vec::new();
+			for block_hash in block_hashes {
+				let Some(entry) = this.get::<BlockEntry>(COL_BLOCKS, block_hash.as_ref())? else {
+					continue;
+				};
+
+				for transaction_hash in &entry.transaction_hashes {
+					let location = this
+						.get::<TransactionLocation>(COL_TRANSACTIONS, transaction_hash.as_ref())?;
+
+					// The transaction may have been re-indexed in another block.
+					if location.is_some_and(|location| location.block_hash == block_hash) {
+						changes.push((
+							COL_TRANSACTIONS,
+							transaction_hash.as_bytes().to_vec(),
+							None,
+						));
+					}
+				}
+
+				let number_key = entry.number.to_be_bytes();
+				if this.get::<H256>(COL_BLOCK_NUMBERS, &number_key)? == Some(block_hash) {
+					changes.push((COL_BLOCK_NUMBERS, number_key.to_vec(), None));
+				}
+
+				changes.push((COL_BLOCKS, block_hash.as_bytes().to_vec(), None));
+				changes.push((COL_LOGS, block_hash.as_bytes().to_vec(), None));
+			}
+
+			Ok(this.db.commit(changes)?)
+		})
+		.await
+	}
+
+	async fn transaction_location(&self, transaction_hash: &H256) -> Option<(H256, usize)> {
+		let transaction_hash = *transaction_hash;
+		self.blocking(move |this| {
+			let location = this
+				.get::<TransactionLocation>(COL_TRANSACTIONS, transaction_hash.as_ref())
+				.ok()??;
+			Some((location.block_hash, location.transaction_index as usize))
+		})
+		.await
+	}
+
+	async fn receipts_count_per_block(&self, block_hash: &H256) -> Option<usize> {
+		let block_hash = *block_hash;
+		self.blocking(move |this| {
+			let count = this
+				.get::<BlockEntry>(COL_BLOCKS, block_hash.as_ref())
+				.ok()?
+				.map_or(0, |entry| entry.transaction_hashes.len());
+			Some(count)
+		})
+		.await
+	}
+
+	async fn block_transaction_hashes(&self, block_hash: &H256) -> Option<HashMap<usize, H256>> {
+		let block_hash = *block_hash;
+		self.blocking(move |this| {
+			let entry = this.get::<BlockEntry>(COL_BLOCKS, block_hash.as_ref()).ok()?;
+			let transaction_hashes =
+				entry.map(|entry| entry.transaction_hashes).unwrap_or_default();
+			Some(transaction_hashes.into_iter().enumerate().collect())
+		})
+		.await
+	}
+
+	async fn logs(&self, query: &LogQuery) -> anyhow::Result<Vec<Log>> {
+		let query = query.clone();
+		self.blocking(move |this| this.logs_blocking(&query)).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use sp_core::{H160, U256};
+
+	fn receipt(block_hash: H256, transaction_hash: H256, logs: Vec<Log>) -> ReceiptInfo {
+		let logs = logs
+			.into_iter()
+			.map(|log| Log { block_hash, transaction_hash, ..log })
+			.collect();
+		ReceiptInfo { transaction_hash, logs, ..Default::default() }
+	}
+
+	#[tokio::test]
+	async fn kv_store_works() -> anyhow::Result<()> {
+		let dir = tempfile::tempdir()?;
+		let store = KvReceiptStore::open(dir.path())?;
+
+		let block1 = H256::from([1u8; 32]);
+		let block2 = H256::from([2u8; 32]);
+		let log1 = Log {
+			address: H160::from([1u8; 20]),
+			topics: vec![H256::from([1u8; 32])],
+			block_number: U256::from(1),
+			..Default::default()
+		};
+		let log2 = Log {
+			address: H160::from([2u8; 20]),
+			topics: vec![H256::from([2u8; 32])],
+			block_number: U256::from(2),
+			..Default::default()
+		};
+
+		let receipt1 = receipt(block1, H256::from([11u8; 32]), vec![log1.clone()]);
+		let receipt2 = receipt(block2, H256::from([12u8; 32]), vec![log2.clone()]);
+		store
+			.insert(block1, 1, &[(TransactionSigned::default(), receipt1.clone())])
+			.await?;
+		store
+			.insert(block2, 2, &[(TransactionSigned::default(), receipt2.clone())])
+			.await?;
+
+		assert!(store.contains_block(&block1).await?);
+		assert_eq!(store.transaction_location(&receipt2.transaction_hash).await, Some((block2, 0)));
+		assert_eq!(store.receipts_count_per_block(&block1).await, Some(1));
+
+		let query = LogQuery {
+			blocks: LogQueryBlocks::Range(0, 2),
+			addresses: None,
+			topics: vec![vec![H256::from([2u8; 32])]],
+		};
+		assert_eq!(store.logs(&query).await?, receipt2.logs);
+
+		let query = LogQuery {
+			blocks: LogQueryBlocks::Hash(block1),
+			addresses: Some(vec![log1.address]),
+			topics: vec![],
+		};
+		assert_eq!(store.logs(&query).await?, receipt1.logs);
+
+		// ranges only visit the indexed blocks, in number order
+		let block3 = H256::from([3u8; 32]);
+		let log3 = Log { block_number: U256::from(1_000), ..log1.clone() };
+		let receipt3 = receipt(block3, H256::from([13u8; 32]), vec![log3]);
+		store
+			.insert(block3, 1_000, &[(TransactionSigned::default(), receipt3.clone())])
+			.await?;
+		let query = |from, to| LogQuery {
+			blocks: LogQueryBlocks::Range(from, to),
+			addresses: None,
+			topics: vec![],
+		};
+		assert_eq!(store.logs(&query(3, 999)).await?, vec![]);
+		assert_eq!(
+			store.logs(&query(0, u32::MAX)).await?,
+			[&receipt1.logs[..], &receipt2.logs[..], &receipt3.logs[..]].concat()
+		);
+
+		store.remove(&[block1]).await?;
+		assert!(!store.contains_block(&block1).await?);
+		assert_eq!(store.transaction_location(&receipt1.transaction_hash).await, None);
+		assert_eq!(store.logs(&query(0, 2)).await?, receipt2.logs);
+		Ok(())
+	}
+
+	#[test]
+	fn logs_bloom_works() {
+		let mut bloom = LogsBloom::default();
+		bloom.accrue(H160::from([1u8; 20]).as_ref());
+
+		let query = |address: H160| LogQuery {
+			blocks: LogQueryBlocks::Range(0, 0),
+			addresses: Some(vec![address]),
+			topics: vec![],
+		};
+		assert!(bloom.may_match(&query(H160::from([1u8; 20]))));
+		assert!(!bloom.may_match(&query(H160::from([2u8; 20]))));
+	}
+}
@@ -0,0 +1,282 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{
+	client::SubstrateBlockNumber, Address, Bytes, ClientError, LogQuery, LogQueryBlocks,
+	ReceiptStore, LOG_TARGET, MAX_LOGS_PER_QUERY,
+};
+use jsonrpsee::core::async_trait;
+use pallet_revive::evm::{Log, ReceiptInfo, TransactionSigned};
+use sp_core::{H256, U256};
+use sqlx::{query, QueryBuilder, Row, Sqlite, SqlitePool};
+use std::collections::HashMap;
+
+/// A [`ReceiptStore`] backed by a SQLite database.
+#[derive(Clone)]
+pub struct SqliteReceiptStore {
+	/// The database pool.
+	pub(crate) pool: SqlitePool,
+}
+
+impl SqliteReceiptStore {
+	/// Connect to the database at the given URL and run the pending migrations.
+	pub async fn new(database_url: &str) -> Result<Self, sqlx::Error> {
+		let pool = SqlitePool::connect(database_url).await?;
+		sqlx::migrate!().run(&pool).await?;
+		Ok(Self { pool })
+	}
+}
+
+#[async_trait]
+impl ReceiptStore for SqliteReceiptStore {
+	async fn contains_block(&self, block_hash: &H256) -> Result<bool, ClientError> {
+		let block_hash_ref = block_hash.as_ref();
+		let result = sqlx::query!(
+			r#"SELECT EXISTS(SELECT 1 FROM transaction_hashes WHERE block_hash = $1) AS "exists!: bool""#,
+			block_hash_ref
+		)
+		.fetch_one(&self.pool)
+		.await?;
+
+		Ok(result.exists)
+	}
+
+	async fn insert(
+		&self,
+		block_hash: H256,
+		block_number: SubstrateBlockNumber,
+		receipts: &[(TransactionSigned, ReceiptInfo)],
+	) -> Result<(), ClientError> {
+		let block_hash_ref = block_hash.as_ref();
+		let block_number = block_number as i64;
+
+		for (_, receipt) in receipts {
+			let transaction_hash: &[u8] = receipt.transaction_hash.as_ref();
+			let transaction_index = receipt.transaction_index.as_u32() as i32;
+
+			query!(
+				r#"
+				INSERT OR REPLACE INTO transaction_hashes (transaction_hash, block_hash, transaction_index)
+				VALUES ($1, $2, $3)
+				"#,
+				transaction_hash,
+				block_hash_ref,
+				transaction_index
+			)
+			.execute(&self.pool)
+			.await?;
+
+			for log in &receipt.logs {
+				let log_index = log.log_index.as_u32() as i32;
+				let address: &[u8] = log.address.as_ref();
+
+				let topic_0 = log.topics.first().as_ref().map(|v| &v[..]);
+				let topic_1 = log.topics.get(1).as_ref().map(|v| &v[..]);
+				let topic_2 = log.topics.get(2).as_ref().map(|v| &v[..]);
+				let topic_3 = log.topics.get(3).as_ref().map(|v| &v[..]);
+				let data = log.data.as_ref().map(|v| &v.0[..]);
+
+				query!(
+					r#"
+					INSERT OR REPLACE INTO logs(
+						block_hash,
+						transaction_index,
+						log_index,
+						address,
+						block_number,
+						transaction_hash,
+						topic_0, topic_1, topic_2, topic_3,
+						data)
+					VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+					"#,
+					block_hash_ref,
+					transaction_index,
+					log_index,
+					address,
+					block_number,
+					transaction_hash,
+					topic_0,
+					topic_1,
+					topic_2,
+					topic_3,
+					data
+				)
+				.execute(&self.pool)
+				.await?;
+			}
+		}
+		Ok(())
+	}
+
+	async fn remove(&self, block_hashes: &[H256]) -> Result<(), ClientError> {
+		if block_hashes.is_empty() {
+			return Ok(());
+		}
+		log::debug!(target: LOG_TARGET, "Removing block hashes: {block_hashes:?}");
+
+		let placeholders = vec!["?"; block_hashes.len()].join(", ");
+		let sql = format!("DELETE FROM transaction_hashes WHERE block_hash in ({placeholders})");
+		let mut delete_tx_query = sqlx::query(&sql);
+
+		let sql = format!("DELETE FROM logs WHERE block_hash in ({placeholders})");
+		let mut delete_logs_query = sqlx::query(&sql);
+
+		for block_hash in block_hashes {
+			delete_tx_query = delete_tx_query.bind(block_hash.as_ref());
+			delete_logs_query = delete_logs_query.bind(block_hash.as_ref());
+		}
+
+		let delete_transaction_hashes = delete_tx_query.execute(&self.pool);
+		let delete_logs = delete_logs_query.execute(&self.pool);
+		tokio::try_join!(delete_transaction_hashes, delete_logs)?;
+		Ok(())
+	}
+
+	async fn transaction_location(&self, transaction_hash: &H256) -> Option<(H256, usize)> {
+		let transaction_hash = transaction_hash.as_ref();
+		let result = query!(
+			r#"
+			SELECT block_hash, transaction_index
+			FROM transaction_hashes
+			WHERE transaction_hash = $1
+			"#,
+			transaction_hash
+		)
+		.fetch_optional(&self.pool)
+		.await
+		.ok()??;
+
+		let block_hash = H256::from_slice(&result.block_hash[..]);
+		let transaction_index = result.transaction_index.try_into().ok()?;
+		Some((block_hash, transaction_index))
+	}
+
+	async fn receipts_count_per_block(&self, block_hash: &H256) -> Option<usize> {
+		let block_hash = block_hash.as_ref();
+		let row = query!(
+			r#"
+            SELECT COUNT(*) as count
+            FROM transaction_hashes
+            WHERE block_hash = $1
+            "#,
+			block_hash
+		)
+		.fetch_one(&self.pool)
+		.await
+		.ok()?;
+
+		let count = row.count as usize;
+		Some(count)
+	}
+
+	async fn block_transaction_hashes(&self, block_hash: &H256) -> Option<HashMap<usize, H256>> {
+		let block_hash = block_hash.as_ref();
+		let rows = query!(
+			r#"
+		      SELECT transaction_index, transaction_hash
+		      FROM transaction_hashes
+		      WHERE block_hash = $1
+		      "#,
+			block_hash
+		)
+		.map(|row| {
+			let transaction_index = row.transaction_index as usize;
+			let transaction_hash = H256::from_slice(&row.transaction_hash);
+			(transaction_index, transaction_hash)
+		})
+		.fetch_all(&self.pool)
+		.await
+		.ok()?;
+
+		Some(rows.into_iter().collect())
+	}
+
+	async fn logs(&self, query: &LogQuery) -> anyhow::Result<Vec<Log>> {
+		let mut qb = QueryBuilder::<Sqlite>::new("SELECT logs.* FROM logs WHERE 1=1");
+
+		match query.blocks {
+			LogQueryBlocks::Range(from_block, to_block) if from_block == to_block => {
+				qb.push(" AND block_number = ").push_bind(from_block as i64);
+			},
+			LogQueryBlocks::Range(from_block, to_block) => {
+				qb.push(" AND block_number BETWEEN ")
+					.push_bind(from_block as i64)
+					.push(" AND ")
+					.push_bind(to_block as i64);
+			},
+			LogQueryBlocks::Hash(hash) => {
+				qb.push(" AND block_hash = ").push_bind(hash.0.to_vec());
+			},
+		}
+
+		if let Some(addresses) = &query.addresses {
+			qb.push(" AND address IN (");
+			let mut separated = qb.separated(", ");
+			for addr in addresses {
+				separated.push_bind(addr.0.to_vec());
+			}
+			separated.push_unseparated(")");
+		}
+
+		for (i, topics) in query.topics.iter().enumerate() {
+			qb.push(format_args!(" AND topic_{i} IN ("));
+			let mut separated = qb.separated(", ");
+			for hash in topics {
+				separated.push_bind(hash.0.to_vec());
+			}
+			separated.push_unseparated(")");
+		}
+
+		qb.push(format_args!(" LIMIT {MAX_LOGS_PER_QUERY}"));
+
+		let logs = qb
+			.build()
+			.try_map(|row| {
+				let block_hash: Vec<u8> = row.try_get("block_hash")?;
+				let transaction_index: i64 = row.try_get("transaction_index")?;
+				let log_index: i64 = row.try_get("log_index")?;
+				let address: Vec<u8> = row.try_get("address")?;
+				let block_number: i64 = row.try_get("block_number")?;
+				let transaction_hash: Vec<u8> = row.try_get("transaction_hash")?;
+				let topic_0: Option<Vec<u8>> = row.try_get("topic_0")?;
+				let topic_1: Option<Vec<u8>> = row.try_get("topic_1")?;
+				let topic_2: Option<Vec<u8>> = row.try_get("topic_2")?;
+				let topic_3: Option<Vec<u8>> = row.try_get("topic_3")?;
+				let data: Option<Vec<u8>> = row.try_get("data")?;
+
+				let topics = [topic_0, topic_1, topic_2, topic_3]
+					.iter()
+					.filter_map(|t| t.as_ref().map(|t| H256::from_slice(t)))
+					.collect::<Vec<_>>();
+
+				Ok(Log {
+					address: Address::from_slice(&address),
+					block_hash: H256::from_slice(&block_hash),
+					block_number: U256::from(block_number as u64),
+					data: data.map(Bytes::from),
+					log_index: U256::from(log_index as u64),
+					topics,
+					transaction_hash: H256::from_slice(&transaction_hash),
+					transaction_index: U256::from(transaction_index as u64),
+					removed: None,
+				})
+			})
+			.fetch_all(&self.pool)
+			.await?;
+
+		Ok(logs)
+	}
+}
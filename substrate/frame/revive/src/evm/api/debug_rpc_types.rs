@@ -16,7 +16,7 @@
 // limitations under the License.
 
 use crate::evm::Bytes;
-use alloc::{string::String, vec::Vec};
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
 use codec::{Decode, Encode};
 use derive_more::From;
 use scale_info::TypeInfo;
@@ -24,12 +24,17 @@ use serde::{Deserialize, Serialize};
 use sp_core::{H160, H256, U256};
 
 /// The type of tracer to use.
-/// Only "callTracer" is supported for now.
 #[derive(TypeInfo, Debug, Clone, Encode, Decode, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "tracer", content = "tracerConfig", rename_all = "camelCase")]
 pub enum TracerType {
 	/// A tracer that traces calls.
 	CallTracer(Option<CallTracerConfig>),
+
+	/// A tracer that reports the state of the accounts touched by a transaction.
+	PrestateTracer(Option<PrestateTracerConfig>),
+
+	/// A tracer that logs every host function invoked by the executed contracts.
+	StructLogger(Option<StructLoggerConfig>),
 }
 
 impl From<CallTracerConfig> for TracerType {
@@ -38,6 +43,18 @@ impl From<CallTracerConfig> for TracerType {
 	}
 }
 
+impl From<PrestateTracerConfig> for TracerType {
+	fn from(config: PrestateTracerConfig) -> Self {
+		TracerType::PrestateTracer(Some(config))
+	}
+}
+
+impl From<StructLoggerConfig> for TracerType {
+	fn from(config: StructLoggerConfig) -> Self {
+		TracerType::StructLogger(Some(config))
+	}
+}
+
 impl Default for TracerType {
 	fn default() -> Self {
 		TracerType::CallTracer(Some(CallTracerConfig::default()))
@@ -74,6 +91,40 @@ impl Default for CallTracerConfig {
 	}
 }
 
+/// The configuration for the prestate tracer.
+#[derive(Clone, Debug, Default, Decode, Serialize, Deserialize, Encode, PartialEq, TypeInfo)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PrestateTracerConfig {
+	/// Whether to return the state before and after the execution, instead of only the
+	/// state before.
+	pub diff_mode: bool,
+
+	/// Whether to omit the code of the touched accounts.
+	pub disable_code: bool,
+
+	/// Whether to omit the storage of the touched accounts.
+	pub disable_storage: bool,
+}
+
+/// The configuration for the struct logger.
+///
+/// PolkaVM contracts don't have an EVM-like linear memory, hence `enableMemory` is not supported.
+#[derive(Clone, Debug, Default, Decode, Serialize, Deserialize, Encode, PartialEq, TypeInfo)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StructLoggerConfig {
+	/// Whether to omit the argument registers of each step.
+	pub disable_stack: bool,
+
+	/// Whether to omit the storage accessed so far by the current contract.
+	pub disable_storage: bool,
+
+	/// Whether to include the return data of the last call in each step.
+	pub enable_return_data: bool,
+
+	/// The maximum number of steps to record, `0` means no limit.
+	pub limit: u64,
+}
+
 /// Serialization should support the following JSON format:
 ///
 /// ```json
@@ -83,6 +134,10 @@ impl Default for CallTracerConfig {
 /// ```json
 /// { "tracer": "callTracer" }
 /// ```
+///
+/// ```json
+/// { "tracer": "prestateTracer", "tracerConfig": { "diffMode": true } }
+/// ```
 #[test]
 fn test_tracer_config_serialization() {
 	let tracers = vec![
@@ -111,6 +166,21 @@ fn test_tracer_config_serialization() {
 				timeout: Some(core::time::Duration::from_millis(10)),
 			},
 		),
+		(
+			r#"{"tracer": "prestateTracer", "tracerConfig": { "diffMode": true }}"#,
+			TracerConfig {
+				config: PrestateTracerConfig { diff_mode: true, ..Default::default() }.into(),
+				timeout: None,
+			},
+		),
+		(
+			r#"{"tracer": "structLogger", "tracerConfig": { "disableStack": true, "limit": 10 }}"#,
+			TracerConfig {
+				config: StructLoggerConfig { disable_stack: true, limit: 10, ..Default::default() }
+					.into(),
+				timeout: None,
+			},
+		),
 	];
 
 	for (json_data, expected) in tracers {
@@ -141,6 +211,99 @@ pub enum CallType {
 pub enum Trace {
 	/// A call trace.
 	Call(CallTrace),
+	/// A struct log trace.
+	StructLog(StructLogTrace),
+	/// A prestate trace.
+	Prestate(PrestateTrace),
+}
+
+/// A prestate trace.
+#[derive(TypeInfo, Encode, Decode, Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum PrestateTrace {
+	/// The state of the accounts that changed, before and after the execution.
+	DiffMode {
+		/// The state before the execution.
+		pre: BTreeMap<H160, PrestateTraceInfo>,
+		/// The state after the execution.
+		post: BTreeMap<H160, PrestateTraceInfo>,
+	},
+	/// The state of all the touched accounts, before the execution.
+	Prestate(BTreeMap<H160, PrestateTraceInfo>),
+}
+
+impl Default for PrestateTrace {
+	fn default() -> Self {
+		PrestateTrace::Prestate(BTreeMap::new())
+	}
+}
+
+/// The state of an account reported by the prestate tracer.
+#[derive(
+	TypeInfo, Default, Encode, Decode, Serialize, Deserialize, Clone, Debug, Eq, PartialEq,
+)]
+pub struct PrestateTraceInfo {
+	/// The balance of the account.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub balance: Option<U256>,
+	/// The nonce of the account.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub nonce: Option<u64>,
+	/// The code of the contract.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub code: Option<Bytes>,
+	/// The storage slots accessed during the execution.
+	/// A `None` value means that the slot is empty.
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub storage: BTreeMap<Bytes, Option<Bytes>>,
+}
+
+/// A struct log trace.
+#[derive(
+	TypeInfo, Default, Encode, Decode, Serialize, Deserialize, Clone, Debug, Eq, PartialEq,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLogTrace<Gas = U256> {
+	/// Amount of gas used by the execution.
+	pub gas: Gas,
+	/// Whether the execution failed.
+	pub failed: bool,
+	/// The data returned by the execution.
+	pub return_value: Bytes,
+	/// The steps of the execution.
+	pub struct_logs: Vec<StructLog<Gas>>,
+}
+
+/// A single step of a struct log trace.
+///
+/// A step is recorded for each host function called by a contract.
+#[derive(
+	TypeInfo, Default, Encode, Decode, Serialize, Deserialize, Clone, Debug, Eq, PartialEq,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog<Gas = U256> {
+	/// The program counter of the instruction that invoked the host function.
+	pub pc: u64,
+	/// The name of the host function.
+	pub op: String,
+	/// Amount of gas left before the step.
+	pub gas: Gas,
+	/// Amount of gas used by the step.
+	pub gas_cost: Gas,
+	/// The depth of the call stack.
+	pub depth: u32,
+	/// The argument registers passed to the host function.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub stack: Option<Vec<U256>>,
+	/// The data returned by the last call.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub return_data: Option<Bytes>,
+	/// The storage slots accessed so far by the current contract.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub storage: Option<BTreeMap<Bytes, Bytes>>,
+	/// The error message if the step failed.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
 }
 
 /// A smart contract execution call trace.
@@ -182,6 +345,36 @@ pub struct CallTrace<Gas = U256> {
 	pub call_type: CallType,
 }
 
+impl<Gas> CallTrace<Gas> {
+	/// Returns the logs emitted by the call and its sub-calls, in the order they were emitted.
+	///
+	/// The logs of the calls that failed are discarded, as their effects were reverted.
+	pub fn into_logs(self) -> Vec<CallLog> {
+		let mut logs = Vec::new();
+		self.collect_logs(&mut logs);
+		logs
+	}
+
+	fn collect_logs(self, logs: &mut Vec<CallLog>) {
+		if self.error.is_some() {
+			return
+		}
+
+		// A log's position is the number of sub-calls made before it was emitted.
+		let mut calls = self.calls.into_iter();
+		let mut calls_done = 0;
+		for log in self.logs {
+			while calls_done < log.position {
+				let Some(call) = calls.next() else { break };
+				call.collect_logs(logs);
+				calls_done += 1;
+			}
+			logs.push(log);
+		}
+		calls.for_each(|call| call.collect_logs(logs));
+	}
+}
+
 /// A log emitted during a call.
 #[derive(
 	Debug, Default, Clone, Encode, Decode, TypeInfo, Serialize, Deserialize, Eq, PartialEq,
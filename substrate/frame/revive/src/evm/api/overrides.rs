@@ -0,0 +1,66 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! State and block overrides for dry-run calls, see `eth_call` in Geth.
+use crate::evm::Bytes;
+use alloc::collections::BTreeMap;
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
+use sp_core::{H160, H256, U256};
+
+/// The state overrides of a call, indexed by account address.
+pub type StateOverrides = BTreeMap<H160, AccountOverride>;
+
+/// The overrides applied to the state of an account before a call is executed.
+#[derive(Clone, Debug, Default, Decode, Serialize, Deserialize, Encode, PartialEq, Eq, TypeInfo)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub struct AccountOverride {
+	/// The balance of the account.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub balance: Option<U256>,
+
+	/// The nonce of the account.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub nonce: Option<U256>,
+
+	/// The code of the account. This turns the account into a contract if it isn't one already.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub code: Option<Bytes>,
+
+	/// Storage slots replacing the entire storage of the account.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub state: Option<BTreeMap<H256, H256>>,
+
+	/// Storage slots patched on top of the existing storage of the account.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub state_diff: Option<BTreeMap<H256, H256>>,
+}
+
+/// The overrides applied to the block context of a call.
+///
+/// Only the fields observable by PolkaVM contracts can be overridden.
+#[derive(Clone, Debug, Default, Decode, Serialize, Deserialize, Encode, PartialEq, Eq, TypeInfo)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub struct BlockOverrides {
+	/// The block number.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub number: Option<U256>,
+
+	/// The block timestamp, in seconds.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub time: Option<U256>,
+}
@@ -16,7 +16,8 @@
 // limitations under the License.
 //! Utility impl for the RPC types.
 use super::*;
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
+use serde::{Deserialize, Serialize};
 use sp_core::{H160, U256};
 
 impl From<BlockNumberOrTag> for BlockNumberOrTagOrHash {
@@ -344,6 +345,53 @@ impl GenericTransaction {
 	}
 }
 
+/// The result of `eth_getProof`.
+///
+/// Unlike Ethereum, the proofs are made of the Substrate trie nodes proving the state of the
+/// account against the state root of the block.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountProof {
+	/// The address of the account.
+	pub address: Address,
+	/// The balance of the account.
+	pub balance: U256,
+	/// The hash of the code of the account.
+	pub code_hash: H256,
+	/// The nonce of the account.
+	pub nonce: U256,
+	/// The root of the contract's child trie, or zero if the account has no storage.
+	pub storage_hash: H256,
+	/// The trie nodes proving the account entries of the state trie.
+	pub account_proof: Vec<Bytes>,
+	/// The proofs of the requested storage slots.
+	pub storage_proof: Vec<StorageProof>,
+}
+
+/// The proof of a storage slot, part of an [`AccountProof`].
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct StorageProof {
+	/// The storage slot.
+	pub key: U256,
+	/// The value of the storage slot.
+	pub value: U256,
+	/// The trie nodes proving the slot entry of the contract's child trie.
+	pub proof: Vec<Bytes>,
+}
+
+/// The result of `eth_createAccessList`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListResult {
+	/// The accounts and storage slots accessed by the transaction.
+	pub access_list: AccessList,
+	/// The error message, if the transaction failed.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	/// The gas used by the transaction.
+	pub gas_used: U256,
+}
+
 #[test]
 fn from_unsigned_works_for_legacy() {
 	let base_gas_price = U256::from(10);
@@ -0,0 +1,93 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Types of the `eth_simulateV1` method.
+use crate::evm::{BlockOverrides, Bytes, GenericTransaction, Log, StateOverrides};
+use alloc::{string::String, vec::Vec};
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
+use sp_core::U256;
+
+/// The calls to simulate, grouped in blocks.
+#[derive(Clone, Debug, Default, Decode, Serialize, Deserialize, Encode, PartialEq, Eq, TypeInfo)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SimulatePayload {
+	/// The blocks to simulate, in order.
+	pub block_state_calls: Vec<SimulateBlock>,
+
+	/// Whether to check the nonce and the balance of the senders, as done for real transactions.
+	pub validation: bool,
+}
+
+/// A block of calls to simulate.
+#[derive(Clone, Debug, Default, Decode, Serialize, Deserialize, Encode, PartialEq, Eq, TypeInfo)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SimulateBlock {
+	/// The overrides applied to the block context of the calls.
+	///
+	/// The number and time of the block default to the ones of the previous block, incremented.
+	pub block_overrides: BlockOverrides,
+
+	/// The overrides applied to the state before the calls are executed.
+	pub state_overrides: StateOverrides,
+
+	/// The calls to execute, in order.
+	pub calls: Vec<GenericTransaction>,
+}
+
+/// A block simulated by `eth_simulateV1`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlockResult {
+	/// The number of the block.
+	pub number: U256,
+	/// The timestamp of the block, in seconds.
+	pub timestamp: U256,
+	/// The gas used by all the calls of the block.
+	pub gas_used: U256,
+	/// The results of the calls, in order.
+	pub calls: Vec<SimulatedCallResult>,
+}
+
+/// A call simulated by `eth_simulateV1`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedCallResult {
+	/// `1` if the call succeeded, `0` otherwise.
+	pub status: U256,
+	/// The data returned by the call.
+	pub return_data: Bytes,
+	/// The gas used by the call.
+	pub gas_used: U256,
+	/// The logs emitted by the call.
+	pub logs: Vec<Log>,
+	/// Why the call failed, if it did.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<SimulateCallError>,
+}
+
+/// The error of a call simulated by `eth_simulateV1`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SimulateCallError {
+	/// The JSON-RPC error code.
+	pub code: i32,
+	/// The error message.
+	pub message: String,
+	/// The revert data, if the call reverted.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub data: Option<Bytes>,
+}
@@ -15,20 +15,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 use crate::{
-	evm::{CallTrace, Trace},
+	evm::{CallTrace, PrestateTrace, PrestateTraceInfo, StructLogTrace, Trace},
 	tracing::Tracing,
 	Weight,
 };
-use sp_core::U256;
+use sp_core::{H160, U256};
 
 mod call_tracing;
 pub use call_tracing::*;
 
+mod prestate_tracing;
+pub use prestate_tracing::*;
+
+mod struct_log_tracing;
+pub use struct_log_tracing::*;
+
 /// A composite tracer.
 #[derive(derive_more::From, Debug)]
 pub enum Tracer {
 	/// A tracer that traces calls.
 	CallTracer(CallTracer<U256, fn(Weight) -> U256>),
+	/// A tracer that reports the state of the accounts touched by a transaction.
+	PrestateTracer(PrestateTracer<fn(H160) -> PrestateTraceInfo>),
+	/// A tracer that logs every host function call.
+	StructLogger(StructLogger<U256, fn(Weight) -> U256>),
 }
 
 impl Tracer {
@@ -36,6 +46,8 @@ impl Tracer {
 	pub fn empty_trace(&self) -> Trace {
 		match self {
 			Tracer::CallTracer(_) => CallTrace::default().into(),
+			Tracer::PrestateTracer(_) => PrestateTrace::default().into(),
+			Tracer::StructLogger(_) => StructLogTrace::default().into(),
 		}
 	}
 
@@ -43,6 +55,8 @@ impl Tracer {
 	pub fn as_tracing(&mut self) -> &mut (dyn Tracing + 'static) {
 		match self {
 			Tracer::CallTracer(inner) => inner as &mut dyn Tracing,
+			Tracer::PrestateTracer(inner) => inner as &mut dyn Tracing,
+			Tracer::StructLogger(inner) => inner as &mut dyn Tracing,
 		}
 	}
 
@@ -50,6 +64,8 @@ impl Tracer {
 	pub fn collect_trace(&mut self) -> Option<Trace> {
 		match self {
 			Tracer::CallTracer(inner) => inner.collect_trace().map(Trace::Call),
+			Tracer::PrestateTracer(inner) => inner.collect_trace().map(Trace::Prestate),
+			Tracer::StructLogger(inner) => inner.collect_trace().map(Trace::StructLog),
 		}
 	}
 }
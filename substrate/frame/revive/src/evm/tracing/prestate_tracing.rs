@@ -0,0 +1,199 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{
+	evm::{Bytes, PrestateTrace, PrestateTraceInfo, PrestateTracerConfig},
+	primitives::ExecReturnValue,
+	tracing::Tracing,
+	DispatchError, Weight,
+};
+use alloc::{collections::BTreeMap, vec::Vec};
+use sp_core::{H160, U256};
+
+/// The storage writes of a call frame, indexed by contract address and key.
+type FrameWrites = BTreeMap<(H160, Bytes), Option<Bytes>>;
+
+/// A Tracer that reports the state of the accounts touched by a transaction.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct PrestateTracer<AccountLoader> {
+	/// Load the balance, nonce and code of an account from the current state.
+	account_loader: AccountLoader,
+	/// The tracer configuration.
+	config: PrestateTracerConfig,
+	/// The state of the touched accounts, before they were first accessed.
+	pre: BTreeMap<H160, PrestateTraceInfo>,
+	/// The contract whose storage is accessed by each of the active call frames.
+	current_stack: Vec<H160>,
+	/// The storage writes of each of the active call frames.
+	pending_writes: Vec<FrameWrites>,
+	/// The storage writes of the call frames that completed successfully.
+	writes: FrameWrites,
+}
+
+impl<AccountLoader: Fn(H160) -> PrestateTraceInfo> PrestateTracer<AccountLoader> {
+	/// Create a new [`PrestateTracer`] instance.
+	pub fn new(config: PrestateTracerConfig, account_loader: AccountLoader) -> Self {
+		Self {
+			account_loader,
+			config,
+			pre: BTreeMap::new(),
+			current_stack: Vec::new(),
+			pending_writes: Vec::new(),
+			writes: BTreeMap::new(),
+		}
+	}
+
+	/// Collect the trace and return it.
+	///
+	/// This must be called once the traced execution completed, as the state after the execution
+	/// is read from storage.
+	pub fn collect_trace(&mut self) -> Option<PrestateTrace> {
+		let pre = core::mem::take(&mut self.pre);
+		let writes = core::mem::take(&mut self.writes);
+		self.current_stack.clear();
+		self.pending_writes.clear();
+
+		if pre.is_empty() {
+			return None
+		}
+
+		if !self.config.diff_mode {
+			return Some(PrestateTrace::Prestate(pre))
+		}
+
+		let mut pre_diff = BTreeMap::new();
+		let mut post_diff = BTreeMap::new();
+		for (address, before) in pre {
+			let after = self.load_account(address);
+			let mut pre_info = PrestateTraceInfo::default();
+			let mut post_info = PrestateTraceInfo::default();
+
+			if before.balance != after.balance {
+				pre_info.balance = before.balance;
+				post_info.balance = after.balance;
+			}
+			if before.nonce != after.nonce {
+				pre_info.nonce = before.nonce;
+				post_info.nonce = after.nonce;
+			}
+			if before.code != after.code {
+				pre_info.code = before.code;
+				post_info.code = after.code;
+			}
+			for (key, old_value) in before.storage {
+				let Some(new_value) = writes.get(&(address, key.clone())) else { continue };
+				if *new_value != old_value {
+					pre_info.storage.insert(key.clone(), old_value);
+					post_info.storage.insert(key, new_value.clone());
+				}
+			}
+
+			if pre_info != PrestateTraceInfo::default() {
+				pre_diff.insert(address, pre_info);
+				post_diff.insert(address, post_info);
+			}
+		}
+
+		Some(PrestateTrace::DiffMode { pre: pre_diff, post: post_diff })
+	}
+
+	fn load_account(&self, address: H160) -> PrestateTraceInfo {
+		let mut info = (self.account_loader)(address);
+		if self.config.disable_code {
+			info.code = None;
+		}
+		info
+	}
+
+	/// Record the state of the given account, if it was not touched before.
+	fn touch(&mut self, address: H160) {
+		if !self.pre.contains_key(&address) {
+			let info = self.load_account(address);
+			self.pre.insert(address, info);
+		}
+	}
+
+	/// Record the value of a storage slot of the current contract, if it was not accessed before.
+	fn touch_storage(&mut self, key: &[u8], value: Option<&[u8]>) {
+		if self.config.disable_storage {
+			return
+		}
+
+		let Some(address) = self.current_stack.last() else { return };
+		if let Some(info) = self.pre.get_mut(address) {
+			info.storage
+				.entry(Bytes(key.to_vec()))
+				.or_insert_with(|| value.map(|v| Bytes(v.to_vec())));
+		}
+	}
+
+	fn exit_frame(&mut self, success: bool) {
+		self.current_stack.pop();
+		let Some(frame_writes) = self.pending_writes.pop() else { return };
+		if !success {
+			return
+		}
+
+		match self.pending_writes.last_mut() {
+			Some(parent) => parent.extend(frame_writes),
+			None => self.writes.extend(frame_writes),
+		}
+	}
+}
+
+impl<AccountLoader: Fn(H160) -> PrestateTraceInfo> Tracing for PrestateTracer<AccountLoader> {
+	fn enter_child_span(
+		&mut self,
+		from: H160,
+		to: H160,
+		_is_delegate_call: bool,
+		_is_read_only: bool,
+		_value: U256,
+		_input: &[u8],
+		_gas: Weight,
+	) {
+		self.touch(from);
+		self.touch(to);
+		self.current_stack.push(to);
+		self.pending_writes.push(BTreeMap::new());
+	}
+
+	fn exit_child_span(&mut self, output: &ExecReturnValue, _gas_used: Weight) {
+		self.exit_frame(!output.did_revert());
+	}
+
+	fn exit_child_span_with_error(&mut self, _error: DispatchError, _gas_used: Weight) {
+		self.exit_frame(false);
+	}
+
+	fn storage_read(&mut self, key: &[u8], value: Option<&[u8]>) {
+		self.touch_storage(key, value);
+	}
+
+	fn storage_write(&mut self, key: &[u8], old_value: Option<&[u8]>, new_value: Option<&[u8]>) {
+		self.touch_storage(key, old_value);
+		if self.config.disable_storage {
+			return
+		}
+
+		let (Some(address), Some(frame_writes)) =
+			(self.current_stack.last(), self.pending_writes.last_mut())
+		else {
+			return
+		};
+		frame_writes.insert((*address, Bytes(key.to_vec())), new_value.map(|v| Bytes(v.to_vec())));
+	}
+}
@@ -0,0 +1,174 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{
+	evm::{Bytes, StructLog, StructLogTrace, StructLoggerConfig},
+	primitives::ExecReturnValue,
+	tracing::Tracing,
+	DispatchError, Weight,
+};
+use alloc::{collections::BTreeMap, format, string::ToString, vec::Vec};
+use sp_core::{H160, U256};
+
+/// A Tracer that records a step for every host function called by the executed contracts.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct StructLogger<Gas, GasMapper> {
+	/// Map Weight to Gas equivalent.
+	gas_mapper: GasMapper,
+	/// The tracer configuration.
+	config: StructLoggerConfig,
+	/// The recorded steps.
+	struct_logs: Vec<StructLog<Gas>>,
+	/// The steps that are in progress, and the gas left before they started.
+	current_steps: Vec<(usize, Weight)>,
+	/// The storage slots accessed by each of the active call frames.
+	storage_stack: Vec<BTreeMap<Bytes, Bytes>>,
+	/// The data returned by the last call that completed.
+	last_return_data: Bytes,
+	/// The result of the top level call, once it completed.
+	result: Option<(Gas, bool, Bytes)>,
+}
+
+impl<Gas, GasMapper> StructLogger<Gas, GasMapper> {
+	/// Create a new [`StructLogger`] instance.
+	pub fn new(config: StructLoggerConfig, gas_mapper: GasMapper) -> Self {
+		Self {
+			gas_mapper,
+			config,
+			struct_logs: Vec::new(),
+			current_steps: Vec::new(),
+			storage_stack: Vec::new(),
+			last_return_data: Bytes::default(),
+			result: None,
+		}
+	}
+
+	/// Collect the trace and return it.
+	pub fn collect_trace(&mut self) -> Option<StructLogTrace<Gas>> {
+		let struct_logs = core::mem::take(&mut self.struct_logs);
+		self.current_steps.clear();
+		self.storage_stack.clear();
+		self.last_return_data = Bytes::default();
+
+		let (gas, failed, return_value) = self.result.take()?;
+		Some(StructLogTrace { gas, failed, return_value, struct_logs })
+	}
+
+	fn depth(&self) -> u32 {
+		self.storage_stack.len() as u32
+	}
+
+	fn limit_reached(&self) -> bool {
+		self.config.limit != 0 && self.struct_logs.len() as u64 >= self.config.limit
+	}
+
+	/// Record an access to the storage of the current contract.
+	fn record_storage(&mut self, key: &[u8], value: Option<&[u8]>) {
+		let Some(storage) = self.storage_stack.last_mut() else { return };
+		storage.insert(Bytes(key.to_vec()), Bytes(value.map(|v| v.to_vec()).unwrap_or_default()));
+
+		if self.config.disable_storage {
+			return
+		}
+
+		let storage = storage.clone();
+		if let Some(log) = self.current_steps.last().and_then(|(i, _)| self.struct_logs.get_mut(*i))
+		{
+			log.storage = Some(storage);
+		}
+	}
+}
+
+impl<Gas: Default, GasMapper: Fn(Weight) -> Gas> StructLogger<Gas, GasMapper> {
+	fn exit_frame(&mut self, gas_used: Weight, failed: bool, output: Bytes) {
+		self.storage_stack.pop();
+		if self.storage_stack.is_empty() {
+			self.result = Some(((self.gas_mapper)(gas_used), failed, output));
+		} else {
+			self.last_return_data = output;
+		}
+	}
+}
+
+impl<Gas: Default, GasMapper: Fn(Weight) -> Gas> Tracing for StructLogger<Gas, GasMapper> {
+	fn enter_child_span(
+		&mut self,
+		_from: H160,
+		_to: H160,
+		_is_delegate_call: bool,
+		_is_read_only: bool,
+		_value: U256,
+		_input: &[u8],
+		_gas: Weight,
+	) {
+		self.storage_stack.push(BTreeMap::new());
+	}
+
+	fn exit_child_span(&mut self, output: &ExecReturnValue, gas_used: Weight) {
+		self.exit_frame(gas_used, output.did_revert(), output.data.clone().into());
+	}
+
+	fn exit_child_span_with_error(&mut self, error: DispatchError, gas_used: Weight) {
+		let error = match error {
+			DispatchError::Module(sp_runtime::ModuleError { message, .. }) =>
+				message.unwrap_or_default().to_string(),
+			_ => format!("{:?}", error),
+		};
+
+		let depth = self.depth();
+		if let Some(log) = self.struct_logs.iter_mut().rev().find(|log| log.depth == depth) {
+			log.error = Some(error);
+		}
+
+		self.exit_frame(gas_used, true, Bytes::default());
+	}
+
+	fn enter_ecall(&mut self, ecall: &str, pc: u64, args: &[u64], gas_left: Weight) {
+		if self.limit_reached() {
+			self.current_steps.push((usize::MAX, gas_left));
+			return
+		}
+
+		self.struct_logs.push(StructLog {
+			pc,
+			op: ecall.to_string(),
+			gas: (self.gas_mapper)(gas_left),
+			gas_cost: Gas::default(),
+			depth: self.depth(),
+			stack: (!self.config.disable_stack)
+				.then(|| args.iter().map(|arg| U256::from(*arg)).collect()),
+			return_data: self.config.enable_return_data.then(|| self.last_return_data.clone()),
+			storage: None,
+			error: None,
+		});
+		self.current_steps.push((self.struct_logs.len() - 1, gas_left));
+	}
+
+	fn exit_ecall(&mut self, gas_left: Weight) {
+		let Some((index, gas_before)) = self.current_steps.pop() else { return };
+		if let Some(log) = self.struct_logs.get_mut(index) {
+			log.gas_cost = (self.gas_mapper)(gas_before.saturating_sub(gas_left));
+		}
+	}
+
+	fn storage_read(&mut self, key: &[u8], value: Option<&[u8]>) {
+		self.record_storage(key, value);
+	}
+
+	fn storage_write(&mut self, key: &[u8], _old_value: Option<&[u8]>, new_value: Option<&[u8]>) {
+		self.record_storage(key, new_value);
+	}
+}
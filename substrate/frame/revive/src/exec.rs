@@ -17,6 +17,7 @@
 
 use crate::{
 	address::{self, AddressMapper},
+	evm::{AccessList, AccessListEntry, BlockOverrides},
 	gas::GasMeter,
 	limits,
 	precompiles::{All as AllPrecompiles, Instance as PrecompileInstance, Precompiles},
@@ -28,8 +29,12 @@ use crate::{
 	BalanceOf, CodeInfo, CodeInfoOf, Config, ContractInfo, ContractInfoOf, ConversionPrecision,
 	Error, Event, ImmutableData, ImmutableDataOf, Pallet as Contracts,
 };
-use alloc::vec::Vec;
+use alloc::{
+	collections::{BTreeMap, BTreeSet},
+	vec::Vec,
+};
 use core::{fmt::Debug, marker::PhantomData, mem};
+use environmental::environmental;
 use frame_support::{
 	crypto::ecdsa::ECDSAExt,
 	dispatch::DispatchResult,
@@ -73,6 +78,80 @@ const FRAME_ALWAYS_EXISTS_ON_INSTANTIATE: &str = "The return value is only `None
 pub const EMPTY_CODE_HASH: H256 =
 	H256(sp_core::hex2array!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"));
 
+environmental!(access_recorder: AccessRecorder);
+
+/// Records the accounts and storage slots accessed by the executed contracts.
+///
+/// See [`record_access`].
+#[derive(Default)]
+struct AccessRecorder {
+	/// The storage slots accessed, indexed by the address of the account they belong to.
+	accessed: BTreeMap<H160, BTreeSet<H256>>,
+}
+
+impl AccessRecorder {
+	/// Record an access to the given account.
+	fn record_account(&mut self, address: H160) {
+		self.accessed.entry(address).or_default();
+	}
+
+	/// Record an access to a storage slot of the given account.
+	///
+	/// Only fixed sized keys are recorded, as variable sized keys can't be expressed in an
+	/// EIP-2930 access list.
+	fn record_storage(&mut self, address: H160, key: &Key) {
+		let slots = self.accessed.entry(address).or_default();
+		if let Key::Fix(key) = key {
+			slots.insert(H256(*key));
+		}
+	}
+
+	/// Convert the recorded accesses into an access list, ordered by address.
+	fn into_access_list(self) -> AccessList {
+		self.accessed
+			.into_iter()
+			.map(|(address, slots)| AccessListEntry {
+				address,
+				storage_keys: slots.into_iter().collect(),
+			})
+			.collect()
+	}
+}
+
+/// Run the given closure in access recording mode.
+///
+/// Returns the result of the closure together with the contracts, accounts and storage slots
+/// accessed by the call stacks it executed. Precompiles are never recorded.
+///
+/// # Warning
+///
+/// Only meant to be called from off-chain code, such as dry-runs, as its additional resource
+/// usage is not accounted for in the weights or memory envelope.
+pub fn record_access<R, F: FnOnce() -> R>(f: F) -> (R, AccessList) {
+	let mut recorder = AccessRecorder::default();
+	let result = access_recorder::using_once(&mut recorder, f);
+	(result, recorder.into_access_list())
+}
+
+/// Run the closure when access recording is enabled.
+///
+/// This is never the case for on-chain code, hence the closure is not executed there.
+fn if_recording_access<F: FnOnce(&mut AccessRecorder)>(f: F) {
+	access_recorder::with(f);
+}
+
+environmental!(block_overrides: BlockOverrides);
+
+/// Run the given closure with the block context of the executed call stacks overridden.
+///
+/// # Warning
+///
+/// Only meant to be called from off-chain code, such as dry-runs, as the overridden values are
+/// only observed by the contracts and not by the rest of the runtime.
+pub fn with_block_overrides<R, F: FnOnce() -> R>(overrides: &mut BlockOverrides, f: F) -> R {
+	block_overrides::using_once(overrides, f)
+}
+
 /// Combined key type for both fixed and variable sized storage keys.
 pub enum Key {
 	/// Variant for fixed sized keys.
@@ -86,8 +165,8 @@ impl Key {
 	///
 	/// # Note
 	///
-	/// Only used by benchmarking in order to generate storage collisions on purpose.
-	#[cfg(feature = "runtime-benchmarks")]
+	/// Used by benchmarking in order to generate storage collisions on purpose, and by tracers
+	/// to report the accessed storage slots.
 	pub fn unhashed(&self) -> &[u8] {
 		match self {
 			Key::Fix(v) => v.as_ref(),
@@ -882,12 +961,24 @@ where
 			return Ok(None);
 		};
 
+		let mut timestamp = T::Time::now();
+		let mut block_number = <frame_system::Pallet<T>>::block_number();
+		block_overrides::with(|overrides| {
+			if let Some(number) = overrides.number {
+				block_number = u64::try_from(number).unwrap_or(u64::MAX).saturated_into();
+			}
+			if let Some(time) = overrides.time {
+				let millis = u64::try_from(time).unwrap_or(u64::MAX).saturating_mul(1000);
+				timestamp = millis.saturated_into();
+			}
+		});
+
 		let stack = Self {
 			origin,
 			gas_meter,
 			storage_meter,
-			timestamp: T::Time::now(),
-			block_number: <frame_system::Pallet<T>>::block_number(),
+			timestamp,
+			block_number,
 			first_frame,
 			frames: Default::default(),
 			transient_storage: TransientStorage::new(limits::TRANSIENT_STORAGE_BYTES),
@@ -916,6 +1007,9 @@ where
 			FrameArgs::Call { dest, cached_info, delegated_call } => {
 				let address = T::AddressMapper::to_address(&dest);
 				let precompile = <AllPrecompiles<T>>::get(address.as_fixed_bytes());
+				if precompile.is_none() {
+					if_recording_access(|recorder| recorder.record_account(address));
+				}
 
 				// which contract info to load is unaffected by the fact if this
 				// is a delegate call or not
@@ -948,6 +1042,9 @@ where
 							_phantom: Default::default(),
 						}
 					} else {
+						if_recording_access(|recorder| {
+							recorder.record_account(delegated_call.callee)
+						});
 						let Some(info) = ContractInfoOf::<T>::get(&delegated_call.callee) else {
 							return Ok(None);
 						};
@@ -1446,6 +1543,23 @@ where
 		!self.frames().any(|f| &f.account_id == id && !f.allows_reentry)
 	}
 
+	/// Record an access to the given account when running in access recording mode.
+	fn record_account_access(&self, address: &H160) {
+		if_recording_access(|recorder| {
+			if <AllPrecompiles<T>>::get::<Self>(address.as_fixed_bytes()).is_none() {
+				recorder.record_account(*address);
+			}
+		});
+	}
+
+	/// Record an access to the given storage slot of the current contract when running in access
+	/// recording mode.
+	fn record_storage_access(&self, key: &Key) {
+		if_recording_access(|recorder| {
+			recorder.record_storage(T::AddressMapper::to_address(&self.top_frame().account_id), key)
+		});
+	}
+
 	/// Returns the *free* balance of the supplied AccountId.
 	fn account_balance(&self, who: &T::AccountId) -> U256 {
 		crate::Pallet::<T>::convert_native_to_evm(T::Currency::reducible_balance(
@@ -1626,10 +1740,16 @@ where
 	T::Hash: frame_support::traits::IsType<H256>,
 {
 	fn get_storage(&mut self, key: &Key) -> Option<Vec<u8>> {
-		self.top_frame_mut().contract_info().read(key)
+		self.record_storage_access(key);
+		let value = self.top_frame_mut().contract_info().read(key);
+		if_tracing(|tracer| {
+			tracer.storage_read(key.unhashed(), value.as_deref());
+		});
+		value
 	}
 
 	fn get_storage_size(&mut self, key: &Key) -> Option<u32> {
+		self.record_storage_access(key);
 		self.top_frame_mut().contract_info().size(key.into())
 	}
 
@@ -1639,13 +1759,17 @@ where
 		value: Option<Vec<u8>>,
 		take_old: bool,
 	) -> Result<WriteOutcome, DispatchError> {
+		self.record_storage_access(key);
 		let frame = self.top_frame_mut();
-		frame.contract_info.get(&frame.account_id).write(
-			key.into(),
-			value,
-			Some(&mut frame.nested_storage),
-			take_old,
-		)
+		let contract_info = frame.contract_info.get(&frame.account_id);
+		if_tracing(|tracer| {
+			tracer.storage_write(
+				key.unhashed(),
+				contract_info.read(key).as_deref(),
+				value.as_deref(),
+			);
+		});
+		contract_info.write(key.into(), value, Some(&mut frame.nested_storage), take_old)
 	}
 
 	fn charge_storage(&mut self, diff: &Diff) {
@@ -1843,6 +1967,7 @@ where
 	}
 
 	fn code_hash(&self, address: &H160) -> H256 {
+		self.record_account_access(address);
 		<ContractInfoOf<T>>::get(&address)
 			.map(|contract| contract.code_hash)
 			.unwrap_or_else(|| {
@@ -1854,6 +1979,7 @@ where
 	}
 
 	fn code_size(&self, address: &H160) -> u64 {
+		self.record_account_access(address);
 		<ContractInfoOf<T>>::get(&address)
 			.and_then(|contract| CodeInfoOf::<T>::get(contract.code_hash))
 			.map(|info| info.code_len())
@@ -1874,6 +2000,7 @@ where
 	}
 
 	fn balance_of(&self, address: &H160) -> U256 {
+		self.record_account_access(address);
 		self.account_balance(&<Self::T as Config>::AddressMapper::to_account_id(address))
 	}
 
@@ -44,10 +44,15 @@ pub mod weights;
 
 use crate::{
 	evm::{
-		runtime::GAS_PRICE, CallTracer, GasEncoder, GenericTransaction, Trace, Tracer, TracerType,
+		runtime::GAS_PRICE, AccountOverride, BlockOverrides, Bytes, CallTrace, CallTracer,
+		CallTracerConfig, GasEncoder, GenericTransaction, PrestateTraceInfo, PrestateTracer,
+		SimulateBlock, SimulatePayload, StateOverrides, StructLogger, Trace, Tracer, TracerType,
 		TYPE_EIP1559,
 	},
-	exec::{AccountIdOf, ExecError, Executable, Key, Stack as ExecStack},
+	exec::{
+		record_access, with_block_overrides, AccountIdOf, ExecError, Executable, Key,
+		Stack as ExecStack,
+	},
 	gas::GasMeter,
 	storage::{meter::Meter as StorageMeter, ContractInfo, DeletionQueueManager},
 	wasm::{CodeInfo, RuntimeCosts, WasmBlob},
@@ -78,7 +83,7 @@ use frame_system::{
 use scale_info::TypeInfo;
 use sp_runtime::{
 	traits::{BadOrigin, Bounded, Convert, Dispatchable, Saturating, Zero},
-	AccountId32, DispatchError,
+	AccountId32, DispatchError, SaturatedConversion,
 };
 
 pub use crate::{
@@ -121,6 +126,9 @@ const SENTINEL: u32 = u32::MAX;
 /// Example: `RUST_LOG=runtime::revive=debug my_code --dev`
 const LOG_TARGET: &str = "runtime::revive";
 
+/// The time between two blocks simulated by [`Pallet::dry_run_eth_simulate`], in seconds.
+const SIMULATED_BLOCK_TIME: u32 = 12;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -271,6 +279,28 @@ pub mod pallet {
 		/// Only valid value is `()`. See [`GasEncoder`].
 		#[pallet::no_default_bounds]
 		type EthGasEncoder: GasEncoder<BalanceOf<Self>>;
+
+		/// Used by the XCM pre-compile to execute, send and weigh XCM messages.
+		///
+		/// Set to `pallet_xcm::Pallet` in order to enable XCM for contracts or to `()` to
+		/// disable it. Requires the `xcm-precompile` feature.
+		#[pallet::no_default_bounds]
+		type Xcm: precompiles::XcmController<Self>;
+
+		/// Used by the nomination pools pre-compile to let contracts take part in nomination
+		/// pools.
+		///
+		/// Set to `pallet_nomination_pools::Pallet` in order to enable it or to `()` to disable
+		/// it. Requires the `nomination-pools-precompile` feature.
+		#[pallet::no_default_bounds]
+		type NominationPools: precompiles::NominationPoolsController<Self>;
+
+		/// Used by the conviction voting pre-compile to let contracts vote and delegate.
+		///
+		/// Set to `pallet_conviction_voting::Pallet` in order to enable it or to `()` to disable
+		/// it. Requires the `conviction-voting-precompile` feature.
+		#[pallet::no_default_bounds]
+		type ConvictionVoting: precompiles::ConvictionVotingController<Self>;
 	}
 
 	/// Container for different types that implement [`DefaultConfig`]` of this pallet.
@@ -342,6 +372,9 @@ pub mod pallet {
 			type NativeToEthRatio = ConstU32<1>;
 			type EthGasEncoder = ();
 			type FindAuthor = ();
+			type Xcm = ();
+			type NominationPools = ();
+			type ConvictionVoting = ();
 		}
 	}
 
@@ -1323,6 +1356,238 @@ where
 		Ok(result)
 	}
 
+	/// Dry-run an Ethereum transaction in access recording mode.
+	///
+	/// Returns the EIP-2930 access list of the transaction together with the dry-run result.
+	///
+	/// # Parameters
+	///
+	/// - `tx`: The Ethereum transaction to simulate.
+	/// - `dry_run`: A function that dry-runs the transaction, see [`Self::dry_run_eth_transact`].
+	pub fn dry_run_eth_access_list(
+		tx: GenericTransaction,
+		dry_run: impl FnOnce(
+			GenericTransaction,
+		) -> Result<EthTransactInfo<BalanceOf<T>>, EthTransactError>,
+	) -> EthAccessListInfo<BalanceOf<T>> {
+		let from = tx.from.unwrap_or_default();
+		let to = tx.to.unwrap_or_else(|| {
+			// The nonce is bumped by the dry-run, the deployed contract address is derived from
+			// the nonce before the bump.
+			let nonce = <System<T>>::account_nonce(&T::AddressMapper::to_account_id(&from));
+			create1(&from, nonce.saturated_into())
+		});
+
+		let (result, mut access_list) = record_access(|| dry_run(tx));
+		// The sender and the recipient are warm anyway, only their storage slots are listed.
+		access_list.retain(|entry| {
+			!entry.storage_keys.is_empty() || (entry.address != from && entry.address != to)
+		});
+
+		log::trace!(target: LOG_TARGET, "dry_run_eth_access_list: {access_list:?}");
+		EthAccessListInfo { access_list, result }
+	}
+
+	/// Dry-run an Ethereum transaction on top of the given state and block overrides.
+	///
+	/// The state overrides are written to storage before the transaction is executed, this must
+	/// only be called from a runtime API, whose storage changes are discarded.
+	///
+	/// # Parameters
+	///
+	/// - `tx`: The Ethereum transaction to simulate.
+	/// - `state_overrides`: The overrides applied to the state of the given accounts.
+	/// - `block_overrides`: The overrides applied to the block context of the executed contracts.
+	/// - `dry_run`: A function that dry-runs the transaction, see [`Self::dry_run_eth_transact`].
+	pub fn dry_run_eth_transact_with_overrides(
+		tx: GenericTransaction,
+		state_overrides: StateOverrides,
+		mut block_overrides: BlockOverrides,
+		dry_run: impl FnOnce(
+			GenericTransaction,
+		) -> Result<EthTransactInfo<BalanceOf<T>>, EthTransactError>,
+	) -> Result<EthTransactInfo<BalanceOf<T>>, EthTransactError> {
+		Self::apply_state_overrides(state_overrides)?;
+		with_block_overrides(&mut block_overrides, || dry_run(tx))
+	}
+
+	/// Dry-run a sequence of Ethereum transactions, each executed on top of the state left by the
+	/// previous ones.
+	///
+	/// The state overrides are written to storage before the transactions are executed, this
+	/// must only be called from a runtime API, whose storage changes are discarded.
+	///
+	/// A failing transaction doesn't abort the simulation, its error is reported in its
+	/// [`EthSimulatedCall::result`] and the following transactions are still executed.
+	///
+	/// # Parameters
+	///
+	/// - `payload`: The transactions to simulate, grouped in blocks.
+	/// - `dry_run`: A function that dry-runs a transaction, see [`Self::dry_run_eth_transact`].
+	pub fn dry_run_eth_simulate(
+		payload: SimulatePayload,
+		dry_run: impl Fn(GenericTransaction) -> Result<EthTransactInfo<BalanceOf<T>>, EthTransactError>,
+	) -> Result<Vec<EthSimulatedBlock<BalanceOf<T>>>, EthTransactError>
+	where
+		T::Nonce: Into<U256>,
+	{
+		let SimulatePayload { block_state_calls, validation } = payload;
+		let mut number = U256::from(<System<T>>::block_number().saturated_into::<u64>());
+		let now: U256 = T::Time::now().into();
+		let mut timestamp = now / 1000;
+
+		let mut blocks = Vec::with_capacity(block_state_calls.len());
+		for SimulateBlock { mut block_overrides, state_overrides, calls } in block_state_calls {
+			number = block_overrides.number.unwrap_or_else(|| number.saturating_add(1.into()));
+			timestamp = block_overrides
+				.time
+				.unwrap_or_else(|| timestamp.saturating_add(SIMULATED_BLOCK_TIME.into()));
+			block_overrides.number = Some(number);
+			block_overrides.time = Some(timestamp);
+
+			Self::apply_state_overrides(state_overrides)?;
+			let calls = with_block_overrides(&mut block_overrides, || {
+				calls
+					.into_iter()
+					.map(|tx| Self::simulate_call(tx, validation, &dry_run))
+					.collect::<Vec<_>>()
+			});
+
+			blocks.push(EthSimulatedBlock { number, timestamp, calls });
+		}
+
+		Ok(blocks)
+	}
+
+	/// Dry-run a transaction of [`Self::dry_run_eth_simulate`], collecting the logs it emitted.
+	///
+	/// A transaction failing the validation is not executed, the error is recorded as its result.
+	fn simulate_call(
+		tx: GenericTransaction,
+		validation: bool,
+		dry_run: impl Fn(GenericTransaction) -> Result<EthTransactInfo<BalanceOf<T>>, EthTransactError>,
+	) -> EthSimulatedCall<BalanceOf<T>>
+	where
+		T::Nonce: Into<U256>,
+	{
+		if validation {
+			if let Err(err) = Self::validate_simulated_call(&tx) {
+				return EthSimulatedCall { result: Err(err), gas_used: 0.into(), logs: Vec::new() }
+			}
+		}
+
+		let config = CallTracerConfig { with_logs: true, only_top_call: false };
+		let mut tracer = CallTracer::new(config, Self::evm_gas_from_weight as fn(Weight) -> U256);
+		let result = tracing::trace(&mut tracer, || dry_run(tx));
+		let trace = tracer.collect_trace();
+
+		let (gas_used, logs) = match &result {
+			Ok(info) => (info.eth_gas, trace.map(CallTrace::into_logs).unwrap_or_default()),
+			Err(_) => (trace.map(|trace| trace.gas_used).unwrap_or_default(), Vec::new()),
+		};
+
+		EthSimulatedCall { result, gas_used, logs }
+	}
+
+	/// Check the nonce and the balance of the sender of the given transaction.
+	fn validate_simulated_call(tx: &GenericTransaction) -> Result<(), EthTransactError>
+	where
+		T::Nonce: Into<U256>,
+	{
+		let from = tx.from.unwrap_or_default();
+		let account_id = T::AddressMapper::to_account_id(&from);
+		let nonce: U256 = <System<T>>::account_nonce(&account_id).into();
+		match tx.nonce {
+			Some(tx_nonce) if tx_nonce < nonce =>
+				return Err(EthTransactError::Message(format!(
+					"nonce too low: address {from:?}, tx: {tx_nonce} state: {nonce}"
+				))),
+			Some(tx_nonce) if tx_nonce > nonce =>
+				return Err(EthTransactError::Message(format!(
+					"nonce too high: address {from:?}, tx: {tx_nonce} state: {nonce}"
+				))),
+			_ => {},
+		}
+
+		let gas_price = tx.max_fee_per_gas.or(tx.gas_price).unwrap_or_default();
+		let cost = tx
+			.gas
+			.unwrap_or_default()
+			.saturating_mul(gas_price)
+			.saturating_add(tx.value.unwrap_or_default());
+		let balance = Self::evm_balance(&from);
+		if balance < cost {
+			return Err(EthTransactError::Message(format!(
+				"insufficient funds for gas * price + value: address {from:?} have {balance} want {cost}"
+			)));
+		}
+
+		Ok(())
+	}
+
+	/// Apply the given overrides to the state.
+	fn apply_state_overrides(state_overrides: StateOverrides) -> Result<(), EthTransactError> {
+		for (address, account_override) in state_overrides {
+			Self::apply_account_override(address, account_override).map_err(|err| {
+				EthTransactError::Message(format!(
+					"Failed to apply state override for {address:?}: {err:?}"
+				))
+			})?;
+		}
+
+		Ok(())
+	}
+
+	/// Apply the given overrides to the state of the account at `address`.
+	fn apply_account_override(
+		address: H160,
+		account_override: AccountOverride,
+	) -> Result<(), DispatchError> {
+		let AccountOverride { balance, nonce, code, state, state_diff } = account_override;
+		let account_id = T::AddressMapper::to_account_id(&address);
+
+		if let Some(balance) = balance {
+			let balance = Self::convert_evm_to_native(balance, ConversionPrecision::RoundUp)?;
+			let _ = T::Currency::set_balance(&account_id, balance);
+		}
+
+		if let Some(nonce) = nonce {
+			let nonce = u64::try_from(nonce).unwrap_or(u64::MAX).saturated_into();
+			frame_system::Account::<T>::mutate(&account_id, |info| info.nonce = nonce);
+		}
+
+		if let Some(code) = code {
+			let mut module = WasmBlob::<T>::from_code(code.0, account_id.clone())?;
+			module.store_code(true)?;
+			let code_hash = *module.code_hash();
+			let contract = match ContractInfoOf::<T>::get(&address) {
+				Some(mut contract) => {
+					contract.code_hash = code_hash;
+					contract
+				},
+				None =>
+					ContractInfo::new(&address, System::<T>::account_nonce(&account_id), code_hash)?,
+			};
+			ContractInfoOf::<T>::insert(&address, contract);
+		}
+
+		if state.is_none() && state_diff.is_none() {
+			return Ok(());
+		}
+
+		let contract = ContractInfoOf::<T>::get(&address).ok_or(Error::<T>::ContractNotFound)?;
+		if state.is_some() {
+			let _ = frame_support::storage::child::kill_storage(&contract.child_trie_info(), None);
+		}
+
+		for (key, value) in state.into_iter().chain(state_diff).flatten() {
+			let value = (!value.is_zero()).then(|| value.0.to_vec());
+			contract.write(&Key::from_fixed(key.0), value, None, false)?;
+		}
+
+		Ok(())
+	}
+
 	/// Get the balance with EVM decimals of the given `address`.
 	pub fn evm_balance(address: &H160) -> U256 {
 		let account = T::AddressMapper::to_account_id(&address);
@@ -1377,6 +1642,31 @@ where
 				Self::evm_gas_from_weight as fn(Weight) -> U256,
 			)
 			.into(),
+			TracerType::PrestateTracer(config) => PrestateTracer::new(
+				config.unwrap_or_default(),
+				Self::evm_account_info as fn(H160) -> PrestateTraceInfo,
+			)
+			.into(),
+			TracerType::StructLogger(config) => StructLogger::new(
+				config.unwrap_or_default(),
+				Self::evm_gas_from_weight as fn(Weight) -> U256,
+			)
+			.into(),
+		}
+	}
+
+	/// Get the balance, nonce and code of the given `address`, as reported by the prestate tracer.
+	fn evm_account_info(address: H160) -> PrestateTraceInfo {
+		let account = T::AddressMapper::to_account_id(&address);
+		let code = ContractInfoOf::<T>::get(&address)
+			.and_then(|info| PristineCode::<T>::get(info.code_hash))
+			.map(|code| Bytes(code.into_inner()));
+
+		PrestateTraceInfo {
+			balance: Some(Self::evm_balance(&address)),
+			nonce: Some(<System<T>>::account_nonce(&account).saturated_into()),
+			code,
+			storage: Default::default(),
 		}
 	}
 
@@ -1597,6 +1887,27 @@ sp_api::decl_runtime_apis! {
 		/// See eth-rpc `debug_traceCall` for usage.
 		fn trace_call(tx: GenericTransaction, config: TracerType) -> Result<Trace, EthTransactError>;
 
+		/// Dry run the given call and return the accounts and storage slots it accessed.
+		///
+		/// See [`crate::Pallet::dry_run_eth_access_list`]
+		fn eth_access_list(tx: GenericTransaction) -> EthAccessListInfo<Balance>;
+
+		/// Perform a sequence of Ethereum calls, each on top of the state left by the previous ones.
+		///
+		/// See [`crate::Pallet::dry_run_eth_simulate`]
+		fn eth_simulate(
+			payload: SimulatePayload,
+		) -> Result<Vec<EthSimulatedBlock<Balance>>, EthTransactError>;
+
+		/// Perform an Ethereum call on top of the given state and block overrides.
+		///
+		/// See [`crate::Pallet::dry_run_eth_transact_with_overrides`]
+		fn eth_transact_with_overrides(
+			tx: GenericTransaction,
+			state_overrides: StateOverrides,
+			block_overrides: BlockOverrides,
+		) -> Result<EthTransactInfo<Balance>, EthTransactError>;
+
 	}
 }
 
@@ -1803,6 +2114,31 @@ macro_rules! impl_runtime_apis_plus_revive {
 						Ok(tracer.empty_trace())
 					}
 				}
+
+				fn eth_access_list(
+					tx: $crate::evm::GenericTransaction,
+				) -> $crate::EthAccessListInfo<Balance> {
+					$crate::Pallet::<Self>::dry_run_eth_access_list(tx, Self::eth_transact)
+				}
+
+				fn eth_simulate(
+					payload: $crate::evm::SimulatePayload,
+				) -> Result<Vec<$crate::EthSimulatedBlock<Balance>>, $crate::EthTransactError> {
+					$crate::Pallet::<Self>::dry_run_eth_simulate(payload, Self::eth_transact)
+				}
+
+				fn eth_transact_with_overrides(
+					tx: $crate::evm::GenericTransaction,
+					state_overrides: $crate::evm::StateOverrides,
+					block_overrides: $crate::evm::BlockOverrides,
+				) -> Result<$crate::EthTransactInfo<Balance>, $crate::EthTransactError> {
+					$crate::Pallet::<Self>::dry_run_eth_transact_with_overrides(
+						tx,
+						state_overrides,
+						block_overrides,
+						Self::eth_transact,
+					)
+				}
 			}
 		}
 	};
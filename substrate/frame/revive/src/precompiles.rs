@@ -26,6 +26,7 @@
 //! Use `alloy` through our re-export in this module to implement Eth ABI.
 
 mod builtin;
+pub mod utils;
 
 mod tests;
 
@@ -37,6 +38,10 @@ pub use crate::{
 	AddressMapper,
 };
 pub use alloy_core as alloy;
+pub use builtin::{
+	ConvictionVotingController, IConvictionVoting, INominationPools, IXcm,
+	NominationPoolsController, XcmController, MAX_CONVICTION,
+};
 pub use sp_core::{H160, H256, U256};
 
 use crate::{
@@ -17,12 +17,15 @@
 
 mod blake2f;
 mod bn128;
+mod conviction_voting;
 mod ecrecover;
 mod identity;
 mod modexp;
+mod nomination_pools;
 mod point_eval;
 mod ripemd160;
 mod sha256;
+mod xcm;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
@@ -36,6 +39,12 @@ use crate::{
 #[cfg(feature = "runtime-benchmarks")]
 pub use benchmarking::{IBenchmarking, NoInfo, WithInfo};
 
+pub use conviction_voting::{
+	ConvictionVoting, ConvictionVotingController, IConvictionVoting, MAX_CONVICTION,
+};
+pub use nomination_pools::{INominationPools, NominationPools, NominationPoolsController};
+pub use xcm::{IXcm, Xcm, XcmController};
+
 #[cfg(not(feature = "runtime-benchmarks"))]
 pub type Builtin<T> = Production<T>;
 
@@ -53,6 +62,9 @@ type Production<T> = (
 	bn128::Bn128Pairing<T>,
 	blake2f::Blake2F<T>,
 	point_eval::PointEval<T>,
+	xcm::Xcm<T>,
+	nomination_pools::NominationPools<T>,
+	conviction_voting::ConvictionVoting<T>,
 );
 
 #[cfg(feature = "runtime-benchmarks")]
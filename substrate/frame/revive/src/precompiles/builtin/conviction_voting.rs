@@ -0,0 +1,347 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	precompiles::{
+		utils, BuiltinAddressMatcher, BuiltinPrecompile, Error, Ext, RuntimeCosts, H160,
+	},
+	AddressMapper, Config,
+};
+use alloc::vec::Vec;
+use alloy_core::{primitives::U256, sol, sol_types::Revert};
+use core::{marker::PhantomData, num::NonZero};
+use frame_support::{dispatch::DispatchResultWithPostInfo, weights::Weight};
+use frame_system::pallet_prelude::OriginFor;
+use sp_runtime::{DispatchError, DispatchResult};
+
+#[cfg(feature = "conviction-voting-precompile")]
+use frame_support::traits::Get;
+#[cfg(feature = "conviction-voting-precompile")]
+use pallet_conviction_voting::{
+	AccountVote, BalanceOf, ClassOf, Conviction, PollIndexOf, Vote, WeightInfo as _,
+};
+#[cfg(feature = "conviction-voting-precompile")]
+use sp_runtime::traits::StaticLookup;
+
+#[cfg(feature = "conviction-voting-precompile")]
+type WeightInfoOf<T, I> = <T as pallet_conviction_voting::Config<I>>::WeightInfo;
+
+sol! {
+	interface IConvictionVoting {
+		/// Emitted when `voter` cast a standard vote on the poll `pollIndex`.
+		event Voted(
+			address indexed voter,
+			uint32 indexed pollIndex,
+			bool aye,
+			uint8 conviction,
+			uint256 balance
+		);
+
+		/// Emitted when `voter` removed its vote from the poll `pollIndex`.
+		event VoteRemoved(address indexed voter, uint32 indexed pollIndex);
+
+		/// Emitted when `delegator` delegated its voting power for the class `class` to `target`.
+		event Delegated(
+			address indexed delegator,
+			address indexed target,
+			uint16 indexed class,
+			uint8 conviction,
+			uint256 balance
+		);
+
+		/// Vote `aye` or nay on the poll `pollIndex` locking `balance` with `conviction`.
+		///
+		/// `conviction` is the lock period multiplier in the range `0..=6`.
+		function vote(uint32 pollIndex, bool aye, uint8 conviction, uint256 balance) external;
+
+		/// Remove the vote of the caller from the poll `pollIndex` of the class `class`.
+		function removeVote(uint16 class, uint32 pollIndex) external;
+
+		/// Delegate `balance` of the voting power of the caller for the class `class` to
+		/// `target` with `conviction`.
+		function delegate(uint16 class, address target, uint8 conviction, uint256 balance)
+			external;
+	}
+}
+
+const ERR_INVALID_CONVICTION: &str = "Invalid conviction";
+
+/// The highest lock period multiplier accepted by [`ConvictionVoting`].
+pub const MAX_CONVICTION: u8 = 6;
+
+/// The conviction voting functionality exposed to contracts by the conviction voting pre-compile.
+///
+/// Implemented by `pallet_conviction_voting::Pallet` when the `conviction-voting-precompile`
+/// feature is enabled. Use `()` in order to disable conviction voting for contracts.
+pub trait ConvictionVotingController<T: Config> {
+	/// The balance type used for voting.
+	type Balance: TryFrom<U256>;
+
+	/// Let `origin` vote `aye` or nay on `poll_index`, locking `balance` with `conviction`.
+	///
+	/// `conviction` is never larger than [`MAX_CONVICTION`].
+	fn vote(
+		origin: OriginFor<T>,
+		poll_index: u32,
+		aye: bool,
+		conviction: u8,
+		balance: Self::Balance,
+	) -> DispatchResult;
+
+	/// The weight of [`Self::vote`].
+	fn vote_weight() -> Weight;
+
+	/// Remove the vote of `origin` from `poll_index` of `class`.
+	fn remove_vote(origin: OriginFor<T>, class: u16, poll_index: u32) -> DispatchResult;
+
+	/// The weight of [`Self::remove_vote`].
+	fn remove_vote_weight() -> Weight;
+
+	/// Delegate `balance` of the voting power of `origin` for `class` to `target`.
+	///
+	/// `conviction` is never larger than [`MAX_CONVICTION`].
+	fn delegate(
+		origin: OriginFor<T>,
+		class: u16,
+		target: T::AccountId,
+		conviction: u8,
+		balance: Self::Balance,
+	) -> DispatchResultWithPostInfo;
+
+	/// The maximum weight of [`Self::delegate`].
+	fn delegate_weight() -> Weight;
+}
+
+impl<T: Config> ConvictionVotingController<T> for () {
+	type Balance = u128;
+
+	fn vote(
+		_origin: OriginFor<T>,
+		_poll_index: u32,
+		_aye: bool,
+		_conviction: u8,
+		_balance: Self::Balance,
+	) -> DispatchResult {
+		Err(DispatchError::Unavailable)
+	}
+
+	fn vote_weight() -> Weight {
+		Weight::zero()
+	}
+
+	fn remove_vote(_origin: OriginFor<T>, _class: u16, _poll_index: u32) -> DispatchResult {
+		Err(DispatchError::Unavailable)
+	}
+
+	fn remove_vote_weight() -> Weight {
+		Weight::zero()
+	}
+
+	fn delegate(
+		_origin: OriginFor<T>,
+		_class: u16,
+		_target: T::AccountId,
+		_conviction: u8,
+		_balance: Self::Balance,
+	) -> DispatchResultWithPostInfo {
+		Err(DispatchError::Unavailable.into())
+	}
+
+	fn delegate_weight() -> Weight {
+		Weight::zero()
+	}
+}
+
+#[cfg(feature = "conviction-voting-precompile")]
+impl<T, I: 'static> ConvictionVotingController<T> for pallet_conviction_voting::Pallet<T, I>
+where
+	T: Config + pallet_conviction_voting::Config<I>,
+	PollIndexOf<T, I>: From<u32>,
+	ClassOf<T, I>: From<u16>,
+	BalanceOf<T, I>: TryFrom<U256>,
+{
+	type Balance = BalanceOf<T, I>;
+
+	fn vote(
+		origin: OriginFor<T>,
+		poll_index: u32,
+		aye: bool,
+		conviction: u8,
+		balance: Self::Balance,
+	) -> DispatchResult {
+		let vote = AccountVote::Standard {
+			vote: Vote { aye, conviction: to_conviction(conviction)? },
+			balance,
+		};
+		Self::vote(origin, poll_index.into(), vote)
+	}
+
+	fn vote_weight() -> Weight {
+		WeightInfoOf::<T, I>::vote_new().max(WeightInfoOf::<T, I>::vote_existing())
+	}
+
+	fn remove_vote(origin: OriginFor<T>, class: u16, poll_index: u32) -> DispatchResult {
+		Self::remove_vote(origin, Some(class.into()), poll_index.into())
+	}
+
+	fn remove_vote_weight() -> Weight {
+		WeightInfoOf::<T, I>::remove_vote()
+	}
+
+	fn delegate(
+		origin: OriginFor<T>,
+		class: u16,
+		target: T::AccountId,
+		conviction: u8,
+		balance: Self::Balance,
+	) -> DispatchResultWithPostInfo {
+		Self::delegate(
+			origin,
+			class.into(),
+			<T as frame_system::Config>::Lookup::unlookup(target),
+			to_conviction(conviction)?,
+			balance,
+		)
+	}
+
+	fn delegate_weight() -> Weight {
+		WeightInfoOf::<T, I>::delegate(<T as pallet_conviction_voting::Config<I>>::MaxVotes::get())
+	}
+}
+
+/// Convert the lock period multiplier passed by the caller into a `Conviction`.
+#[cfg(feature = "conviction-voting-precompile")]
+fn to_conviction(value: u8) -> Result<Conviction, DispatchError> {
+	Conviction::try_from(value).map_err(|()| DispatchError::Other(ERR_INVALID_CONVICTION))
+}
+
+/// Lets contracts vote on and delegate for polls through [`Config::ConvictionVoting`].
+pub struct ConvictionVoting<T>(PhantomData<T>);
+
+impl<T: Config> BuiltinPrecompile for ConvictionVoting<T> {
+	type T = T;
+	type Interface = IConvictionVoting::IConvictionVotingCalls;
+	const MATCHER: BuiltinAddressMatcher =
+		BuiltinAddressMatcher::Fixed(NonZero::new(0x0A_00).unwrap());
+	const HAS_CONTRACT_INFO: bool = false;
+
+	fn call(
+		_address: &[u8; 20],
+		input: &Self::Interface,
+		env: &mut impl Ext<T = Self::T>,
+	) -> Result<Vec<u8>, Error> {
+		use IConvictionVoting::{IConvictionVotingCalls, IConvictionVotingEvents};
+
+		match input {
+			IConvictionVotingCalls::vote(IConvictionVoting::voteCall {
+				pollIndex,
+				aye,
+				conviction,
+				balance,
+			}) => {
+				env.charge(T::ConvictionVoting::vote_weight())?;
+
+				let (voter, origin) = utils::signed_origin(env)?;
+				T::ConvictionVoting::vote(
+					origin,
+					*pollIndex,
+					*aye,
+					Self::check_conviction(*conviction)?,
+					utils::to_balance(*balance)?,
+				)?;
+				utils::deposit_event(
+					env,
+					IConvictionVotingEvents::Voted(IConvictionVoting::Voted {
+						voter: voter.0.into(),
+						pollIndex: *pollIndex,
+						aye: *aye,
+						conviction: *conviction,
+						balance: *balance,
+					}),
+				)?;
+				Ok(Vec::new())
+			},
+			IConvictionVotingCalls::removeVote(IConvictionVoting::removeVoteCall {
+				class,
+				pollIndex,
+			}) => {
+				env.charge(T::ConvictionVoting::remove_vote_weight())?;
+
+				let (voter, origin) = utils::signed_origin(env)?;
+				T::ConvictionVoting::remove_vote(origin, *class, *pollIndex)?;
+				utils::deposit_event(
+					env,
+					IConvictionVotingEvents::VoteRemoved(IConvictionVoting::VoteRemoved {
+						voter: voter.0.into(),
+						pollIndex: *pollIndex,
+					}),
+				)?;
+				Ok(Vec::new())
+			},
+			IConvictionVotingCalls::delegate(IConvictionVoting::delegateCall {
+				class,
+				target,
+				conviction,
+				balance,
+			}) => {
+				let max_weight = T::ConvictionVoting::delegate_weight();
+				let charged = env.charge(max_weight)?;
+
+				let (delegator, origin) = utils::signed_origin(env)?;
+				let result = T::ConvictionVoting::delegate(
+					origin,
+					*class,
+					T::AddressMapper::to_account_id(&H160::from(target.into_array())),
+					Self::check_conviction(*conviction)?,
+					utils::to_balance(*balance)?,
+				);
+
+				let post_info = match &result {
+					Ok(post_info) => *post_info,
+					Err(err) => err.post_info,
+				};
+				env.gas_meter_mut().adjust_gas(
+					charged,
+					RuntimeCosts::Precompile(post_info.calc_actual_weight(&max_weight)),
+				);
+
+				result.map_err(|err| Error::Error(err.error.into()))?;
+				utils::deposit_event(
+					env,
+					IConvictionVotingEvents::Delegated(IConvictionVoting::Delegated {
+						delegator: delegator.0.into(),
+						target: *target,
+						class: *class,
+						conviction: *conviction,
+						balance: *balance,
+					}),
+				)?;
+				Ok(Vec::new())
+			},
+		}
+	}
+}
+
+impl<T: Config> ConvictionVoting<T> {
+	/// Revert if the lock period multiplier passed by the caller is out of range.
+	fn check_conviction(value: u8) -> Result<u8, Error> {
+		if value > MAX_CONVICTION {
+			return Err(Error::Revert(Revert { reason: ERR_INVALID_CONVICTION.into() }));
+		}
+		Ok(value)
+	}
+}
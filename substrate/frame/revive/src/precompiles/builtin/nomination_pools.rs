@@ -0,0 +1,259 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	precompiles::{utils, BuiltinAddressMatcher, BuiltinPrecompile, Error, Ext},
+	AddressMapper, Config,
+};
+use alloc::vec::Vec;
+use alloy_core::{primitives::U256, sol};
+use core::{marker::PhantomData, num::NonZero};
+use frame_support::weights::Weight;
+use frame_system::pallet_prelude::OriginFor;
+use sp_runtime::{DispatchError, DispatchResult};
+
+#[cfg(feature = "nomination-pools-precompile")]
+use pallet_nomination_pools::{BondExtra, WeightInfo as _};
+#[cfg(feature = "nomination-pools-precompile")]
+use sp_runtime::traits::StaticLookup;
+
+#[cfg(feature = "nomination-pools-precompile")]
+type WeightInfoOf<T> = <T as pallet_nomination_pools::Config>::WeightInfo;
+
+sol! {
+	interface INominationPools {
+		/// Emitted when `member` joined the pool `poolId` by bonding `amount`.
+		event Joined(address indexed member, uint32 indexed poolId, uint256 amount);
+
+		/// Emitted when `member` bonded an additional `amount` into its pool.
+		event BondedExtra(address indexed member, uint256 amount);
+
+		/// Emitted when `member` claimed its pending rewards.
+		event PayoutClaimed(address indexed member);
+
+		/// Emitted when `member` started unbonding `points` from its pool.
+		event Unbonded(address indexed member, uint256 points);
+
+		/// Join the pool `poolId` by bonding `amount` from the free balance of the caller.
+		function join(uint256 amount, uint32 poolId) external;
+
+		/// Bond an additional `amount` from the free balance of the caller into its pool.
+		function bondExtra(uint256 amount) external;
+
+		/// Claim the pending rewards of the caller.
+		function claimPayout() external;
+
+		/// Start unbonding `points` of the caller from its pool.
+		function unbond(uint256 points) external;
+	}
+}
+
+/// The nomination pools functionality exposed to contracts by the nomination pools pre-compile.
+///
+/// Implemented by `pallet_nomination_pools::Pallet` when the `nomination-pools-precompile`
+/// feature is enabled. Use `()` in order to disable nomination pools for contracts.
+pub trait NominationPoolsController<T: Config> {
+	/// The balance type of the nomination pools.
+	type Balance: TryFrom<U256>;
+
+	/// Let `origin` join the pool `pool_id` by bonding `amount`.
+	fn join(origin: OriginFor<T>, amount: Self::Balance, pool_id: u32) -> DispatchResult;
+
+	/// The weight of [`Self::join`].
+	fn join_weight() -> Weight;
+
+	/// Bond an additional `amount` of the free balance of `origin` into its pool.
+	fn bond_extra(origin: OriginFor<T>, amount: Self::Balance) -> DispatchResult;
+
+	/// The weight of [`Self::bond_extra`].
+	fn bond_extra_weight() -> Weight;
+
+	/// Claim the pending rewards of `origin`.
+	fn claim_payout(origin: OriginFor<T>) -> DispatchResult;
+
+	/// The weight of [`Self::claim_payout`].
+	fn claim_payout_weight() -> Weight;
+
+	/// Unbond `points` of `member` from its pool.
+	fn unbond(origin: OriginFor<T>, member: T::AccountId, points: Self::Balance) -> DispatchResult;
+
+	/// The weight of [`Self::unbond`].
+	fn unbond_weight() -> Weight;
+}
+
+impl<T: Config> NominationPoolsController<T> for () {
+	type Balance = u128;
+
+	fn join(_origin: OriginFor<T>, _amount: Self::Balance, _pool_id: u32) -> DispatchResult {
+		Err(DispatchError::Unavailable)
+	}
+
+	fn join_weight() -> Weight {
+		Weight::zero()
+	}
+
+	fn bond_extra(_origin: OriginFor<T>, _amount: Self::Balance) -> DispatchResult {
+		Err(DispatchError::Unavailable)
+	}
+
+	fn bond_extra_weight() -> Weight {
+		Weight::zero()
+	}
+
+	fn claim_payout(_origin: OriginFor<T>) -> DispatchResult {
+		Err(DispatchError::Unavailable)
+	}
+
+	fn claim_payout_weight() -> Weight {
+		Weight::zero()
+	}
+
+	fn unbond(
+		_origin: OriginFor<T>,
+		_member: T::AccountId,
+		_points: Self::Balance,
+	) -> DispatchResult {
+		Err(DispatchError::Unavailable)
+	}
+
+	fn unbond_weight() -> Weight {
+		Weight::zero()
+	}
+}
+
+#[cfg(feature = "nomination-pools-precompile")]
+impl<T> NominationPoolsController<T> for pallet_nomination_pools::Pallet<T>
+where
+	T: Config + pallet_nomination_pools::Config,
+	pallet_nomination_pools::BalanceOf<T>: TryFrom<U256>,
+{
+	type Balance = pallet_nomination_pools::BalanceOf<T>;
+
+	fn join(origin: OriginFor<T>, amount: Self::Balance, pool_id: u32) -> DispatchResult {
+		Self::join(origin, amount, pool_id)
+	}
+
+	fn join_weight() -> Weight {
+		WeightInfoOf::<T>::join()
+	}
+
+	fn bond_extra(origin: OriginFor<T>, amount: Self::Balance) -> DispatchResult {
+		Self::bond_extra(origin, BondExtra::FreeBalance(amount))
+	}
+
+	fn bond_extra_weight() -> Weight {
+		WeightInfoOf::<T>::bond_extra_transfer()
+	}
+
+	fn claim_payout(origin: OriginFor<T>) -> DispatchResult {
+		Self::claim_payout(origin)
+	}
+
+	fn claim_payout_weight() -> Weight {
+		WeightInfoOf::<T>::claim_payout()
+	}
+
+	fn unbond(origin: OriginFor<T>, member: T::AccountId, points: Self::Balance) -> DispatchResult {
+		Self::unbond(origin, <T as frame_system::Config>::Lookup::unlookup(member), points)
+	}
+
+	fn unbond_weight() -> Weight {
+		WeightInfoOf::<T>::unbond()
+	}
+}
+
+/// Lets contracts join and interact with nomination pools through [`Config::NominationPools`].
+pub struct NominationPools<T>(PhantomData<T>);
+
+impl<T: Config> BuiltinPrecompile for NominationPools<T> {
+	type T = T;
+	type Interface = INominationPools::INominationPoolsCalls;
+	const MATCHER: BuiltinAddressMatcher =
+		BuiltinAddressMatcher::Fixed(NonZero::new(0x09_00).unwrap());
+	const HAS_CONTRACT_INFO: bool = false;
+
+	fn call(
+		_address: &[u8; 20],
+		input: &Self::Interface,
+		env: &mut impl Ext<T = Self::T>,
+	) -> Result<Vec<u8>, Error> {
+		use INominationPools::{INominationPoolsCalls, INominationPoolsEvents};
+
+		match input {
+			INominationPoolsCalls::join(INominationPools::joinCall { amount, poolId }) => {
+				env.charge(T::NominationPools::join_weight())?;
+
+				let (member, origin) = utils::signed_origin(env)?;
+				T::NominationPools::join(origin, utils::to_balance(*amount)?, *poolId)?;
+				utils::deposit_event(
+					env,
+					INominationPoolsEvents::Joined(INominationPools::Joined {
+						member: member.0.into(),
+						poolId: *poolId,
+						amount: *amount,
+					}),
+				)?;
+				Ok(Vec::new())
+			},
+			INominationPoolsCalls::bondExtra(INominationPools::bondExtraCall { amount }) => {
+				env.charge(T::NominationPools::bond_extra_weight())?;
+
+				let (member, origin) = utils::signed_origin(env)?;
+				T::NominationPools::bond_extra(origin, utils::to_balance(*amount)?)?;
+				utils::deposit_event(
+					env,
+					INominationPoolsEvents::BondedExtra(INominationPools::BondedExtra {
+						member: member.0.into(),
+						amount: *amount,
+					}),
+				)?;
+				Ok(Vec::new())
+			},
+			INominationPoolsCalls::claimPayout(_) => {
+				env.charge(T::NominationPools::claim_payout_weight())?;
+
+				let (member, origin) = utils::signed_origin(env)?;
+				T::NominationPools::claim_payout(origin)?;
+				utils::deposit_event(
+					env,
+					INominationPoolsEvents::PayoutClaimed(INominationPools::PayoutClaimed {
+						member: member.0.into(),
+					}),
+				)?;
+				Ok(Vec::new())
+			},
+			INominationPoolsCalls::unbond(INominationPools::unbondCall { points }) => {
+				env.charge(T::NominationPools::unbond_weight())?;
+
+				let (member, origin) = utils::signed_origin(env)?;
+				T::NominationPools::unbond(
+					origin,
+					T::AddressMapper::to_account_id(&member),
+					utils::to_balance(*points)?,
+				)?;
+				utils::deposit_event(
+					env,
+					INominationPoolsEvents::Unbonded(INominationPools::Unbonded {
+						member: member.0.into(),
+						points: *points,
+					}),
+				)?;
+				Ok(Vec::new())
+			},
+		}
+	}
+}
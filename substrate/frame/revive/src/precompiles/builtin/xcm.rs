@@ -0,0 +1,233 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	precompiles::{utils, BuiltinAddressMatcher, BuiltinPrecompile, Error, Ext},
+	wasm::RuntimeCosts,
+	Config,
+};
+use alloc::vec::Vec;
+use alloy_core::{
+	sol,
+	sol_types::{Revert, SolCall},
+};
+use codec::{Decode, DecodeLimit};
+use core::{marker::PhantomData, num::NonZero};
+use frame_support::{dispatch::DispatchErrorWithPostInfo, weights::Weight};
+use frame_system::pallet_prelude::OriginFor;
+use sp_runtime::DispatchError;
+use xcm::{prelude::XcmHash, VersionedLocation, VersionedXcm, MAX_XCM_DECODE_DEPTH};
+
+#[cfg(feature = "xcm-precompile")]
+use alloc::boxed::Box;
+
+sol! {
+	interface IXcm {
+		/// The weight of an XCM message.
+		struct Weight {
+			uint64 refTime;
+			uint64 proofSize;
+		}
+
+		/// Execute a SCALE encoded `VersionedXcm` on behalf of the caller.
+		///
+		/// At most `weight` is consumed by the execution of the message.
+		function execute(bytes calldata message, Weight calldata weight) external;
+
+		/// Send a SCALE encoded `VersionedXcm` to the SCALE encoded `VersionedLocation`
+		/// `destination` on behalf of the caller.
+		function send(bytes calldata destination, bytes calldata message) external;
+
+		/// Compute the weight that is required to execute the SCALE encoded `VersionedXcm`.
+		function weighMessage(bytes calldata message) external view returns (Weight memory weight);
+	}
+}
+
+/// The XCM functionality exposed to contracts by the XCM pre-compile.
+///
+/// Implemented by `pallet_xcm::Pallet` when the `xcm-precompile` feature is enabled. Use `()` in
+/// order to disable XCM for contracts.
+pub trait XcmController<T: Config> {
+	/// Execute `message` locally on behalf of `origin`, consuming at most `max_weight`.
+	///
+	/// Returns the weight that was consumed by the execution.
+	fn execute(
+		origin: OriginFor<T>,
+		message: VersionedXcm<<T as Config>::RuntimeCall>,
+		max_weight: Weight,
+	) -> Result<Weight, DispatchErrorWithPostInfo>;
+
+	/// The weight of [`Self::execute`] excluding the weight of the message itself.
+	fn execute_weight() -> Weight;
+
+	/// Send `message` to `dest` on behalf of `origin`.
+	fn send(
+		origin: OriginFor<T>,
+		dest: VersionedLocation,
+		message: VersionedXcm<()>,
+	) -> Result<XcmHash, DispatchError>;
+
+	/// The weight of [`Self::send`].
+	fn send_weight() -> Weight;
+
+	/// Compute the weight that is required to execute `message`.
+	fn weigh(message: VersionedXcm<<T as Config>::RuntimeCall>) -> Result<Weight, DispatchError>;
+}
+
+impl<T: Config> XcmController<T> for () {
+	fn execute(
+		_origin: OriginFor<T>,
+		_message: VersionedXcm<<T as Config>::RuntimeCall>,
+		_max_weight: Weight,
+	) -> Result<Weight, DispatchErrorWithPostInfo> {
+		Err(DispatchError::Unavailable.into())
+	}
+
+	fn execute_weight() -> Weight {
+		Weight::zero()
+	}
+
+	fn send(
+		_origin: OriginFor<T>,
+		_dest: VersionedLocation,
+		_message: VersionedXcm<()>,
+	) -> Result<XcmHash, DispatchError> {
+		Err(DispatchError::Unavailable)
+	}
+
+	fn send_weight() -> Weight {
+		Weight::zero()
+	}
+
+	fn weigh(_message: VersionedXcm<<T as Config>::RuntimeCall>) -> Result<Weight, DispatchError> {
+		Err(DispatchError::Unavailable)
+	}
+}
+
+#[cfg(feature = "xcm-precompile")]
+impl<T> XcmController<T> for pallet_xcm::Pallet<T>
+where
+	T: Config + pallet_xcm::Config<RuntimeCall = <T as Config>::RuntimeCall>,
+{
+	fn execute(
+		origin: OriginFor<T>,
+		message: VersionedXcm<<T as Config>::RuntimeCall>,
+		max_weight: Weight,
+	) -> Result<Weight, DispatchErrorWithPostInfo> {
+		use xcm_builder::ExecuteController;
+		<Self as ExecuteController<_, _>>::execute(origin, Box::new(message), max_weight)
+	}
+
+	fn execute_weight() -> Weight {
+		<Self as xcm_builder::ExecuteControllerWeightInfo>::execute()
+	}
+
+	fn send(
+		origin: OriginFor<T>,
+		dest: VersionedLocation,
+		message: VersionedXcm<()>,
+	) -> Result<XcmHash, DispatchError> {
+		use xcm_builder::SendController;
+		<Self as SendController<_>>::send(origin, Box::new(dest), Box::new(message))
+	}
+
+	fn send_weight() -> Weight {
+		<Self as xcm_builder::SendControllerWeightInfo>::send()
+	}
+
+	fn weigh(message: VersionedXcm<<T as Config>::RuntimeCall>) -> Result<Weight, DispatchError> {
+		use xcm_executor::traits::WeightBounds;
+		let mut message: xcm::latest::Xcm<<T as Config>::RuntimeCall> = message
+			.try_into()
+			.map_err(|()| DispatchError::Other("Unsupported XCM version"))?;
+		<T as pallet_xcm::Config>::Weigher::weight(&mut message, Weight::MAX)
+			.map_err(|_| DispatchError::Other("Failed to weigh XCM message"))
+	}
+}
+
+const ERR_DECODING_FAILED: &str = "Failed to decode XCM";
+
+/// Lets contracts execute, send and weigh XCM messages through [`Config::Xcm`].
+pub struct Xcm<T>(PhantomData<T>);
+
+impl<T: Config> BuiltinPrecompile for Xcm<T> {
+	type T = T;
+	type Interface = IXcm::IXcmCalls;
+	const MATCHER: BuiltinAddressMatcher =
+		BuiltinAddressMatcher::Fixed(NonZero::new(0x08_00).unwrap());
+	const HAS_CONTRACT_INFO: bool = false;
+
+	fn call(
+		_address: &[u8; 20],
+		input: &Self::Interface,
+		env: &mut impl Ext<T = Self::T>,
+	) -> Result<Vec<u8>, Error> {
+		match input {
+			IXcm::IXcmCalls::execute(IXcm::executeCall { message, weight }) => {
+				env.gas_meter_mut()
+					.charge(RuntimeCosts::PrecompileDecode(message.len() as u32))?;
+				let max_weight = Weight::from_parts(weight.refTime, weight.proofSize);
+				let charged = env.charge(T::Xcm::execute_weight().saturating_add(max_weight))?;
+
+				let (_, origin) = utils::signed_origin(env)?;
+				let message = Self::decode(message)?;
+				let result = T::Xcm::execute(origin, message, max_weight);
+
+				let consumed = match &result {
+					Ok(consumed) => *consumed,
+					Err(err) => err.post_info.actual_weight.unwrap_or(max_weight),
+				};
+				env.gas_meter_mut().adjust_gas(
+					charged,
+					RuntimeCosts::Precompile(T::Xcm::execute_weight().saturating_add(consumed)),
+				);
+
+				result.map_err(|err| Error::Error(err.error.into()))?;
+				Ok(Vec::new())
+			},
+			IXcm::IXcmCalls::send(IXcm::sendCall { destination, message }) => {
+				let input_len = destination.len().saturating_add(message.len());
+				env.gas_meter_mut().charge(RuntimeCosts::PrecompileDecode(input_len as u32))?;
+				env.charge(T::Xcm::send_weight())?;
+
+				let (_, origin) = utils::signed_origin(env)?;
+				let destination = Self::decode(destination)?;
+				let message = Self::decode(message)?;
+				T::Xcm::send(origin, destination, message)?;
+				Ok(Vec::new())
+			},
+			IXcm::IXcmCalls::weighMessage(IXcm::weighMessageCall { message }) => {
+				env.gas_meter_mut()
+					.charge(RuntimeCosts::PrecompileDecode(message.len() as u32))?;
+
+				let weight = T::Xcm::weigh(Self::decode(message)?)?;
+				Ok(IXcm::weighMessageCall::abi_encode_returns(&IXcm::Weight {
+					refTime: weight.ref_time(),
+					proofSize: weight.proof_size(),
+				}))
+			},
+		}
+	}
+}
+
+impl<T: Config> Xcm<T> {
+	/// Decode a SCALE encoded XCM type, reverting if the input is invalid.
+	fn decode<D: Decode>(mut input: &[u8]) -> Result<D, Error> {
+		D::decode_all_with_depth_limit(MAX_XCM_DECODE_DEPTH, &mut input)
+			.map_err(|_| Error::Revert(Revert { reason: ERR_DECODING_FAILED.into() }))
+	}
+}
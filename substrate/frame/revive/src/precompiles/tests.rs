@@ -220,6 +220,27 @@ fn builtin_matching_works() {
 		false,
 	);
 
+	assert_eq!(
+		<Builtin<Test>>::get::<Env>(&hex!("0000000000000000000000000000000000000800"))
+			.unwrap()
+			.has_contract_info(),
+		false,
+	);
+
+	assert_eq!(
+		<Builtin<Test>>::get::<Env>(&hex!("0000000000000000000000000000000000000900"))
+			.unwrap()
+			.has_contract_info(),
+		false,
+	);
+
+	assert_eq!(
+		<Builtin<Test>>::get::<Env>(&hex!("0000000000000000000000000000000000000a00"))
+			.unwrap()
+			.has_contract_info(),
+		false,
+	);
+
 	#[cfg(feature = "runtime-benchmarks")]
 	assert_eq!(
 		<Builtin<Test>>::get::<Env>(&hex!("000000000000000000000000000000000000FFFF"))
@@ -254,3 +275,27 @@ fn public_matching_works() {
 	assert_eq!(matcher_prefix.base_address(), hex!("0000000000000000000000000000000000080000"));
 	assert_eq!(matcher_prefix.highest_address(), hex!("FFFFFFFF00000000000000000000000000080000"));
 }
+
+#[test]
+fn xcm_without_controller_fails() {
+	use xcm::{latest::prelude::*, VersionedLocation, VersionedXcm};
+
+	// Runtimes disable XCM by setting `Config::Xcm` to `()`.
+	let message = VersionedXcm::from(Xcm::new());
+	assert_eq!(<() as XcmController<Test>>::weigh(message), Err(DispatchError::Unavailable));
+	let origin = frame_system::RawOrigin::Signed([1u8; 32].into()).into();
+	let dest = VersionedLocation::from(Location::parent());
+	let message = VersionedXcm::<()>::from(Xcm::new());
+	assert_eq!(
+		<() as XcmController<Test>>::send(origin, dest, message),
+		Err(DispatchError::Unavailable)
+	);
+}
+
+#[test]
+fn xcm_invalid_message_reverts() {
+	use alloy_core::sol_types::SolCall;
+
+	let input = IXcm::weighMessageCall { message: vec![0xff].into() }.abi_encode();
+	assert!(matches!(run_primitive::<builtin::Xcm<Test>>(input), Err(Error::Revert(_))));
+}
@@ -0,0 +1,79 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers shared by pre-compiles that act on behalf of their caller.
+
+use crate::{
+	precompiles::{
+		alloy::{self, primitives::IntoLogData, sol_types::Revert},
+		AddressMapper, Error, Ext, RuntimeCosts, H160, H256,
+	},
+	Config, Error as CrateError,
+};
+use alloc::vec::Vec;
+use frame_system::{pallet_prelude::OriginFor, RawOrigin};
+
+const ERR_INVALID_CALLER: &str = "Invalid caller";
+const ERR_BALANCE_CONVERSION_FAILED: &str = "Balance conversion failed";
+
+/// The address of the caller.
+///
+/// Reverts if the caller is not a signed account.
+pub fn caller<T: Config>(env: &mut impl Ext<T = T>) -> Result<H160, Error> {
+	env.caller()
+		.account_id()
+		.map(T::AddressMapper::to_address)
+		.map_err(|_| Error::Revert(Revert { reason: ERR_INVALID_CALLER.into() }))
+}
+
+/// The address of the caller together with its signed origin.
+///
+/// Meant for calls that change state. Those are denied in a read-only context.
+pub fn signed_origin<T: Config>(env: &mut impl Ext<T = T>) -> Result<(H160, OriginFor<T>), Error> {
+	if env.is_read_only() {
+		return Err(CrateError::<T>::StateChangeDenied.into());
+	}
+
+	let caller = caller(env)?;
+	let origin = RawOrigin::Signed(T::AddressMapper::to_account_id(&caller)).into();
+	Ok((caller, origin))
+}
+
+/// Convert a `U256` value to the balance type `B`, reverting if it doesn't fit.
+pub fn to_balance<B>(value: alloy::primitives::U256) -> Result<B, Error>
+where
+	alloy::primitives::U256: TryInto<B>,
+{
+	value
+		.try_into()
+		.map_err(|_| Error::Revert(Revert { reason: ERR_BALANCE_CONVERSION_FAILED.into() }))
+}
+
+/// Charge for and deposit a Solidity event on behalf of the pre-compile.
+pub fn deposit_event<T: Config>(
+	env: &mut impl Ext<T = T>,
+	event: impl IntoLogData,
+) -> Result<(), Error> {
+	let (topics, data) = event.into_log_data().split();
+	let topics = topics.into_iter().map(|v| H256(v.0)).collect::<Vec<_>>();
+	env.gas_meter_mut().charge(RuntimeCosts::DepositEvent {
+		num_topic: topics.len() as u32,
+		len: data.len() as u32,
+	})?;
+	env.deposit_event(topics, data.to_vec());
+	Ok(())
+}
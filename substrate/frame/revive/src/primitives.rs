@@ -17,7 +17,10 @@
 
 //! A crate that hosts a common definitions that are relevant for the pallet-revive.
 
-use crate::{H160, U256};
+use crate::{
+	evm::{AccessList, CallLog},
+	H160, U256,
+};
 use alloc::{string::String, vec::Vec};
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::weights::Weight;
@@ -108,6 +111,42 @@ pub enum EthTransactError {
 	Message(String),
 }
 
+/// The result of a `eth_access_list` call.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct EthAccessListInfo<Balance> {
+	/// The accounts and storage slots accessed by the transaction.
+	///
+	/// This excludes the sender, the recipient and the precompiles, as they are always accessed.
+	pub access_list: AccessList,
+	/// The result of the dry-run of the transaction.
+	pub result: Result<EthTransactInfo<Balance>, EthTransactError>,
+}
+
+/// A block of calls simulated by a `eth_simulate` call.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct EthSimulatedBlock<Balance> {
+	/// The number of the block, as seen by the contracts.
+	pub number: U256,
+	/// The timestamp of the block in seconds, as seen by the contracts.
+	pub timestamp: U256,
+	/// The results of the calls of the block, in order.
+	pub calls: Vec<EthSimulatedCall<Balance>>,
+}
+
+/// A call simulated by a `eth_simulate` call.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct EthSimulatedCall<Balance> {
+	/// The result of the dry-run of the call.
+	pub result: Result<EthTransactInfo<Balance>, EthTransactError>,
+	/// The gas used by the call.
+	///
+	/// This is the gas estimated for the transaction when the call succeeded, and the gas consumed
+	/// until the failure otherwise.
+	pub gas_used: U256,
+	/// The logs emitted by the call, in order.
+	pub logs: Vec<CallLog>,
+}
+
 /// Precision used for converting between Native and EVM balances.
 pub enum ConversionPrecision {
 	/// Exact conversion without any rounding.
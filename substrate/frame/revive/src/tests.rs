@@ -347,6 +347,9 @@ impl Config for Test {
 	type ChainId = ChainId;
 	type FindAuthor = Test;
 	type Precompiles = (precompiles::WithInfo<Self>, precompiles::NoInfo<Self>);
+	type NominationPools = precompiles::MockNominationPools;
+	type ConvictionVoting = precompiles::MockConvictionVoting;
+	type Xcm = precompiles::MockXcm;
 }
 
 impl TryFrom<RuntimeCall> for crate::Call<Test> {
@@ -3982,6 +3985,191 @@ fn skip_transfer_works() {
 	});
 }
 
+#[test]
+fn dry_run_eth_access_list_works() {
+	use crate::evm::AccessListEntry;
+	let (code_caller, _) = compile_module("call").unwrap();
+	let (code_callee, _) = compile_module("clear_storage_on_zero_value").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		<Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+
+		let Contract { addr: caller_addr, .. } =
+			builder::bare_instantiate(Code::Upload(code_caller)).build_and_unwrap_contract();
+		let Contract { addr: callee_addr, .. } =
+			builder::bare_instantiate(Code::Upload(code_callee)).build_and_unwrap_contract();
+
+		let info = Pallet::<Test>::dry_run_eth_access_list(
+			GenericTransaction {
+				from: Some(ALICE_ADDR),
+				to: Some(caller_addr),
+				input: (0u32, &callee_addr).encode().into(),
+				..Default::default()
+			},
+			|tx| Pallet::<Test>::dry_run_eth_transact(tx, Weight::MAX, |_, _| 0u64),
+		);
+
+		// The sender and the recipient are excluded, transient storage is not recorded.
+		assert_ok!(info.result);
+		assert_eq!(
+			info.access_list,
+			vec![AccessListEntry {
+				address: callee_addr,
+				storage_keys: vec![H256::repeat_byte(1)]
+			}]
+		);
+
+		// The storage slots of the recipient are kept.
+		let info = Pallet::<Test>::dry_run_eth_access_list(
+			GenericTransaction {
+				from: Some(ALICE_ADDR),
+				to: Some(callee_addr),
+				..Default::default()
+			},
+			|tx| Pallet::<Test>::dry_run_eth_transact(tx, Weight::MAX, |_, _| 0u64),
+		);
+		assert_ok!(info.result);
+		assert_eq!(
+			info.access_list,
+			vec![AccessListEntry {
+				address: callee_addr,
+				storage_keys: vec![H256::repeat_byte(1)]
+			}]
+		);
+	});
+}
+
+#[test]
+fn dry_run_eth_transact_with_overrides_works() {
+	use crate::evm::{AccountOverride, StateOverrides};
+	let (code, _) = compile_module("dummy").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		<Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(code.clone())).build_and_unwrap_contract();
+		get_contract(&addr)
+			.write(&Key::from_fixed([1u8; 32]), Some(vec![1u8; 32]), None, false)
+			.unwrap();
+
+		let bob = <Test as Config>::AddressMapper::to_account_id(&BOB_ADDR);
+		let state_overrides: StateOverrides = [
+			(
+				BOB_ADDR,
+				AccountOverride {
+					balance: Some(Pallet::<Test>::convert_native_to_evm(1_000)),
+					nonce: Some(7u32.into()),
+					code: Some(code.into()),
+					..Default::default()
+				},
+			),
+			(
+				addr,
+				AccountOverride {
+					state: Some([(H256::repeat_byte(2), H256::repeat_byte(3))].into()),
+					..Default::default()
+				},
+			),
+		]
+		.into();
+
+		assert_ok!(Pallet::<Test>::dry_run_eth_transact_with_overrides(
+			GenericTransaction { from: Some(ALICE_ADDR), to: Some(BOB_ADDR), ..Default::default() },
+			state_overrides,
+			Default::default(),
+			|tx| Pallet::<Test>::dry_run_eth_transact(tx, Weight::MAX, |_, _| 0u64),
+		));
+
+		assert_eq!(test_utils::get_balance(&bob), 1_000);
+		assert_eq!(System::account_nonce(&bob), 7);
+		assert!(get_contract_checked(&BOB_ADDR).is_some());
+
+		// The whole storage is replaced by `state`.
+		assert_eq!(Pallet::<Test>::get_storage(addr, [1u8; 32]), Ok(None));
+		assert_eq!(Pallet::<Test>::get_storage(addr, [2u8; 32]), Ok(Some(vec![3u8; 32])));
+	});
+}
+
+#[test]
+fn dry_run_eth_transact_with_block_overrides_works() {
+	use crate::evm::BlockOverrides;
+	let (code, _) = compile_module("block_info").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		<Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(code)).build_and_unwrap_contract();
+
+		let block_info = |block_overrides: BlockOverrides| {
+			let tx =
+				GenericTransaction { from: Some(ALICE_ADDR), to: Some(addr), ..Default::default() };
+			let data = Pallet::<Test>::dry_run_eth_transact_with_overrides(
+				tx,
+				Default::default(),
+				block_overrides,
+				|tx| Pallet::<Test>::dry_run_eth_transact(tx, Weight::MAX, |_, _| 0u64),
+			)
+			.unwrap()
+			.data;
+			(U256::from_little_endian(&data[..32]), U256::from_little_endian(&data[32..]))
+		};
+
+		// Without overrides the contract sees the current block.
+		let now = U256::from(Timestamp::get() / 1000);
+		assert_eq!(block_info(Default::default()), (U256::from(System::block_number()), now));
+
+		let overrides = BlockOverrides { number: Some(42u32.into()), time: Some(1_234u32.into()) };
+		assert_eq!(block_info(overrides), (U256::from(42), U256::from(1_234)));
+
+		// Fields that are not overridden keep their value.
+		let overrides = BlockOverrides { time: Some(5_678u32.into()), ..Default::default() };
+		assert_eq!(block_info(overrides), (U256::from(System::block_number()), U256::from(5_678)));
+	});
+}
+
+#[test]
+fn dry_run_eth_simulate_works() {
+	use crate::evm::{SimulateBlock, SimulatePayload};
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		<Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		let call = |nonce: u32| GenericTransaction {
+			from: Some(ALICE_ADDR),
+			to: Some(BOB_ADDR),
+			nonce: Some(nonce.into()),
+			..Default::default()
+		};
+
+		let payload = SimulatePayload {
+			block_state_calls: vec![
+				SimulateBlock { calls: vec![call(0), call(1)], ..Default::default() },
+				SimulateBlock { calls: vec![call(0), call(2)], ..Default::default() },
+			],
+			validation: true,
+		};
+
+		let blocks = Pallet::<Test>::dry_run_eth_simulate(payload, |tx| {
+			Pallet::<Test>::dry_run_eth_transact(tx, Weight::MAX, |_, _| 0u64)
+		})
+		.unwrap();
+
+		assert_eq!(blocks.len(), 2);
+		assert_eq!(blocks[1].number, blocks[0].number + 1);
+		assert_eq!(blocks[1].timestamp, blocks[0].timestamp + crate::SIMULATED_BLOCK_TIME);
+
+		// The nonce is bumped by each simulated call.
+		assert!(blocks[0].calls.iter().all(|call| call.result.is_ok()));
+		assert!(matches!(
+			&blocks[1].calls[0].result,
+			Err(EthTransactError::Message(msg)) if msg.starts_with("nonce too low")
+		));
+		assert_eq!(blocks[1].calls[0].gas_used, U256::zero());
+
+		// A call failing the validation doesn't abort the simulation.
+		assert!(blocks[1].calls[1].result.is_ok());
+	});
+}
+
 #[test]
 fn gas_limit_api_works() {
 	let (code, _) = compile_module("gas_limit").unwrap();
@@ -4058,6 +4246,104 @@ fn tracing_works_for_transfers() {
 	});
 }
 
+#[test]
+fn prestate_tracing_works_for_transfers() {
+	use crate::evm::{PrestateTrace, PrestateTracer, PrestateTracerConfig};
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 100_000_000);
+		let alice_before = Pallet::<Test>::evm_account_info(ALICE_ADDR);
+		let bob_before = Pallet::<Test>::evm_account_info(BOB_ADDR);
+
+		let mut tracer = PrestateTracer::new(Default::default(), Pallet::<Test>::evm_account_info);
+		trace(&mut tracer, || {
+			builder::bare_call(BOB_ADDR).value(10_000_000).build_and_unwrap_result();
+		});
+		assert_eq!(
+			tracer.collect_trace(),
+			Some(PrestateTrace::Prestate(
+				[(ALICE_ADDR, alice_before.clone()), (BOB_ADDR, bob_before.clone())].into()
+			))
+		);
+
+		let config = PrestateTracerConfig { diff_mode: true, ..Default::default() };
+		let mut tracer = PrestateTracer::new(config, Pallet::<Test>::evm_account_info);
+		trace(&mut tracer, || {
+			builder::bare_call(BOB_ADDR).value(10_000_000).build_and_unwrap_result();
+		});
+		let Some(PrestateTrace::DiffMode { pre, post }) = tracer.collect_trace() else {
+			panic!("expected a diff mode trace");
+		};
+		let bob_after = Pallet::<Test>::evm_balance(&BOB_ADDR);
+		let value = Pallet::<Test>::convert_native_to_evm(10_000_000);
+		assert_eq!(pre[&BOB_ADDR].balance, Some(bob_after - value));
+		assert_eq!(post[&BOB_ADDR].balance, Some(bob_after));
+		assert_eq!(post[&ALICE_ADDR].balance, Some(Pallet::<Test>::evm_balance(&ALICE_ADDR)));
+	});
+}
+
+#[test]
+fn struct_logger_works() {
+	use crate::evm::{Bytes, StructLogger, StructLoggerConfig};
+	let (code, _code_hash) = compile_module("store_call").unwrap();
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 100_000_000);
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(code)).build_and_unwrap_contract();
+
+		// The contract stores `len` zero bytes at this key.
+		let mut key = [0u8; 32];
+		key[0] = 1;
+		let store = |config: StructLoggerConfig, len: u32| {
+			let mut tracer = StructLogger::new(config, |weight: Weight| weight.ref_time());
+			trace(&mut tracer, || {
+				builder::bare_call(addr).data(len.encode()).build_and_unwrap_result();
+			});
+			tracer.collect_trace().unwrap()
+		};
+
+		let trace = store(Default::default(), 16);
+		assert!(!trace.failed);
+		assert!(trace.gas > 0);
+		assert!(trace.return_value.is_empty());
+		let logs = trace.struct_logs;
+		assert!(logs
+			.iter()
+			.all(|log| log.depth == 1 && log.error.is_none() && log.return_data.is_none()));
+		// The gas left never increases from one step to the next.
+		assert!(logs.windows(2).all(|steps| steps[1].gas <= steps[0].gas - steps[0].gas_cost));
+
+		let sstore = logs.iter().position(|log| log.op == "set_storage").unwrap();
+		let step = &logs[sstore];
+		assert!(step.gas_cost > 0 && step.gas_cost <= step.gas);
+		// The registers hold the flags, key and value of the host function.
+		let stack = step.stack.clone().unwrap();
+		assert_eq!(stack.len(), 6);
+		assert_eq!(stack[0], U256::zero());
+		assert_eq!(stack[2], U256::from(32));
+		assert_eq!(stack[4], U256::from(16));
+		assert_eq!(step.storage, Some([(Bytes(key.to_vec()), Bytes(vec![0; 16]))].into()));
+		assert!(logs[..sstore].iter().all(|log| log.storage.is_none()));
+
+		// Overwriting the slot reports the new value.
+		let logs = store(Default::default(), 8).struct_logs;
+		let step = logs.iter().find(|log| log.op == "set_storage").unwrap();
+		assert_eq!(step.storage, Some([(Bytes(key.to_vec()), Bytes(vec![0; 8]))].into()));
+
+		let config = StructLoggerConfig {
+			disable_stack: true,
+			disable_storage: true,
+			enable_return_data: true,
+			limit: 1,
+		};
+		let trace = store(config, 16);
+		assert!(!trace.failed);
+		assert_eq!(trace.struct_logs.len(), 1);
+		let step = &trace.struct_logs[0];
+		assert_eq!((&step.stack, &step.storage), (&None, &None));
+		assert_eq!(step.return_data, Some(Bytes::default()));
+	});
+}
+
 #[test]
 fn tracing_works() {
 	use crate::evm::*;
@@ -4444,3 +4730,413 @@ fn precompiles_with_info_creates_contract() {
 		});
 	}
 }
+
+fn assert_precompile_event(precompile: H160, event: impl alloy_core::primitives::IntoLogData) {
+	let (topics, data) = event.into_log_data().split();
+	let event = RuntimeEvent::Contracts(crate::Event::ContractEmitted {
+		contract: precompile,
+		data: data.to_vec(),
+		topics: topics.into_iter().map(|v| H256(v.0)).collect(),
+	});
+	assert!(System::events().iter().any(|record| record.event == event), "missing {event:?}");
+}
+
+#[test]
+fn nomination_pools_precompile_works() {
+	use crate::precompiles::INominationPools::{
+		self, INominationPoolsCalls as Calls, INominationPoolsEvents as Events,
+	};
+	use alloy_core::{primitives::U256, sol_types::SolInterface};
+	use precompiles::{MockCall, MockCalls};
+
+	let precompile_addr = H160::from_low_u64_be(0x09_00);
+
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 100_000_000_000);
+		let member = ALICE_ADDR.0.into();
+
+		let cases = vec![
+			(
+				Calls::join(INominationPools::joinCall { amount: U256::from(100), poolId: 1 }),
+				MockCall::Join { who: ALICE, amount: 100, pool_id: 1 },
+				Events::Joined(INominationPools::Joined {
+					member,
+					poolId: 1,
+					amount: U256::from(100),
+				}),
+			),
+			(
+				Calls::bondExtra(INominationPools::bondExtraCall { amount: U256::from(200) }),
+				MockCall::BondExtra { who: ALICE, amount: 200 },
+				Events::BondedExtra(INominationPools::BondedExtra {
+					member,
+					amount: U256::from(200),
+				}),
+			),
+			(
+				Calls::claimPayout(INominationPools::claimPayoutCall {}),
+				MockCall::ClaimPayout { who: ALICE },
+				Events::PayoutClaimed(INominationPools::PayoutClaimed { member }),
+			),
+			(
+				Calls::unbond(INominationPools::unbondCall { points: U256::from(300) }),
+				MockCall::Unbond { who: ALICE, member: ALICE, points: 300 },
+				Events::Unbonded(INominationPools::Unbonded { member, points: U256::from(300) }),
+			),
+		];
+
+		for (input, call, event) in cases {
+			let result = builder::bare_call(precompile_addr)
+				.data(input.abi_encode())
+				.build_and_unwrap_result();
+			assert!(!result.did_revert());
+			assert_eq!(MockCalls::take(), vec![call]);
+			assert_precompile_event(precompile_addr, event);
+		}
+	});
+}
+
+#[test]
+fn nomination_pools_precompile_errors() {
+	use crate::precompiles::INominationPools::{self, INominationPoolsCalls as Calls};
+	use alloy_core::{
+		primitives::U256,
+		sol_types::{Revert, SolError, SolInterface},
+	};
+	use precompiles::{MockCalls, INVALID_POOL, MOCK_ERROR};
+
+	let precompile_addr = H160::from_low_u64_be(0x09_00);
+	let (code, _code_hash) = compile_module("read_only_call").unwrap();
+
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 100_000_000_000);
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(code)).build_and_unwrap_contract();
+
+		// Errors of the pallet are passed through.
+		let input = Calls::join(INominationPools::joinCall {
+			amount: U256::from(100),
+			poolId: INVALID_POOL,
+		});
+		let result = builder::bare_call(precompile_addr).data(input.abi_encode()).build().result;
+		assert_err!(result, MOCK_ERROR);
+
+		// Amounts that don't fit into the balance type revert.
+		let input = Calls::bondExtra(INominationPools::bondExtraCall { amount: U256::MAX });
+		let result = builder::bare_call(precompile_addr)
+			.data(input.abi_encode())
+			.build_and_unwrap_result();
+		assert!(result.did_revert());
+		assert_eq!(result.data, Revert::from("Balance conversion failed").abi_encode());
+
+		// Only signed accounts can use the pre-compile.
+		let input = Calls::claimPayout(INominationPools::claimPayoutCall {});
+		let result = builder::bare_call(precompile_addr)
+			.origin(RuntimeOrigin::root())
+			.data(input.abi_encode())
+			.build_and_unwrap_result();
+		assert!(result.did_revert());
+		assert_eq!(result.data, Revert::from("Invalid caller").abi_encode());
+
+		// State changes are denied in a read-only context.
+		let input = Calls::unbond(INominationPools::unbondCall { points: U256::from(1) });
+		let result = builder::bare_call(addr)
+			.data(precompile_addr.0.iter().copied().chain(input.abi_encode()).collect())
+			.build()
+			.result;
+		assert_err!(result, <Error<Test>>::ContractTrapped);
+
+		assert!(MockCalls::take().is_empty());
+	});
+}
+
+#[test]
+fn conviction_voting_precompile_works() {
+	use crate::precompiles::IConvictionVoting::{
+		self, IConvictionVotingCalls as Calls, IConvictionVotingEvents as Events,
+	};
+	use alloy_core::{primitives::U256, sol_types::SolInterface};
+	use precompiles::{MockCall, MockCalls, MockConvictionVoting};
+
+	let precompile_addr = H160::from_low_u64_be(0x0A_00);
+
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 100_000_000_000);
+		let voter = ALICE_ADDR.0.into();
+
+		let input = Calls::vote(IConvictionVoting::voteCall {
+			pollIndex: 1,
+			aye: true,
+			conviction: 2,
+			balance: U256::from(100),
+		});
+		let result = builder::bare_call(precompile_addr)
+			.data(input.abi_encode())
+			.build_and_unwrap_result();
+		assert!(!result.did_revert());
+		assert_eq!(
+			MockCalls::take(),
+			vec![MockCall::Vote {
+				who: ALICE,
+				poll_index: 1,
+				aye: true,
+				conviction: 2,
+				balance: 100
+			}]
+		);
+		assert_precompile_event(
+			precompile_addr,
+			Events::Voted(IConvictionVoting::Voted {
+				voter,
+				pollIndex: 1,
+				aye: true,
+				conviction: 2,
+				balance: U256::from(100),
+			}),
+		);
+
+		let input = Calls::removeVote(IConvictionVoting::removeVoteCall { class: 3, pollIndex: 1 });
+		let result = builder::bare_call(precompile_addr)
+			.data(input.abi_encode())
+			.build_and_unwrap_result();
+		assert!(!result.did_revert());
+		assert_eq!(
+			MockCalls::take(),
+			vec![MockCall::RemoveVote { who: ALICE, class: 3, poll_index: 1 }]
+		);
+		assert_precompile_event(
+			precompile_addr,
+			Events::VoteRemoved(IConvictionVoting::VoteRemoved { voter, pollIndex: 1 }),
+		);
+
+		let input = Calls::delegate(IConvictionVoting::delegateCall {
+			class: 3,
+			target: BOB_ADDR.0.into(),
+			conviction: 6,
+			balance: U256::from(400),
+		});
+		let result = builder::bare_call(precompile_addr).data(input.abi_encode()).build();
+		assert!(!result.result.unwrap().did_revert());
+		assert_eq!(
+			MockCalls::take(),
+			vec![MockCall::Delegate {
+				who: ALICE,
+				class: 3,
+				target: BOB,
+				conviction: 6,
+				balance: 400
+			}]
+		);
+		assert_precompile_event(
+			precompile_addr,
+			Events::Delegated(IConvictionVoting::Delegated {
+				delegator: voter,
+				target: BOB_ADDR.0.into(),
+				class: 3,
+				conviction: 6,
+				balance: U256::from(400),
+			}),
+		);
+		// Only the weight that was actually used by the delegation is charged.
+		assert!(
+			result.gas_consumed.ref_time() < MockConvictionVoting::delegate_weight().ref_time()
+		);
+	});
+}
+
+#[test]
+fn conviction_voting_precompile_errors() {
+	use crate::precompiles::{
+		ConvictionVotingController,
+		IConvictionVoting::{self, IConvictionVotingCalls as Calls},
+	};
+	use alloy_core::{
+		primitives::U256,
+		sol_types::{Revert, SolError, SolInterface},
+	};
+	use precompiles::{MockCalls, MockConvictionVoting, INVALID_CLASS, INVALID_POLL, MOCK_ERROR};
+
+	let precompile_addr = H160::from_low_u64_be(0x0A_00);
+	let (code, _code_hash) = compile_module("read_only_call").unwrap();
+
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 100_000_000_000);
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(code)).build_and_unwrap_contract();
+		let vote = |conviction, balance| {
+			Calls::vote(IConvictionVoting::voteCall {
+				pollIndex: 1,
+				aye: false,
+				conviction,
+				balance,
+			})
+		};
+
+		// Errors of the pallet are passed through.
+		let input = Calls::removeVote(IConvictionVoting::removeVoteCall {
+			class: 1,
+			pollIndex: INVALID_POLL,
+		});
+		let result = builder::bare_call(precompile_addr).data(input.abi_encode()).build().result;
+		assert_err!(result, MOCK_ERROR);
+
+		// Failed delegations are only charged for the weight they actually used.
+		let input = Calls::delegate(IConvictionVoting::delegateCall {
+			class: INVALID_CLASS,
+			target: BOB_ADDR.0.into(),
+			conviction: 1,
+			balance: U256::from(1),
+		});
+		let result = builder::bare_call(precompile_addr).data(input.abi_encode()).build();
+		assert_err!(result.result, MOCK_ERROR);
+		assert!(
+			result.gas_consumed.ref_time() < MockConvictionVoting::delegate_weight().ref_time()
+		);
+
+		// Convictions above the maximum revert.
+		let input = vote(crate::precompiles::MAX_CONVICTION + 1, U256::from(1));
+		let result = builder::bare_call(precompile_addr)
+			.data(input.abi_encode())
+			.build_and_unwrap_result();
+		assert!(result.did_revert());
+		assert_eq!(result.data, Revert::from("Invalid conviction").abi_encode());
+
+		// Balances that don't fit into the balance type revert.
+		let input = vote(1, U256::MAX);
+		let result = builder::bare_call(precompile_addr)
+			.data(input.abi_encode())
+			.build_and_unwrap_result();
+		assert!(result.did_revert());
+		assert_eq!(result.data, Revert::from("Balance conversion failed").abi_encode());
+
+		// Only signed accounts can use the pre-compile.
+		let result = builder::bare_call(precompile_addr)
+			.origin(RuntimeOrigin::root())
+			.data(vote(1, U256::from(1)).abi_encode())
+			.build_and_unwrap_result();
+		assert!(result.did_revert());
+		assert_eq!(result.data, Revert::from("Invalid caller").abi_encode());
+
+		// State changes are denied in a read-only context.
+		let result = builder::bare_call(addr)
+			.data(
+				precompile_addr
+					.0
+					.iter()
+					.copied()
+					.chain(vote(1, U256::from(1)).abi_encode())
+					.collect(),
+			)
+			.build()
+			.result;
+		assert_err!(result, <Error<Test>>::ContractTrapped);
+
+		assert!(MockCalls::take().is_empty());
+	});
+}
+
+#[test]
+fn xcm_precompile_works() {
+	use crate::precompiles::IXcm::{self, IXcmCalls as Calls};
+	use alloy_core::sol_types::{SolCall, SolInterface};
+	use precompiles::{MockCall, MockCalls, MOCK_ERROR, XCM_MESSAGE_WEIGHT};
+	use xcm::{latest::prelude::*, VersionedLocation, VersionedXcm};
+
+	let precompile_addr = H160::from_low_u64_be(0x08_00);
+	let weight = IXcm::Weight {
+		refTime: XCM_MESSAGE_WEIGHT.ref_time(),
+		proofSize: XCM_MESSAGE_WEIGHT.proof_size(),
+	};
+
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 100_000_000_000);
+
+		let message = VersionedXcm::<RuntimeCall>::from(Xcm(vec![ClearOrigin]));
+		let input =
+			Calls::weighMessage(IXcm::weighMessageCall { message: message.encode().into() });
+		let result = builder::bare_call(precompile_addr)
+			.data(input.abi_encode())
+			.build_and_unwrap_result();
+		assert!(!result.did_revert());
+		assert_eq!(result.data, IXcm::weighMessageCall::abi_encode_returns(&weight));
+		assert!(MockCalls::take().is_empty());
+
+		let input = Calls::execute(IXcm::executeCall {
+			message: message.encode().into(),
+			weight: weight.clone(),
+		});
+		let result = builder::bare_call(precompile_addr)
+			.data(input.abi_encode())
+			.build_and_unwrap_result();
+		assert!(!result.did_revert());
+		assert_eq!(
+			MockCalls::take(),
+			vec![MockCall::XcmExecute { who: ALICE, message, max_weight: XCM_MESSAGE_WEIGHT }]
+		);
+
+		let dest = VersionedLocation::from(Location::parent());
+		let message = VersionedXcm::<()>::from(Xcm(vec![ClearOrigin]));
+		let input = Calls::send(IXcm::sendCall {
+			destination: dest.encode().into(),
+			message: message.encode().into(),
+		});
+		let result = builder::bare_call(precompile_addr)
+			.data(input.abi_encode())
+			.build_and_unwrap_result();
+		assert!(!result.did_revert());
+		assert_eq!(MockCalls::take(), vec![MockCall::XcmSend { who: ALICE, dest, message }]);
+
+		// Errors of the controller are passed through.
+		let message = VersionedXcm::<RuntimeCall>::from(Xcm(vec![ClearOrigin]));
+		let input = Calls::execute(IXcm::executeCall {
+			message: message.encode().into(),
+			weight: IXcm::Weight { refTime: 1, proofSize: 1 },
+		});
+		let result = builder::bare_call(precompile_addr).data(input.abi_encode()).build().result;
+		assert_err!(result, MOCK_ERROR);
+		assert!(MockCalls::take().is_empty());
+	});
+}
+
+#[test]
+fn xcm_precompile_charges_input_before_decoding() {
+	use crate::{
+		gas::Token,
+		precompiles::IXcm::{self, IXcmCalls as Calls},
+		wasm::RuntimeCosts,
+	};
+	use alloy_core::sol_types::{Revert, SolError, SolInterface};
+	use precompiles::MockCalls;
+	use xcm::{latest::prelude::*, VersionedXcm};
+
+	let precompile_addr = H160::from_low_u64_be(0x08_00);
+	let message = VersionedXcm::<()>::from(Xcm(vec![ClearOrigin])).encode();
+	let decode_cost = |len: usize| {
+		<RuntimeCosts as Token<Test>>::weight(&RuntimeCosts::PrecompileDecode(len as u32))
+	};
+
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 100_000_000_000);
+		let send = |destination: Vec<u8>| {
+			let input = Calls::send(IXcm::sendCall {
+				destination: destination.into(),
+				message: message.clone().into(),
+			});
+			let result = builder::bare_call(precompile_addr).data(input.abi_encode()).build();
+			let data = result.result.unwrap();
+			assert!(data.did_revert());
+			assert_eq!(data.data, Revert::from("Failed to decode XCM").abi_encode());
+			result.gas_consumed
+		};
+
+		// Invalid destinations are paid for by their length even though decoding fails.
+		let short = send(vec![0xff]);
+		let long = send(vec![0xff; 10 * 1024]);
+		let decode_difference =
+			decode_cost(10 * 1024 + message.len()).saturating_sub(decode_cost(1 + message.len()));
+		assert!(decode_difference.ref_time() > 0);
+		assert!(long.ref_time() >= short.ref_time() + decode_difference.ref_time());
+
+		assert!(MockCalls::take().is_empty());
+	});
+}
@@ -17,9 +17,13 @@
 
 //! Precompiles added to the test runtime.
 
+use super::{RuntimeCall, Test};
 use crate::{
 	exec::{ErrorOrigin, ExecError},
-	precompiles::{AddressMatcher, Error, Ext, ExtWithInfo, Precompile, Token},
+	precompiles::{
+		AddressMatcher, ConvictionVotingController, Error, Ext, ExtWithInfo,
+		NominationPoolsController, Precompile, Token, XcmController,
+	},
 	Config, DispatchError, Origin, Weight,
 };
 use alloc::vec::Vec;
@@ -27,10 +31,15 @@ use alloy_core::{
 	sol,
 	sol_types::{PanicKind, SolValue},
 };
-use codec::Decode;
+use codec::{Decode, Encode};
 use core::{marker::PhantomData, num::NonZero};
-use frame_system::RawOrigin;
-use sp_runtime::traits::Dispatchable;
+use frame_support::{
+	dispatch::{DispatchErrorWithPostInfo, DispatchResultWithPostInfo},
+	parameter_types,
+};
+use frame_system::{ensure_signed, pallet_prelude::OriginFor, RawOrigin};
+use sp_runtime::{traits::Dispatchable, AccountId32, DispatchResult};
+use xcm::{prelude::XcmHash, VersionedLocation, VersionedXcm};
 
 sol! {
 	interface IWithInfo {
@@ -117,3 +126,184 @@ impl<T: Config> Token<T> for MaxGasToken {
 		Weight::MAX
 	}
 }
+
+/// A call that reached [`MockNominationPools`], [`MockConvictionVoting`] or [`MockXcm`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MockCall {
+	Join { who: AccountId32, amount: u64, pool_id: u32 },
+	BondExtra { who: AccountId32, amount: u64 },
+	ClaimPayout { who: AccountId32 },
+	Unbond { who: AccountId32, member: AccountId32, points: u64 },
+	Vote { who: AccountId32, poll_index: u32, aye: bool, conviction: u8, balance: u64 },
+	RemoveVote { who: AccountId32, class: u16, poll_index: u32 },
+	Delegate { who: AccountId32, class: u16, target: AccountId32, conviction: u8, balance: u64 },
+	XcmExecute { who: AccountId32, message: VersionedXcm<RuntimeCall>, max_weight: Weight },
+	XcmSend { who: AccountId32, dest: VersionedLocation, message: VersionedXcm<()> },
+}
+
+parameter_types! {
+	/// The calls that were dispatched by the mock controllers so far.
+	pub static MockCalls: Vec<MockCall> = Vec::new();
+}
+
+/// The pool id for which [`MockNominationPools`] fails.
+pub const INVALID_POOL: u32 = 0;
+
+/// The poll index for which [`MockConvictionVoting`] fails.
+pub const INVALID_POLL: u32 = 0;
+
+/// The class for which [`MockConvictionVoting::delegate`] fails.
+pub const INVALID_CLASS: u16 = 0;
+
+/// The error returned by the mock controllers for invalid pools and polls.
+pub const MOCK_ERROR: DispatchError = DispatchError::Other("mock controller failed");
+
+/// The weight that is actually consumed by [`MockConvictionVoting::delegate`].
+pub const DELEGATE_ACTUAL_WEIGHT: Weight = Weight::from_parts(1_000, 0);
+
+/// The weight of every message executed by [`MockXcm`].
+///
+/// Executions with a lower `max_weight` fail after consuming all of it.
+pub const XCM_MESSAGE_WEIGHT: Weight = Weight::from_parts(1_000_000, 1_000);
+
+fn record(origin: OriginFor<Test>, call: impl FnOnce(AccountId32) -> MockCall) -> DispatchResult {
+	let who = ensure_signed(origin)?;
+	MockCalls::mutate(|calls| calls.push(call(who)));
+	Ok(())
+}
+
+/// Records the calls of the nomination pools pre-compile.
+pub struct MockNominationPools;
+
+impl NominationPoolsController<Test> for MockNominationPools {
+	type Balance = u64;
+
+	fn join(origin: OriginFor<Test>, amount: u64, pool_id: u32) -> DispatchResult {
+		if pool_id == INVALID_POOL {
+			return Err(MOCK_ERROR);
+		}
+		record(origin, |who| MockCall::Join { who, amount, pool_id })
+	}
+
+	fn join_weight() -> Weight {
+		Weight::from_parts(1_000_000, 1_000)
+	}
+
+	fn bond_extra(origin: OriginFor<Test>, amount: u64) -> DispatchResult {
+		record(origin, |who| MockCall::BondExtra { who, amount })
+	}
+
+	fn bond_extra_weight() -> Weight {
+		Weight::from_parts(2_000_000, 1_000)
+	}
+
+	fn claim_payout(origin: OriginFor<Test>) -> DispatchResult {
+		record(origin, |who| MockCall::ClaimPayout { who })
+	}
+
+	fn claim_payout_weight() -> Weight {
+		Weight::from_parts(3_000_000, 1_000)
+	}
+
+	fn unbond(origin: OriginFor<Test>, member: AccountId32, points: u64) -> DispatchResult {
+		record(origin, |who| MockCall::Unbond { who, member, points })
+	}
+
+	fn unbond_weight() -> Weight {
+		Weight::from_parts(4_000_000, 1_000)
+	}
+}
+
+/// Records the calls of the conviction voting pre-compile.
+pub struct MockConvictionVoting;
+
+impl ConvictionVotingController<Test> for MockConvictionVoting {
+	type Balance = u64;
+
+	fn vote(
+		origin: OriginFor<Test>,
+		poll_index: u32,
+		aye: bool,
+		conviction: u8,
+		balance: u64,
+	) -> DispatchResult {
+		if poll_index == INVALID_POLL {
+			return Err(MOCK_ERROR);
+		}
+		record(origin, |who| MockCall::Vote { who, poll_index, aye, conviction, balance })
+	}
+
+	fn vote_weight() -> Weight {
+		Weight::from_parts(1_000_000, 1_000)
+	}
+
+	fn remove_vote(origin: OriginFor<Test>, class: u16, poll_index: u32) -> DispatchResult {
+		if poll_index == INVALID_POLL {
+			return Err(MOCK_ERROR);
+		}
+		record(origin, |who| MockCall::RemoveVote { who, class, poll_index })
+	}
+
+	fn remove_vote_weight() -> Weight {
+		Weight::from_parts(2_000_000, 1_000)
+	}
+
+	fn delegate(
+		origin: OriginFor<Test>,
+		class: u16,
+		target: AccountId32,
+		conviction: u8,
+		balance: u64,
+	) -> DispatchResultWithPostInfo {
+		let post_info = Some(DELEGATE_ACTUAL_WEIGHT).into();
+		if class == INVALID_CLASS {
+			return Err(DispatchErrorWithPostInfo { post_info, error: MOCK_ERROR });
+		}
+		record(origin, |who| MockCall::Delegate { who, class, target, conviction, balance })?;
+		Ok(post_info)
+	}
+
+	fn delegate_weight() -> Weight {
+		Weight::from_parts(50_000_000_000, 1_000)
+	}
+}
+
+/// Records the messages executed and sent by the XCM pre-compile.
+pub struct MockXcm;
+
+impl XcmController<Test> for MockXcm {
+	fn execute(
+		origin: OriginFor<Test>,
+		message: VersionedXcm<RuntimeCall>,
+		max_weight: Weight,
+	) -> Result<Weight, DispatchErrorWithPostInfo> {
+		if !max_weight.all_gte(XCM_MESSAGE_WEIGHT) {
+			let post_info = Some(max_weight).into();
+			return Err(DispatchErrorWithPostInfo { post_info, error: MOCK_ERROR });
+		}
+		record(origin, |who| MockCall::XcmExecute { who, message, max_weight })?;
+		Ok(XCM_MESSAGE_WEIGHT)
+	}
+
+	fn execute_weight() -> Weight {
+		Weight::from_parts(1_000_000, 1_000)
+	}
+
+	fn send(
+		origin: OriginFor<Test>,
+		dest: VersionedLocation,
+		message: VersionedXcm<()>,
+	) -> Result<XcmHash, DispatchError> {
+		let hash = sp_io::hashing::blake2_256(&message.encode());
+		record(origin, |who| MockCall::XcmSend { who, dest, message })?;
+		Ok(hash)
+	}
+
+	fn send_weight() -> Weight {
+		Weight::from_parts(2_000_000, 1_000)
+	}
+
+	fn weigh(_message: VersionedXcm<RuntimeCall>) -> Result<Weight, DispatchError> {
+		Ok(XCM_MESSAGE_WEIGHT)
+	}
+}
@@ -62,4 +62,17 @@ pub trait Tracing {
 
 	/// Called when a contract call terminates with an error
 	fn exit_child_span_with_error(&mut self, _error: DispatchError, _gas_left: Weight) {}
+
+	/// Called before a contract invokes a host function
+	fn enter_ecall(&mut self, _ecall: &str, _pc: u64, _args: &[u64], _gas_left: Weight) {}
+
+	/// Called after a host function returns
+	fn exit_ecall(&mut self, _gas_left: Weight) {}
+
+	/// Record a read of the current contract's storage
+	fn storage_read(&mut self, _key: &[u8], _value: Option<&[u8]>) {}
+
+	/// Record a write to the current contract's storage
+	fn storage_write(&mut self, _key: &[u8], _old_value: Option<&[u8]>, _new_value: Option<&[u8]>) {
+	}
 }
@@ -0,0 +1,23 @@
+// SPDX-License-Identifier: MIT
+// OpenZeppelin Contracts (last updated v5.1.0) (token/ERC721/IERC721Receiver.sol)
+// https://github.com/OpenZeppelin/openzeppelin-contracts/blob/master/contracts/token/ERC721/IERC721Receiver.sol
+pragma solidity ^0.8.20;
+
+///
+/// @dev Interface for any contract that wants to support safeTransfers
+/// from ERC-721 asset contracts.
+///
+interface IERC721Receiver {
+     /// @dev Whenever an {IERC721} `tokenId` token is transferred to this contract via
+     /// {IERC721-safeTransferFrom} by `operator` from `from`, this function is called.
+     ///
+     /// It must return its Solidity selector to confirm the token transfer.
+     /// If any other value is returned or the interface is not implemented by the recipient,
+     /// the transfer will be reverted.
+    function onERC721Received(
+        address operator,
+        address from,
+        uint256 tokenId,
+        bytes calldata data
+    ) external returns (bytes4);
+}
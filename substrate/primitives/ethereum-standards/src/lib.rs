@@ -20,3 +20,5 @@
 #![no_std]
 
 alloy_core::sol!("src/IERC20.sol");
+alloy_core::sol!("src/IERC721.sol");
+alloy_core::sol!("src/IERC721Receiver.sol");
@@ -22,11 +22,14 @@
 
 #![warn(missing_docs)]
 
+use futures::channel::mpsc;
 use jsonrpsee::RpcModule;
 use minimal_template_runtime::interface::{AccountId, Nonce, OpaqueBlock};
 use polkadot_sdk::{
+	sc_consensus_manual_seal::EngineCommand,
 	sc_transaction_pool_api::TransactionPool,
 	sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata},
+	sp_runtime::traits::Block as BlockT,
 	*,
 };
 use std::sync::Arc;
@@ -37,6 +40,8 @@ pub struct FullDeps<C, P> {
 	pub client: Arc<C>,
 	/// Transaction pool instance.
 	pub pool: Arc<P>,
+	/// Sends commands to the manual seal authorship task, if the node uses manual seal.
+	pub command_sink: Option<mpsc::Sender<EngineCommand<<OpaqueBlock as BlockT>::Hash>>>,
 }
 
 #[docify::export]
@@ -56,11 +61,18 @@ where
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<OpaqueBlock, AccountId, Nonce>,
 	P: TransactionPool + 'static,
 {
-	use polkadot_sdk::substrate_frame_rpc_system::{System, SystemApiServer};
+	use polkadot_sdk::{
+		sc_consensus_manual_seal::rpc::{ManualSeal, ManualSealApiServer},
+		substrate_frame_rpc_system::{System, SystemApiServer},
+	};
 	let mut module = RpcModule::new(());
-	let FullDeps { client, pool } = deps;
+	let FullDeps { client, pool, command_sink } = deps;
 
 	module.merge(System::new(client.clone(), pool.clone()).into_rpc())?;
+	if let Some(command_sink) = command_sink {
+		// The `engine_*` methods seal blocks, travel in time and take snapshots of the chain.
+		module.merge(ManualSeal::new(command_sink).into_rpc())?;
+	}
 
 	Ok(module)
 }
@@ -169,12 +169,20 @@ pub fn new_full<Network: sc_network::NetworkBackend<Block, <Block as BlockT>::Ha
 		);
 	}
 
+	// Manual seal is driven by a timer and by the `engine_*` RPC methods.
+	let (command_sink, commands_stream) = futures::channel::mpsc::channel(1024);
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
+		let command_sink =
+			matches!(consensus, Consensus::ManualSeal(_)).then(|| command_sink.clone());
 
 		Box::new(move |_| {
-			let deps = crate::rpc::FullDeps { client: client.clone(), pool: pool.clone() };
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				command_sink: command_sink.clone(),
+			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		})
 	};
@@ -188,7 +196,7 @@ pub fn new_full<Network: sc_network::NetworkBackend<Block, <Block as BlockT>::Ha
 		task_manager: &mut task_manager,
 		transaction_pool: transaction_pool.clone(),
 		rpc_builder: rpc_extensions_builder,
-		backend,
+		backend: backend.clone(),
 		system_rpc_tx,
 		tx_handler_controller,
 		sync_service,
@@ -227,7 +235,7 @@ pub fn new_full<Network: sc_network::NetworkBackend<Block, <Block as BlockT>::Ha
 			);
 		},
 		Consensus::ManualSeal(block_time) => {
-			let (mut sink, commands_stream) = futures::channel::mpsc::channel(1024);
+			let mut sink = command_sink;
 			task_manager.spawn_handle().spawn("block_authoring", None, async move {
 				loop {
 					futures_timer::Delay::new(std::time::Duration::from_millis(block_time)).await;
@@ -241,17 +249,25 @@ pub fn new_full<Network: sc_network::NetworkBackend<Block, <Block as BlockT>::Ha
 				}
 			});
 
+			let time_travel = sc_consensus_manual_seal::TimeTravel::new();
 			let params = sc_consensus_manual_seal::ManualSealParams {
 				block_import: client.clone(),
+				backend: Some(backend),
 				env: proposer,
 				client,
 				pool: transaction_pool,
 				select_chain,
 				commands_stream: Box::pin(commands_stream),
 				consensus_data_provider: None,
-				create_inherent_data_providers: move |_, ()| async move {
-					Ok(sp_timestamp::InherentDataProvider::from_system_time())
+				create_inherent_data_providers: {
+					let time_travel = time_travel.clone();
+					move |_, ()| {
+						let timestamp = time_travel.inherent_data_provider();
+						async move { Ok(timestamp) }
+					}
 				},
+				time_travel: Some(time_travel),
+				impersonation_provider: None,
 			};
 			let authorship_future = sc_consensus_manual_seal::run_manual_seal(params);
 
@@ -616,6 +616,10 @@ pub use pallet_revive_eth_rpc;
 #[cfg(feature = "pallet-revive-proc-macro")]
 pub use pallet_revive_proc_macro;
 
+/// Build and verify the storage proofs returned by the pallet-revive eth_getProof endpoint.
+#[cfg(feature = "pallet-revive-proof")]
+pub use pallet_revive_proof;
+
 /// Exposes all the host functions that a contract can import.
 #[cfg(feature = "pallet-revive-uapi")]
 pub use pallet_revive_uapi;
@@ -1080,6 +1084,10 @@ pub use sc_consensus_pow;
 #[cfg(feature = "sc-consensus-slots")]
 pub use sc_consensus_slots;
 
+/// Index of the runtime events emitted by finalized blocks.
+#[cfg(feature = "sc-event-index")]
+pub use sc_event_index;
+
 /// A crate that provides means of executing/dispatching calls into the runtime.
 #[cfg(feature = "sc-executor")]
 pub use sc_executor;
@@ -1156,6 +1164,10 @@ pub use sc_rpc;
 #[cfg(feature = "sc-rpc-api")]
 pub use sc_rpc_api;
 
+/// GraphQL queries over the blocks, extrinsics, events and storage of the chain.
+#[cfg(feature = "sc-rpc-graphql")]
+pub use sc_rpc_graphql;
+
 /// Substrate RPC servers.
 #[cfg(feature = "sc-rpc-server")]
 pub use sc_rpc_server;